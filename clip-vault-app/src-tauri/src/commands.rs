@@ -1,27 +1,231 @@
 #![allow(clippy::used_underscore_binding)]
 
-use arboard::ImageData;
 use base64::engine::general_purpose;
 use base64::Engine;
-use clip_vault_core::{ClipboardItem, SqliteVault, Vault};
+use clip_vault_core::{
+    files_from_text, ActivityBucket, ClipboardItem, ClipboardItemWithTimestamp, Config, CopyEvent,
+    CopyFormat, DuplicatePolicy, EntryDescription, HealthEvent, HealthLevel, HistogramBucket,
+    ItemKind, ItemStats, Language, ListedEntry, MultiVault, Permission, PermissionCheck,
+    PreviewOptions, Relation, RetentionPolicy, SavedSearch, SearchPreview, SecurityProfile,
+    SensitiveAction, SqliteVault, StorageInfo, TextNormalization, TextPreview, Timestamp,
+    TimestampStyle, UninstallReport, UnlockPolicy, Vault, DEFAULT_PROFILE, FILES_MIME,
+};
+use clip_vault_monitor::CaptureDecision;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
-use std::borrow::Cow;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
-use tracing::info;
-
-use crate::modules::clipboard_monitor::{start_clipboard_monitoring, stop_clipboard_monitoring};
-use crate::modules::window_manager::show_settings_window;
-use crate::state::{current_timestamp, is_session_expired, AppSettings, AppState, SessionInfo};
-
-#[derive(Debug, Serialize, Deserialize)]
+use tracing::{info, warn};
+
+use crate::modules::actions::{self, Action};
+use crate::modules::clipboard_monitor::{
+    start_clipboard_monitoring, stop_clipboard_monitoring, write_clipboard,
+    write_clipboard_unrecorded,
+};
+use crate::modules::logging::{self, LogLevel};
+use crate::modules::navigation::{announce_selection, SearchNavigation, SelectionChanged};
+use crate::modules::onboarding::{self, ExistingVault};
+use crate::modules::session;
+use crate::modules::system_tray::{
+    capture_status, refresh_capture_status, refresh_quick_filters, CaptureStatus,
+};
+use crate::modules::updates::{self, UpdateChannel};
+use crate::modules::vault_events::forward_vault_events;
+use crate::modules::window_manager::{
+    apply_search_window_behavior, dismiss_search_window, show_settings_window, PendingSearch,
+};
+use crate::state::{current_timestamp, AppSettings, AppState, SessionInfo, WindowBehavior};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub id: String,
+    /// The open vault's id for the entry, which `delete_item` and the
+    /// update commands take. `None` for entries from other profiles or
+    /// backups, so they can't be passed to commands that would act on an
+    /// unrelated entry of the open vault.
+    pub entry_id: Option<i64>,
+    /// Hex item hash, the key used by `item-*` events.
+    pub hash: String,
     pub content: String,
-    pub timestamp: u64,
+    pub timestamp: Timestamp,
     pub content_type: String,
+    /// What the entry holds, e.g. "url", "code" or "image"; see
+    /// [`ItemKind`](clip_vault_core::ItemKind).
+    pub kind: String,
+    /// e.g. "1920×1080 PNG, 2.3 MB"; only set for images with a readable header.
+    pub image_info: Option<String>,
+    /// Base64 PNG the results list shows in place of a large image.
+    pub thumbnail: Option<String>,
+    /// The color a color entry spells, for the results list's swatch.
+    pub color: Option<Rgba>,
+    /// Size, line and word counts, and them as a label like
+    /// "3.2 KB, 120 lines".
+    pub stats: ItemStats,
+    pub stats_label: String,
+    /// Relative capture time ("5m ago") in the system locale and timezone.
+    pub time_label: String,
+    /// The entry in words, for screen readers (see [`EntryDescription`]).
+    pub description: String,
+    /// The lines the results list shows for text entries, cut to the app's
+    /// preview settings; `None` for images and where no list shows them.
+    pub preview: Option<TextPreview>,
+    /// The app the entry was copied from, and its window title.
+    pub source_app: Option<String>,
+    pub window_title: Option<String>,
+    /// The vault profile the entry came from, in searches across profiles.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Pinned above the history and out of pruning.
+    pub pinned: bool,
+}
+
+impl SearchResult {
+    /// Attach the text preview the results list shows.
+    #[must_use]
+    pub fn with_preview(mut self, options: PreviewOptions) -> Self {
+        if self.content_type.starts_with("text/") || self.content_type == FILES_MIME {
+            self.preview = Some(TextPreview::new(&self.content, options));
+        }
+        self
+    }
+}
+
+/// How much of each text entry the results list shows, from the shared
+/// config.
+fn preview_options() -> PreviewOptions {
+    Config::load().map(|c| c.previews.app).unwrap_or_default()
+}
+
+impl SearchResult {
+    /// `entry` with the size, line and word counts `vault` recorded when it
+    /// stored it, rather than counted again.
+    pub fn stored_in(vault: &dyn Vault, entry: ClipboardItemWithTimestamp) -> Result<Self, String> {
+        let stats = vault
+            .item_stats(entry.item.hash())
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default();
+        Ok(Self::new(entry, stats))
+    }
+
+    /// [`stored_in`](Self::stored_in) for an entry of a vault other than
+    /// the open one, a backup or another profile. It gets no `entry_id`,
+    /// since the commands taking one act on the open vault.
+    pub fn foreign(vault: &dyn Vault, entry: ClipboardItemWithTimestamp) -> Result<Self, String> {
+        Ok(Self {
+            entry_id: None,
+            ..Self::stored_in(vault, entry)?
+        })
+    }
+
+    fn new(entry: ClipboardItemWithTimestamp, stats: ItemStats) -> Self {
+        let hash = clip_vault_core::hash_to_hex(&entry.item.hash());
+        let image_info = entry.item.image_info().map(|info| info.to_string());
+        let kind = ItemKind::of(&entry.item).as_str().to_string();
+        let color = match &entry.item {
+            ClipboardItem::Text(text) => Rgba::parse(text),
+            _ => None,
+        };
+        let time_label = entry.timestamp.relative();
+        let description = EntryDescription::new(&entry, Timestamp::now()).to_string();
+        let (source_app, window_title) = entry.source.map_or((None, None), |source| {
+            (Some(source.name), source.window_title)
+        });
+        let (content, content_type) = entry.item.into_parts();
+        Self {
+            id: entry.timestamp.as_nanos().to_string(),
+            entry_id: entry.id,
+            hash,
+            content,
+            timestamp: entry.timestamp,
+            content_type,
+            kind,
+            image_info,
+            thumbnail: None,
+            color,
+            stats,
+            stats_label: stats.to_string(),
+            time_label,
+            description,
+            preview: None,
+            source_app,
+            window_title,
+            profile: None,
+            pinned: false,
+        }
+    }
+}
+
+/// A listed entry; a large image's thumbnail goes in `thumbnail` and its
+/// `content` is left empty until `get_item_content` loads it.
+impl From<ListedEntry> for SearchResult {
+    fn from(listed: ListedEntry) -> Self {
+        let description = EntryDescription::of_listed(&listed, Timestamp::now()).to_string();
+        let ListedEntry {
+            hash,
+            entry,
+            thumbnail,
+            image,
+            stats,
+        } = listed;
+        let mut result = Self::new(entry, stats);
+        if thumbnail {
+            result.thumbnail = Some(std::mem::take(&mut result.content));
+        }
+        Self {
+            hash: clip_vault_core::hash_to_hex(&hash),
+            image_info: image.map(|info| info.to_string()),
+            description,
+            ..result
+        }
+    }
+}
+
+/// Result of `create_vault`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedVault {
+    pub created: bool,
+    /// Shown to the user once; never stored.
+    pub recovery_key: Option<String>,
+}
+
+/// Everything the first-run flow needs to decide what to show.
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingStatus {
+    /// Where `create_vault` will put a new vault.
+    pub vault_path: String,
+    pub vault_exists: bool,
+    /// Vaults already on disk, including ones created by the CLI, that
+    /// `adopt_vault` can take over.
+    pub existing_vaults: Vec<ExistingVault>,
+    pub permissions: Vec<PermissionCheck>,
+    pub launch_at_login: bool,
+}
+
+/// Payload of `item-added` and `item-deleted`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemEvent {
+    pub hash: String,
+}
+
+/// Payload of `vault-health`; the latest are also kept for `daemon_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthNotice {
+    pub level: HealthLevel,
+    pub message: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub event: HealthEvent,
+}
+
+/// Payload of `item-updated`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemUpdated {
+    pub old_hash: String,
+    pub hash: String,
 }
 
 #[tauri::command]
@@ -34,28 +238,142 @@ pub async fn list_clipboard(
     let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
 
     // Default limit to 20 if not specified
-    let effective_limit = limit.or(Some(20));
-
-    let items = vault
-        .list(effective_limit, after_timestamp)
+    let effective_limit = limit.unwrap_or(20);
+
+    // Pinned entries head the first page and are left out of the rest, so
+    // each page still holds `limit` entries of the history.
+    let pinned = vault.pinned_thumbnails().map_err(|e| e.to_string())?;
+    let pinned_hashes: HashSet<[u8; 32]> = pinned.iter().map(|listed| listed.hash).collect();
+    let page = vault
+        .list_thumbnails(Some(effective_limit + pinned.len()), after_timestamp)
         .map_err(|e| e.to_string())?;
+    warn_skipped(vault);
+
+    let mut items = if after_timestamp.is_none() {
+        pinned
+    } else {
+        Vec::new()
+    };
+    items.extend(
+        page.into_iter()
+            .filter(|listed| !pinned_hashes.contains(&listed.hash))
+            .take(effective_limit),
+    );
 
+    let options = preview_options();
     let results: Vec<SearchResult> = items
         .into_iter()
-        .map(|item| {
-            let (content, content_type) = item.item.clone().into_parts();
-            SearchResult {
-                id: format!("{}", item.timestamp),
-                content,
-                timestamp: item.timestamp,
-                content_type,
-            }
+        .map(|listed| SearchResult {
+            pinned: pinned_hashes.contains(&listed.hash),
+            ..SearchResult::from(listed).with_preview(options)
         })
         .collect();
 
     Ok(results)
 }
 
+fn pinned_hashes(vault: &SqliteVault) -> Result<HashSet<[u8; 32]>, String> {
+    let pinned = vault.pinned_thumbnails().map_err(|e| e.to_string())?;
+    Ok(pinned.into_iter().map(|listed| listed.hash).collect())
+}
+
+/// Pin an entry above the history, or unpin it if it is. Returns whether
+/// it's pinned afterwards.
+#[tauri::command]
+pub async fn toggle_pin(id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    let hash = entry_hash(vault, id)?;
+    let pin = !pinned_hashes(vault)?.contains(&hash);
+    if vault.pin(hash, pin).map_err(|e| e.to_string())? == 0 {
+        return Err("Item not found".to_string());
+    }
+    Ok(pin)
+}
+
+/// When an entry expires; `None` if it's kept until deleted or pruned.
+#[tauri::command]
+pub async fn get_item_expiry(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<Timestamp>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    vault
+        .expiry(entry_hash(vault, id)?)
+        .map_err(|e| e.to_string())
+}
+
+/// Delete an entry `ttl_secs` from now, or keep it with `None`. Returns
+/// when it expires. Setting an expiry counts as a delete for the unlock
+/// policy, so it may need `password`.
+#[tauri::command]
+pub async fn set_item_expiry(
+    id: i64,
+    ttl_secs: Option<u64>,
+    password: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<Timestamp>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    if ttl_secs.is_some() {
+        authorize(vault, SensitiveAction::Delete, password)?;
+    }
+
+    let expires_at =
+        ttl_secs.map(|secs| Timestamp::now().saturating_add(Duration::from_secs(secs)));
+    let hash = entry_hash(vault, id)?;
+    if vault
+        .set_expiry(hash, expires_at)
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        return Err("Item not found".to_string());
+    }
+    Ok(expires_at)
+}
+
+/// A screen-reader description of an entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct DescribedEntry {
+    pub hash: String,
+    #[serde(flatten)]
+    pub description: EntryDescription,
+    /// The whole description as one sentence.
+    pub text: String,
+}
+
+/// Describe the latest entries in words (type, size, age and first line),
+/// for screen readers and other assistive tools.
+#[tauri::command]
+pub async fn describe_entries(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DescribedEntry>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    let items = vault
+        .list(limit.or(Some(10)), None)
+        .map_err(|e| e.to_string())?;
+    warn_skipped(vault);
+
+    let now = Timestamp::now();
+    Ok(items
+        .iter()
+        .map(|entry| {
+            let description = EntryDescription::new(entry, now);
+            DescribedEntry {
+                hash: clip_vault_core::hash_to_hex(&entry.item.hash()),
+                text: description.to_string(),
+                description,
+            }
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub async fn search_clipboard(
     query: String,
@@ -69,24 +387,369 @@ pub async fn search_clipboard(
     // Default limit to 20 if not specified
     let effective_limit = limit.or(Some(20));
 
-    let items = vault
-        .search(&query, effective_limit, after_timestamp)
+    let previews = vault
+        .search_previews(&query, effective_limit, after_timestamp)
         .map_err(|e| e.to_string())?;
 
-    let results: Vec<SearchResult> = items
+    let pinned_hashes = pinned_hashes(vault)?;
+    let options = preview_options();
+    let mut results = Vec::with_capacity(previews.len());
+    for preview in previews {
+        let hash = preview.hash;
+        if let Some(result) = preview_result(vault, preview)? {
+            results.push(SearchResult {
+                pinned: pinned_hashes.contains(&hash),
+                ..result.with_preview(options)
+            });
+        }
+    }
+    warn_skipped(vault);
+
+    Ok(results)
+}
+
+/// The entry behind a search hit. Text comes straight from the preview;
+/// only images, which the list shows as thumbnails, and files are loaded
+/// from the vault.
+fn preview_result(
+    vault: &SqliteVault,
+    preview: SearchPreview,
+) -> Result<Option<SearchResult>, String> {
+    match preview.text {
+        Some(text) => Ok(Some(SearchResult::from(ListedEntry {
+            hash: preview.hash,
+            entry: ClipboardItemWithTimestamp {
+                item: ClipboardItem::Text(text),
+                timestamp: preview.timestamp,
+                context: preview.context,
+                source: preview.source,
+                id: Some(preview.id),
+            },
+            thumbnail: false,
+            image: None,
+            stats: preview.stats,
+        }))),
+        None => Ok(vault
+            .get_listed(preview.hash)
+            .map_err(|e| e.to_string())?
+            .map(SearchResult::from)),
+    }
+}
+
+/// One hour of the timeline tab.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineGroup {
+    pub hour: u32,
+    pub items: Vec<SearchResult>,
+}
+
+/// Everything copied on `day` (`YYYY-MM-DD`, local time), oldest first.
+#[tauri::command]
+pub async fn get_timeline(
+    day: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<TimelineGroup>, String> {
+    let day = day
+        .parse::<chrono::NaiveDate>()
+        .map_err(|e| format!("Invalid date {day}: {e}"))?;
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    let hours = vault.timeline(day).map_err(|e| e.to_string())?;
+    warn_skipped(vault);
+
+    hours
         .into_iter()
-        .map(|item| {
-            let (content, content_type) = item.item.clone().into_parts();
-            SearchResult {
-                id: format!("{}", item.timestamp),
-                content,
-                timestamp: item.timestamp,
-                content_type,
-            }
+        .map(|hour| {
+            Ok(TimelineGroup {
+                hour: hour.hour,
+                items: hour
+                    .entries
+                    .into_iter()
+                    .map(|entry| SearchResult::stored_in(vault, entry))
+                    .collect::<Result<_, String>>()?,
+            })
+        })
+        .collect()
+}
+
+/// An entry shown under "Related" in the preview, with why it's related.
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedResult {
+    #[serde(flatten)]
+    pub item: SearchResult,
+    pub relations: Vec<Relation>,
+}
+
+/// Entries related to the one with `id`, most related first.
+#[tauri::command]
+pub async fn get_related(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<RelatedResult>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    let related = vault
+        .related(entry_hash(vault, id)?)
+        .map_err(|e| e.to_string())?;
+    warn_skipped(vault);
+
+    related
+        .into_iter()
+        .map(|related| {
+            Ok(RelatedResult {
+                item: SearchResult::stored_in(vault, related.entry)?,
+                relations: related.relations,
+            })
+        })
+        .collect()
+}
+
+/// When the entry with `id` was captured, copied again or copied back out,
+/// newest first.
+#[tauri::command]
+pub async fn get_copy_events(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<CopyEvent>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    vault
+        .copy_events(entry_hash(vault, id)?)
+        .map_err(|e| e.to_string())
+}
+
+fn warn_skipped(vault: &SqliteVault) {
+    for diag in vault.diagnostics() {
+        warn!(
+            "Skipped undecodable clipboard entry {}: {}",
+            clip_vault_core::hash_to_hex(&diag.hash),
+            diag.error
+        );
+    }
+}
+
+/// Capture counts per hour/day, used by the activity heatmap.
+#[tauri::command]
+pub async fn activity_histogram(
+    bucket: HistogramBucket,
+    from: Option<Timestamp>,
+    to: Option<Timestamp>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ActivityBucket>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    vault
+        .activity_histogram(
+            bucket,
+            from.unwrap_or(Timestamp::EPOCH)..to.unwrap_or(Timestamp::MAX),
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    #[serde(flatten)]
+    pub info: StorageInfo,
+    /// Size that triggers a warning, from the shared config.
+    pub warning_mb: Option<u64>,
+    pub warning: Option<String>,
+}
+
+/// One entry edited into different versions on different devices.
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflictReport {
+    pub original: String,
+    /// Newest first.
+    pub versions: Vec<ConflictVersionReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictVersionReport {
+    /// The version's entry id, which `resolve_sync_conflict` takes.
+    pub id: Option<i64>,
+    pub device: String,
+    pub generation: u64,
+    pub description: String,
+}
+
+/// Conflicting edits from machines sharing the vault, for the settings
+/// window.
+#[tauri::command]
+pub async fn list_sync_conflicts(
+    state: State<'_, AppState>,
+) -> Result<Vec<SyncConflictReport>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let now = Timestamp::now();
+    Ok(vault
+        .sync_conflicts()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|conflict| SyncConflictReport {
+            original: clip_vault_core::hash_to_hex(&conflict.original),
+            versions: conflict
+                .versions
+                .iter()
+                .map(|version| ConflictVersionReport {
+                    id: version.entry.id,
+                    device: version.device.clone(),
+                    generation: version.generation,
+                    description: EntryDescription::new(&version.entry, now).to_string(),
+                })
+                .collect(),
         })
+        .collect())
+}
+
+/// Keep the version with entry `id` of a conflicting entry and delete the
+/// others. Returns how many were deleted, or an error if `id` isn't a
+/// version of a conflict.
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    id: i64,
+    password: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    authorize(vault, SensitiveAction::Delete, password)?;
+    let keep = entry_hash(vault, id)?;
+    match vault.resolve_conflict(keep).map_err(|e| e.to_string())? {
+        0 => Err("Not a version of a conflicting entry".to_string()),
+        removed => Ok(removed),
+    }
+}
+
+/// Vault size on disk and recent growth, for the settings window.
+#[tauri::command]
+pub async fn get_storage_info(state: State<'_, AppState>) -> Result<StorageReport, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let info = vault.storage_info().map_err(|e| e.to_string())?;
+    let warning_mb = Config::load()
+        .map_err(|e| e.to_string())?
+        .storage_warning_mb;
+    Ok(StorageReport {
+        info,
+        warning_mb,
+        warning: warning_mb.and_then(|mb| {
+            clip_vault_core::storage_warning(info.file_bytes, mb.saturating_mul(1024 * 1024))
+        }),
+    })
+}
+
+/// Set the size past which the vault warns; `None` turns the warning off.
+/// Kept in the shared config, so it applies to the CLI too.
+#[tauri::command]
+pub async fn set_storage_warning(mb: Option<u64>) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    config.storage_warning_mb = mb;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))
+}
+
+/// The language chosen for backend messages; `None` follows the system
+/// locale.
+#[tauri::command]
+pub async fn get_language() -> Result<Option<Language>, String> {
+    Config::load()
+        .map(|config| config.language)
+        .map_err(|e| format!("Failed to read config: {e}"))
+}
+
+/// Kept in the shared config, so the CLI follows it too. The tray tooltip
+/// and quick filters switch right away; the rest of the tray menu on the
+/// next launch.
+#[tauri::command]
+pub async fn set_language(language: Option<Language>, app: AppHandle) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    config.language = language;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))?;
+    clip_vault_core::set_language(language);
+    refresh_capture_status(&app);
+    refresh_quick_filters(&app);
+    Ok(())
+}
+
+/// How captured text is tidied before it's hashed.
+#[tauri::command]
+pub async fn get_text_normalization() -> Result<TextNormalization, String> {
+    Config::load()
+        .map(|config| config.text_normalization)
+        .map_err(|e| format!("Failed to read config: {e}"))
+}
+
+/// Kept in the shared config, so captures by the CLI follow it too. Applies
+/// from the next capture.
+#[tauri::command]
+pub async fn set_text_normalization(normalization: TextNormalization) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    config.text_normalization = normalization;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))
+}
+
+/// How long captures from each app are kept, in seconds, keyed by part of
+/// the app's name.
+#[tauri::command]
+pub async fn get_app_ttls() -> Result<BTreeMap<String, u64>, String> {
+    Config::load()
+        .map(|config| config.app_ttl_secs)
+        .map_err(|e| format!("Failed to read config: {e}"))
+}
+
+/// Kept in the shared config, so captures by the CLI follow it too. Applies
+/// from the next capture.
+#[tauri::command]
+pub async fn set_app_ttls(ttls: BTreeMap<String, u64>) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    config.app_ttl_secs = ttls
+        .into_iter()
+        .filter(|(app, secs)| !app.trim().is_empty() && *secs > 0)
+        .map(|(app, secs)| (app.trim().to_string(), secs))
         .collect();
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))
+}
 
-    Ok(results)
+/// Named searches offered as quick filters in the tray menu.
+#[tauri::command]
+pub async fn get_saved_searches() -> Result<Vec<SavedSearch>, String> {
+    Config::load()
+        .map(|config| config.saved_searches)
+        .map_err(|e| format!("Failed to read config: {e}"))
+}
+
+/// Kept in the shared config next to the other settings the CLI reads.
+#[tauri::command]
+pub async fn set_saved_searches(searches: Vec<SavedSearch>, app: AppHandle) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    config.saved_searches = searches
+        .into_iter()
+        .filter(|search| !search.name.trim().is_empty())
+        .collect();
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))?;
+    refresh_quick_filters(&app);
+    Ok(())
+}
+
+/// The query a quick filter opened the search window with, if any. Asked
+/// for as the window gains focus, in place of starting empty.
+#[tauri::command]
+pub async fn take_pending_search(
+    pending: State<'_, PendingSearch>,
+) -> Result<Option<String>, String> {
+    Ok(pending.take())
 }
 
 #[tauri::command]
@@ -95,23 +758,11 @@ pub async fn copy_to_clipboard(
     content_type: String,
     app: AppHandle,
 ) -> Result<(), String> {
-    use arboard::Clipboard;
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    if content_type == "text/plain" {
-        clipboard.set_text(content).map_err(|e| e.to_string())?;
-    } else if content_type == "image/png" {
-        let image_data = general_purpose::STANDARD
-            .decode(content)
-            .map_err(|e| e.to_string())?;
-        let image = image::load_from_memory(&image_data).map_err(|e| e.to_string())?;
-
-        let data = ImageData {
-            width: image.width() as usize,
-            height: image.height() as usize,
-            bytes: Cow::from(image.to_rgba8().into_raw()),
-        };
-        clipboard.set_image(data).map_err(|e| e.to_string())?;
-    }
+    let Some(item) = item_from_content(content, &content_type)? else {
+        return Ok(());
+    };
+    // Images go back as raw pixels, so metadata isn't re-copied.
+    write_clipboard(&app, &item)?;
 
     // Show toast notification
     show_toast_notification(app).await?;
@@ -119,6 +770,36 @@ pub async fn copy_to_clipboard(
     Ok(())
 }
 
+/// Put content on the clipboard without it entering the history, e.g. a
+/// generated password. It isn't captured by this app or by another
+/// process's monitor, and isn't read aloud.
+#[tauri::command]
+pub async fn copy_without_history(
+    content: String,
+    content_type: String,
+    app: AppHandle,
+) -> Result<(), String> {
+    let Some(item) = item_from_content(content, &content_type)? else {
+        return Ok(());
+    };
+    write_clipboard_unrecorded(&app, &item)
+}
+
+/// The item for `content` sent by the frontend: text, a base64 PNG, or
+/// file paths one per line. `None` for other content types.
+fn item_from_content(content: String, content_type: &str) -> Result<Option<ClipboardItem>, String> {
+    Ok(match content_type {
+        "text/plain" => Some(ClipboardItem::Text(content)),
+        FILES_MIME => Some(ClipboardItem::Files(files_from_text(&content))),
+        "image/png" => Some(ClipboardItem::Image(
+            general_purpose::STANDARD
+                .decode(content)
+                .map_err(|e| e.to_string())?,
+        )),
+        _ => None,
+    })
+}
+
 #[tauri::command]
 pub async fn show_toast_notification(app: AppHandle) -> Result<(), String> {
     use crate::modules::window_manager::show_toast_window;
@@ -126,92 +807,855 @@ pub async fn show_toast_notification(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// The hash of the entry with `id`. Every command that acts on one entry
+/// takes its id, the one `SearchResult::entry_id` carries.
+fn entry_hash(vault: &SqliteVault, id: i64) -> Result<[u8; 32], String> {
+    vault
+        .hash_of(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Item not found".to_string())
+}
+
 #[tauri::command]
-pub async fn delete_item(
-    content: String,
-    state: State<'_, AppState>,
+pub async fn has_html(id: i64, state: State<'_, AppState>) -> Result<bool, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    let html = vault
+        .html(entry_hash(vault, id)?)
+        .map_err(|e| e.to_string())?;
+    Ok(html.is_some())
+}
+
+/// Copy a text entry's HTML flavour converted to Markdown.
+#[tauri::command]
+pub async fn copy_as_markdown(
+    id: i64,
     app: AppHandle,
+    state: State<'_, AppState>,
 ) -> Result<(), String> {
-    // compute hash of content (text only)
-    let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
-    let hash = hasher.finalize();
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&hash);
+    let markdown = {
+        let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+        let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+        let html = vault
+            .html(entry_hash(vault, id)?)
+            .map_err(|e| e.to_string())?
+            .ok_or("No HTML version of this entry")?;
+        clip_vault_core::html_to_markdown(&html)
+    };
+
+    write_clipboard(&app, &ClipboardItem::Text(markdown))?;
+
+    show_toast_notification(app).await?;
+    Ok(())
+}
+
+/// The entry with hex `hash` as the results list shows it, so one new or
+/// edited entry can be patched in without reloading the list. `None` if
+/// there's no such entry. This is the one command addressed by hash, since
+/// the `item-*` events it follows carry the hash rather than an id.
+#[tauri::command]
+pub async fn get_item(
+    hash: String,
+    state: State<'_, AppState>,
+) -> Result<Option<SearchResult>, String> {
+    let hash = clip_vault_core::hash_from_hex(&hash).ok_or("Invalid item hash")?;
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let listed = vault.get_listed(hash).map_err(|e| e.to_string())?;
+    listed.map(|listed| list_result(vault, listed)).transpose()
+}
 
+/// Like [`get_item`], by the entry's id.
+#[tauri::command]
+pub async fn get_item_by_id(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<SearchResult>, String> {
     let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
     let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let listed = vault.get_listed_by_id(id).map_err(|e| e.to_string())?;
+    listed.map(|listed| list_result(vault, listed)).transpose()
+}
 
-    vault.delete(arr).map_err(|e| e.to_string())?;
+/// `listed` with its preview and pin, as `list_clipboard` returns it.
+fn list_result(vault: &SqliteVault, listed: ListedEntry) -> Result<SearchResult, String> {
+    let pinned = pinned_hashes(vault)?.contains(&listed.hash);
+    Ok(SearchResult {
+        pinned,
+        ..SearchResult::from(listed).with_preview(preview_options())
+    })
+}
 
-    // Emit event to refresh search results
-    app.emit("clipboard-updated", ()).ok();
+/// The full content of the entry with `id`, for a result listed by its
+/// thumbnail: base64 for images. `None` if there's no such entry.
+#[tauri::command]
+pub async fn get_item_content(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let Some(hash) = vault.hash_of(id).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let entry = vault.get(hash).map_err(|e| e.to_string())?;
+    Ok(entry.map(|entry| entry.item.into_parts().0))
+}
+
+/// Copy the text entry with `id` reshaped into `format`, e.g. as
+/// pretty-printed JSON or a fenced code block.
+#[tauri::command]
+pub async fn copy_as(
+    id: i64,
+    format: CopyFormat,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let text = {
+        let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+        let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+        let hash = entry_hash(vault, id)?;
+        let entry = vault
+            .get(hash)
+            .map_err(|e| e.to_string())?
+            .ok_or("Item not found")?;
+        let ClipboardItem::Text(text) = entry.item else {
+            return Err("Only text entries can be copied as another format".to_string());
+        };
+        let text = clip_vault_core::copy_as(&text, format).map_err(|e| e.to_string())?;
+        vault.record_use(hash).map_err(|e| e.to_string())?;
+        text
+    };
+
+    write_clipboard(&app, &ClipboardItem::Text(text))?;
+
+    show_toast_notification(app).await?;
+    Ok(())
+}
+
+/// Move an entry into the protected area.
+#[tauri::command]
+pub async fn protect_item(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    if vault
+        .set_protected(entry_hash(vault, id)?, true)
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        return Err("Item not found".to_string());
+    }
+    Ok(())
+}
+
+/// Protected entries; `password` must be re-entered even while unlocked.
+#[tauri::command]
+pub async fn list_protected(
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    let items = vault
+        .protected_items(&password)
+        .map_err(|e| e.to_string())?;
+    items
+        .into_iter()
+        .map(|entry| SearchResult::stored_in(vault, entry))
+        .collect()
+}
+
+/// Open a backup snapshot read-only to browse next to the vault. Returns
+/// how many entries it holds. Captures keep going to the vault, and the
+/// snapshot is closed again when the vault locks.
+#[tauri::command]
+pub async fn open_backup(
+    path: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    if state
+        .vault
+        .lock()
+        .map_err(|_| "Vault lock poisoned")?
+        .is_none()
+    {
+        return Err("Vault not unlocked".to_string());
+    }
+    let snapshot = SqliteVault::open_read_only(&path, &password)
+        .map_err(|e| format!("Failed to open {path}: {e}"))?;
+    let entries = snapshot.len().map_err(|e| e.to_string())?;
+    *state.backup.lock().map_err(|_| "Backup lock poisoned")? = Some(snapshot);
+    info!("Browsing backup {path}");
+    Ok(entries)
+}
+
+/// Entries of the backup opened with `open_backup`, newest first; all of
+/// them without a query.
+#[tauri::command]
+pub async fn browse_backup(
+    query: Option<String>,
+    limit: Option<usize>,
+    after_timestamp: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchResult>, String> {
+    let backup_guard = state.backup.lock().map_err(|_| "Backup lock poisoned")?;
+    let backup = backup_guard.as_ref().ok_or("No backup open")?;
+
+    let limit = limit.or(Some(20));
+    let items = match query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => backup.search(query, limit, after_timestamp),
+        _ => backup.list(limit, after_timestamp),
+    }
+    .map_err(|e| e.to_string())?;
+    let options = preview_options();
+    items
+        .into_iter()
+        .map(|entry| Ok(SearchResult::foreign(backup, entry)?.with_preview(options)))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn close_backup(state: State<'_, AppState>) -> Result<(), String> {
+    state
+        .backup
+        .lock()
+        .map_err(|_| "Backup lock poisoned")?
+        .take();
+    Ok(())
+}
+
+/// A configured vault profile and whether it's unlocked for searching.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileStatus {
+    pub name: String,
+    pub path: String,
+    pub unlocked: bool,
+}
+
+/// Result of `search_all_profiles`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSearch {
+    /// Newest first, each with its profile.
+    pub results: Vec<SearchResult>,
+    /// Profiles left out because they couldn't be unlocked; `unlock_profile`
+    /// takes their password.
+    pub locked: Vec<String>,
+}
+
+/// The vault profiles in the shared config, besides the default vault.
+#[tauri::command]
+pub async fn list_profiles(state: State<'_, AppState>) -> Result<Vec<ProfileStatus>, String> {
+    let profiles = state
+        .profiles
+        .lock()
+        .map_err(|_| "Profiles lock poisoned")?;
+    Ok(Config::load()
+        .map_err(|e| e.to_string())?
+        .profiles
+        .into_iter()
+        .map(|profile| ProfileStatus {
+            unlocked: profiles.iter().any(|(name, _)| *name == profile.name),
+            path: profile.path.display().to_string(),
+            name: profile.name,
+        })
+        .collect())
+}
+
+/// Open the vault of profile `name` read-only for searching across
+/// profiles. It is closed again when the vault locks.
+#[tauri::command]
+pub async fn unlock_profile(
+    name: String,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let profile = Config::load()
+        .map_err(|e| e.to_string())?
+        .profiles
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("No profile named {name}"))?;
+    let vault = SqliteVault::open_read_only(&profile.path, &password)
+        .map_err(|e| format!("Failed to unlock {name}: {e}"))?;
+    let mut profiles = state
+        .profiles
+        .lock()
+        .map_err(|_| "Profiles lock poisoned")?;
+    profiles.retain(|(open, _)| *open != name);
+    profiles.push((name, vault));
+    Ok(())
+}
+
+/// Search the vault and every unlocked profile, results newest first with
+/// a profile badge. Profiles with a password in the keyring are unlocked
+/// on the way; the others are reported as locked.
+#[tauri::command]
+pub async fn search_all_profiles(
+    query: String,
+    limit: Option<usize>,
+    after_timestamp: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<ProfileSearch, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let mut profiles = state
+        .profiles
+        .lock()
+        .map_err(|_| "Profiles lock poisoned")?;
+
+    let mut locked = Vec::new();
+    for profile in Config::load().map_err(|e| e.to_string())?.profiles {
+        if profiles.iter().any(|(name, _)| *name == profile.name) {
+            continue;
+        }
+        let opened = clip_vault_core::keyring_password(&profile.path)
+            .ok()
+            .flatten()
+            .and_then(|password| SqliteVault::open_read_only(&profile.path, &password).ok());
+        match opened {
+            Some(opened) => profiles.push((profile.name, opened)),
+            None => locked.push(profile.name),
+        }
+    }
+
+    let mut vaults = MultiVault::new();
+    vaults.add(DEFAULT_PROFILE, vault);
+    for (name, opened) in profiles.iter() {
+        vaults.add(name.clone(), opened);
+    }
+    let options = preview_options();
+    let results = vaults
+        .search(&query, limit.or(Some(20)), after_timestamp)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|found| {
+            let source = vaults.get(&found.profile).ok_or("Profile closed")?;
+            let result = if found.profile == DEFAULT_PROFILE {
+                SearchResult::stored_in(source, found.entry)?
+            } else {
+                SearchResult::foreign(source, found.entry)?
+            };
+            Ok(SearchResult {
+                profile: Some(found.profile),
+                ..result.with_preview(options)
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(ProfileSearch { results, locked })
+}
+
+/// Move a protected entry back into the history.
+#[tauri::command]
+pub async fn unprotect_item(
+    id: i64,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+
+    if !vault.verify_key(&password).map_err(|e| e.to_string())? {
+        return Err(clip_vault_core::Error::InvalidKey.to_string());
+    }
+    vault
+        .set_protected(entry_hash(vault, id)?, false)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// What re-copying a stored entry does. Kept in the vault rather than the
+/// app settings, so it matches the CLI.
+#[tauri::command]
+pub async fn get_duplicate_policy(state: State<'_, AppState>) -> Result<DuplicatePolicy, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    vault.duplicate_policy().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_duplicate_policy(
+    policy: DuplicatePolicy,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    vault
+        .set_duplicate_policy(policy)
+        .map_err(|e| e.to_string())
+}
+
+/// Add `tag` to an entry, or remove it with `remove`. Returns the entry's
+/// tags afterwards.
+#[tauri::command]
+pub async fn tag_item(
+    id: i64,
+    tag: String,
+    remove: bool,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag is empty".to_string());
+    }
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let hash = entry_hash(vault, id)?;
+
+    if remove {
+        vault.remove_tag(hash, tag).map_err(|e| e.to_string())?;
+    } else {
+        vault.add_tag(hash, tag).map_err(|e| e.to_string())?;
+    }
+    vault.tags(hash).map_err(|e| e.to_string())
+}
+
+/// Tags of an entry, alphabetically.
+#[tauri::command]
+pub async fn get_tags(id: i64, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let hash = entry_hash(vault, id)?;
+    vault.tags(hash).map_err(|e| e.to_string())
+}
+
+/// The note kept with an entry, if any.
+#[tauri::command]
+pub async fn get_item_note(id: i64, state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let hash = entry_hash(vault, id)?;
+    vault.note(hash).map_err(|e| e.to_string())
+}
+
+/// Keep `note` with an entry; `None` or a blank note removes it. Returns
+/// the note as stored.
+#[tauri::command]
+pub async fn set_item_note(
+    id: i64,
+    note: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let hash = entry_hash(vault, id)?;
+    if vault
+        .set_note(hash, note.as_deref())
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        return Err("Item not found".to_string());
+    }
+    vault.note(hash).map_err(|e| e.to_string())
+}
+
+/// Every tag in use, for the tag editor's suggestions.
+#[tauri::command]
+pub async fn list_tags(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    vault.all_tags().map_err(|e| e.to_string())
+}
+
+/// Everything the search window can do, with its shortcut.
+#[tauri::command]
+pub async fn list_actions() -> Result<Vec<Action>, String> {
+    Ok(actions::ACTIONS.to_vec())
+}
+
+/// Actions for the command palette, fuzzy-matched against `query`.
+#[tauri::command]
+pub async fn search_actions(query: String) -> Result<Vec<Action>, String> {
+    Ok(actions::search(&query))
+}
+
+/// Let `action` go ahead if the unlock policy allows it, checking
+/// `password` when the policy wants it re-entered.
+fn authorize(
+    vault: &SqliteVault,
+    action: SensitiveAction,
+    password: Option<String>,
+) -> Result<(), String> {
+    let policy = Config::load().map_err(|e| e.to_string())?.unlock;
+    policy
+        .authorize(action, vault, |_| password)
+        .map_err(|e| match e {
+            clip_vault_core::Error::InvalidKey => format!("Password required to {action}"),
+            e => e.to_string(),
+        })
+}
+
+/// Result of `preview_retention`: what the retention rules would delete.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionPreview {
+    pub count: usize,
+    pub bytes: usize,
+    /// e.g. "1.2 MB".
+    pub size_label: String,
+    pub oldest: Option<Timestamp>,
+    pub newest: Option<Timestamp>,
+    /// Oldest first.
+    pub entries: Vec<AffectedResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AffectedResult {
+    pub hash: String,
+    pub timestamp: Timestamp,
+    pub time_label: String,
+    pub bytes: usize,
+    pub description: String,
+}
+
+/// The entries `retention` would delete now, picked the same way the
+/// monitor's pruning picks them, so the settings window can show them
+/// before they are saved.
+#[tauri::command]
+pub async fn preview_retention(
+    retention: RetentionPolicy,
+    state: State<'_, AppState>,
+) -> Result<RetentionPreview, String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    let dry_run = vault.preview_prune(&retention).map_err(|e| e.to_string())?;
+
+    Ok(RetentionPreview {
+        count: dry_run.count(),
+        bytes: dry_run.bytes(),
+        size_label: clip_vault_core::format_size(dry_run.bytes()),
+        oldest: dry_run.oldest(),
+        newest: dry_run.newest(),
+        entries: dry_run
+            .entries
+            .into_iter()
+            .map(|entry| AffectedResult {
+                hash: clip_vault_core::hash_to_hex(&entry.hash),
+                timestamp: entry.timestamp,
+                time_label: entry.timestamp.absolute(),
+                bytes: entry.bytes,
+                description: entry.description,
+            })
+            .collect(),
+    })
+}
+
+/// Delete an entry. `password` is only needed when the unlock policy asks
+/// for it before deletions.
+#[tauri::command]
+pub async fn delete_item(
+    id: i64,
+    password: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    authorize(vault, SensitiveAction::Delete, password)?;
+
+    if vault.delete_by_id(id).map_err(|e| e.to_string())? == 0 {
+        return Err("Item not found".to_string());
+    }
+
+    info!("Item deleted successfully");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+pub async fn save_settings(
+    new_settings: AppSettings,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let updates = new_settings.update_preferences();
+    apply_settings(new_settings, &state, &app)?;
+    // TODO: Persist the rest of the settings; so far only the update
+    // choices are kept across restarts.
+    updates.save()
+}
+
+/// Write the shared config plus the app settings to `path`, so another
+/// machine can be set up the same way with [`import_settings`].
+#[tauri::command]
+pub async fn export_settings(path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?
+        .clone();
+    config.app = Some(serde_json::to_value(settings).map_err(|e| e.to_string())?);
+    config
+        .save_to(Path::new(&path))
+        .map_err(|e| format!("Failed to export settings: {e}"))?;
+    info!("Settings exported to {path}");
+    Ok(())
+}
+
+/// Apply settings written by [`export_settings`] (or `clip-vault settings
+/// export`) and store their backup targets in the shared config.
+#[tauri::command]
+pub async fn import_settings(
+    path: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<AppSettings, String> {
+    let config =
+        Config::load_from(Path::new(&path)).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let imported = match &config.app {
+        Some(value) => Some(
+            serde_json::from_value::<AppSettings>(value.clone())
+                .map_err(|e| format!("Invalid app settings in {path}: {e}"))?,
+        ),
+        None => None,
+    };
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))?;
+    if let Some(settings) = imported {
+        apply_settings(settings, &state, &app)?;
+    }
+    info!("Settings imported from {path}");
+
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?;
+    Ok(settings.clone())
+}
+
+fn apply_settings(
+    new_settings: AppSettings,
+    state: &State<'_, AppState>,
+    app: &AppHandle,
+) -> Result<(), String> {
+    // Check if global shortcut changed
+    let old_shortcut = {
+        let settings = state
+            .settings
+            .lock()
+            .map_err(|_| "Settings lock poisoned")?;
+        settings.global_shortcut.clone()
+    };
+
+    let mut settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?;
+
+    if old_shortcut != new_settings.global_shortcut {
+        // Update the global shortcut
+        let gs = app.global_shortcut();
+
+        // Unregister old shortcut
+        if let Err(e) = gs.unregister(
+            old_shortcut
+                .parse::<Shortcut>()
+                .map_err(|e| format!("Invalid old shortcut: {e}"))?,
+        ) {
+            eprintln!("Failed to unregister old shortcut: {e}");
+        }
+
+        // Register new shortcut
+        let app_handle = app.app_handle().clone();
+        let new_shortcut: Shortcut = new_settings
+            .global_shortcut
+            .parse()
+            .map_err(|e| format!("Invalid shortcut: {e}"))?;
+        gs.on_shortcut(new_shortcut, move |_, _, _| {
+            crate::modules::window_manager::show_search_window(&app_handle);
+        })
+        .map_err(|e| format!("Failed to register new shortcut: {e}"))?;
+    }
+
+    let behavior = new_settings.search_window;
+    logging::set_level(new_settings.log_level);
+    if let Some(vault) = state
+        .vault
+        .lock()
+        .map_err(|_| "Vault lock poisoned")?
+        .as_ref()
+    {
+        vault
+            .set_strip_image_metadata(new_settings.strip_image_metadata)
+            .map_err(|e| format!("Failed to save the image metadata setting: {e}"))?;
+    }
+    *settings = new_settings;
+    drop(settings);
+    apply_search_window_behavior(app, behavior);
+    Ok(())
+}
+
+/// The last `lines` lines of the app log at `level` (default: the
+/// configured level) or more severe, oldest first.
+#[tauri::command]
+pub async fn get_recent_logs(
+    lines: usize,
+    level: Option<LogLevel>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let level = match level {
+        Some(level) => level,
+        None => {
+            state
+                .settings
+                .lock()
+                .map_err(|_| "Settings lock poisoned")?
+                .log_level
+        }
+    };
+    logging::recent_logs(lines, level).map_err(|e| e.to_string())
+}
+
+/// Change how the search window sits among other windows, applying it to
+/// the open window straight away.
+#[tauri::command]
+pub async fn set_window_behavior(
+    behavior: WindowBehavior,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?
+        .search_window = behavior;
+    apply_search_window_behavior(&app, behavior);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn vault_exists(state: State<'_, AppState>) -> Result<bool, String> {
+    let vault_path = {
+        let settings = state
+            .settings
+            .lock()
+            .map_err(|_| "Settings lock poisoned")?;
+        PathBuf::from(&settings.vault_path)
+    };
+
+    Ok(vault_path.exists())
+}
+
+#[tauri::command]
+pub async fn onboarding_status(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<OnboardingStatus, String> {
+    let vault_path = {
+        let settings = state
+            .settings
+            .lock()
+            .map_err(|_| "Settings lock poisoned")?;
+        PathBuf::from(&settings.vault_path)
+    };
+
+    Ok(OnboardingStatus {
+        vault_path: vault_path.to_string_lossy().to_string(),
+        vault_exists: vault_path.exists(),
+        existing_vaults: onboarding::find_existing_vaults(&vault_path),
+        permissions: onboarding::check_permissions(&app),
+        launch_at_login: app.autolaunch().is_enabled().unwrap_or(false),
+    })
+}
 
-    info!("Item deleted successfully");
-    Ok(())
+/// Preflight checks for clipboard access, Accessibility (paste) and
+/// notifications, with the System Settings page to fix each where there is one.
+#[tauri::command]
+pub async fn check_permissions(app: AppHandle) -> Result<Vec<PermissionCheck>, String> {
+    Ok(onboarding::check_permissions(&app))
 }
 
+/// Open the System Settings page where `permission` is granted.
 #[tauri::command]
-pub async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
-    let settings = state
-        .settings
-        .lock()
-        .map_err(|_| "Settings lock poisoned")?;
-    Ok(settings.clone())
+pub async fn open_permission_settings(permission: Permission) -> Result<(), String> {
+    let url = permission
+        .settings_url()
+        .ok_or_else(|| format!("No settings page for {permission}"))?;
+    std::process::Command::new("open")
+        .arg(url)
+        .spawn()
+        .map_err(|e| format!("Failed to open System Settings: {e}"))?;
+    Ok(())
 }
 
+/// Switch to a vault that already exists (e.g. one the CLI created) and
+/// unlock it. The configured path is left alone if `password` doesn't open it.
 #[tauri::command]
-pub async fn save_settings(
-    new_settings: AppSettings,
+pub async fn adopt_vault(
+    path: String,
+    password: String,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<(), String> {
-    // Check if global shortcut changed
-    let old_shortcut = {
-        let settings = state
+) -> Result<bool, String> {
+    if !PathBuf::from(&path).is_file() {
+        return Err(format!("No vault at {path}"));
+    }
+    let previous = {
+        let mut settings = state
             .settings
             .lock()
             .map_err(|_| "Settings lock poisoned")?;
-        settings.global_shortcut.clone()
+        std::mem::replace(&mut settings.vault_path, path)
     };
 
-    let mut settings = state
-        .settings
-        .lock()
-        .map_err(|_| "Settings lock poisoned")?;
-
-    if old_shortcut != new_settings.global_shortcut {
-        // Update the global shortcut
-        let gs = app.global_shortcut();
-
-        // Unregister old shortcut
-        if let Err(e) = gs.unregister(
-            old_shortcut
-                .parse::<Shortcut>()
-                .map_err(|e| format!("Invalid old shortcut: {e}"))?,
-        ) {
-            eprintln!("Failed to unregister old shortcut: {e}");
-        }
+    let unlocked = unlock_vault(password, state.clone(), app).await?;
+    if !unlocked {
+        let mut settings = state
+            .settings
+            .lock()
+            .map_err(|_| "Settings lock poisoned")?;
+        settings.vault_path = previous;
+    }
+    Ok(unlocked)
+}
 
-        // Register new shortcut
-        let app_handle = app.app_handle().clone();
-        let new_shortcut: Shortcut = new_settings
-            .global_shortcut
-            .parse()
-            .map_err(|e| format!("Invalid shortcut: {e}"))?;
-        gs.on_shortcut(new_shortcut, move |_, _, _| {
-            crate::modules::window_manager::show_search_window(&app_handle);
-        })
-        .map_err(|e| format!("Failed to register new shortcut: {e}"))?;
+/// Start capturing now and, if asked, every time the user logs in.
+#[tauri::command]
+pub async fn register_daemon(
+    launch_at_login: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if launch_at_login {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
     }
+    .map_err(|e| format!("Failed to update launch at login: {e}"))?;
 
-    *settings = new_settings;
-    // TODO: Persist settings to file or config
+    let unlocked = state
+        .vault
+        .lock()
+        .map_err(|_| "Vault lock poisoned")?
+        .is_some();
+    if unlocked {
+        let poll_interval = {
+            let settings = state
+                .settings
+                .lock()
+                .map_err(|_| "Settings lock poisoned")?;
+            settings.poll_interval_ms
+        };
+        start_clipboard_monitoring(&state.vault, &state.daemon, poll_interval, app)?;
+    }
     Ok(())
 }
 
+/// The unencrypted hint shown on the unlock prompt, if one was set.
 #[tauri::command]
-pub async fn vault_exists(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn password_hint(state: State<'_, AppState>) -> Result<Option<String>, String> {
     let vault_path = {
         let settings = state
             .settings
@@ -220,16 +1664,19 @@ pub async fn vault_exists(state: State<'_, AppState>) -> Result<bool, String> {
         PathBuf::from(&settings.vault_path)
     };
 
-    Ok(vault_path.exists())
+    clip_vault_core::password_hint(&vault_path).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn create_vault(
     password: String,
     settings: AppSettings,
+    password_hint: Option<String>,
+    recovery_key: bool,
+    security_profile: Option<SecurityProfile>,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<bool, String> {
+) -> Result<CreatedVault, String> {
     // Check if global shortcut changed
     let old_shortcut = {
         let app_settings = state
@@ -283,8 +1730,32 @@ pub async fn create_vault(
         PathBuf::from(&settings.vault_path)
     };
 
-    match SqliteVault::open(&vault_path, &password) {
+    // Cipher settings can only be picked for a vault that isn't there yet.
+    let opened = match security_profile {
+        Some(_) if vault_path.exists() => {
+            return Err("The vault already exists; its security profile can't be changed".into());
+        }
+        Some(profile) => {
+            SqliteVault::create_with_options(&vault_path, &password, &profile.options())
+        }
+        None => SqliteVault::open(&vault_path, &password),
+    };
+    match opened {
         Ok(new_vault) => {
+            clip_vault_core::set_password_hint(&vault_path, password_hint.as_deref())
+                .map_err(|e| format!("Failed to save password hint: {e}"))?;
+            let recovery_key = if recovery_key {
+                Some(
+                    new_vault
+                        .create_recovery_key(&password)
+                        .map_err(|e| format!("Failed to create recovery key: {e}"))?,
+                )
+            } else {
+                None
+            };
+
+            new_vault.set_dedupe_rules(Config::load().unwrap_or_default().dedupe);
+            forward_vault_events(&new_vault, app.clone());
             let mut vault = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
             *vault = Some(new_vault);
 
@@ -306,13 +1777,134 @@ pub async fn create_vault(
 
             start_clipboard_monitoring(&state.vault, &state.daemon, poll_interval, app)?;
 
-            Ok(true)
+            Ok(CreatedVault {
+                created: true,
+                recovery_key,
+            })
         }
         Err(e) => {
             eprintln!("Failed to create vault: {e}");
-            Ok(false)
+            Ok(CreatedVault {
+                created: false,
+                recovery_key: None,
+            })
+        }
+    }
+}
+
+/// How the vault unlocks. Kept in the shared config rather than the app
+/// settings, so it applies to the CLI too.
+#[tauri::command]
+pub async fn get_unlock_policy() -> Result<UnlockPolicy, String> {
+    Ok(Config::load().map_err(|e| e.to_string())?.unlock)
+}
+
+/// Switch unlock policies. Needs the vault unlocked and `password`, both to
+/// save it in the keyring and so a weaker policy can't be chosen without it.
+#[tauri::command]
+pub async fn set_unlock_policy(
+    policy: UnlockPolicy,
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let vault_path = vault_path(&state)?;
+    {
+        let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+        let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+        if !vault.verify_key(&password).map_err(|e| e.to_string())? {
+            return Err("Invalid password".to_string());
+        }
+    }
+    if policy.uses_keyring() {
+        clip_vault_core::save_keyring_password(&vault_path, &password)
+    } else {
+        clip_vault_core::forget_keyring_password(&vault_path).map(|_| ())
+    }
+    .map_err(|e| e.to_string())?;
+
+    let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+    config.unlock = policy;
+    config
+        .save()
+        .map_err(|e| format!("Failed to save config: {e}"))?;
+    info!("Unlock policy set to {policy:?}");
+    Ok(())
+}
+
+/// Undo what the app set up: launch at login, capturing, the cached
+/// session and the keyring entry, as `clip-vault uninstall` does. Shredding
+/// the vault too needs it unlocked and `password`. The frontend quits the
+/// app afterwards.
+#[tauri::command]
+pub async fn uninstall_app(
+    shred_vault: bool,
+    password: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<UninstallReport, String> {
+    let vault_path = vault_path(&state)?;
+    if shred_vault {
+        let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+        let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+        let password = password.ok_or("Password required to shred the vault")?;
+        if !vault.verify_key(&password).map_err(|e| e.to_string())? {
+            return Err("Invalid password".to_string());
         }
     }
+
+    if let Err(e) = app.autolaunch().disable() {
+        warn!("Failed to turn off launch at login: {e}");
+    }
+    stop_clipboard_monitoring(&state.daemon)?;
+    // Close the vault so its files can be shredded.
+    state.lock_now()?;
+    refresh_capture_status(&app);
+
+    let report = clip_vault_core::uninstall(&[vault_path], shred_vault);
+    for error in &report.errors {
+        warn!("Uninstall: {error}");
+    }
+    info!(
+        "Uninstalled: {} startup entries removed, vault {}",
+        report.startup_entries.len(),
+        if shred_vault { "shredded" } else { "kept" }
+    );
+    Ok(report)
+}
+
+fn vault_path(state: &AppState) -> Result<PathBuf, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?;
+    Ok(PathBuf::from(&settings.vault_path))
+}
+
+/// Unlock with the password saved in the keyring, if the unlock policy uses
+/// it. Run at startup, so the search window opens unlocked.
+pub fn unlock_from_keyring(app: &AppHandle) {
+    let policy = Config::load().map(|c| c.unlock).unwrap_or_default();
+    if !policy.uses_keyring() {
+        return;
+    }
+    let state = app.state::<AppState>();
+    let saved = vault_path(&state).and_then(|path| {
+        clip_vault_core::keyring_password(&path)
+            .map_err(|e| format!("Failed to read the keyring: {e}"))
+    });
+    let password = match saved {
+        Ok(Some(password)) => password,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("{e}");
+            return;
+        }
+    };
+    match open_vault(&password, &state, app) {
+        Ok(true) => info!("Vault unlocked from the keyring"),
+        Ok(false) => warn!("The password in the keyring doesn't open the vault"),
+        Err(e) => warn!("Failed to unlock from the keyring: {e}"),
+    }
 }
 
 #[tauri::command]
@@ -321,16 +1913,34 @@ pub async fn unlock_vault(
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<bool, String> {
-    let vault_path = {
-        let settings = state
-            .settings
-            .lock()
-            .map_err(|_| "Settings lock poisoned")?;
-        PathBuf::from(&settings.vault_path)
-    };
+    let unlocked = open_vault(&password, &state, &app)?;
+    // Keep the keyring in step with the password, e.g. after it changed.
+    let policy = Config::load().map(|c| c.unlock).unwrap_or_default();
+    if unlocked && policy.uses_keyring() {
+        if let Err(e) = clip_vault_core::save_keyring_password(&vault_path(&state)?, &password) {
+            warn!("Failed to save the password in the keyring: {e}");
+        }
+    }
+    Ok(unlocked)
+}
 
-    match SqliteVault::open(&vault_path, &password) {
+/// Open the vault with `password`, start a session and start capturing.
+/// `Ok(false)` if the password is wrong.
+fn open_vault(password: &str, state: &AppState, app: &AppHandle) -> Result<bool, String> {
+    let vault_path = vault_path(state)?;
+
+    match SqliteVault::open(&vault_path, password) {
         Ok(new_vault) => {
+            let strip = state
+                .settings
+                .lock()
+                .map_err(|_| "Settings lock poisoned")?
+                .strip_image_metadata;
+            if let Err(e) = new_vault.set_strip_image_metadata(strip) {
+                warn!("Failed to apply the image metadata setting: {e}");
+            }
+            new_vault.set_dedupe_rules(Config::load().unwrap_or_default().dedupe);
+            forward_vault_events(&new_vault, app.clone());
             let mut vault = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
             *vault = Some(new_vault);
 
@@ -350,10 +1960,12 @@ pub async fn unlock_vault(
                 settings.poll_interval_ms
             };
 
-            start_clipboard_monitoring(&state.vault, &state.daemon, poll_interval, app)?;
+            start_clipboard_monitoring(&state.vault, &state.daemon, poll_interval, app.clone())?;
 
             Ok(true)
         }
+        // Not a wrong password, so say why instead of asking again.
+        Err(e @ clip_vault_core::Error::IncompatibleSchema { .. }) => Err(e.to_string()),
         Err(e) => {
             eprintln!("Failed to unlock vault: {e}");
             Ok(false)
@@ -361,40 +1973,138 @@ pub async fn unlock_vault(
     }
 }
 
+/// Whether the vault is unlocked. The window asks on focus, so this counts
+/// as activity too.
 #[tauri::command]
-pub async fn check_vault_status(state: State<'_, AppState>) -> Result<bool, String> {
-    // Check if vault is unlocked and session is valid
-    let auto_lock_minutes = {
-        let settings = state
-            .settings
-            .lock()
-            .map_err(|_| "Settings lock poisoned")?;
-        settings.auto_lock_minutes
-    };
+pub async fn check_vault_status(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<bool, String> {
+    activity_ping(state, app).await
+}
 
-    let mut session_guard = state.session.lock().map_err(|_| "Session lock poisoned")?;
+/// Note user activity, such as a search or copy, so auto-lock counts idle
+/// time from now. Returns whether the vault is still unlocked; if the
+/// session had already expired it is locked now instead of extended.
+#[tauri::command]
+pub async fn activity_ping(state: State<'_, AppState>, app: AppHandle) -> Result<bool, String> {
+    if state.lock_if_expired()? {
+        session::notify_locked(&app);
+        return Ok(false);
+    }
+    state.touch_session()
+}
 
-    if let Some(session) = session_guard.as_ref() {
-        if is_session_expired(session, auto_lock_minutes) {
-            // Session expired, clear vault and session
-            *session_guard = None;
-            drop(session_guard);
+/// Hide the search window, e.g. on Esc.
+#[tauri::command]
+pub async fn dismiss_search(app: AppHandle) -> Result<(), String> {
+    dismiss_search_window(&app);
+    Ok(())
+}
 
-            let mut vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
-            *vault_guard = None;
-            drop(vault_guard);
+/// Tell the backend which entries the search window lists, in order, so
+/// `select_next`, `select_previous` and `activate_selected` move through
+/// the same list. The selection stays on its entry if it is still listed.
+#[tauri::command]
+pub async fn set_search_results(
+    hashes: Vec<String>,
+    navigation: State<'_, SearchNavigation>,
+) -> Result<Option<SelectionChanged>, String> {
+    let entries = hashes
+        .iter()
+        .map(|hex| clip_vault_core::hash_from_hex(hex).ok_or(format!("Invalid hash {hex}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut navigation = navigation
+        .0
+        .lock()
+        .map_err(|_| "Navigation lock poisoned")?;
+    navigation.set_entries(entries);
+    Ok(navigation.selected().map(SelectionChanged::from))
+}
 
-            Ok(false) // Vault is locked due to expired session
-        } else {
-            // Update last activity
-            if let Some(session) = session_guard.as_mut() {
-                session.last_activity = current_timestamp();
-            }
-            Ok(true) // Vault is unlocked and session is valid
-        }
-    } else {
-        Ok(false) // No active session
-    }
+/// Select the entry at `index`, e.g. after a click in the search window.
+#[tauri::command]
+pub async fn select_index(
+    index: usize,
+    navigation: State<'_, SearchNavigation>,
+) -> Result<Option<SelectionChanged>, String> {
+    let mut navigation = navigation
+        .0
+        .lock()
+        .map_err(|_| "Navigation lock poisoned")?;
+    Ok(navigation.select(index).map(SelectionChanged::from))
+}
+
+/// Move the selection one entry down; the search window follows through
+/// `search-selection-changed`.
+#[tauri::command]
+pub async fn select_next(
+    navigation: State<'_, SearchNavigation>,
+    app: AppHandle,
+) -> Result<Option<SelectionChanged>, String> {
+    let selection = navigation
+        .0
+        .lock()
+        .map_err(|_| "Navigation lock poisoned")?
+        .select_next();
+    announce_selection(&app, selection);
+    Ok(selection.map(SelectionChanged::from))
+}
+
+/// Move the selection one entry up.
+#[tauri::command]
+pub async fn select_previous(
+    navigation: State<'_, SearchNavigation>,
+    app: AppHandle,
+) -> Result<Option<SelectionChanged>, String> {
+    let selection = navigation
+        .0
+        .lock()
+        .map_err(|_| "Navigation lock poisoned")?
+        .select_previous();
+    announce_selection(&app, selection);
+    Ok(selection.map(SelectionChanged::from))
+}
+
+#[tauri::command]
+pub async fn get_selection(
+    navigation: State<'_, SearchNavigation>,
+) -> Result<Option<SelectionChanged>, String> {
+    let navigation = navigation
+        .0
+        .lock()
+        .map_err(|_| "Navigation lock poisoned")?;
+    Ok(navigation.selected().map(SelectionChanged::from))
+}
+
+/// Copy the selected entry and hide the search window, as Enter does.
+/// Returns whether there was an entry to copy.
+#[tauri::command]
+pub async fn activate_selected(
+    navigation: State<'_, SearchNavigation>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<bool, String> {
+    let Some(selection) = navigation
+        .0
+        .lock()
+        .map_err(|_| "Navigation lock poisoned")?
+        .selected()
+    else {
+        return Ok(false);
+    };
+    let entry = {
+        let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+        let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+        vault.get(selection.hash).map_err(|e| e.to_string())?
+    };
+    let Some(entry) = entry else {
+        return Err("The selected entry is no longer in the vault".to_string());
+    };
+    write_clipboard(&app, &entry.item)?;
+    show_toast_notification(app.clone()).await?;
+    dismiss_search_window(&app);
+    Ok(true)
 }
 
 #[tauri::command]
@@ -428,108 +2138,257 @@ pub async fn stop_daemon(state: State<'_, AppState>) -> Result<(), String> {
     stop_clipboard_monitoring(&state.daemon)
 }
 
+/// What the tray icon shows: capturing, incognito, paused, locked or error.
+#[tauri::command]
+pub async fn get_capture_status(app: AppHandle) -> Result<CaptureStatus, String> {
+    Ok(capture_status(&app))
+}
+
+/// Result of `daemon_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DaemonStatus {
+    pub running: bool,
+    pub last_error: Option<String>,
+    pub trace_capture: bool,
+    /// Latest capture decisions, oldest first, for diagnosing copies that
+    /// weren't captured.
+    pub decisions: Vec<CaptureDecision>,
+    /// Latest vault health notices, oldest first.
+    pub health: Vec<HealthNotice>,
+}
+
 #[tauri::command]
-pub async fn daemon_status(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn daemon_status(state: State<'_, AppState>) -> Result<DaemonStatus, String> {
     let daemon_guard = state.daemon.lock().map_err(|_| "Daemon lock poisoned")?;
-    Ok(daemon_guard.is_running)
+    Ok(DaemonStatus {
+        running: daemon_guard.is_running,
+        last_error: daemon_guard.last_error.clone(),
+        trace_capture: daemon_guard.trace_capture,
+        decisions: daemon_guard.trace.decisions(),
+        health: daemon_guard.health.iter().cloned().collect(),
+    })
+}
+
+/// Route captures to a throwaway in-memory vault (or back to disk). The
+/// incognito history is dropped as soon as the mode is turned off or the vault
+/// locks.
+#[tauri::command]
+pub async fn set_incognito(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<bool, String> {
+    let mut vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let mut stash = state
+        .incognito_stash
+        .lock()
+        .map_err(|_| "Vault lock poisoned")?;
+
+    if enabled == stash.is_some() {
+        return Ok(enabled);
+    }
+    if enabled {
+        if vault_guard.is_none() {
+            return Err("Vault not unlocked".to_string());
+        }
+        let memory =
+            SqliteVault::open_in_memory("clip-vault-incognito").map_err(|e| e.to_string())?;
+        forward_vault_events(&memory, app.clone());
+        *stash = vault_guard.replace(memory);
+        info!("Incognito mode on");
+    } else {
+        *vault_guard = stash.take();
+        info!("Incognito mode off, session history discarded");
+    }
+    drop(stash);
+    drop(vault_guard);
+
+    app.emit("incognito-changed", enabled).ok();
+    refresh_capture_status(&app);
+    app.emit("clipboard-updated", ()).ok();
+    Ok(enabled)
 }
 
+#[tauri::command]
+pub async fn incognito_status(state: State<'_, AppState>) -> Result<bool, String> {
+    let stash = state
+        .incognito_stash
+        .lock()
+        .map_err(|_| "Vault lock poisoned")?;
+    Ok(stash.is_some())
+}
+
+/// Replace a text entry's content. Edits count as deletes for the unlock
+/// policy, so they may need `password`.
 #[tauri::command]
 pub async fn update_item(
-    old_content: String,
+    id: i64,
     new_content: String,
+    password: Option<String>,
     state: State<'_, AppState>,
-    app: AppHandle,
 ) -> Result<(), String> {
-    // compute hash of old content (text only)
-    let mut hasher = Sha256::new();
-    hasher.update(old_content.as_bytes());
-    let old_hash = hasher.finalize();
-    let mut arr = [0u8; 32];
-    arr.copy_from_slice(&old_hash);
-
-    let new_item = ClipboardItem::Text(new_content.clone());
+    let new_item = ClipboardItem::Text(new_content);
 
     let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
     let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    authorize(vault, SensitiveAction::Delete, password)?;
 
-    vault.update(arr, &new_item).map_err(|e| e.to_string())?;
-
-    // Emit event to refresh search results
-    app.emit("clipboard-updated", ()).ok();
+    if vault
+        .update_by_id(id, &new_item)
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        return Err("Item not found".to_string());
+    }
 
     info!("Item updated successfully");
     Ok(())
 }
 
+/// Replace an image entry with an edited version, e.g. after cropping or
+/// annotating it in the search window. The edit is base64-encoded and
+/// must be a PNG.
+#[tauri::command]
+pub async fn update_image_item(
+    id: i64,
+    new_content: String,
+    password: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let new_data = general_purpose::STANDARD
+        .decode(new_content)
+        .map_err(|e| e.to_string())?;
+    if clip_vault_core::ImageInfo::from_bytes(&new_data).is_none_or(|info| info.format != "PNG") {
+        return Err("Edited image must be a PNG".to_string());
+    }
+
+    // The vault strips its metadata, if the settings say so.
+    let new_item = ClipboardItem::Image(new_data);
+
+    let vault_guard = state.vault.lock().map_err(|_| "Vault lock poisoned")?;
+    let vault = vault_guard.as_ref().ok_or("Vault not unlocked")?;
+    authorize(vault, SensitiveAction::Delete, password)?;
+
+    if vault
+        .update_by_id(id, &new_item)
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        return Err("Item not found".to_string());
+    }
+
+    info!("Image item updated successfully");
+    Ok(())
+}
+
+/// Format a nanosecond capture time in the system timezone. `locale` (e.g.
+/// `de-DE`) defaults to the system locale.
+#[tauri::command]
+pub async fn format_timestamp(
+    timestamp: Timestamp,
+    style: TimestampStyle,
+    locale: Option<String>,
+) -> Result<String, String> {
+    Ok(timestamp.format(style, locale.as_deref()))
+}
+
 #[tauri::command]
 pub async fn get_platform() -> Result<String, String> {
     Ok(std::env::consts::OS.to_string())
 }
 
+fn update_channel(state: &State<'_, AppState>) -> Result<UpdateChannel, String> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?;
+    Ok(settings.update_channel)
+}
+
+/// Check the configured channel for a newer release without installing it.
 #[tauri::command]
-pub async fn check_for_updates(app: AppHandle) -> Result<Option<String>, String> {
-    use tauri_plugin_updater::UpdaterExt;
+pub async fn check_for_updates(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let channel = update_channel(&state)?;
+    match updates::check(&app, channel).await? {
+        Some(update) => {
+            info!("Update available on {channel:?}: {}", update.version);
+            Ok(Some(update.version))
+        }
+        None => {
+            info!("No updates available");
+            Ok(None)
+        }
+    }
+}
 
-    match app.updater() {
-        Ok(updater) => match updater.check().await {
-            Ok(Some(update)) => {
-                info!("Update available: {}", update.version);
-                Ok(Some(update.version.to_string()))
-            }
-            Ok(None) => {
-                info!("No updates available");
-                Ok(None)
-            }
-            Err(e) => {
-                eprintln!("Failed to check for updates: {e}");
-                Err(format!("Failed to check for updates: {e}"))
-            }
-        },
-        Err(e) => Err(format!("Failed to get updater: {e}")),
+#[tauri::command]
+pub async fn set_update_channel(
+    channel: UpdateChannel,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut settings = state
+        .settings
+        .lock()
+        .map_err(|_| "Settings lock poisoned")?;
+    settings.update_channel = channel;
+    let updates = settings.update_preferences();
+    drop(settings);
+    info!("Update channel set to {channel:?}");
+    updates.save()
+}
+
+/// Download the latest release now and install it when the app quits.
+/// Returns the scheduled version, or `None` when already up to date.
+#[tauri::command]
+pub async fn schedule_update_install(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let channel = update_channel(&state)?;
+    match updates::check(&app, channel).await? {
+        Some(update) => updates::schedule(&app, update).await.map(Some),
+        None => Ok(None),
     }
 }
 
+#[tauri::command]
+pub async fn cancel_scheduled_update(app: AppHandle) -> Result<bool, String> {
+    Ok(updates::cancel(&app))
+}
+
 #[tauri::command]
 #[allow(clippy::cast_precision_loss)]
-pub async fn install_update(app: AppHandle) -> Result<(), String> {
-    use tauri_plugin_updater::UpdaterExt;
-
-    match app.updater() {
-        Ok(updater) => {
-            match updater.check().await {
-                Ok(Some(update)) => {
-                    info!("Installing update: {}", update.version);
-
-                    let mut downloaded = 0;
-
-                    // Download and install with progress
-                    update
-                        .download_and_install(
-                            |chunk_length, content_length| {
-                                downloaded += chunk_length;
-                                if let Some(total) = content_length {
-                                    let progress = (downloaded as f64 / total as f64) * 100.0;
-                                    info!("Download progress: {:.1}%", progress);
-
-                                    // Emit progress event
-                                    let _ = app.emit("update-progress", progress);
-                                }
-                            },
-                            || {
-                                info!("Update download completed, restarting application...");
-                                let _ = app.emit("update-installed", ());
-                            },
-                        )
-                        .await
-                        .map_err(|e| format!("Failed to install update: {e}"))?;
-
-                    Ok(())
+pub async fn install_update(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let channel = update_channel(&state)?;
+    let Some(update) = updates::check(&app, channel).await? else {
+        return Err("No updates available".to_string());
+    };
+    info!("Installing update: {}", update.version);
+    updates::cancel(&app);
+
+    let mut downloaded = 0;
+
+    // Download and install with progress
+    update
+        .download_and_install(
+            |chunk_length, content_length| {
+                downloaded += chunk_length;
+                if let Some(total) = content_length {
+                    let progress = (downloaded as f64 / total as f64) * 100.0;
+                    info!("Download progress: {:.1}%", progress);
+
+                    // Emit progress event
+                    let _ = app.emit("update-progress", progress);
                 }
-                Ok(None) => Err("No updates available".to_string()),
-                Err(e) => Err(format!("Failed to check for updates: {e}")),
-            }
-        }
-        Err(e) => Err(format!("Failed to get updater: {e}")),
-    }
+            },
+            || {
+                info!("Update download completed, restarting application...");
+                let _ = app.emit("update-installed", ());
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to install update: {e}"))
 }
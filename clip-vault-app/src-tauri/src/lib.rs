@@ -2,7 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use clip_vault_core::default_db_path;
-use tauri::Manager;
+use tauri::{Manager, WindowEvent};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
 mod commands;
@@ -10,12 +10,29 @@ mod modules;
 mod state;
 
 use commands::{
-    check_for_updates, check_vault_status, copy_to_clipboard, create_vault, daemon_status,
-    delete_item, get_platform, get_settings, install_update, list_clipboard, open_settings_window,
-    quit_app, save_settings, search_clipboard, show_toast_notification, start_daemon, stop_daemon, unlock_vault,
-    update_item, vault_exists,
+    activate_selected, activity_histogram, activity_ping, adopt_vault, browse_backup,
+    cancel_scheduled_update, check_for_updates, check_permissions, check_vault_status,
+    close_backup, copy_as, copy_as_markdown, copy_to_clipboard, copy_without_history, create_vault,
+    daemon_status, delete_item, describe_entries, dismiss_search, export_settings,
+    format_timestamp, get_app_ttls, get_capture_status, get_copy_events, get_duplicate_policy,
+    get_item, get_item_by_id, get_item_content, get_item_expiry, get_item_note, get_language,
+    get_platform, get_recent_logs, get_related, get_saved_searches, get_selection, get_settings,
+    get_storage_info, get_tags, get_text_normalization, get_timeline, get_unlock_policy, has_html,
+    import_settings, incognito_status, install_update, list_actions, list_clipboard, list_profiles,
+    list_protected, list_sync_conflicts, list_tags, onboarding_status, open_backup,
+    open_permission_settings, open_settings_window, password_hint, preview_retention, protect_item,
+    quit_app, register_daemon, resolve_sync_conflict, save_settings, schedule_update_install,
+    search_actions, search_all_profiles, search_clipboard, select_index, select_next,
+    select_previous, set_app_ttls, set_duplicate_policy, set_incognito, set_item_expiry,
+    set_item_note, set_language, set_saved_searches, set_search_results, set_storage_warning,
+    set_text_normalization, set_unlock_policy, set_update_channel, set_window_behavior,
+    show_toast_notification, start_daemon, stop_daemon, tag_item, take_pending_search, toggle_pin,
+    uninstall_app, unlock_profile, unlock_vault, unprotect_item, update_image_item, update_item,
+    vault_exists,
 };
-use modules::{system_tray::create_system_tray, window_manager::show_search_window};
+use modules::logging::LogLevel;
+use modules::system_tray::create_system_tray;
+use modules::window_manager::{dismiss_search_window, search_window_behavior, show_search_window};
 use state::AppState;
 
 /// Bootstraps the Tauri application.
@@ -33,22 +50,62 @@ pub fn run() {
     }
 
     let app_state = AppState::new();
+    app_state.install_panic_hook();
+    let trace_capture = std::env::args().any(|arg| arg == "--trace-capture");
+    if let Ok(mut daemon) = app_state.daemon.lock() {
+        daemon.trace_capture = trace_capture;
+    }
+    if let Ok(settings) = app_state.settings.lock() {
+        // Trace lines are logged at info, so don't let the setting hide them.
+        let level = if trace_capture {
+            settings.log_level.max(LogLevel::Info)
+        } else {
+            settings.log_level
+        };
+        modules::logging::init(level);
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            None,
+        ))
         .manage(app_state)
+        .manage(modules::updates::PendingUpdate::default())
+        .manage(modules::window_manager::PendingSearch::default())
+        .manage(modules::navigation::SearchNavigation::default())
         .setup(|app| {
             // Hide the main window immediately
             if let Some(main_window) = app.get_webview_window("main") {
                 main_window.hide().ok();
             }
 
+            // Before the search window asks whether the vault is unlocked
+            commands::unlock_from_keyring(app.handle());
+            modules::session::start_expiry_timer(app.handle().clone());
+            modules::session::start_lock_request_listener(app.handle().clone());
+
             // Create system tray
             create_system_tray(app.handle())?;
             show_search_window(app.handle());
 
+            // Dismiss the search window when another window takes focus,
+            // unless the user wants it to stay put.
+            if let Some(search_window) = app.get_webview_window("search") {
+                let app_handle = app.handle().clone();
+                search_window.on_window_event(move |event| {
+                    if matches!(event, WindowEvent::Focused(false))
+                        && search_window_behavior(&app_handle).hide_on_focus_loss
+                    {
+                        dismiss_search_window(&app_handle);
+                    }
+                });
+            }
+
             // Register global shortcut from settings
             let app_handle = app.handle().clone();
             let shortcut = {
@@ -77,25 +134,106 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             list_clipboard,
             search_clipboard,
+            describe_entries,
+            get_timeline,
+            get_related,
+            get_copy_events,
             copy_to_clipboard,
+            copy_without_history,
+            copy_as,
+            copy_as_markdown,
+            has_html,
+            protect_item,
+            list_protected,
+            open_backup,
+            browse_backup,
+            close_backup,
+            list_profiles,
+            unlock_profile,
+            search_all_profiles,
+            get_duplicate_policy,
+            set_duplicate_policy,
+            unprotect_item,
             delete_item,
+            preview_retention,
+            dismiss_search,
+            set_search_results,
+            select_index,
+            select_next,
+            select_previous,
+            get_selection,
+            activate_selected,
+            tag_item,
+            get_tags,
+            list_tags,
+            toggle_pin,
+            get_item,
+            get_item_by_id,
+            get_item_content,
+            get_item_expiry,
+            set_item_expiry,
+            get_item_note,
+            set_item_note,
+            get_app_ttls,
+            set_app_ttls,
+            list_actions,
+            search_actions,
             get_settings,
+            get_recent_logs,
             save_settings,
+            export_settings,
+            import_settings,
+            set_window_behavior,
             unlock_vault,
+            get_unlock_policy,
+            set_unlock_policy,
             check_vault_status,
+            activity_ping,
             open_settings_window,
             quit_app,
             start_daemon,
             stop_daemon,
+            uninstall_app,
             daemon_status,
+            get_capture_status,
             update_item,
+            update_image_item,
             vault_exists,
+            onboarding_status,
+            check_permissions,
+            open_permission_settings,
+            adopt_vault,
+            register_daemon,
+            password_hint,
             create_vault,
             get_platform,
+            format_timestamp,
             check_for_updates,
+            set_update_channel,
+            schedule_update_install,
+            cancel_scheduled_update,
             install_update,
             show_toast_notification,
+            activity_histogram,
+            get_storage_info,
+            set_storage_warning,
+            get_saved_searches,
+            set_saved_searches,
+            get_language,
+            set_language,
+            get_text_normalization,
+            set_text_normalization,
+            take_pending_search,
+            set_incognito,
+            incognito_status,
+            list_sync_conflicts,
+            resolve_sync_conflict,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                modules::updates::install_pending(app);
+            }
+        });
 }
@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+/// Something the search window can do, with the keys that trigger it.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Action {
+    pub id: &'static str,
+    pub title: &'static str,
+    /// `Mod` is Cmd on macOS and Ctrl elsewhere, e.g. `Mod+Shift+C`.
+    pub keys: &'static str,
+    /// Whether it acts on the selected entry.
+    pub needs_item: bool,
+    /// Whether it only applies to text entries.
+    pub text_only: bool,
+    /// The one kind of entry it applies to, e.g. `color`, if any.
+    pub kind: Option<&'static str>,
+}
+
+const fn action(id: &'static str, title: &'static str, keys: &'static str) -> Action {
+    Action {
+        id,
+        title,
+        keys,
+        needs_item: true,
+        text_only: false,
+        kind: None,
+    }
+}
+
+const fn text_action(id: &'static str, title: &'static str, keys: &'static str) -> Action {
+    Action {
+        text_only: true,
+        ..action(id, title, keys)
+    }
+}
+
+const fn color_action(id: &'static str, title: &'static str, keys: &'static str) -> Action {
+    Action {
+        kind: Some("color"),
+        ..text_action(id, title, keys)
+    }
+}
+
+const fn window_action(id: &'static str, title: &'static str, keys: &'static str) -> Action {
+    Action {
+        needs_item: false,
+        ..action(id, title, keys)
+    }
+}
+
+/// Every action, in the order the palette lists them with an empty query.
+pub const ACTIONS: &[Action] = &[
+    action("copy", "Copy", "Enter"),
+    text_action("paste_plain", "Copy as Plain Text", "Shift+Enter"),
+    text_action("copy_markdown", "Copy as Markdown", "Mod+Shift+M"),
+    text_action("copy_json_minified", "Copy as Minified JSON", "Mod+Shift+N"),
+    text_action("copy_json_pretty", "Copy as Pretty JSON", "Mod+Shift+J"),
+    text_action("copy_shell_escaped", "Copy Shell-Escaped", "Mod+Shift+S"),
+    text_action("copy_code_block", "Copy as Code Block", "Mod+Shift+B"),
+    color_action("copy_color_hex", "Copy Color as Hex", "Mod+Alt+H"),
+    color_action("copy_color_rgb", "Copy Color as RGB", "Mod+Alt+R"),
+    color_action("copy_color_hsl", "Copy Color as HSL", "Mod+Alt+L"),
+    text_action("edit", "Edit", "Mod+E"),
+    action("tag", "Add Tag…", "Mod+T"),
+    action("pin", "Pin / Unpin", "Mod+P"),
+    text_action("protect", "Move to Protected Area", "Mod+L"),
+    action("delete", "Delete", "Mod+Backspace"),
+    window_action("focus_search", "Search", "/"),
+    window_action("command_palette", "Show Actions", "Mod+K"),
+    window_action("open_settings", "Open Settings", "Mod+,"),
+    window_action("close", "Close Window", "Escape"),
+];
+
+/// Actions matching `query`, best first. Every character of the query must
+/// appear in the title in order; runs of adjacent characters and matches at
+/// word starts rank higher.
+pub fn search(query: &str) -> Vec<Action> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let mut scored: Vec<(u32, usize, Action)> = ACTIONS
+        .iter()
+        .enumerate()
+        .filter_map(|(i, action)| Some((fuzzy_score(&query, action.title)?, i, *action)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, _, action)| action).collect()
+}
+
+fn fuzzy_score(query: &[char], title: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut adjacent = false;
+    let mut remaining = query.iter().peekable();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        let Some(&&q) = remaining.peek() else {
+            break;
+        };
+        if c == q {
+            score += 1;
+            if adjacent {
+                score += 2;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            remaining.next();
+            adjacent = true;
+        } else {
+            adjacent = false;
+        }
+        previous = Some(c);
+    }
+    remaining.peek().is_none().then_some(score)
+}
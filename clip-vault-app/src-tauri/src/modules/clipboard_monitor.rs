@@ -1,12 +1,31 @@
-use clip_vault_core::{ClipboardItem, SqliteVault, Vault};
-use image::{ImageBuffer, ImageFormat, RgbaImage};
+use clip_vault_core::{
+    format_size, tr, CachedConfig, CaptureAlert, ClipboardItem, Config, CustomFormat,
+    EntryDescription, Msg, Representation, RichText, SqliteVault, Vault,
+};
+use clip_vault_monitor::{
+    ArboardClipboard, CaptureOptions, ClipboardProvider, Monitor, PollOutcome,
+};
+use serde::Serialize;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
 use tokio::sync::mpsc;
 use tracing::{info, warn};
 
-use crate::state::DaemonState;
+use crate::modules::system_tray::refresh_capture_status;
+use crate::state::{AppState, DaemonState};
+
+/// How often the monitor applies the retention policy.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+/// Images from before thumbnails were made that get one each interval,
+/// few enough that capturing isn't held up decoding them.
+const THUMBNAIL_BATCH: usize = 8;
+const FIRST_RESTART_DELAY: Duration = Duration::from_millis(500);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+/// A run this long resets the backoff, so failures hours apart don't
+/// add up.
+const STABLE_RUN: Duration = Duration::from_secs(60);
 
 pub fn start_clipboard_monitoring(
     vault: &Arc<Mutex<Option<SqliteVault>>>,
@@ -20,108 +39,546 @@ pub fn start_clipboard_monitoring(
         return Ok(()); // Already running
     }
 
-    let (shutdown_tx, mut shutdown_rx) = mpsc::unbounded_channel();
+    let (shutdown_tx, shutdown_rx) = mpsc::unbounded_channel();
     daemon_guard.shutdown_sender = Some(shutdown_tx);
     daemon_guard.is_running = true;
+    daemon_guard.last_error = None;
     drop(daemon_guard);
+    refresh_capture_status(&app_handle);
+
+    tokio::spawn(supervise(
+        vault.clone(),
+        daemon.clone(),
+        poll_interval_ms,
+        app_handle,
+        shutdown_rx,
+    ));
+
+    Ok(())
+}
+
+/// Why a monitor run ended on its own.
+enum MonitorExit {
+    /// The vault was locked; there's nothing to capture into.
+    Locked,
+    /// Something panicked holding the vault, so every restart would find its
+    /// lock poisoned too.
+    Poisoned,
+    Failed(String),
+}
 
-    let vault_clone = vault.clone();
-    let daemon_clone = daemon.clone();
+/// Sent to the frontend as `monitor-failed` each time the monitor dies.
+#[derive(Clone, Serialize)]
+struct MonitorFailure {
+    error: String,
+    consecutive_failures: u32,
+    /// `None` once the monitor has given up.
+    retry_in_ms: Option<u64>,
+}
 
-    tokio::spawn(async move {
-        let mut clipboard = match arboard::Clipboard::new() {
-            Ok(cb) => cb,
-            Err(e) => {
-                warn!("Failed to create clipboard: {}", e);
-                return;
+/// Keep the monitor running until it's stopped or the vault locks,
+/// restarting it with exponential backoff when it fails or panics. A
+/// poisoned vault lock stops it for good.
+async fn supervise(
+    vault: Arc<Mutex<Option<SqliteVault>>>,
+    daemon: Arc<Mutex<DaemonState>>,
+    poll_interval_ms: u64,
+    app_handle: AppHandle,
+    mut shutdown_rx: mpsc::UnboundedReceiver<()>,
+) {
+    let mut failures = 0;
+    let mut fatal = None;
+    loop {
+        let started = Instant::now();
+        let mut run = tokio::spawn(monitor(
+            vault.clone(),
+            daemon.clone(),
+            poll_interval_ms,
+            app_handle.clone(),
+        ));
+        let outcome = tokio::select! {
+            _ = shutdown_rx.recv() => {
+                info!("Clipboard monitoring shutdown requested");
+                run.abort();
+                break;
             }
+            outcome = &mut run => outcome,
+        };
+        let error = match outcome {
+            Ok(MonitorExit::Locked) => break,
+            Ok(MonitorExit::Poisoned) => {
+                let error = "vault lock poisoned".to_string();
+                warn!("Clipboard monitor stopped: {}", error);
+                if let Ok(mut daemon_guard) = daemon.lock() {
+                    daemon_guard.last_error = Some(error.clone());
+                }
+                fatal = Some(error);
+                break;
+            }
+            Ok(MonitorExit::Failed(error)) => error,
+            Err(e) => format!("monitor task ended unexpectedly: {e}"),
         };
 
-        let mut last_hash: Option<[u8; 32]> = None;
-        let poll_duration = Duration::from_millis(poll_interval_ms);
+        if started.elapsed() >= STABLE_RUN {
+            failures = 0;
+        }
+        failures += 1;
+        let delay = restart_delay(failures);
+        warn!(
+            "Clipboard monitor failed ({} in a row): {}; restarting in {:?}",
+            failures, error, delay
+        );
+        if let Ok(mut daemon_guard) = daemon.lock() {
+            daemon_guard.last_error = Some(error.clone());
+        }
+        refresh_capture_status(&app_handle);
+        app_handle
+            .emit(
+                "monitor-failed",
+                MonitorFailure {
+                    error,
+                    consecutive_failures: failures,
+                    retry_in_ms: Some(u64::try_from(delay.as_millis()).unwrap_or(u64::MAX)),
+                },
+            )
+            .ok();
 
-        info!("Clipboard monitoring started");
+        tokio::select! {
+            _ = shutdown_rx.recv() => {
+                info!("Clipboard monitoring shutdown requested");
+                break;
+            }
+            () = tokio::time::sleep(delay) => {}
+        }
+    }
 
-        loop {
-            tokio::select! {
-                _ = shutdown_rx.recv() => {
-                    info!("Clipboard monitoring shutdown requested");
-                    break;
-                }
-                () = tokio::time::sleep(poll_duration) => {
-                    // Check if vault is still available
-                    let Ok(vault_guard) = vault_clone.lock() else {
-                        warn!("Vault lock poisoned, stopping daemon");
-                        break;
-                    };
-
-                    if vault_guard.is_none() {
-                        // Vault is locked, stop monitoring
-                        break;
+    // Mark daemon as stopped, unless it has been restarted meanwhile with a
+    // new shutdown channel.
+    drop(shutdown_rx);
+    if let Ok(mut daemon_guard) = daemon.lock() {
+        if daemon_guard
+            .shutdown_sender
+            .as_ref()
+            .is_none_or(mpsc::UnboundedSender::is_closed)
+        {
+            daemon_guard.is_running = false;
+            daemon_guard.shutdown_sender = None;
+        }
+    }
+    refresh_capture_status(&app_handle);
+    // After the status, which would otherwise clear it.
+    if let Some(error) = fatal {
+        app_handle
+            .emit(
+                "monitor-failed",
+                MonitorFailure {
+                    error,
+                    consecutive_failures: failures + 1,
+                    retry_in_ms: None,
+                },
+            )
+            .ok();
+    }
+
+    info!("Clipboard monitoring stopped");
+}
+
+/// 0.5s after the first failure, doubling up to `MAX_RESTART_DELAY`.
+fn restart_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    (FIRST_RESTART_DELAY * 2u32.pow(doublings)).min(MAX_RESTART_DELAY)
+}
+
+/// Poll the clipboard and store new copies until the vault locks or
+/// something fails.
+async fn monitor(
+    vault: Arc<Mutex<Option<SqliteVault>>>,
+    daemon: Arc<Mutex<DaemonState>>,
+    poll_interval_ms: u64,
+    app_handle: AppHandle,
+) -> MonitorExit {
+    let mut clipboard = match ArboardClipboard::new() {
+        Ok(cb) => cb,
+        Err(e) => return MonitorExit::Failed(e.to_string()),
+    };
+
+    // Carry on from the previous run so the current clipboard isn't
+    // captured again after a restart.
+    let last_hash = daemon.lock().ok().and_then(|d| d.last_hash);
+    // Read again only when the file changes, rather than every poll.
+    let mut config = CachedConfig::default();
+    let mut captures = Monitor::new(
+        last_hash,
+        capture_options(&app_handle, config.get()).overflow,
+    );
+    let poll_duration = Duration::from_millis(poll_interval_ms);
+    let mut last_prune: Option<Instant> = None;
+    let mut last_sequence = None;
+    // Warn about a large vault once per unlock, not every minute.
+    let mut storage_warned = false;
+
+    info!("Clipboard monitoring started");
+    if record_capture_error(&daemon, None) {
+        refresh_capture_status(&app_handle);
+    }
+
+    loop {
+        tokio::time::sleep(poll_duration).await;
+
+        // Read the clipboard before taking the vault, so a slow read or a
+        // burst of changes doesn't hold up commands.
+        let options = capture_options(&app_handle, config.get());
+        let sequence = clipboard.sequence_number();
+        let started = Instant::now();
+        let read = if sequence.is_some() && sequence == last_sequence {
+            Err(PollOutcome::Unchanged)
+        } else {
+            clip_vault_monitor::read_outcome(&mut clipboard, &options)
+        };
+        let elapsed = started.elapsed();
+        if !matches!(read, Err(PollOutcome::Failed { .. })) {
+            last_sequence = sequence;
+        }
+        let outcome = match read {
+            Ok(new) => {
+                let description = describe(&new.item);
+                let alerts = options.alerts.check_capture(&new);
+                let hash = new.hash;
+                let captured = PollOutcome::captured(&new.item);
+                captures.set_overflow(options.overflow);
+                let outcome = if clip_vault_monitor::take_suppressed(&new) {
+                    captures.skip_marked(&new);
+                    // Skipped from now on like the app's own copies.
+                    if let Ok(mut daemon_guard) = daemon.lock() {
+                        daemon_guard.own_copy = Some(hash);
+                    }
+                    PollOutcome::Skipped {
+                        reason: "marked not to be recorded".to_string(),
+                    }
+                } else if is_own_copy(&daemon, hash) {
+                    PollOutcome::Skipped {
+                        reason: "copied from Clip Vault".to_string(),
+                    }
+                } else if captures.offer(new) {
+                    if let Ok(mut daemon_guard) = daemon.lock() {
+                        daemon_guard.own_copy = None;
                     }
+                    info!("New clipboard {}", description);
+                    show_alerts(&app_handle, &alerts);
+                    captures.hold_alerts(hash, alerts);
+                    captured
+                } else {
+                    PollOutcome::Duplicate
+                };
+                let dropped = captures.take_dropped();
+                if dropped > 0 {
+                    warn!(
+                        "Clipboard is changing faster than it can be stored; dropped {} change(s)",
+                        dropped
+                    );
+                }
+                outcome
+            }
+            Err(outcome) => {
+                if let PollOutcome::Failed { error } = &outcome {
+                    warn!("Failed to encode clipboard image: {}", error);
+                }
+                outcome
+            }
+        };
+        trace_poll(&daemon, outcome, elapsed);
+
+        // Check if vault is still available
+        let Ok(vault_guard) = vault.lock() else {
+            return MonitorExit::Poisoned;
+        };
 
-                    let clipboard_item = if let Ok(image_data) = clipboard.get_image() {
-                        let image: RgbaImage = ImageBuffer::from_raw(
-                            image_data.width.try_into().unwrap(),
-                            image_data.height.try_into().unwrap(),
-                            image_data.bytes.into_owned(),
-                        ).unwrap();
-                        let mut buffer = std::io::Cursor::new(Vec::new());
-                        image.write_to(&mut buffer, ImageFormat::Png).unwrap();
-                        Some(ClipboardItem::Image(buffer.into_inner()))
-                    } else if let Ok(text) = clipboard.get_text() {
-                        Some(ClipboardItem::Text(text))
-                    } else {
-                        None
-                    };
-
-                    if let Some(item) = clipboard_item {
-                        let hash = item.hash();
-
-                        if last_hash != Some(hash) {
-                            let item_description = match &item {
-                                ClipboardItem::Text(t) => format!("text: {}…", t.chars().take(40).collect::<String>()),
-                                ClipboardItem::Image(data) => format!("image: {} bytes", data.len()),
-                            };
-
-                            info!("New clipboard {}", item_description);
-
-                            if let Some(vault) = vault_guard.as_ref() {
-                                if let Err(e) = vault.insert(hash, &item) {
-                                    warn!("Failed to store clipboard item: {}", e);
-                                } else {
-                                    last_hash = Some(hash);
-
-                                    // Update last hash in daemon state
-                                    if let Ok(mut daemon_guard) = daemon_clone.lock() {
-                                        daemon_guard.last_hash = Some(hash);
-                                    }
-
-                                    // Emit event to frontend about new clipboard item
-                                    app_handle.emit("clipboard-updated", ()).ok();
-
-                                    info!("New clipboard item stored successfully");
-                                }
-                            }
-                        }
+        if vault_guard.is_none() {
+            // Vault is locked, stop monitoring
+            return MonitorExit::Locked;
+        }
+
+        // Writes from other processes (e.g. the CLI) reach the
+        // frontend through the vault's change notifications.
+        if let Some(vault) = vault_guard.as_ref() {
+            vault.poll_changes().ok();
+            // Edits made in the app follow the config as it changes.
+            vault.set_dedupe_rules(options.dedupe);
+        }
+
+        if last_prune.is_none_or(|t| t.elapsed() >= PRUNE_INTERVAL) {
+            if let Some(vault) = vault_guard.as_ref() {
+                // What these remove reaches the log as health events.
+                apply_retention(vault, config.get());
+                if let Err(e) = vault.purge_expired() {
+                    warn!("Failed to remove expired entries: {}", e);
+                }
+                archive_old_entries(vault, config.get());
+                if let Err(e) = vault.backfill_thumbnails(THUMBNAIL_BATCH) {
+                    warn!("Failed to make thumbnails: {}", e);
+                }
+                if !storage_warned {
+                    storage_warned = warn_storage_size(vault, &app_handle, config.get());
+                }
+            }
+            last_prune = Some(Instant::now());
+        }
+
+        let mut status_changed = false;
+        if let Some(vault) = vault_guard.as_ref().filter(|_| captures.flush_due()) {
+            match captures.flush(vault) {
+                Ok(flushed) => {
+                    for failure in &flushed.failed {
+                        warn!(
+                            "Failed to store a clipboard item copied at {}: {}",
+                            failure.captured_at.absolute(),
+                            failure.error
+                        );
+                    }
+                    status_changed = record_capture_error(
+                        &daemon,
+                        flushed.failed.last().map(|f| f.error.clone()),
+                    );
+                    // A flush may only have counted skipped secrets.
+                    if let (Ok(mut daemon_guard), Some(newest)) = (daemon.lock(), flushed.newest) {
+                        daemon_guard.last_hash = Some(newest);
+                    }
+                    // The size cap is kept as new copies come in, not
+                    // just every minute.
+                    if config.get().retention.max_size_mb.is_some() {
+                        apply_retention(vault, config.get());
+                    }
+                    info!("Stored {} new clipboard item(s)", flushed.stored);
+                    for (id, alerts) in captures.stored_alerts(vault) {
+                        clip_vault_monitor::send_alerts(&options.alerts, id, alerts, |e| {
+                            warn!("Failed to send capture alert to webhook: {}", e);
+                        });
                     }
                 }
+                Err(e) => {
+                    // The batch stays queued and is retried next time.
+                    warn!("Failed to store clipboard items: {}", e);
+                    status_changed = record_capture_error(&daemon, Some(e.to_string()));
+                }
             }
         }
 
-        // Mark daemon as stopped
-        if let Ok(mut daemon_guard) = daemon_clone.lock() {
-            daemon_guard.is_running = false;
-            daemon_guard.shutdown_sender = None;
-            daemon_guard.last_hash = last_hash;
+        // The status reads the vault, so release it first.
+        drop(vault_guard);
+        if status_changed {
+            refresh_capture_status(&app_handle);
         }
+    }
+}
 
-        info!("Clipboard monitoring stopped");
-    });
+/// Show each alert as a native notification. They go to the webhook, if
+/// one is set, once the capture is stored.
+fn show_alerts(app_handle: &AppHandle, alerts: &[CaptureAlert]) {
+    for alert in alerts {
+        warn!("Capture alert: {}", alert);
+        if let Err(e) = app_handle
+            .notification()
+            .builder()
+            .title("Clip Vault")
+            .body(alert.to_string())
+            .show()
+        {
+            warn!("Failed to show capture alert: {}", e);
+        }
+    }
+}
+
+/// The kind and size of a capture, never its content: the log is plaintext.
+fn describe(item: &ClipboardItem) -> String {
+    match item {
+        ClipboardItem::Text(t) => format!("text, {}", format_size(t.len())),
+        ClipboardItem::Image(data) => format!("image, {}", format_size(data.len())),
+        ClipboardItem::Files(paths) => format!("{} files", paths.len()),
+        _ => format_size(item.size()),
+    }
+}
+
+/// Whether the capture with `hash` is something the app copied itself.
+fn is_own_copy(daemon: &Arc<Mutex<DaemonState>>, hash: [u8; 32]) -> bool {
+    daemon
+        .lock()
+        .is_ok_and(|daemon_guard| daemon_guard.own_copy == Some(hash))
+}
 
+/// Put `item` on the clipboard for the user and remember it, so the monitor
+/// doesn't capture it again as a new copy.
+pub fn write_clipboard(app_handle: &AppHandle, item: &ClipboardItem) -> Result<(), String> {
+    place_clipboard(app_handle, item)?;
+    announce_copy(app_handle, item);
     Ok(())
 }
 
+/// Put `item` on the clipboard so it is never captured, not even by a
+/// monitor in another process, and isn't read aloud.
+pub fn write_clipboard_unrecorded(
+    app_handle: &AppHandle,
+    item: &ClipboardItem,
+) -> Result<(), String> {
+    clip_vault_monitor::suppress_capture(item).map_err(|e| e.to_string())?;
+    place_clipboard(app_handle, item)
+}
+
+fn place_clipboard(app_handle: &AppHandle, item: &ClipboardItem) -> Result<(), String> {
+    let daemon = app_handle
+        .try_state::<AppState>()
+        .map(|state| state.daemon.clone());
+    let remember = |hash| {
+        if let Some(mut daemon_guard) = daemon.as_ref().and_then(|d| d.lock().ok()) {
+            daemon_guard.own_copy = Some(hash);
+        }
+    };
+    // Before writing, so a poll landing in between doesn't see it as new.
+    remember(item.hash());
+
+    let (rich, formats, representations) = stored_formats(app_handle, item);
+    let mut clipboard = ArboardClipboard::new().map_err(|e| e.to_string())?;
+    clipboard
+        .set_bundle(item, &rich, &formats, &representations)
+        .map_err(|e| e.to_string())?;
+    // The monitor hashes what it reads back: images re-encoded, text after
+    // the capture transforms. Match that.
+    if let Ok(Some(capture)) = clip_vault_monitor::read(
+        &mut clipboard,
+        &capture_options(app_handle, &Config::load().unwrap_or_default()),
+    ) {
+        remember(capture.hash);
+    }
+    Ok(())
+}
+
+/// The HTML and RTF flavours, custom formats and other forms captured with
+/// `item`, to put back with it. None when the vault is locked or `item`
+/// isn't an entry.
+fn stored_formats(
+    app_handle: &AppHandle,
+    item: &ClipboardItem,
+) -> (RichText, Vec<CustomFormat>, Vec<Representation>) {
+    app_handle
+        .try_state::<AppState>()
+        .and_then(|state| {
+            let vault = state.vault.lock().ok()?;
+            let vault = vault.as_ref()?;
+            let hash = item.hash();
+            Some((
+                vault.rich_text(hash).unwrap_or_default(),
+                vault.custom_formats(hash).unwrap_or_default(),
+                vault.representations(hash).unwrap_or_default(),
+            ))
+        })
+        .unwrap_or_default()
+}
+
+/// Say what was copied, if the speak-on-copy setting is on. Speaking takes
+/// a while, so it happens on its own thread.
+fn announce_copy(app_handle: &AppHandle, item: &ClipboardItem) {
+    let enabled = app_handle
+        .try_state::<AppState>()
+        .and_then(|state| state.settings.lock().ok().map(|s| s.speak_on_copy))
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+    let text = format!("Copied {}", EntryDescription::of_item(item));
+    std::thread::spawn(move || {
+        if let Err(e) = clip_vault_core::speak(&text) {
+            warn!("Failed to speak copy feedback: {}", e);
+        }
+    });
+}
+
+/// Add a poll to the capture trace, logging it in `--trace-capture` mode.
+fn trace_poll(daemon: &Arc<Mutex<DaemonState>>, outcome: PollOutcome, elapsed: Duration) {
+    let Ok(mut daemon_guard) = daemon.lock() else {
+        return;
+    };
+    let line = daemon_guard.trace.record(outcome, elapsed);
+    if let Some(line) = line.filter(|_| daemon_guard.trace_capture) {
+        info!("Capture trace: {}", line);
+    }
+}
+
+/// Remember whether the latest capture failed. Returns whether that changed
+/// the capture status.
+fn record_capture_error(daemon: &Arc<Mutex<DaemonState>>, error: Option<String>) -> bool {
+    daemon.lock().is_ok_and(|mut daemon_guard| {
+        let changed = daemon_guard.last_error.is_some() != error.is_some();
+        daemon_guard.last_error = error;
+        changed
+    })
+}
+
+/// Delete entries past the shared config's retention rules, the ones
+/// `clip-vault prune` applies.
+fn apply_retention(vault: &SqliteVault, config: &Config) {
+    if let Err(e) = vault.prune(&config.retention) {
+        warn!("Failed to apply retention policy: {}", e);
+    }
+}
+
+/// Move entries past the configured archive age out of the vault.
+fn archive_old_entries(vault: &SqliteVault, config: &Config) {
+    let Some(days) = config.archive_after_days else {
+        return;
+    };
+    if let Err(e) = vault.archive_older_than(Duration::from_secs(days * 86_400)) {
+        warn!("Failed to archive old entries: {}", e);
+    }
+}
+
+/// Capture settings from the app's current settings and the shared
+/// `config`.
+fn capture_options(app_handle: &AppHandle, config: &Config) -> CaptureOptions {
+    app_handle
+        .try_state::<AppState>()
+        .and_then(|state| {
+            let settings = state.settings.lock().ok()?;
+            Some(CaptureOptions {
+                strip_image_metadata: settings.strip_image_metadata,
+                custom_formats: settings.capture_custom_formats,
+                text_limit: settings.text_limit.clone(),
+                transforms: settings.capture_transforms.clone(),
+                normalize_text: config.text_normalization,
+                record_source: settings.record_source_app,
+                app_ttls: config.app_ttls(),
+                dedupe: config.dedupe,
+                retention: config.retention.clone(),
+                overflow: settings.capture_overflow,
+                alerts: settings.capture_alerts.clone(),
+                ..CaptureOptions::default()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Notify if the vault file has grown past the configured warning size.
+/// Returns whether it did.
+fn warn_storage_size(vault: &SqliteVault, app_handle: &AppHandle, config: &Config) -> bool {
+    let Some(limit_mb) = config.storage_warning_mb else {
+        return false;
+    };
+    let Some(warning) =
+        clip_vault_core::storage_warning(vault.file_size(), limit_mb.saturating_mul(1024 * 1024))
+    else {
+        return false;
+    };
+    warn!("{}", warning);
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title("Clip Vault")
+        .body(format!("{warning}. {}", tr(Msg::StorageWarningHint)))
+        .show()
+    {
+        warn!("Failed to show storage warning: {}", e);
+    }
+    true
+}
+
 pub fn stop_clipboard_monitoring(daemon: &Arc<Mutex<DaemonState>>) -> Result<(), String> {
     let mut daemon_guard = daemon.lock().map_err(|_| "Daemon lock poisoned")?;
 
@@ -0,0 +1,239 @@
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{reload, Registry};
+
+const FILE_PREFIX: &str = "clip-vault-app";
+const FILE_SUFFIX: &str = "log";
+/// Days of logs kept; older files are deleted as the log rotates.
+const KEEP_FILES: usize = 7;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            Self::Error => LevelFilter::ERROR,
+            Self::Warn => LevelFilter::WARN,
+            Self::Info => LevelFilter::INFO,
+            Self::Debug => LevelFilter::DEBUG,
+            Self::Trace => LevelFilter::TRACE,
+        }
+    }
+
+    /// The level of a line as the log file writes it.
+    fn of_line(line: &str) -> Option<Self> {
+        // "<timestamp>  INFO target: message"
+        match line.split_whitespace().nth(1)? {
+            "ERROR" => Some(Self::Error),
+            "WARN" => Some(Self::Warn),
+            "INFO" => Some(Self::Info),
+            "DEBUG" => Some(Self::Debug),
+            "TRACE" => Some(Self::Trace),
+            _ => None,
+        }
+    }
+}
+
+struct Logger {
+    level: reload::Handle<LevelFilter, Registry>,
+    _flush: WorkerGuard,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// A log file per UTC day, readable only by the owner.
+struct DailyFile {
+    dir: PathBuf,
+    day: Option<NaiveDate>,
+    file: Option<File>,
+}
+
+impl DailyFile {
+    fn new(dir: PathBuf) -> io::Result<Self> {
+        create_private_dir(&dir)?;
+        let mut daily = Self {
+            dir,
+            day: None,
+            file: None,
+        };
+        daily.roll()?;
+        Ok(daily)
+    }
+
+    /// Switch to today's file when the day changed, dropping old ones.
+    fn roll(&mut self) -> io::Result<()> {
+        let today = today();
+        if self.day == Some(today) && self.file.is_some() {
+            return Ok(());
+        }
+        let path = self
+            .dir
+            .join(format!("{FILE_PREFIX}.{today}.{FILE_SUFFIX}"));
+        self.file = Some(open_private(&path)?);
+        self.day = Some(today);
+        remove_old_files(&self.dir);
+        Ok(())
+    }
+}
+
+impl Write for DailyFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.roll()?;
+        self.file.as_mut().map_or(Ok(buf.len()), |f| f.write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), File::flush)
+    }
+}
+
+fn today() -> NaiveDate {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400);
+    NaiveDate::default()
+        .checked_add_days(Days::new(days))
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir)
+}
+
+/// Open a log file for appending, creating it as owner-only.
+fn open_private(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(path)?;
+        // Files written before logs were private keep their old mode otherwise.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        Ok(file)
+    }
+    #[cfg(not(unix))]
+    options.open(path)
+}
+
+fn log_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(&format!("{FILE_PREFIX}."))
+                        && name.ends_with(&format!(".{FILE_SUFFIX}"))
+                })
+        })
+        .collect();
+    // Dated names sort oldest to newest.
+    files.sort();
+    Ok(files)
+}
+
+fn remove_old_files(dir: &Path) {
+    let Ok(files) = log_files(dir) else { return };
+    let excess = files.len().saturating_sub(KEEP_FILES);
+    for file in &files[..excess] {
+        std::fs::remove_file(file).ok();
+    }
+}
+
+/// Send `tracing` output to a daily log file in the log directory.
+pub fn init(level: LogLevel) {
+    let dir = clip_vault_core::log_dir();
+    let file = match DailyFile::new(dir.clone()) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Failed to open log file in {}: {e}", dir.display());
+            return;
+        }
+    };
+    let (writer, flush) = tracing_appender::non_blocking(file);
+    let (filter, handle) = reload::Layer::new(level.filter());
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer),
+        )
+        .try_init()
+        .is_ok();
+    if installed {
+        LOGGER
+            .set(Logger {
+                level: handle,
+                _flush: flush,
+            })
+            .ok();
+    }
+}
+
+/// Change how much is logged from now on.
+pub fn set_level(level: LogLevel) {
+    if let Some(logger) = LOGGER.get() {
+        logger.level.reload(level.filter()).ok();
+    }
+}
+
+/// The last `lines` log lines at `level` or more severe, oldest first.
+pub fn recent_logs(lines: usize, level: LogLevel) -> io::Result<Vec<String>> {
+    read_recent(&clip_vault_core::log_dir(), lines, level)
+}
+
+fn read_recent(dir: &Path, lines: usize, level: LogLevel) -> io::Result<Vec<String>> {
+    let files = log_files(dir)?;
+
+    let mut recent: Vec<String> = Vec::new();
+    for file in files.iter().rev() {
+        let mut matched: Vec<String> = Vec::new();
+        // Lines without a level continue the entry above them.
+        let mut keep = false;
+        for line in std::fs::read_to_string(file)?.lines() {
+            keep = LogLevel::of_line(line).map_or(keep, |l| l <= level);
+            if keep {
+                matched.push(line.to_string());
+            }
+        }
+        matched.append(&mut recent);
+        recent = matched;
+        if recent.len() >= lines {
+            break;
+        }
+    }
+    let skip = recent.len().saturating_sub(lines);
+    Ok(recent.split_off(skip))
+}
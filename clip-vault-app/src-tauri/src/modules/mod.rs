@@ -1,3 +1,10 @@
+pub mod actions;
 pub mod clipboard_monitor;
+pub mod logging;
+pub mod navigation;
+pub mod onboarding;
+pub mod session;
 pub mod system_tray;
+pub mod updates;
+pub mod vault_events;
 pub mod window_manager;
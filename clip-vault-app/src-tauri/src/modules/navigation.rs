@@ -0,0 +1,37 @@
+use clip_vault_core::{hash_to_hex, ListNavigation, Selection};
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// The search window's results and selection, kept here so commands can
+/// move through the list the window shows.
+#[derive(Default)]
+pub struct SearchNavigation(pub Mutex<ListNavigation>);
+
+/// Payload of `search-selection-changed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectionChanged {
+    pub index: usize,
+    /// Hex item hash, as in `SearchResult::hash`.
+    pub hash: String,
+}
+
+impl From<Selection> for SelectionChanged {
+    fn from(selection: Selection) -> Self {
+        Self {
+            index: selection.index,
+            hash: hash_to_hex(&selection.hash),
+        }
+    }
+}
+
+/// Tell the search window a command moved the selection.
+pub fn announce_selection(app: &AppHandle, selection: Option<Selection>) {
+    if let Some(selection) = selection {
+        app.emit(
+            "search-selection-changed",
+            SelectionChanged::from(selection),
+        )
+        .ok();
+    }
+}
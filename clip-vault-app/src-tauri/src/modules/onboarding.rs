@@ -0,0 +1,70 @@
+use clip_vault_core::{PermissionCheck, PermissionStatus};
+use clip_vault_monitor::{ArboardClipboard, ClipboardError, ClipboardProvider};
+use serde::Serialize;
+use std::path::Path;
+use tauri::plugin::PermissionState;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// A vault file found on disk during first run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExistingVault {
+    pub path: String,
+    pub size_bytes: u64,
+    pub password_hint: Option<String>,
+    pub has_recovery_key: bool,
+}
+
+/// Vaults the app could adopt, starting with the configured path. The CLI
+/// creates its vault at the platform default unless `CLIP_VAULT_DB_PATH` is
+/// set in its shell, which the app doesn't see, so the default location
+/// without the override is checked too.
+pub fn find_existing_vaults(configured: &Path) -> Vec<ExistingVault> {
+    let mut candidates = vec![configured.to_path_buf(), clip_vault_core::default_db_path()];
+    if let Some(data_dir) = dirs::data_dir() {
+        candidates.push(data_dir.join("clip-vault").join("clip_vault.db"));
+    }
+
+    let mut found: Vec<ExistingVault> = Vec::new();
+    for path in candidates {
+        let Ok(meta) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let path_str = path.to_string_lossy().to_string();
+        if !meta.is_file() || found.iter().any(|v| v.path == path_str) {
+            continue;
+        }
+        found.push(ExistingVault {
+            path: path_str,
+            size_bytes: meta.len(),
+            password_hint: clip_vault_core::password_hint(&path).ok().flatten(),
+            has_recovery_key: path.with_extension("recovery.db").exists(),
+        });
+    }
+    found
+}
+
+/// Clipboard, Accessibility and notification permission, in the order the
+/// UI lists them.
+pub fn check_permissions(app: &AppHandle) -> Vec<PermissionCheck> {
+    // The desktop notification plugin reports granted unconditionally; the
+    // OS decides per notification.
+    let notifications = match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => PermissionStatus::Granted,
+        Ok(PermissionState::Denied) => PermissionStatus::Denied,
+        Ok(_) | Err(_) => PermissionStatus::NotDetermined,
+    };
+    vec![
+        PermissionCheck::clipboard(clipboard_access()),
+        PermissionCheck::accessibility(),
+        PermissionCheck::notifications(notifications),
+    ]
+}
+
+fn clipboard_access() -> Result<(), String> {
+    match ArboardClipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+        // An empty or image-only clipboard still proves access.
+        Ok(_) | Err(ClipboardError::Empty) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
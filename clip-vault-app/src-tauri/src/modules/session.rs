@@ -0,0 +1,65 @@
+//! Auto-lock. A background timer locks the vault once the session has been
+//! idle for the configured time, instead of waiting for the window to ask,
+//! and tells the frontend so it can prompt for the password. The vault is
+//! also locked on request, from the tray or by `clip-vault lock`.
+
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::modules::system_tray::refresh_capture_status;
+use crate::state::AppState;
+
+/// How often the timer checks for an expired session.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// How often to check for a lock requested by another process.
+const LOCK_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Check for an expired session every [`CHECK_INTERVAL`] for as long as
+/// the app runs.
+pub fn start_expiry_timer(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        match app.state::<AppState>().lock_if_expired() {
+            Ok(true) => {
+                info!("Vault locked after inactivity");
+                notify_locked(&app);
+            }
+            Ok(false) => {}
+            Err(e) => warn!("Auto-lock check failed: {e}"),
+        }
+    });
+}
+
+/// Lock as soon as another process asks with
+/// [`clip_vault_core::request_lock`]. Requests made before the app started
+/// are ignored.
+pub fn start_lock_request_listener(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut lock_requests = clip_vault_core::LockRequests::new();
+        loop {
+            std::thread::sleep(LOCK_REQUEST_INTERVAL);
+            if lock_requests.requested() {
+                lock_now(&app);
+            }
+        }
+    });
+}
+
+/// Lock the vault right away, e.g. from the tray's Lock Vault Now.
+pub fn lock_now(app: &AppHandle) {
+    match app.state::<AppState>().lock_now() {
+        Ok(true) => {
+            info!("Vault locked on request");
+            notify_locked(app);
+        }
+        Ok(false) => {}
+        Err(e) => warn!("Failed to lock the vault: {e}"),
+    }
+}
+
+/// Tell the tray and the frontend that the vault was locked.
+pub fn notify_locked(app: &AppHandle) {
+    refresh_capture_status(app);
+    app.emit("vault-locked", ()).ok();
+}
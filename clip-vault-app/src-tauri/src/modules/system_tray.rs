@@ -1,30 +1,212 @@
-use crate::commands::{start_daemon, stop_daemon};
-use crate::modules::window_manager::{show_search_window, show_settings_window};
+use crate::commands::{set_incognito, start_daemon, stop_daemon};
+use crate::modules::session::lock_now;
+use crate::modules::window_manager::{
+    show_search_window, show_search_with_query, show_settings_window,
+};
 use crate::state::AppState;
+use clip_vault_core::{tr, Config, Msg};
+use serde::Serialize;
 use tauri::{
-    menu::{Menu, MenuItem},
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, Submenu},
     tray::{TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
+use tracing::warn;
+
+/// Menu ids of quick filters are this followed by the saved search's index.
+const QUICK_FILTER_PREFIX: &str = "quick_filter:";
+
+/// The tray's Quick Filters submenu, rebuilt when saved searches change.
+struct QuickFilters(Submenu<Wry>);
+
+/// Whether history is being recorded, as shown by the tray icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureStatus {
+    Capturing,
+    /// Captures go to a throwaway in-memory vault.
+    Incognito,
+    Paused,
+    Locked,
+    /// The monitor is running but its last capture failed.
+    Error,
+}
+
+impl CaptureStatus {
+    fn tooltip(self) -> &'static str {
+        tr(match self {
+            Self::Capturing => Msg::TrayRecording,
+            Self::Incognito => Msg::TrayIncognito,
+            Self::Paused => Msg::TrayPaused,
+            Self::Locked => Msg::TrayLocked,
+            Self::Error => Msg::TrayError,
+        })
+    }
+}
+
+/// Work out the capture status from the vault, session and daemon state.
+pub fn capture_status(app: &AppHandle) -> CaptureStatus {
+    let Some(state) = app.try_state::<AppState>() else {
+        return CaptureStatus::Locked;
+    };
+    if state.vault.lock().map_or(true, |vault| vault.is_none()) {
+        return CaptureStatus::Locked;
+    }
+    let (running, failing) = state.daemon.lock().map_or((false, false), |daemon| {
+        (daemon.is_running, daemon.last_error.is_some())
+    });
+    if !running {
+        CaptureStatus::Paused
+    } else if failing {
+        CaptureStatus::Error
+    } else if state
+        .incognito_stash
+        .lock()
+        .is_ok_and(|stash| stash.is_some())
+    {
+        CaptureStatus::Incognito
+    } else {
+        CaptureStatus::Capturing
+    }
+}
+
+/// Bring the tray icon and tooltip in line with the current state and tell
+/// the windows. Call after anything that locks, unlocks, starts or stops
+/// capturing.
+pub fn refresh_capture_status(app: &AppHandle) {
+    let status = capture_status(app);
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        tray.set_tooltip(Some(status.tooltip())).ok();
+        if let Some(icon) = app.default_window_icon() {
+            tray.set_icon(Some(status_icon(icon, status))).ok();
+        }
+    }
+    app.emit("capture-status", status).ok();
+}
+
+/// The app icon as is while recording, faded while paused or locked, and
+/// tinted red when captures are failing.
+fn status_icon(icon: &Image<'_>, status: CaptureStatus) -> Image<'static> {
+    let mut rgba = icon.rgba().to_vec();
+    for pixel in rgba.chunks_exact_mut(4) {
+        match status {
+            CaptureStatus::Capturing | CaptureStatus::Incognito => {}
+            CaptureStatus::Paused | CaptureStatus::Locked => {
+                let sum: u16 = pixel[..3].iter().map(|&c| u16::from(c)).sum();
+                let grey = u8::try_from(sum / 3).unwrap_or(u8::MAX);
+                pixel[..3].fill(grey);
+                pixel[3] /= 2;
+            }
+            CaptureStatus::Error => {
+                pixel[0] = pixel[0].saturating_add(96);
+                pixel[1] /= 2;
+                pixel[2] /= 2;
+            }
+        }
+    }
+    Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+/// Rebuild the Quick Filters submenu from the saved searches in the shared
+/// config.
+pub fn refresh_quick_filters(app: &AppHandle) {
+    if let Some(quick_filters) = app.try_state::<QuickFilters>() {
+        if let Err(e) = fill_quick_filters(app, &quick_filters.0) {
+            warn!("Failed to update quick filters: {}", e);
+        }
+    }
+}
+
+fn fill_quick_filters(app: &AppHandle, submenu: &Submenu<Wry>) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+    let searches = Config::load().unwrap_or_default().saved_searches;
+    if searches.is_empty() {
+        let hint = MenuItem::with_id(
+            app,
+            "quick_filter_none",
+            tr(Msg::MenuNoSavedSearches),
+            false,
+            None::<&str>,
+        )?;
+        submenu.append(&hint)?;
+    }
+    for (index, search) in searches.iter().enumerate() {
+        let item = MenuItem::with_id(
+            app,
+            format!("{QUICK_FILTER_PREFIX}{index}"),
+            &search.name,
+            true,
+            None::<&str>,
+        )?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Open the search window on the saved search with menu id `id`.
+fn open_quick_filter(app: &AppHandle, id: &str) {
+    let search = id
+        .strip_prefix(QUICK_FILTER_PREFIX)
+        .and_then(|index| index.parse::<usize>().ok())
+        .and_then(|index| {
+            Config::load()
+                .unwrap_or_default()
+                .saved_searches
+                .into_iter()
+                .nth(index)
+        });
+    match search {
+        Some(search) => show_search_with_query(app, &search.query),
+        // Changed outside the app since the menu was built.
+        None => refresh_quick_filters(app),
+    }
+}
 
 pub fn create_system_tray(app: &AppHandle) -> tauri::Result<()> {
-    let search_item = MenuItem::with_id(app, "search", "Search Clipboard", true, None::<&str>)?;
+    let search_item = MenuItem::with_id(app, "search", tr(Msg::MenuSearch), true, None::<&str>)?;
+    let quick_filters = Submenu::with_id(app, "quick_filters", tr(Msg::MenuQuickFilters), true)?;
+    fill_quick_filters(app, &quick_filters)?;
+    app.manage(QuickFilters(quick_filters.clone()));
     let separator1 = tauri::menu::PredefinedMenuItem::separator(app)?;
-    let daemon_start_item =
-        MenuItem::with_id(app, "daemon_start", "Start Daemon", true, None::<&str>)?;
-    let daemon_stop_item =
-        MenuItem::with_id(app, "daemon_stop", "Stop Daemon", true, None::<&str>)?;
+    let daemon_start_item = MenuItem::with_id(
+        app,
+        "daemon_start",
+        tr(Msg::MenuStartDaemon),
+        true,
+        None::<&str>,
+    )?;
+    let daemon_stop_item = MenuItem::with_id(
+        app,
+        "daemon_stop",
+        tr(Msg::MenuStopDaemon),
+        true,
+        None::<&str>,
+    )?;
+    let incognito_item = CheckMenuItem::with_id(
+        app,
+        "incognito",
+        tr(Msg::MenuIncognito),
+        true,
+        false,
+        None::<&str>,
+    )?;
+    let lock_item = MenuItem::with_id(app, "lock", tr(Msg::MenuLockNow), true, None::<&str>)?;
     let separator2 = tauri::menu::PredefinedMenuItem::separator(app)?;
-    let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit Clip Vault", true, None::<&str>)?;
+    let settings_item =
+        MenuItem::with_id(app, "settings", tr(Msg::MenuSettings), true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", tr(Msg::MenuQuit), true, None::<&str>)?;
 
     let menu = Menu::with_items(
         app,
         &[
             &search_item,
+            &quick_filters,
             &separator1,
             &daemon_start_item,
             &daemon_stop_item,
+            &incognito_item,
+            &lock_item,
             &separator2,
             &settings_item,
             &quit_item,
@@ -34,7 +216,7 @@ pub fn create_system_tray(app: &AppHandle) -> tauri::Result<()> {
     TrayIconBuilder::with_id("main-tray")
         .menu(&menu)
         .icon(app.default_window_icon().unwrap().clone())
-        .tooltip("Clip Vault")
+        .tooltip(capture_status(app).tooltip())
         .on_menu_event(move |app, event| match event.id().as_ref() {
             "search" => {
                 show_search_window(app);
@@ -59,12 +241,33 @@ pub fn create_system_tray(app: &AppHandle) -> tauri::Result<()> {
                     }
                 });
             }
+            "incognito" => {
+                let app_handle = app.clone();
+                let item = incognito_item.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(state) = app_handle.try_state::<AppState>() {
+                        let enabled = item.is_checked().unwrap_or(false);
+                        if let Err(e) = set_incognito(enabled, state, app_handle.clone()).await {
+                            eprintln!("Failed to toggle incognito mode: {e}");
+                            item.set_checked(!enabled).ok();
+                        }
+                    }
+                });
+            }
+            "lock" => {
+                lock_now(app);
+                // The CLI's cached password goes too.
+                if let Err(e) = clip_vault_core::request_lock() {
+                    warn!("Failed to ask other processes to lock: {}", e);
+                }
+            }
             "settings" => {
                 show_settings_window(app);
             }
             "quit" => {
                 app.exit(0);
             }
+            id if id.starts_with(QUICK_FILTER_PREFIX) => open_quick_filter(app, id),
             _ => {}
         })
         .on_tray_icon_event(|_tray, event| {
@@ -0,0 +1,125 @@
+use clip_vault_core::Config;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tracing::{error, info};
+
+const STABLE_ENDPOINT: &str =
+    "https://github.com/densumesh/clip-vault/releases/latest/download/latest.json";
+/// Pre-releases publish their manifest under the moving `beta` tag.
+const BETA_ENDPOINT: &str =
+    "https://github.com/densumesh/clip-vault/releases/download/beta/latest.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    fn endpoint(self) -> &'static str {
+        match self {
+            Self::Stable => STABLE_ENDPOINT,
+            Self::Beta => BETA_ENDPOINT,
+        }
+    }
+}
+
+/// The update choices from the app's settings, kept in the `app` section
+/// of the shared config so they survive restarts.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdatePreferences {
+    pub update_channel: UpdateChannel,
+    pub install_updates_on_quit: bool,
+}
+
+impl UpdatePreferences {
+    /// The saved choices, or the defaults if there are none.
+    pub fn load() -> Self {
+        Config::load()
+            .ok()
+            .and_then(|config| config.app)
+            .and_then(|app| serde_json::from_value(app).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the choices, leaving the rest of the `app` section as it is.
+    pub fn save(self) -> Result<(), String> {
+        let mut config = Config::load().map_err(|e| format!("Failed to read config: {e}"))?;
+        let mut app = match config.app.take() {
+            Some(Value::Object(app)) => app,
+            _ => Map::new(),
+        };
+        if let Value::Object(choices) = serde_json::to_value(self).map_err(|e| e.to_string())? {
+            app.extend(choices);
+        }
+        config.app = Some(Value::Object(app));
+        config
+            .save()
+            .map_err(|e| format!("Failed to save config: {e}"))
+    }
+}
+
+/// A downloaded update waiting for the app to quit.
+#[derive(Default)]
+pub struct PendingUpdate(Mutex<Option<(Update, Vec<u8>)>>);
+
+/// Look for a newer release on `channel` without downloading it.
+pub async fn check(app: &AppHandle, channel: UpdateChannel) -> Result<Option<Update>, String> {
+    let endpoint = channel
+        .endpoint()
+        .parse::<Url>()
+        .map_err(|e| format!("Invalid update endpoint: {e}"))?;
+    let updater = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(tauri_plugin_updater::UpdaterBuilder::build)
+        .map_err(|e| format!("Failed to get updater: {e}"))?;
+    updater
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {e}"))
+}
+
+/// Download `update` now and hold it until [`install_pending`] runs on quit.
+/// Returns the version that will be installed.
+pub async fn schedule(app: &AppHandle, update: Update) -> Result<String, String> {
+    let version = update.version.clone();
+    info!("Downloading update {version} to install on quit");
+    let bytes = update
+        .download(|_, _| {}, || {})
+        .await
+        .map_err(|e| format!("Failed to download update: {e}"))?;
+
+    let pending = app.state::<PendingUpdate>();
+    *pending.0.lock().map_err(|_| "Update lock poisoned")? = Some((update, bytes));
+    let _ = app.emit("update-scheduled", &version);
+    Ok(version)
+}
+
+/// Drop a scheduled update. Returns whether one was waiting.
+pub fn cancel(app: &AppHandle) -> bool {
+    app.try_state::<PendingUpdate>()
+        .and_then(|pending| pending.0.lock().ok()?.take())
+        .is_some()
+}
+
+/// Install the update scheduled by [`schedule`], if any. Called as the app
+/// exits; on Windows the installer takes over and this does not return.
+pub fn install_pending(app: &AppHandle) {
+    let Some(pending) = app.try_state::<PendingUpdate>() else {
+        return;
+    };
+    let Some((update, bytes)) = pending.0.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    info!("Installing update {} before exiting", update.version);
+    if let Err(e) = update.install(bytes) {
+        error!("Failed to install scheduled update: {e}");
+    }
+}
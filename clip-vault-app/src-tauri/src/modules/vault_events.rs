@@ -0,0 +1,177 @@
+use clip_vault_core::{hash_to_hex, HealthEvent, HealthLevel, SqliteVault, Vault, VaultEvent};
+use serde::Serialize;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{error, info, warn};
+
+use crate::commands::{HealthNotice, ItemEvent, ItemUpdated};
+use crate::state::{current_timestamp, AppState};
+
+/// Health notices kept for `daemon_status`.
+const HEALTH_HISTORY: usize = 20;
+
+/// Changes arriving this close together are treated as one burst: a change
+/// is held this long in case another follows.
+const BATCH_WINDOW: Duration = Duration::from_millis(100);
+/// A long burst is still reported this often, so the UI keeps up with a
+/// large import or prune instead of waiting for it to end.
+const MAX_BATCH_DURATION: Duration = Duration::from_millis(500);
+/// Bursts of up to this many changes are sent as their individual events;
+/// larger ones become a single `clipboard-updated` with a [`ChangeBatch`].
+const BATCH_THRESHOLD: usize = 8;
+
+/// Payload of `clipboard-updated`: what changed since the last update, so
+/// the frontend reloads once per burst rather than once per entry.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ChangeBatch {
+    pub added: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    /// Whether another connection wrote to the vault, changing entries no
+    /// event names.
+    pub external: bool,
+    /// When the first and last change of the batch arrived, in milliseconds
+    /// since the Unix epoch.
+    pub first_at: u64,
+    pub last_at: u64,
+}
+
+impl ChangeBatch {
+    fn record(&mut self, event: &VaultEvent) {
+        match event {
+            VaultEvent::Added { .. } => self.added += 1,
+            VaultEvent::Updated { .. } => self.updated += 1,
+            VaultEvent::Deleted { .. } => self.deleted += 1,
+            _ => self.external = true,
+        }
+    }
+}
+
+/// Relay the vault's change notifications to the frontend, coalescing
+/// bursts (see [`ChangeBatch`]). A change is sent once [`BATCH_WINDOW`]
+/// passes without another, or [`MAX_BATCH_DURATION`] after the first of a
+/// long burst. The thread ends on its own once the vault is dropped (lock,
+/// incognito switch).
+pub fn forward_vault_events(vault: &SqliteVault, app_handle: AppHandle) {
+    forward_health(vault.subscribe_health(), app_handle.clone());
+    let events = vault.subscribe();
+    std::thread::spawn(move || {
+        while let Ok(first) = events.recv() {
+            let started = Instant::now();
+            let first_at = now_millis();
+            let mut last_at = first_at;
+            let mut burst = vec![first];
+            let mut open = true;
+            while let Some(left) = MAX_BATCH_DURATION.checked_sub(started.elapsed()) {
+                match events.recv_timeout(BATCH_WINDOW.min(left)) {
+                    Ok(event) => {
+                        burst.push(event);
+                        last_at = now_millis();
+                    }
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        open = false;
+                        break;
+                    }
+                }
+            }
+            emit_burst(&app_handle, burst, first_at, last_at);
+            if !open {
+                break;
+            }
+        }
+    });
+}
+
+fn emit_burst(app_handle: &AppHandle, burst: Vec<VaultEvent>, first_at: u64, last_at: u64) {
+    let batch = |events: &[VaultEvent]| {
+        let mut batch = ChangeBatch {
+            first_at,
+            last_at,
+            ..ChangeBatch::default()
+        };
+        for event in events {
+            batch.record(event);
+        }
+        batch
+    };
+    if burst.len() > BATCH_THRESHOLD {
+        app_handle.emit("clipboard-updated", batch(&burst)).ok();
+        return;
+    }
+    for event in burst {
+        match event {
+            VaultEvent::Added { hash } => app_handle.emit(
+                "item-added",
+                ItemEvent {
+                    hash: hash_to_hex(&hash),
+                },
+            ),
+            VaultEvent::Deleted { hash } => app_handle.emit(
+                "item-deleted",
+                ItemEvent {
+                    hash: hash_to_hex(&hash),
+                },
+            ),
+            VaultEvent::Updated { old_hash, hash } => app_handle.emit(
+                "item-updated",
+                ItemUpdated {
+                    old_hash: hash_to_hex(&old_hash),
+                    hash: hash_to_hex(&hash),
+                },
+            ),
+            _ => app_handle.emit("clipboard-updated", batch(&[event])),
+        }
+        .ok();
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Log the vault's health events, keep the latest for `daemon_status` and
+/// pass them to the frontend as `vault-health`. Warnings and errors also
+/// get a notification, since the search window may not be open while
+/// copies are being lost.
+fn forward_health(events: Receiver<HealthEvent>, app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        for event in events {
+            let notice = HealthNotice {
+                level: event.level(),
+                message: event.to_string(),
+                timestamp: current_timestamp(),
+                event,
+            };
+            match notice.level {
+                HealthLevel::Info => info!("{}", notice.message),
+                HealthLevel::Warning => warn!("{}", notice.message),
+                HealthLevel::Error => error!("{}", notice.message),
+            }
+            if let Some(state) = app_handle.try_state::<AppState>() {
+                if let Ok(mut daemon_guard) = state.daemon.lock() {
+                    daemon_guard.health.push_back(notice.clone());
+                    if daemon_guard.health.len() > HEALTH_HISTORY {
+                        daemon_guard.health.pop_front();
+                    }
+                }
+            }
+            if notice.level >= HealthLevel::Warning {
+                if let Err(e) = app_handle
+                    .notification()
+                    .builder()
+                    .title("Clip Vault")
+                    .body(&notice.message)
+                    .show()
+                {
+                    warn!("Failed to show vault health notification: {}", e);
+                }
+            }
+            app_handle.emit("vault-health", notice).ok();
+        }
+    });
+}
@@ -1,7 +1,31 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::state::{AppState, WindowBehavior};
+
+/// The configured search window behavior.
+pub fn search_window_behavior(app: &AppHandle) -> WindowBehavior {
+    app.try_state::<AppState>()
+        .and_then(|state| state.settings.lock().ok().map(|s| s.search_window))
+        .unwrap_or_default()
+}
+
+/// Apply `behavior` to the search window, if it has been created.
+pub fn apply_search_window_behavior(app: &AppHandle, behavior: WindowBehavior) {
+    if let Some(window) = app.get_webview_window("search") {
+        set_window_behavior(&window, behavior);
+    }
+}
+
+fn set_window_behavior(window: &WebviewWindow, behavior: WindowBehavior) {
+    window.set_always_on_top(behavior.always_on_top).ok();
+    window.set_skip_taskbar(!behavior.show_in_taskbar).ok();
+}
 
 pub fn show_search_window(app: &AppHandle) {
+    let behavior = search_window_behavior(app);
     if let Some(window) = app.get_webview_window("search") {
+        set_window_behavior(&window, behavior);
         window.show().ok();
         window.set_focus().ok();
     } else {
@@ -13,14 +37,53 @@ pub fn show_search_window(app: &AppHandle) {
                 .center()
                 .resizable(true)
                 .decorations(false)
-                .always_on_top(true)
-                .skip_taskbar(true)
+                .always_on_top(behavior.always_on_top)
+                .skip_taskbar(!behavior.show_in_taskbar)
                 .transparent(true)
                 .build()
                 .expect("Failed to create search window");
     }
 }
 
+/// A query for the search window to start with the next time it gains
+/// focus, instead of an empty one.
+#[derive(Default)]
+pub struct PendingSearch(Mutex<Option<String>>);
+
+impl PendingSearch {
+    pub fn take(&self) -> Option<String> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+/// Show the search window already filtered by `query`, as the tray's quick
+/// filters do.
+pub fn show_search_with_query(app: &AppHandle, query: &str) {
+    let focused = app.get_webview_window("search").filter(|window| {
+        window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false)
+    });
+    if let Some(window) = focused {
+        // No focus event is coming to pick up a pending query.
+        window.emit("apply-search", query).ok();
+        return;
+    }
+    if let Some(pending) = app.try_state::<PendingSearch>() {
+        if let Ok(mut slot) = pending.0.lock() {
+            *slot = Some(query.to_string());
+        }
+    }
+    show_search_window(app);
+}
+
+/// Hide the search window and tell it to reset, as Esc or clicking away
+/// does.
+pub fn dismiss_search_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("search") {
+        window.hide().ok();
+        window.emit("search-dismissed", ()).ok();
+    }
+}
+
 pub fn show_settings_window(app: &AppHandle) {
     if let Some(window) = app.get_webview_window("settings") {
         window.show().ok();
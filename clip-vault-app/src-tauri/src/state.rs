@@ -1,15 +1,73 @@
-use clip_vault_core::{default_db_path, SqliteVault};
+use clip_vault_core::{
+    default_db_path, AlertConfig, CaptureTransform, OverflowPolicy, SqliteVault, TextLimit,
+    Timestamp,
+};
+use clip_vault_monitor::CaptureTrace;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::commands::HealthNotice;
+use crate::modules::logging::LogLevel;
+use crate::modules::updates::{UpdateChannel, UpdatePreferences};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, TryLockError};
 use tokio::sync::mpsc;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct AppSettings {
     pub poll_interval_ms: u64,
     pub vault_path: String,
     pub auto_lock_minutes: u32,
     pub global_shortcut: String,
+    /// Cap on captured text; oversized copies are skipped or truncated.
+    pub text_limit: TextLimit,
+    /// Drop EXIF/GPS and other metadata from captured images before storing.
+    pub strip_image_metadata: bool,
+    /// Application-specific formats kept with each capture, at most this
+    /// many; 0 keeps none.
+    pub capture_custom_formats: usize,
+    /// Cleanups applied to captured text, such as removing terminal colours.
+    pub capture_transforms: Vec<CaptureTransform>,
+    /// Notify about large, binary-looking or secret captures.
+    pub capture_alerts: AlertConfig,
+    /// Record the app and window title each capture came from. Apps with a
+    /// TTL are still looked up when this is off.
+    pub record_source_app: bool,
+    pub search_window: WindowBehavior,
+    /// How much goes into the log file.
+    pub log_level: LogLevel,
+    pub update_channel: UpdateChannel,
+    /// Download new releases in the background and install them on quit
+    /// instead of asking first.
+    pub install_updates_on_quit: bool,
+    /// What capture gives up when the clipboard changes faster than
+    /// entries can be stored.
+    pub capture_overflow: OverflowPolicy,
+    /// Read a short description of each copy aloud with the OS speech
+    /// synthesizer, for using the app without watching the toast.
+    pub speak_on_copy: bool,
+}
+
+/// How the search window sits among other windows. The defaults make it a
+/// floating palette; turning them around makes it a normal window.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct WindowBehavior {
+    /// List the window in the taskbar or Dock.
+    pub show_in_taskbar: bool,
+    pub always_on_top: bool,
+    /// Hide the window when another one takes focus.
+    pub hide_on_focus_loss: bool,
+}
+
+impl Default for WindowBehavior {
+    fn default() -> Self {
+        Self {
+            show_in_taskbar: false,
+            always_on_top: true,
+            hide_on_focus_loss: true,
+        }
+    }
 }
 
 impl Default for AppSettings {
@@ -23,10 +81,56 @@ impl Default for AppSettings {
             } else {
                 "Shift+Ctrl+C".to_string()
             },
+            text_limit: TextLimit::default(),
+            strip_image_metadata: true,
+            capture_custom_formats: 0,
+            capture_transforms: Vec::new(),
+            capture_alerts: AlertConfig::default(),
+            record_source_app: true,
+            search_window: WindowBehavior::default(),
+            log_level: LogLevel::default(),
+            update_channel: UpdateChannel::default(),
+            install_updates_on_quit: false,
+            capture_overflow: OverflowPolicy::default(),
+            speak_on_copy: false,
+        }
+    }
+}
+
+impl AppSettings {
+    /// The defaults, with the choices that are kept across restarts put
+    /// back.
+    fn with_saved_choices() -> Self {
+        let updates = UpdatePreferences::load();
+        Self {
+            update_channel: updates.update_channel,
+            install_updates_on_quit: updates.install_updates_on_quit,
+            ..Self::default()
+        }
+    }
+
+    pub fn update_preferences(&self) -> UpdatePreferences {
+        UpdatePreferences {
+            update_channel: self.update_channel,
+            install_updates_on_quit: self.install_updates_on_quit,
         }
     }
 }
 
+fn clear_after_panic<T>(slot: &Mutex<Option<T>>, what: &str, notes: &mut Vec<String>) {
+    let cleared = match slot.try_lock() {
+        Ok(mut guard) => guard.take().is_some(),
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().take().is_some(),
+        Err(TryLockError::WouldBlock) => {
+            notes.push(format!("{what} was in use and is now unusable"));
+            return;
+        }
+    };
+    if cleared {
+        notes.push(format!("{what} cleared"));
+    }
+}
+
 #[derive(Debug)]
 pub struct SessionInfo {
     pub last_activity: u64,
@@ -37,21 +141,157 @@ pub struct DaemonState {
     pub is_running: bool,
     pub shutdown_sender: Option<mpsc::UnboundedSender<()>>,
     pub last_hash: Option<[u8; 32]>,
+    /// Why the last capture failed, cleared by the next one that succeeds.
+    pub last_error: Option<String>,
+    /// Hash of what the app itself last put on the clipboard, as the
+    /// monitor will read it, so copying from the search window doesn't
+    /// store the entry again. Cleared by the next real capture.
+    pub own_copy: Option<[u8; 32]>,
+    /// Log every capture decision; set by `--trace-capture`.
+    pub trace_capture: bool,
+    /// Latest capture decisions, kept whether or not they are logged.
+    pub trace: CaptureTrace,
+    /// Latest vault health notices, oldest first.
+    pub health: VecDeque<HealthNotice>,
 }
 
 pub struct AppState {
     /// Vault is optional - only initialized after successful unlock
     pub vault: Arc<Mutex<Option<SqliteVault>>>,
+    /// The on-disk vault, set aside while incognito mode has swapped an
+    /// in-memory vault into `vault`.
+    pub incognito_stash: Arc<Mutex<Option<SqliteVault>>>,
+    /// A backup snapshot opened read-only to browse next to the vault.
+    pub backup: Arc<Mutex<Option<SqliteVault>>>,
+    /// Vault profiles unlocked for searching across profiles, by name, each
+    /// open read-only.
+    pub profiles: Arc<Mutex<Vec<(String, SqliteVault)>>>,
     pub settings: Arc<Mutex<AppSettings>>,
     pub session: Arc<Mutex<Option<SessionInfo>>>,
     pub daemon: Arc<Mutex<DaemonState>>,
 }
 
 impl AppState {
+    /// Lock the vault after a panic: drop the vault handles, end the session
+    /// and stop the monitor. Returns notes for the crash report.
+    ///
+    /// Runs on the panicking thread, so locks are only tried; one that
+    /// thread holds is poisoned as it unwinds, and commands then refuse to
+    /// use what it guards.
+    pub fn lock_after_panic(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        clear_after_panic(&self.vault, "vault", &mut notes);
+        clear_after_panic(&self.incognito_stash, "incognito stash", &mut notes);
+        clear_after_panic(&self.backup, "backup", &mut notes);
+        match self.profiles.try_lock() {
+            Ok(mut profiles) => profiles.clear(),
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().clear(),
+            Err(TryLockError::WouldBlock) => {
+                notes.push("profiles were in use and are now unusable".to_string());
+            }
+        }
+        clear_after_panic(&self.session, "session", &mut notes);
+
+        let shutdown = match self.daemon.try_lock() {
+            Ok(mut daemon) => daemon.shutdown_sender.take(),
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().shutdown_sender.take(),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        if let Some(sender) = shutdown {
+            sender.send(()).ok();
+            notes.push("monitor asked to stop".to_string());
+        }
+        notes
+    }
+
+    /// Lock the vault if the session has been idle for longer than the
+    /// auto-lock setting allows. Returns whether it locked.
+    pub fn lock_if_expired(&self) -> Result<bool, String> {
+        let auto_lock_minutes = self
+            .settings
+            .lock()
+            .map_err(|_| "Settings lock poisoned")?
+            .auto_lock_minutes;
+        let mut session = self.session.lock().map_err(|_| "Session lock poisoned")?;
+        if !session
+            .as_ref()
+            .is_some_and(|s| is_session_expired(s, auto_lock_minutes))
+        {
+            return Ok(false);
+        }
+        drop(session);
+        self.lock_now()
+    }
+
+    /// Lock the vault: end the session and drop the open vault, which
+    /// stops the monitor. Returns whether it was unlocked.
+    pub fn lock_now(&self) -> Result<bool, String> {
+        let ended = self
+            .session
+            .lock()
+            .map_err(|_| "Session lock poisoned")?
+            .take()
+            .is_some();
+        let closed = self
+            .vault
+            .lock()
+            .map_err(|_| "Vault lock poisoned")?
+            .take()
+            .is_some();
+        self.incognito_stash
+            .lock()
+            .map_err(|_| "Vault lock poisoned")?
+            .take();
+        self.backup
+            .lock()
+            .map_err(|_| "Backup lock poisoned")?
+            .take();
+        self.profiles
+            .lock()
+            .map_err(|_| "Profiles lock poisoned")?
+            .clear();
+        Ok(ended || closed)
+    }
+
+    /// Push auto-lock back after user activity. Returns whether there was a
+    /// session to extend.
+    pub fn touch_session(&self) -> Result<bool, String> {
+        let mut session = self.session.lock().map_err(|_| "Session lock poisoned")?;
+        Ok(match session.as_mut() {
+            Some(session) => {
+                session.last_activity = current_timestamp();
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Clones sharing this state's handles, for the panic hook.
+    fn share(&self) -> Self {
+        Self {
+            vault: self.vault.clone(),
+            incognito_stash: self.incognito_stash.clone(),
+            backup: self.backup.clone(),
+            profiles: self.profiles.clone(),
+            settings: self.settings.clone(),
+            session: self.session.clone(),
+            daemon: self.daemon.clone(),
+        }
+    }
+
+    /// Lock the vault if any thread panics.
+    pub fn install_panic_hook(&self) {
+        let state = self.share();
+        clip_vault_core::install_panic_hook("app", move || state.lock_after_panic());
+    }
+
     pub fn new() -> Self {
         Self {
             vault: Arc::new(Mutex::new(None)), // No vault initialized
-            settings: Arc::new(Mutex::new(AppSettings::default())),
+            incognito_stash: Arc::new(Mutex::new(None)),
+            backup: Arc::new(Mutex::new(None)),
+            profiles: Arc::new(Mutex::new(Vec::new())),
+            settings: Arc::new(Mutex::new(AppSettings::with_saved_choices())),
             session: Arc::new(Mutex::new(None)), // No session active
             daemon: Arc::new(Mutex::new(DaemonState::default())), // No daemon running
         }
@@ -59,10 +299,7 @@ impl AppState {
 }
 
 pub fn current_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
+    Timestamp::now().as_secs()
 }
 
 pub fn is_session_expired(session: &SessionInfo, auto_lock_minutes: u32) -> bool {
@@ -1,6 +1,14 @@
 import { useState, useEffect } from "react";
-import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import "./App.css";
+import type {
+  Action,
+  CopyFormat,
+  MonitorFailure,
+  PaletteMode,
+  VaultHealth,
+} from "./types";
 
 // Components
 import {
@@ -9,26 +17,46 @@ import {
   PreviewPane,
   PasswordPrompt,
   OnboardingFlow,
+  RecoveryKeyNotice,
+  CommandPalette,
   UpdateNotification,
+  TimelineView,
 } from "./components";
 
 // Hooks
 import { useClipboardSearch } from "./hooks/useClipboardSearch";
 import { useVault } from "./hooks/useVault";
 import { useKeyboardNavigation } from "./hooks/useKeyboardNavigation";
+import { useBackendSelection } from "./hooks/useBackendSelection";
 import { useClipboardUpdates } from "./hooks/useClipboardUpdates";
 import { UpdateService } from "./services/updateService";
+import { ClipboardService } from "./services/clipboardService";
+import { VaultService } from "./services/vaultService";
 
 // Utils
 import {
-  formatTimestamp,
   getWindowedContent,
   highlightText,
 } from "./utils/textUtils";
+import { formatKeys } from "./utils/shortcuts";
+
+/** The format each "copy as" action reshapes the entry into. */
+const COPY_AS: Record<string, CopyFormat> = {
+  copy_json_minified: "json_minified",
+  copy_json_pretty: "json_pretty",
+  copy_shell_escaped: "shell_escaped",
+  copy_code_block: "markdown_code_block",
+  copy_color_hex: "color_hex",
+  copy_color_rgb: "color_rgb",
+  copy_color_hsl: "color_hsl",
+};
 
 function App() {
   const [searching, setSearching] = useState(true);
   const [justCopied, setJustCopied] = useState(false);
+  const [palette, setPalette] = useState<PaletteMode | null>(null);
+  const [editRequest, setEditRequest] = useState(0);
+  const [tab, setTab] = useState<"history" | "timeline">("history");
   // Custom hooks
   const {
     query,
@@ -39,9 +67,14 @@ function App() {
     loading,
     loadingMore,
     hasMore,
+    allProfiles,
+    setAllProfiles,
+    lockedProfiles,
     searchClipboard,
     loadMore,
     copyToClipboard,
+    addItem,
+    removeItem,
   } = useClipboardSearch();
 
   const {
@@ -50,18 +83,116 @@ function App() {
     showOnboarding,
     password,
     setPassword,
+    passwordHint,
+    recoveryKey,
+    dismissRecoveryKey,
     checkVaultStatus,
     handleUnlock,
     handleCancel,
     handleOnboardingComplete,
+    existingVaults,
+    handleAdoptVault,
   } = useVault();
 
+  const closeWindow = () => invoke("dismiss_search");
+
   // Handle copy with toast notification
   const handleCopy = async (content: string, contentType: string) => {
     setJustCopied(true);
     await copyToClipboard(content, contentType);
-    const window = getCurrentWebviewWindow();
-    window.hide();
+    await closeWindow();
+  };
+
+  // Every action the window offers, from a shortcut or the palette
+  const runAction = async (action: Action) => {
+    setPalette(null);
+    let item = results[selectedIndex];
+    try {
+      // A large image listed by its thumbnail is loaded before it's used.
+      if (item?.thumbnail && !item.content && item.entry_id != null) {
+        const content = await ClipboardService.getItemContent(item.entry_id);
+        item = { ...item, content: content ?? "" };
+      }
+      switch (action.id) {
+        case "copy":
+          if (item) await handleCopy(item.content, item.content_type);
+          break;
+        case "paste_plain":
+          // Copies from the app only ever carry the text flavour.
+          if (item) await handleCopy(item.content, "text/plain");
+          break;
+        case "copy_markdown":
+          if (item?.entry_id != null) await invoke("copy_as_markdown", { id: item.entry_id });
+          break;
+        case "copy_json_minified":
+        case "copy_json_pretty":
+        case "copy_shell_escaped":
+        case "copy_code_block":
+        case "copy_color_hex":
+        case "copy_color_rgb":
+        case "copy_color_hsl":
+          if (item?.entry_id != null) await ClipboardService.copyAs(item.entry_id, COPY_AS[action.id]);
+          break;
+        case "edit":
+          setEditRequest((n) => n + 1);
+          break;
+        case "tag":
+          setPalette("tag");
+          break;
+        case "pin":
+          // The vault emits item-updated, which reloads the results.
+          if (item?.entry_id != null) await ClipboardService.togglePin(item.entry_id);
+          break;
+        case "protect":
+          // The vault emits item-deleted, which drops it from the results.
+          if (item?.entry_id != null) await invoke("protect_item", { id: item.entry_id });
+          break;
+        case "delete":
+          if (item?.entry_id == null) break;
+          if ((await VaultService.getUnlockPolicy()) === "keyring_confirm_sensitive") {
+            setPalette("confirm_delete");
+          } else {
+            await ClipboardService.deleteItem(item.entry_id);
+          }
+          break;
+        case "focus_search":
+          setSearching(true);
+          break;
+        case "command_palette":
+          setPalette("actions");
+          break;
+        case "open_settings":
+          await invoke("open_settings_window");
+          break;
+        case "close":
+          await closeWindow();
+          break;
+      }
+    } catch (error) {
+      console.error(`Action ${action.id} failed:`, error);
+    }
+  };
+
+  const handleTag = async (tag: string) => {
+    setPalette(null);
+    const item = results[selectedIndex];
+    if (item?.entry_id == null) return;
+    try {
+      await ClipboardService.tagItem(item.entry_id, tag);
+    } catch {
+      // Logged by the service
+    }
+  };
+
+  const handleConfirmDelete = async (password: string) => {
+    setPalette(null);
+    const item = results[selectedIndex];
+    if (item?.entry_id == null) return;
+    try {
+      await ClipboardService.deleteItem(item.entry_id, password);
+    } catch {
+      alert("Invalid password. Nothing was deleted.");
+    }
   };
 
   // Keyboard navigation
@@ -69,11 +200,11 @@ function App() {
     results,
     selectedIndex,
     setSelectedIndex,
-    onCopy: handleCopy,
+    onAction: runAction,
     showPasswordPrompt,
-    setSearching,
-    setQuery,
+    paletteOpen: palette !== null,
   });
+  useBackendSelection({ results, selectedIndex, setSelectedIndex });
 
   // Clipboard updates
   useClipboardUpdates({
@@ -83,6 +214,12 @@ function App() {
         searchClipboard(query);
       }
     },
+    onItemAdded: (hash) => {
+      if (!justCopied) {
+        addItem(hash);
+      }
+    },
+    onItemDeleted: removeItem,
     showPasswordPrompt,
   });
 
@@ -94,6 +231,11 @@ function App() {
     // Check for updates once on app load
     const checkUpdates = async () => {
       try {
+        const settings = await VaultService.getSettings();
+        if (settings.install_updates_on_quit) {
+          await UpdateService.scheduleInstallOnQuit();
+          return;
+        }
         const version = await UpdateService.checkForUpdates();
         if (version) {
           setUpdateVersion(version);
@@ -111,26 +253,70 @@ function App() {
     setShowUpdateNotification(false);
   };
 
-  // Window management
+  // Capture failures from the monitor, cleared once it recovers
+  const [monitorError, setMonitorError] = useState<string | null>(null);
   useEffect(() => {
-    const handleWindowBlur = () => {
-      // Clear search query when window closes
-      setQuery("");
-      const window = getCurrentWebviewWindow();
-      window.hide();
+    const unlisteners = [
+      listen<MonitorFailure>("monitor-failed", ({ payload }) => {
+        const next =
+          payload.retry_in_ms === null
+            ? "Restart the app to capture again."
+            : `Retrying in ${Math.ceil(payload.retry_in_ms / 1000)}s.`;
+        setMonitorError(
+          `Clipboard capture failed ${payload.consecutive_failures}× (${payload.error}). ${next}`,
+        );
+      }),
+      listen<string>("capture-status", ({ payload }) => {
+        if (payload !== "error") setMonitorError(null);
+      }),
+    ];
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
     };
+  }, []);
 
-    const handleWindowFocus = async () => {
-      // Clear search query and check vault status when window gains focus
+  // Vault problems, e.g. copies no longer being saved, until they clear
+  const [vaultHealth, setVaultHealth] = useState<string | null>(null);
+  useEffect(() => {
+    const unlisteners = [
+      listen<VaultHealth>("vault-health", ({ payload }) => {
+        if (payload.kind === "write_recovered") {
+          setVaultHealth(null);
+        } else if (payload.level !== "info") {
+          setVaultHealth(payload.message);
+        }
+      }),
+    ];
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, []);
+
+  // Window management. The backend hides the window on Esc or when it
+  // loses focus (if configured) and tells us to reset.
+  useEffect(() => {
+    const unlisten = listen("search-dismissed", () => {
+      setPalette(null);
       setQuery("");
+    });
+    // A tray quick filter picked while the window already had focus
+    const unlistenApply = listen<string>("apply-search", ({ payload }) => {
+      setPalette(null);
+      setQuery(payload);
+    });
+
+    const handleWindowFocus = async () => {
+      // Start from an empty search, or the quick filter that opened the
+      // window, and check vault status when window gains focus
+      setQuery((await VaultService.takePendingSearch()) ?? "");
       await checkVaultStatus();
     };
 
-    window.addEventListener("blur", handleWindowBlur);
     window.addEventListener("focus", handleWindowFocus);
 
     return () => {
-      window.removeEventListener("blur", handleWindowBlur);
+      unlisten.then((fn) => fn());
+      unlistenApply.then((fn) => fn());
       window.removeEventListener("focus", handleWindowFocus);
     };
   }, [checkVaultStatus, setQuery]);
@@ -155,38 +341,73 @@ function App() {
         resultsCount={results.length}
         searching={searching}
         setSearching={setSearching}
+        allProfiles={allProfiles}
+        onAllProfilesChange={setAllProfiles}
+        lockedProfiles={lockedProfiles}
       />
 
-      <div className="main-content">
-        <div className="results-panel">
-          <ResultsList
-            results={results}
-            selectedIndex={selectedIndex}
-            query={query}
-            onSelect={setSelectedIndex}
-            loading={loading}
-            loadingMore={loadingMore}
-            hasMore={hasMore}
-            onLoadMore={loadMore}
-            formatTimestamp={formatTimestamp}
-            getWindowedContent={getWindowedContent}
-            highlightText={highlightText}
+      <div className="tab-bar">
+        <button
+          className={`tab ${tab === "history" ? "active" : ""}`}
+          onClick={() => setTab("history")}
+        >
+          History
+        </button>
+        <button
+          className={`tab ${tab === "timeline" ? "active" : ""}`}
+          onClick={() => setTab("timeline")}
+        >
+          Timeline
+        </button>
+      </div>
+
+      {tab === "timeline" ? (
+        <TimelineView onCopy={handleCopy} />
+      ) : (
+        <div className="main-content">
+          <div className="results-panel">
+            <ResultsList
+              results={results}
+              selectedIndex={selectedIndex}
+              query={query}
+              onSelect={setSelectedIndex}
+              loading={loading}
+              loadingMore={loadingMore}
+              hasMore={hasMore}
+              onLoadMore={loadMore}
+              getWindowedContent={getWindowedContent}
+              highlightText={highlightText}
+            />
+          </div>
+
+          <PreviewPane
+            selectedItem={results[selectedIndex] || null}
+            onCopy={handleCopy}
+            editRequest={editRequest}
           />
         </div>
+      )}
 
-        <PreviewPane
-          selectedItem={results[selectedIndex] || null}
-          onCopy={handleCopy}
-        />
-      </div>
+      {monitorError && <div className="monitor-error">{monitorError}</div>}
+      {vaultHealth && <div className="monitor-error">{vaultHealth}</div>}
 
       <div className="help-text">
-        Use ↑↓ to navigate • Enter to copy • Esc to close
+        Use ↑↓ to navigate • Enter to copy • {formatKeys("Mod+K")} for all actions • Esc to close
       </div>
 
+      <CommandPalette
+        mode={palette}
+        selectedItem={results[selectedIndex] || null}
+        onRun={runAction}
+        onTag={handleTag}
+        onConfirmDelete={handleConfirmDelete}
+        onClose={() => setPalette(null)}
+      />
+
       <PasswordPrompt
         isVisible={showPasswordPrompt}
         password={password}
+        hint={passwordHint}
         onPasswordChange={setPassword}
         onUnlock={handleUnlock}
         onCancel={handleCancel}
@@ -194,9 +415,18 @@ function App() {
 
       <OnboardingFlow
         isVisible={showOnboarding}
+        existingVaults={existingVaults}
         onComplete={handleOnboardingComplete}
+        onAdopt={handleAdoptVault}
       />
 
+      {recoveryKey && (
+        <RecoveryKeyNotice
+          recoveryKey={recoveryKey}
+          onDismiss={dismissRecoveryKey}
+        />
+      )}
+
       {showUpdateNotification && updateVersion && (
         <UpdateNotification
           version={updateVersion}
@@ -0,0 +1,117 @@
+import React, { useEffect, useRef, useState } from "react";
+import type { Action, CommandPaletteProps, PaletteMode } from "../types";
+import { ActionService } from "../services/actionService";
+import { actionApplies, formatKeys } from "../utils/shortcuts";
+
+const PLACEHOLDERS: Record<PaletteMode, string> = {
+  actions: "Type an action...",
+  tag: "Tag name, then Enter",
+  confirm_delete: "Vault password to delete, then Enter",
+};
+
+export const CommandPalette: React.FC<CommandPaletteProps> = ({
+  mode,
+  selectedItem,
+  onRun,
+  onTag,
+  onConfirmDelete,
+  onClose,
+}) => {
+  const inputRef = useRef<HTMLInputElement>(null);
+  const [query, setQuery] = useState("");
+  const [actions, setActions] = useState<Action[]>([]);
+  const [highlighted, setHighlighted] = useState(0);
+
+  useEffect(() => {
+    setQuery("");
+    setHighlighted(0);
+    if (mode) {
+      setTimeout(() => inputRef.current?.focus(), 0);
+    }
+  }, [mode]);
+
+  useEffect(() => {
+    if (mode !== "actions") return;
+
+    let cancelled = false;
+    ActionService.searchActions(query).then((found) => {
+      if (cancelled) return;
+      setActions(
+        found.filter(
+          (action) =>
+            action.id !== "command_palette" &&
+            actionApplies(action, selectedItem),
+        ),
+      );
+      setHighlighted(0);
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [mode, query, selectedItem]);
+
+  if (!mode) return null;
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    // Keep the search window's own shortcuts out of the palette.
+    e.stopPropagation();
+    if (e.key === "Escape") {
+      e.preventDefault();
+      onClose();
+    } else if (mode === "tag") {
+      if (e.key === "Enter" && query.trim()) {
+        e.preventDefault();
+        onTag(query.trim());
+      }
+    } else if (mode === "confirm_delete") {
+      if (e.key === "Enter" && query) {
+        e.preventDefault();
+        onConfirmDelete(query);
+      }
+    } else if (e.key === "ArrowDown") {
+      e.preventDefault();
+      setHighlighted(Math.min(highlighted + 1, actions.length - 1));
+    } else if (e.key === "ArrowUp") {
+      e.preventDefault();
+      setHighlighted(Math.max(highlighted - 1, 0));
+    } else if (e.key === "Enter" && actions[highlighted]) {
+      e.preventDefault();
+      onRun(actions[highlighted]);
+    }
+  };
+
+  return (
+    <div className="modal-overlay" onClick={onClose}>
+      <div className="modal command-palette" onClick={(e) => e.stopPropagation()}>
+        <input
+          ref={inputRef}
+          type={mode === "confirm_delete" ? "password" : "text"}
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+          onKeyDown={handleKeyDown}
+          placeholder={PLACEHOLDERS[mode]}
+          className="command-palette-input"
+        />
+        {mode === "actions" && (
+          <div className="command-palette-list">
+            {actions.length === 0 ? (
+              <div className="command-palette-empty">No matching actions</div>
+            ) : (
+              actions.map((action, index) => (
+                <div
+                  key={action.id}
+                  className={`command-palette-item ${index === highlighted ? "selected" : ""}`}
+                  onMouseEnter={() => setHighlighted(index)}
+                  onClick={() => onRun(action)}
+                >
+                  <span>{action.title}</span>
+                  <kbd className="command-palette-keys">{formatKeys(action.keys)}</kbd>
+                </div>
+              ))
+            )}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+};
@@ -0,0 +1,68 @@
+import React, { useEffect, useState } from "react";
+import type { SearchResult } from "../types";
+import { ClipboardService } from "../services/clipboardService";
+
+interface NoteEditorProps {
+  item: SearchResult;
+}
+
+/** A note kept with the selected entry, saved on Enter or when the field
+ * loses focus; Escape undoes the edit. */
+export const NoteEditor: React.FC<NoteEditorProps> = ({ item }) => {
+  const [saved, setSaved] = useState("");
+  const [draft, setDraft] = useState("");
+
+  useEffect(() => {
+    let cancelled = false;
+    setSaved("");
+    setDraft("");
+    if (item.entry_id == null) return;
+    ClipboardService.getItemNote(item.entry_id)
+      .then((note) => {
+        if (cancelled) return;
+        setSaved(note ?? "");
+        setDraft(note ?? "");
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [item.hash]);
+
+  const save = async () => {
+    if (draft.trim() === saved || item.entry_id == null) return;
+    try {
+      const note = (await ClipboardService.setItemNote(item.entry_id, draft)) ?? "";
+      setSaved(note);
+      setDraft(note);
+    } catch {
+      // Logged by the service
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLTextAreaElement>) => {
+    // Keep the window's shortcuts out of the field
+    e.stopPropagation();
+    if (e.key === "Enter" && !e.shiftKey) {
+      e.preventDefault();
+      e.currentTarget.blur();
+    } else if (e.key === "Escape") {
+      setDraft(saved);
+    }
+  };
+
+  return (
+    <div className="note-editor">
+      <textarea
+        className="note-input"
+        rows={1}
+        value={draft}
+        onChange={(e) => setDraft(e.target.value)}
+        onBlur={save}
+        onKeyDown={handleKeyDown}
+        placeholder="Add a note…"
+        aria-label="Note"
+      />
+    </div>
+  );
+};
@@ -1,21 +1,36 @@
 import React, { useState, useEffect } from 'react';
 import { ClipboardService } from '../services/clipboardService';
+import type { ExistingVault, SecurityProfile } from '../types';
 
 interface OnboardingFlowProps {
   isVisible: boolean;
+  existingVaults: ExistingVault[];
   onComplete: (settings: OnboardingSettings) => void;
+  onAdopt: (path: string, password: string, launchAtLogin: boolean) => void;
 }
 
 export interface OnboardingSettings {
   password: string;
+  passwordHint: string;
+  recoveryKey: boolean;
+  securityProfile: SecurityProfile;
   keyCombo: string;
   sessionTimeMinutes: number;
+  launchAtLogin: boolean;
 }
 
-export const OnboardingFlow: React.FC<OnboardingFlowProps> = ({ isVisible, onComplete }) => {
+export const OnboardingFlow: React.FC<OnboardingFlowProps> = ({ isVisible, existingVaults, onComplete, onAdopt }) => {
   const [step, setStep] = useState(0);
+  // Offer existing vaults (e.g. from the CLI) before creating a new one.
+  const [creating, setCreating] = useState(false);
+  const [adoptPath, setAdoptPath] = useState('');
+  const [adoptPassword, setAdoptPassword] = useState('');
+  const [launchAtLogin, setLaunchAtLogin] = useState(true);
   const [password, setPassword] = useState('');
   const [confirmPassword, setConfirmPassword] = useState('');
+  const [passwordHint, setPasswordHint] = useState('');
+  const [recoveryKey, setRecoveryKey] = useState(true);
+  const [securityProfile, setSecurityProfile] = useState<SecurityProfile>('balanced');
   const [keyCombo, setKeyCombo] = useState('');
   const [sessionTime, setSessionTime] = useState(15);
   const [platform, setPlatform] = useState<string>('');
@@ -99,6 +114,44 @@ export const OnboardingFlow: React.FC<OnboardingFlowProps> = ({ isVisible, onCom
             )}
           </div>
 
+          <div className="setting-group">
+            <label htmlFor="passwordHint">Password Hint (optional)</label>
+            <input
+              id="passwordHint"
+              type="text"
+              value={passwordHint}
+              onChange={(e) => setPasswordHint(e.target.value)}
+              placeholder="Something only you would understand"
+            />
+            <small>Shown when unlocking. It isn't encrypted, so don't include the password.</small>
+          </div>
+
+          <div className="setting-group">
+            <label>
+              <input
+                type="checkbox"
+                checked={recoveryKey}
+                onChange={(e) => setRecoveryKey(e.target.checked)}
+              />
+              Generate a recovery key
+            </label>
+            <small>A one-time code that can reset your password if you forget it</small>
+          </div>
+
+          <div className="setting-group">
+            <label htmlFor="securityProfile">Security Profile</label>
+            <select
+              id="securityProfile"
+              value={securityProfile}
+              onChange={(e) => setSecurityProfile(e.target.value as SecurityProfile)}
+            >
+              <option value="fast">Fast</option>
+              <option value="balanced">Balanced</option>
+              <option value="paranoid">Paranoid</option>
+            </select>
+            <small>How hard the password is to guess by brute force, against how quickly the vault unlocks. It can't be changed later.</small>
+          </div>
+
 
         </div>
       )
@@ -140,6 +193,18 @@ export const OnboardingFlow: React.FC<OnboardingFlowProps> = ({ isVisible, onCom
             </select>
             <small>Automatically lock the vault after this period of inactivity</small>
           </div>
+
+          <div className="setting-group">
+            <label>
+              <input
+                type="checkbox"
+                checked={launchAtLogin}
+                onChange={(e) => setLaunchAtLogin(e.target.checked)}
+              />
+              Launch at login
+            </label>
+            <small>Keep capturing copies after a restart</small>
+          </div>
         </div>
       )
     }
@@ -148,6 +213,91 @@ export const OnboardingFlow: React.FC<OnboardingFlowProps> = ({ isVisible, onCom
   // Early return after all hooks are defined
   if (!isVisible) return null;
 
+  if (existingVaults.length > 0 && !creating) {
+    const selected = adoptPath || existingVaults[0].path;
+    const selectedVault = existingVaults.find((v) => v.path === selected);
+
+    return (
+      <div className="modal-overlay onboarding-overlay">
+        <div className="modal onboarding-modal">
+          <div className="onboarding-header">
+            <h2>Existing Vault Found</h2>
+          </div>
+
+          <div className="onboarding-content">
+            <div className="onboarding-password">
+              <div className="password-info">
+                <p>A Clip Vault history already exists on this computer, for example from the command-line tool. Unlock it to keep using it here.</p>
+              </div>
+
+              <div className="setting-group">
+                <label htmlFor="adoptPath">Vault</label>
+                <select
+                  id="adoptPath"
+                  value={selected}
+                  onChange={(e) => setAdoptPath(e.target.value)}
+                >
+                  {existingVaults.map((vault) => (
+                    <option key={vault.path} value={vault.path}>
+                      {vault.path}
+                    </option>
+                  ))}
+                </select>
+              </div>
+
+              <div className="setting-group">
+                <label htmlFor="adoptPassword">Password</label>
+                <input
+                  id="adoptPassword"
+                  type="password"
+                  value={adoptPassword}
+                  onChange={(e) => setAdoptPassword(e.target.value)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter' && adoptPassword) {
+                      onAdopt(selected, adoptPassword, launchAtLogin);
+                    }
+                  }}
+                  placeholder="Vault password"
+                  autoFocus
+                />
+                {selectedVault?.password_hint && (
+                  <div className="input-hint">Hint: {selectedVault.password_hint}</div>
+                )}
+              </div>
+
+              <div className="setting-group">
+                <label>
+                  <input
+                    type="checkbox"
+                    checked={launchAtLogin}
+                    onChange={(e) => setLaunchAtLogin(e.target.checked)}
+                  />
+                  Launch at login
+                </label>
+              </div>
+            </div>
+          </div>
+
+          <div className="onboarding-footer">
+            <button
+              className="modal-button secondary"
+              onClick={() => setCreating(true)}
+            >
+              Create New Vault
+            </button>
+            <button
+              className="modal-button primary"
+              onClick={() => onAdopt(selected, adoptPassword, launchAtLogin)}
+              disabled={!adoptPassword}
+            >
+              Use This Vault
+            </button>
+          </div>
+        </div>
+      </div>
+    );
+  }
+
   const currentStep = steps[step];
   const isLastStep = step === steps.length - 1;
   const canProceed = step === 0 ||
@@ -158,8 +308,12 @@ export const OnboardingFlow: React.FC<OnboardingFlowProps> = ({ isVisible, onCom
     if (isLastStep) {
       onComplete({
         password,
+        passwordHint,
+        recoveryKey,
+        securityProfile,
         keyCombo,
-        sessionTimeMinutes: sessionTime
+        sessionTimeMinutes: sessionTime,
+        launchAtLogin
       });
     } else {
       setStep(step + 1);
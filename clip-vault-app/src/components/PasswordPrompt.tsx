@@ -4,6 +4,7 @@ import type { PasswordPromptProps } from "../types";
 export const PasswordPrompt: React.FC<PasswordPromptProps> = ({
   isVisible,
   password,
+  hint,
   onPasswordChange,
   onUnlock,
   onCancel,
@@ -32,6 +33,7 @@ export const PasswordPrompt: React.FC<PasswordPromptProps> = ({
             placeholder="Vault password"
             autoFocus
           />
+          {hint && <div className="input-hint">Hint: {hint}</div>}
         </div>
         <div className="modal-footer">
           <button
@@ -1,30 +1,46 @@
 import React, { useRef, useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { PreviewPaneProps } from "../types";
-import { getContentStats } from "../utils/textUtils";
+import type { PreviewPaneProps, Relation, RelatedResult } from "../types";
+import { ClipboardService } from "../services/clipboardService";
+import { isEditableText } from "../utils/textUtils";
+import { NoteEditor } from "./NoteEditor";
+import { TagEditor } from "./TagEditor";
 
+const RELATION_LABELS: Record<Relation, string> = {
+  same_domain: "same site",
+  same_context: "same project",
+  similar_text: "similar",
+};
 
-export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }) => {
+const EXPIRY_CHOICES: [string, number | null][] = [
+  ["Keep", null],
+  ["Delete in 1 hour", 60 * 60],
+  ["Delete in 1 day", 24 * 60 * 60],
+  ["Delete in 1 week", 7 * 24 * 60 * 60],
+];
+
+
+export const PreviewPane: React.FC<PreviewPaneProps> = ({
+  selectedItem,
+  onCopy,
+  editRequest = 0,
+}) => {
   const previewRef = useRef<HTMLElement>(null);
   const editorRef = useRef<any>(null);
   const [isEditing, setIsEditing] = useState(false);
   const [editedContent, setEditedContent] = useState("");
   const [isSaving, setIsSaving] = useState(false);
-
-  const formatTimestamp = (timestamp: number): string => {
-    const date = new Date(timestamp / 1_000_000);
-    return date.toLocaleString();
-  };
+  const [hasHtml, setHasHtml] = useState(false);
+  const [capturedAt, setCapturedAt] = useState("");
+  const [related, setRelated] = useState<RelatedResult[]>([]);
+  const [expiresAt, setExpiresAt] = useState("");
 
   const handleSave = async () => {
-    if (!selectedItem || isSaving) return;
+    if (selectedItem?.entry_id == null || isSaving) return;
 
     try {
       setIsSaving(true);
-      await invoke("update_item", {
-        oldContent: selectedItem.content,
-        newContent: editedContent,
-      });
+      await ClipboardService.updateItem(selectedItem.entry_id, editedContent);
       setIsEditing(false);
 
       // Update the selected item content
@@ -93,6 +109,116 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
     updatePreview();
   }, [selectedItem]);
 
+  useEffect(() => {
+    if (!selectedItem) return;
+
+    let cancelled = false;
+    setCapturedAt(selectedItem.time_label);
+    invoke<string>("format_timestamp", {
+      timestamp: selectedItem.timestamp,
+      style: "absolute",
+    })
+      .then((formatted) => {
+        if (!cancelled) setCapturedAt(formatted);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [selectedItem]);
+
+  const showExpiry = (timestamp: number | null) => {
+    setExpiresAt("");
+    if (timestamp == null) return;
+    invoke<string>("format_timestamp", { timestamp, style: "absolute" })
+      .then(setExpiresAt)
+      .catch(() => {});
+  };
+
+  useEffect(() => {
+    setExpiresAt("");
+    if (selectedItem?.entry_id == null) return;
+    ClipboardService.getItemExpiry(selectedItem.entry_id)
+      .then(showExpiry)
+      .catch(() => {});
+  }, [selectedItem]);
+
+  const handleExpiryChange = async (e: React.ChangeEvent<HTMLSelectElement>) => {
+    if (selectedItem?.entry_id == null) return;
+    const ttlSecs = e.target.value === "keep" ? null : Number(e.target.value);
+    try {
+      showExpiry(await ClipboardService.setItemExpiry(selectedItem.entry_id, ttlSecs));
+    } catch {
+      // Logged by the service
+    }
+  };
+
+  useEffect(() => {
+    setRelated([]);
+    if (selectedItem?.entry_id == null) return;
+
+    let cancelled = false;
+    ClipboardService.getRelated(selectedItem.entry_id)
+      .then((items) => {
+        if (!cancelled) setRelated(items);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [selectedItem]);
+
+  useEffect(() => {
+    setHasHtml(false);
+    if (!selectedItem?.content_type.startsWith("text") || selectedItem.entry_id == null) return;
+
+    let cancelled = false;
+    invoke<boolean>("has_html", { id: selectedItem.entry_id })
+      .then((available) => {
+        if (!cancelled) setHasHtml(available);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [selectedItem]);
+
+  const handleProtect = async () => {
+    if (selectedItem?.entry_id == null) return;
+    try {
+      // The vault emits item-deleted, which drops it from the results.
+      await invoke("protect_item", { id: selectedItem.entry_id });
+    } catch (error) {
+      console.error("Failed to protect item:", error);
+    }
+  };
+
+  const handleTogglePin = async () => {
+    if (selectedItem?.entry_id == null) return;
+    try {
+      // The vault emits item-updated, which reloads the results.
+      await ClipboardService.togglePin(selectedItem.entry_id);
+    } catch (error) {
+      console.error("Failed to toggle pin:", error);
+    }
+  };
+
+  const handleCopyMarkdown = async () => {
+    if (selectedItem?.entry_id == null) return;
+    try {
+      await invoke("copy_as_markdown", { id: selectedItem.entry_id });
+    } catch (error) {
+      console.error("Failed to copy as Markdown:", error);
+    }
+  };
+
+  // Editing starts from the window's action shortcuts
+  useEffect(() => {
+    if (editRequest > 0 && !isEditing && selectedItem && isEditableText(selectedItem.content_type)) {
+      handleEdit();
+    }
+  }, [editRequest]);
+
   // Handle keyboard shortcuts in edit mode
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent) => {
@@ -100,9 +226,6 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
         if ((e.key === 's' || e.key === 'Enter') && isEditing) {
           e.preventDefault();
           handleSave();
-        } else if (e.key === 'e' && !isEditing && selectedItem?.content_type.startsWith('text')) {
-          e.preventDefault();
-          handleEdit();
         }
       } else if (e.key === 'Escape' && isEditing) {
         e.preventDefault();
@@ -125,29 +248,26 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
     );
   }
 
-  const stats = getContentStats(selectedItem.content);
-
   return (
     <div className="preview-pane">
       <div className="preview-header">
         <div className="preview-metadata">
           <div className="preview-timestamp">
-            {formatTimestamp(selectedItem.timestamp)}
+            {capturedAt}
           </div>
+          {expiresAt && (
+            <div className="preview-expiry">Expires {expiresAt}</div>
+          )}
           {!selectedItem.content_type.startsWith("image/") ? (
             <div className="preview-stats">
-              <span className="stat-item">{stats.lines} lines</span>
-              <span className="stat-item">{stats.chars} chars</span>
-              <span className="stat-item">{stats.words} words</span>
+              <span className="stat-item">{selectedItem.stats_label}</span>
+              <span className="stat-item">{selectedItem.stats.words} words</span>
             </div>
           ) : (
             <div className="preview-stats">
-              <span className="stat-item">{(() => {
-                const img = new Image();
-                img.src = `data:${selectedItem.content_type};base64,${selectedItem.content}`;
-                return `${img.width} × ${img.height}px`;
-              })()}</span>
-              <span className="stat-item">Image</span>
+              <span className="stat-item">
+                {selectedItem.image_info ?? "Image"}
+              </span>
             </div>
           )}
         </div>
@@ -180,7 +300,16 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
               >
                 Copy
               </button>
-              {selectedItem.content_type.startsWith('text') && (
+              {hasHtml && (
+                <button
+                  className="preview-button"
+                  onClick={handleCopyMarkdown}
+                  title="Copy the formatted version as Markdown"
+                >
+                  Copy as Markdown
+                </button>
+              )}
+              {isEditableText(selectedItem.content_type) && (
                 <button
                   className="preview-button edit"
                   onClick={handleEdit}
@@ -189,6 +318,40 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
                   Edit
                 </button>
               )}
+              <button
+                className="preview-button"
+                onClick={handleTogglePin}
+                title={selectedItem.pinned
+                  ? "Unpin; the entry goes back to its place in the history"
+                  : "Keep above the history and out of pruning"}
+              >
+                {selectedItem.pinned ? "Unpin" : "Pin"}
+              </button>
+              <select
+                className="preview-button"
+                value=""
+                onChange={handleExpiryChange}
+                title="Delete the entry after a while, or keep it"
+                aria-label="Expiry"
+              >
+                <option value="" disabled hidden>
+                  {expiresAt ? "Expires" : "Expire…"}
+                </option>
+                {EXPIRY_CHOICES.map(([label, secs]) => (
+                  <option key={label} value={secs ?? "keep"}>
+                    {label}
+                  </option>
+                ))}
+              </select>
+              {selectedItem.content_type.startsWith('text') && (
+                <button
+                  className="preview-button"
+                  onClick={handleProtect}
+                  title="Move to the protected area; viewing it will need your password"
+                >
+                  Protect
+                </button>
+              )}
             </>
           )}
         </div>
@@ -217,7 +380,9 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
           <div className="preview-image-container">
             <img
               ref={previewRef as React.RefObject<HTMLImageElement>}
-              src={`data:${selectedItem.content_type};base64,${selectedItem.content}`}
+              src={selectedItem.content
+                ? `data:${selectedItem.content_type};base64,${selectedItem.content}`
+                : `data:image/png;base64,${selectedItem.thumbnail ?? ""}`}
               alt="Clipboard image"
               className="preview-image"
             />
@@ -230,6 +395,30 @@ export const PreviewPane: React.FC<PreviewPaneProps> = ({ selectedItem, onCopy }
           </pre>
         )}
       </div>
+      {!isEditing && <NoteEditor item={selectedItem} />}
+      {!isEditing && <TagEditor item={selectedItem} />}
+      {!isEditing && related.length > 0 && (
+        <div className="preview-related">
+          <div className="preview-related-title">Related</div>
+          {related.map((item) => (
+            <div
+              key={item.hash}
+              className="preview-related-item"
+              onClick={() => onCopy(item.content, item.content_type)}
+              title="Copy to clipboard"
+            >
+              <span className="preview-related-text">
+                {item.content_type.startsWith("image/")
+                  ? item.image_info ?? "Image"
+                  : item.content.split("\n", 1)[0]}
+              </span>
+              <span className="preview-related-reasons">
+                {item.relations.map((r) => RELATION_LABELS[r]).join(", ")} · {item.time_label}
+              </span>
+            </div>
+          ))}
+        </div>
+      )}
     </div>
   );
 };
\ No newline at end of file
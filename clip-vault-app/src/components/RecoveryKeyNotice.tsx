@@ -0,0 +1,37 @@
+import React from "react";
+
+interface RecoveryKeyNoticeProps {
+  recoveryKey: string;
+  onDismiss: () => void;
+}
+
+export const RecoveryKeyNotice: React.FC<RecoveryKeyNoticeProps> = ({
+  recoveryKey,
+  onDismiss,
+}) => {
+  return (
+    <div className="modal-overlay">
+      <div className="modal">
+        <div className="modal-header">
+          <h2>Save Your Recovery Key</h2>
+        </div>
+        <div className="modal-content">
+          <p>
+            This key can reset your password if you forget it. It isn't stored
+            anywhere, so write it down and keep it somewhere safe instead of
+            copying it to the clipboard. It won't be shown again.
+          </p>
+          <pre className="recovery-key">{recoveryKey}</pre>
+          <p>
+            To use it, run <code>clip-vault recover</code>.
+          </p>
+        </div>
+        <div className="modal-footer">
+          <button className="modal-button primary" onClick={onDismiss}>
+            I've Saved It
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
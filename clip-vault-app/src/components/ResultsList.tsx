@@ -18,7 +18,6 @@ interface RowProps {
   onSelect: (idx: number) => void;
   getWindowedContent: (content: string, q: string) => string;
   highlightText: (content: string, q: string) => React.ReactNode;
-  formatTimestamp: (ts: number) => string;
 }
 
 const Row = memo(
@@ -32,7 +31,6 @@ const Row = memo(
         onSelect,
         getWindowedContent,
         highlightText,
-        formatTimestamp,
       },
       ref
     ) => {
@@ -40,9 +38,11 @@ const Row = memo(
         if (result.content_type.startsWith("image/")) {
           return {
             type: "image",
-            content: result.content,
-            contentType: result.content_type,
+            // Large images have a small PNG to show instead
+            content: result.thumbnail ?? result.content,
+            contentType: result.thumbnail ? "image/png" : result.content_type,
             size: Math.round((result.content.length * 0.75) / 1024),
+            info: result.image_info,
           } as const;
         }
 
@@ -50,10 +50,20 @@ const Row = memo(
         const cached = TEXT_PROCESSING_CACHE.get(cacheKey);
         if (cached) return cached;
 
-        const windowedContent = getWindowedContent(result.content, query);
+        // Without a query the backend's preview is shown as is; a query
+        // centres the shown text on its match instead.
+        const preview = query.trim() ? null : result.preview;
+        const windowedContent = preview
+          ? preview.lines.join("\n")
+          : getWindowedContent(result.content, query);
         const highlighted = highlightText(windowedContent, query);
 
-        const processed = { type: "text", content: highlighted } as const;
+        const processed = {
+          type: "text",
+          content: highlighted,
+          multiline: (preview?.lines.length ?? 1) > 1,
+          moreLines: preview?.more_lines ?? 0,
+        } as const;
 
         if (TEXT_PROCESSING_CACHE.size >= CACHE_SIZE_LIMIT) {
           const firstKey = TEXT_PROCESSING_CACHE.keys().next().value;
@@ -66,32 +76,78 @@ const Row = memo(
       return (
         <div
           className={`result-item ${isSelected ? "selected" : ""}`}
+          role="option"
+          aria-selected={isSelected}
+          aria-label={result.description}
           onClick={() => onSelect(index)}
           ref={ref}
         >
-          <div className="result-content">
+          <div
+            className={`result-content ${
+              processedContent.multiline ? "multiline" : ""
+            }`}
+          >
             {processedContent.type === "image" ? (
               <div className="image-result">
                 <img
                   src={`data:${processedContent.contentType};base64,${processedContent.content}`}
-                  alt="Clipboard preview"
+                  alt={result.description}
                   className="result-image-thumbnail"
                   loading="lazy"
                   draggable={false}
                 />
                 <div className="image-info">
-                  Image ({processedContent.size} KB)
+                  {processedContent.info
+                    ? processedContent.info
+                    : `Image (${processedContent.size} KB)`}
                 </div>
               </div>
             ) : (
-              processedContent.content
+              <>
+                {result.color && (
+                  <span
+                    className="result-color-swatch"
+                    style={{
+                      backgroundColor: `rgba(${result.color.r}, ${result.color.g}, ${result.color.b}, ${result.color.a / 255})`,
+                    }}
+                    aria-hidden="true"
+                  />
+                )}
+                {processedContent.content}
+                {processedContent.moreLines > 0 && (
+                  <span className="result-more-lines">
+                    +{processedContent.moreLines} more lines
+                  </span>
+                )}
+              </>
             )}
           </div>
           <div className="result-meta">
+            {result.pinned && (
+              <span className="result-pinned" title="Pinned">📌</span>
+            )}
             <span className="result-time">
-              {formatTimestamp(result.timestamp)}
+              {result.time_label}
+            </span>
+            {!result.content_type.startsWith("image/") && (
+              <span className="result-stats">{result.stats_label}</span>
+            )}
+            <span className="result-type">
+              {["text", "image", "files"].includes(result.kind)
+                ? result.content_type
+                : result.kind}
             </span>
-            <span className="result-type">{result.content_type}</span>
+            {result.source_app && (
+              <span
+                className="result-source"
+                title={result.window_title ?? undefined}
+              >
+                {result.source_app}
+              </span>
+            )}
+            {result.profile && (
+              <span className="result-profile">{result.profile}</span>
+            )}
           </div>
         </div>
       );
@@ -108,7 +164,6 @@ export const ResultsList: React.FC<ResultsListProps> = ({
   loadingMore,
   hasMore,
   onLoadMore,
-  formatTimestamp,
   getWindowedContent,
   highlightText,
 }) => {
@@ -178,7 +233,7 @@ export const ResultsList: React.FC<ResultsListProps> = ({
           {query ? "No matches found" : "No clipboard history yet"}
         </div>
       ) : (
-        <div className="results-list">
+        <div className="results-list" role="listbox" aria-label="Clipboard history">
           {results.map((result, idx) => (
             <Row
               ref={(el) => {
@@ -192,7 +247,6 @@ export const ResultsList: React.FC<ResultsListProps> = ({
               onSelect={onSelect}
               getWindowedContent={getWindowedContent}
               highlightText={highlightText}
-              formatTimestamp={formatTimestamp}
             />
           ))}
 
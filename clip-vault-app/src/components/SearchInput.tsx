@@ -7,6 +7,9 @@ export const SearchInput: React.FC<SearchInputProps> = ({
   resultsCount,
   searching,
   setSearching,
+  allProfiles,
+  onAllProfilesChange,
+  lockedProfiles,
 }) => {
   const searchInputRef = useRef<HTMLInputElement>(null);
 
@@ -45,6 +48,18 @@ export const SearchInput: React.FC<SearchInputProps> = ({
         placeholder="Search your clipboard history..."
         className="search-input"
       />
+      <button
+        className={`profiles-toggle ${allProfiles ? "active" : ""}`}
+        onClick={() => onAllProfilesChange(!allProfiles)}
+        aria-pressed={allProfiles}
+        title={
+          allProfiles && lockedProfiles.length > 0
+            ? `Locked: ${lockedProfiles.join(", ")}`
+            : "Search every vault profile"
+        }
+      >
+        All profiles
+      </button>
       {query !== "" && (
         <div className="results-count">
           {resultsCount === 0
@@ -0,0 +1,90 @@
+import React, { useEffect, useState } from "react";
+import type { SearchResult } from "../types";
+import { ClipboardService } from "../services/clipboardService";
+
+interface TagEditorProps {
+  item: SearchResult;
+}
+
+/** The selected entry's tags, with a field to add more and × to remove. */
+export const TagEditor: React.FC<TagEditorProps> = ({ item }) => {
+  const [tags, setTags] = useState<string[]>([]);
+  const [known, setKnown] = useState<string[]>([]);
+  const [draft, setDraft] = useState("");
+
+  useEffect(() => {
+    let cancelled = false;
+    setDraft("");
+    if (item.entry_id == null) {
+      setTags([]);
+      return;
+    }
+    ClipboardService.getTags(item.entry_id)
+      .then((tags) => {
+        if (!cancelled) setTags(tags);
+      })
+      .catch(() => setTags([]));
+    ClipboardService.listTags()
+      .then((known) => {
+        if (!cancelled) setKnown(known);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [item.hash]);
+
+  const update = async (tag: string, remove: boolean) => {
+    if (item.entry_id == null) return;
+    try {
+      setTags(await ClipboardService.tagItem(item.entry_id, tag, remove));
+      if (!remove && !known.includes(tag)) setKnown([...known, tag].sort());
+    } catch {
+      // Logged by the service
+    }
+  };
+
+  const handleKeyDown = (e: React.KeyboardEvent<HTMLInputElement>) => {
+    // Keep the window's shortcuts out of the field
+    e.stopPropagation();
+    if (e.key === "Enter" && draft.trim() !== "") {
+      e.preventDefault();
+      update(draft.trim(), false);
+      setDraft("");
+    }
+  };
+
+  return (
+    <div className="tag-editor">
+      {tags.map((tag) => (
+        <span key={tag} className="tag-chip">
+          {tag}
+          <button
+            className="tag-remove"
+            onClick={() => update(tag, true)}
+            aria-label={`Remove tag ${tag}`}
+            title="Remove tag"
+          >
+            ×
+          </button>
+        </span>
+      ))}
+      <input
+        className="tag-input"
+        value={draft}
+        onChange={(e) => setDraft(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder="Add tag…"
+        aria-label="Add tag"
+        list="tag-suggestions"
+      />
+      <datalist id="tag-suggestions">
+        {known
+          .filter((tag) => !tags.includes(tag))
+          .map((tag) => (
+            <option key={tag} value={tag} />
+          ))}
+      </datalist>
+    </div>
+  );
+};
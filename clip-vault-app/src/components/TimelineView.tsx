@@ -0,0 +1,90 @@
+import React, { useEffect, useState } from "react";
+import { ClipboardService } from "../services/clipboardService";
+import type { TimelineGroup } from "../types";
+
+interface TimelineViewProps {
+  onCopy: (content: string, contentType: string) => void;
+}
+
+/** Local date as YYYY-MM-DD, the format the backend and date inputs use. */
+const toDay = (date: Date) => {
+  const pad = (n: number) => String(n).padStart(2, "0");
+  return `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
+};
+
+const shiftDay = (day: string, by: number) => {
+  const [year, month, date] = day.split("-").map(Number);
+  return toDay(new Date(year, month - 1, date + by));
+};
+
+const timeOf = (timestamp: number) =>
+  new Date(timestamp / 1_000_000).toLocaleTimeString([], {
+    hour: "2-digit",
+    minute: "2-digit",
+  });
+
+export const TimelineView: React.FC<TimelineViewProps> = ({ onCopy }) => {
+  const [day, setDay] = useState(() => toDay(new Date()));
+  const [groups, setGroups] = useState<TimelineGroup[]>([]);
+  const [loading, setLoading] = useState(false);
+
+  useEffect(() => {
+    let stale = false;
+    setLoading(true);
+    ClipboardService.getTimeline(day)
+      .then((groups) => !stale && setGroups(groups))
+      .catch(() => !stale && setGroups([]))
+      .finally(() => !stale && setLoading(false));
+    return () => {
+      stale = true;
+    };
+  }, [day]);
+
+  return (
+    <div className="timeline">
+      <div className="timeline-header">
+        <button className="timeline-nav" onClick={() => setDay(shiftDay(day, -1))}>
+          ‹
+        </button>
+        <input
+          type="date"
+          className="timeline-date"
+          value={day}
+          max={toDay(new Date())}
+          onChange={(e) => e.target.value && setDay(e.target.value)}
+        />
+        <button className="timeline-nav" onClick={() => setDay(shiftDay(day, 1))}>
+          ›
+        </button>
+      </div>
+
+      <div className="timeline-body">
+        {!loading && groups.length === 0 && (
+          <div className="empty-state">Nothing copied on this day.</div>
+        )}
+        {groups.map((group) => (
+          <section key={group.hour} className="timeline-hour">
+            <h3 className="timeline-hour-label">
+              {String(group.hour).padStart(2, "0")}:00
+            </h3>
+            {group.items.map((item) => (
+              <div
+                key={item.id}
+                className="timeline-item"
+                onClick={() => onCopy(item.content, item.content_type)}
+                title="Click to copy"
+              >
+                <span className="result-time">{timeOf(item.timestamp)}</span>
+                <span className="timeline-item-content">
+                  {item.content_type.startsWith("image/")
+                    ? `[Image${item.image_info ? `: ${item.image_info}` : ""}]`
+                    : item.content}
+                </span>
+              </div>
+            ))}
+          </section>
+        ))}
+      </div>
+    </div>
+  );
+};
@@ -32,6 +32,16 @@ export const UpdateNotification: React.FC<UpdateNotificationProps> = ({ version:
     setupListeners();
   }, [onClose]);
 
+  const handleInstallOnQuit = async () => {
+    setError(null);
+    try {
+      await UpdateService.scheduleInstallOnQuit();
+      onClose();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to schedule update');
+    }
+  };
+
   const handleInstallUpdate = async () => {
     if (!updateVersion) return;
 
@@ -98,6 +108,9 @@ export const UpdateNotification: React.FC<UpdateNotificationProps> = ({ version:
         <button className="update-button secondary" onClick={onClose}>
           Later
         </button>
+        <button className="update-button secondary" onClick={handleInstallOnQuit}>
+          Install on Quit
+        </button>
         <button className="update-button primary" onClick={handleInstallUpdate}>
           Install Now
         </button>
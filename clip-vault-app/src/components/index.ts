@@ -3,6 +3,10 @@ export { ResultsList } from "./ResultsList";
 export { PreviewPane } from "./PreviewPane";
 export { PasswordPrompt } from "./PasswordPrompt";
 export { OnboardingFlow } from "./OnboardingFlow";
+export { RecoveryKeyNotice } from "./RecoveryKeyNotice";
+export { CommandPalette } from "./CommandPalette";
 export type { OnboardingSettings } from "./OnboardingFlow";
 export { UpdateNotification } from "./UpdateNotification";
 export { ToastNotification } from "./ToastNotification";
+export { TimelineView } from "./TimelineView";
+export { TagEditor } from "./TagEditor";
@@ -0,0 +1,40 @@
+import { useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import type { SearchResult, SelectionChanged } from "../types";
+
+interface UseBackendSelectionProps {
+  results: SearchResult[];
+  selectedIndex: number;
+  setSelectedIndex: (index: number) => void;
+}
+
+// Mirror the list and selection to the backend, which keeps them for
+// `select_next`, `select_previous` and `activate_selected`, and follow
+// the selection when one of those moves it.
+export const useBackendSelection = ({
+  results,
+  selectedIndex,
+  setSelectedIndex,
+}: UseBackendSelectionProps) => {
+  useEffect(() => {
+    invoke("set_search_results", { hashes: results.map((r) => r.hash) }).catch(
+      (error) => console.error("Failed to share search results:", error),
+    );
+  }, [results]);
+
+  useEffect(() => {
+    invoke("select_index", { index: selectedIndex }).catch((error) =>
+      console.error("Failed to share selection:", error),
+    );
+  }, [selectedIndex, results]);
+
+  useEffect(() => {
+    const unlisten = listen<SelectionChanged>("search-selection-changed", (event) =>
+      setSelectedIndex(event.payload.index),
+    );
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, [setSelectedIndex]);
+};
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { ClipboardService } from "../services/clipboardService";
+import { VaultService } from "../services/vaultService";
 import { cacheService } from "../services/cacheService";
 import type { SearchResult } from "../types";
 
@@ -10,15 +11,24 @@ export const useClipboardSearch = () => {
   const [loading, setLoading] = useState(false);
   const [loadingMore, setLoadingMore] = useState(false);
   const [hasMore, setHasMore] = useState(true);
+  const [allProfiles, setAllProfiles] = useState(false);
+  const [lockedProfiles, setLockedProfiles] = useState<string[]>([]);
   const debounceRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const abortControllerRef = useRef<AbortController | null>(null);
 
   const searchClipboard = useCallback(async (searchQuery: string) => {
     try {
       setLoading(true);
-      const response = searchQuery.trim() === ""
-        ? await ClipboardService.listClipboard(20)
-        : await ClipboardService.searchClipboard(searchQuery, 20);
+      VaultService.activityPing();
+      let response;
+      if (searchQuery.trim() === "") {
+        response = await ClipboardService.listClipboard(20);
+      } else if (allProfiles) {
+        response = await ClipboardService.searchAllProfiles(searchQuery, 20);
+        setLockedProfiles(response.locked);
+      } else {
+        response = await ClipboardService.searchClipboard(searchQuery, 20);
+      }
       setResults(response.results);
       setSelectedIndex(0);
       setHasMore(response.hasMore);
@@ -35,7 +45,7 @@ export const useClipboardSearch = () => {
     } finally {
       setLoading(false);
     }
-  }, []);
+  }, [allProfiles]);
 
   const loadMore = useCallback(async () => {
     if (!hasMore || loadingMore || results.length === 0) return;
@@ -45,7 +55,9 @@ export const useClipboardSearch = () => {
       const lastTimestamp = results[results.length - 1].timestamp;
       const response = query.trim() === ""
         ? await ClipboardService.listClipboard(20, lastTimestamp)
-        : await ClipboardService.searchClipboard(query, 20, lastTimestamp);
+        : allProfiles
+          ? await ClipboardService.searchAllProfiles(query, 20, lastTimestamp)
+          : await ClipboardService.searchClipboard(query, 20, lastTimestamp);
 
       if (response.results.length > 0) {
         const existingTimestamps = new Set(results.map(r => r.timestamp));
@@ -64,10 +76,11 @@ export const useClipboardSearch = () => {
     } finally {
       setLoadingMore(false);
     }
-  }, [query, results, hasMore, loadingMore]);
+  }, [query, results, hasMore, loadingMore, allProfiles]);
 
   const copyToClipboard = useCallback(async (content: string, contentType: string) => {
     try {
+      VaultService.activityPing();
       await ClipboardService.copyToClipboard(content, contentType);
 
       // Only do optimistic update if we're viewing the main list (not searching)
@@ -110,9 +123,9 @@ export const useClipboardSearch = () => {
     }
   }, [query]);
 
-  const updateItem = useCallback(async (oldContent: string, newContent: string) => {
+  const updateItem = useCallback(async (entryId: number, newContent: string) => {
     try {
-      await ClipboardService.updateItem(oldContent, newContent);
+      await ClipboardService.updateItem(entryId, newContent);
       return true;
     } catch (error) {
       console.error("Update failed:", error);
@@ -120,6 +133,55 @@ export const useClipboardSearch = () => {
     }
   }, []);
 
+  // Patch the list for a single new entry instead of refetching a full page
+  const addItem = useCallback(async (hash: string) => {
+    if (query.trim() !== "") {
+      // Can't tell whether the new entry matches the search without asking
+      await searchClipboard(query);
+      return;
+    }
+
+    try {
+      const item = await ClipboardService.getItem(hash);
+      if (!item || item.pinned) {
+        await searchClipboard(query);
+        return;
+      }
+      // New entries go first after the pinned ones
+      setResults(prev => {
+        const rest = prev.filter(entry => entry.hash !== hash);
+        const pinned = rest.filter(entry => entry.pinned).length;
+        return [...rest.slice(0, pinned), item, ...rest.slice(pinned)];
+      });
+    } catch (error) {
+      console.error("Failed to fetch new item:", error);
+    }
+  }, [query, searchClipboard]);
+
+  const removeItem = useCallback((hash: string) => {
+    setResults(prev => prev.filter(item => item.hash !== hash));
+  }, []);
+
+  // Large images are listed by their thumbnail; load the selected one in
+  // full so it can be previewed and copied.
+  const selected = results[selectedIndex];
+  const needsContent = selected?.thumbnail && !selected.content ? selected.entry_id ?? null : null;
+  useEffect(() => {
+    if (needsContent == null) return;
+    let cancelled = false;
+    ClipboardService.getItemContent(needsContent)
+      .then(content => {
+        if (cancelled || content === null) return;
+        setResults(prev => prev.map(entry =>
+          entry.entry_id === needsContent ? { ...entry, content } : entry
+        ));
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, [needsContent]);
+
   // Debounced search effect
   useEffect(() => {
     // Cancel previous debounce timer
@@ -158,9 +220,14 @@ export const useClipboardSearch = () => {
     loading,
     loadingMore,
     hasMore,
+    allProfiles,
+    setAllProfiles,
+    lockedProfiles,
     searchClipboard,
     loadMore,
     copyToClipboard,
     updateItem,
+    addItem,
+    removeItem,
   };
 };
\ No newline at end of file
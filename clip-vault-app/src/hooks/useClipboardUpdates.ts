@@ -4,23 +4,71 @@ import { ClipboardService } from "../services/clipboardService";
 
 interface UseClipboardUpdatesProps {
   onClipboardUpdate: () => void;
+  onItemAdded: (hash: string) => void;
+  onItemDeleted: (hash: string) => void;
   showPasswordPrompt: boolean;
 }
 
-export const useClipboardUpdates = ({ onClipboardUpdate, showPasswordPrompt }: UseClipboardUpdatesProps) => {
+interface ItemEvent {
+  hash: string;
+}
+
+/** Counts of a burst of changes coalesced by the backend. */
+interface ChangeBatch {
+  added: number;
+  updated: number;
+  deleted: number;
+  external: boolean;
+  first_at: number;
+  last_at: number;
+}
+
+interface ItemUpdatedEvent {
+  old_hash: string;
+  hash: string;
+}
+
+export const useClipboardUpdates = ({
+  onClipboardUpdate,
+  onItemAdded,
+  onItemDeleted,
+  showPasswordPrompt,
+}: UseClipboardUpdatesProps) => {
   useEffect(() => {
     if (showPasswordPrompt) return;
 
-    let unlisten: (() => void) | undefined;
+    const unlisteners: Array<() => void> = [];
 
     const setupEventListener = async () => {
       try {
-        unlisten = await listen("clipboard-updated", () => {
-          console.log("Clipboard updated, invalidating cache and refreshing results...");
-          // Invalidate all cached data since new content was added
-          ClipboardService.invalidateCache();
-          onClipboardUpdate();
-        });
+        // Bulk changes (pruning, imports, incognito) arrive coalesced and
+        // need a full refresh
+        unlisteners.push(
+          await listen<ChangeBatch | null>("clipboard-updated", () => {
+            ClipboardService.invalidateCache();
+            onClipboardUpdate();
+          }),
+        );
+        unlisteners.push(
+          await listen<ItemEvent>("item-added", (event) => {
+            ClipboardService.invalidateCache();
+            onItemAdded(event.payload.hash);
+          }),
+        );
+        unlisteners.push(
+          await listen<ItemEvent>("item-deleted", (event) => {
+            ClipboardService.invalidateCache();
+            onItemDeleted(event.payload.hash);
+          }),
+        );
+        unlisteners.push(
+          await listen<ItemUpdatedEvent>("item-updated", (event) => {
+            // An edited entry moves to the top, just like a new capture
+            ClipboardService.invalidateCache();
+            onItemDeleted(event.payload.old_hash);
+            onItemAdded(event.payload.hash);
+          }),
+        );
       } catch (error) {
         console.error("Failed to setup clipboard event listener:", error);
       }
@@ -29,9 +77,7 @@ export const useClipboardUpdates = ({ onClipboardUpdate, showPasswordPrompt }: U
     setupEventListener();
 
     return () => {
-      if (unlisten) {
-        unlisten();
-      }
+      unlisteners.forEach((unlisten) => unlisten());
     };
-  }, [onClipboardUpdate, showPasswordPrompt]);
-};
\ No newline at end of file
+  }, [onClipboardUpdate, onItemAdded, onItemDeleted, showPasswordPrompt]);
+};
@@ -1,54 +1,66 @@
-import { useEffect, useCallback } from "react";
-import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import type { SearchResult } from "../types";
+import { useEffect, useCallback, useState } from "react";
+import type { Action, SearchResult } from "../types";
+import { ActionService } from "../services/actionService";
+import { actionApplies, matchesKeys } from "../utils/shortcuts";
 
 interface UseKeyboardNavigationProps {
   results: SearchResult[];
   selectedIndex: number;
   setSelectedIndex: (index: number) => void;
-  onCopy: (content: string, contentType: string) => void;
+  onAction: (action: Action) => void;
   showPasswordPrompt: boolean;
-  setSearching: (searching: boolean) => void;
-  setQuery: (query: string) => void;
+  paletteOpen: boolean;
 }
 
 export const useKeyboardNavigation = ({
   results,
   selectedIndex,
   setSelectedIndex,
-  onCopy,
+  onAction,
   showPasswordPrompt,
-  setSearching,
-  setQuery,
+  paletteOpen,
 }: UseKeyboardNavigationProps) => {
+  const [actions, setActions] = useState<Action[]>([]);
+
+  useEffect(() => {
+    ActionService.listActions().then(setActions);
+  }, []);
+
   const handleKeyDown = useCallback((e: KeyboardEvent) => {
     // Don't handle shortcuts if modals are open
-    if (showPasswordPrompt) return;
-
-    if (e.key === "Escape") {
-      // Clear search query when closing
-      setQuery("");
-      const window = getCurrentWebviewWindow();
-      window.hide();
-    } else if (e.key === "ArrowDown") {
+    if (showPasswordPrompt || paletteOpen) return;
+    // The editor handles its own keys.
+    if (e.target instanceof HTMLTextAreaElement) return;
+
+    if (e.key === "ArrowDown") {
       e.preventDefault();
       setSelectedIndex(Math.min(selectedIndex + 1, results.length - 1));
+      return;
     } else if (e.key === "ArrowUp") {
       e.preventDefault();
       setSelectedIndex(Math.max(selectedIndex - 1, 0));
-    } else if (e.key === "Enter" || (e.metaKey && e.key === "c")) {
-      e.preventDefault();
-      if (results[selectedIndex]) {
-        onCopy(results[selectedIndex].content, results[selectedIndex].content_type);
-      }
-    } else if (e.key === "/") {
-      e.preventDefault();
-      setSearching(true);
+      return;
+    }
+
+    // Let the search box keep "/" and word deletion while it has text.
+    const typing = e.target instanceof HTMLInputElement && e.target.value !== "";
+    const action = e.metaKey && e.key === "c"
+      ? actions.find((action) => action.id === "copy")
+      : actions.find(
+          (action) =>
+            matchesKeys(e, action.keys) &&
+            !(typing && (action.keys === "/" || action.keys === "Mod+Backspace")),
+        );
+    if (!action) return;
+
+    e.preventDefault();
+    if (actionApplies(action, results[selectedIndex] ?? null)) {
+      onAction(action);
     }
-  }, [results, selectedIndex, setSelectedIndex, onCopy, showPasswordPrompt, setQuery]);
+  }, [actions, results, selectedIndex, setSelectedIndex, onAction, showPasswordPrompt, paletteOpen]);
 
   useEffect(() => {
     window.addEventListener("keydown", handleKeyDown);
     return () => window.removeEventListener("keydown", handleKeyDown);
   }, [handleKeyDown]);
-};
\ No newline at end of file
+};
@@ -1,20 +1,26 @@
 import { useState, useEffect, useCallback } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { VaultService } from "../services/vaultService";
 import { ClipboardService } from "../services/clipboardService";
 import type { OnboardingSettings } from "../components";
+import type { ExistingVault } from "../types";
 
 export const useVault = () => {
   const [isUnlocked, setIsUnlocked] = useState(false);
   const [showPasswordPrompt, setShowPasswordPrompt] = useState(false);
   const [showOnboarding, setShowOnboarding] = useState(false);
   const [password, setPassword] = useState("");
+  const [passwordHint, setPasswordHint] = useState<string | null>(null);
+  const [recoveryKey, setRecoveryKey] = useState<string | null>(null);
+  const [existingVaults, setExistingVaults] = useState<ExistingVault[]>([]);
 
   const checkVaultStatus = useCallback(async () => {
     try {
-      // First check if vault exists
-      const vaultExists = await ClipboardService.vaultExists();
-      
-      if (!vaultExists) {
+      // First check if vault exists, and look for ones to adopt if not
+      const status = await VaultService.getOnboardingStatus();
+
+      if (!status.vault_exists) {
+        setExistingVaults(status.existing_vaults);
         setShowOnboarding(true);
         setShowPasswordPrompt(false);
         setIsUnlocked(false);
@@ -24,6 +30,7 @@ export const useVault = () => {
       const unlocked = await VaultService.checkVaultStatus();
 
       if (!unlocked) {
+        setPasswordHint(await VaultService.getPasswordHint());
         setShowPasswordPrompt(true);
         setShowOnboarding(false);
         setIsUnlocked(false);
@@ -85,9 +92,17 @@ export const useVault = () => {
         global_shortcut: settings.keyCombo,
       };
 
-      const success = await ClipboardService.createVault(settings.password, updatedSettings);
-      
-      if (success) {
+      const result = await ClipboardService.createVault(
+        settings.password,
+        updatedSettings,
+        settings.passwordHint.trim() || null,
+        settings.recoveryKey,
+        settings.securityProfile,
+      );
+
+      if (result.created) {
+        await VaultService.registerDaemon(settings.launchAtLogin);
+        setRecoveryKey(result.recovery_key ?? null);
         setShowOnboarding(false);
         setIsUnlocked(true);
         return true;
@@ -102,20 +117,52 @@ export const useVault = () => {
     }
   }, []);
 
+  const handleAdoptVault = useCallback(
+    async (path: string, vaultPassword: string, launchAtLogin: boolean) => {
+      const success = await VaultService.adoptVault(path, vaultPassword);
+      if (!success) {
+        alert("Invalid password. Please try again.");
+        return false;
+      }
+      await VaultService.registerDaemon(launchAtLogin);
+      setShowOnboarding(false);
+      setIsUnlocked(true);
+      return true;
+    },
+    [],
+  );
+
+  const dismissRecoveryKey = useCallback(() => setRecoveryKey(null), []);
+
   useEffect(() => {
     checkVaultStatus();
   }, [checkVaultStatus]);
 
+  // The backend locks an idle vault on its own; ask for the password again.
+  useEffect(() => {
+    const unlisten = listen("vault-locked", () => {
+      checkVaultStatus();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [checkVaultStatus]);
+
   return {
     isUnlocked,
     showPasswordPrompt,
     showOnboarding,
     password,
     setPassword,
+    passwordHint,
+    recoveryKey,
+    dismissRecoveryKey,
     checkVaultStatus,
     unlockVault,
     handleUnlock,
     handleCancel,
     handleOnboardingComplete,
+    existingVaults,
+    handleAdoptVault,
   };
 };
\ No newline at end of file
@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Action } from "../types";
+
+export class ActionService {
+  static async listActions(): Promise<Action[]> {
+    try {
+      return await invoke<Action[]>("list_actions");
+    } catch (error) {
+      console.error("Failed to list actions:", error);
+      return [];
+    }
+  }
+
+  static async searchActions(query: string): Promise<Action[]> {
+    try {
+      return await invoke<Action[]>("search_actions", { query });
+    } catch (error) {
+      console.error("Failed to search actions:", error);
+      return [];
+    }
+  }
+}
@@ -1,5 +1,16 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { SearchResult } from "../types";
+import type {
+  CopyEvent,
+  CopyFormat,
+  CreatedVault,
+  DescribedEntry,
+  ProfileSearch,
+  RelatedResult,
+  SearchResult,
+  SecurityProfile,
+  TimelineGroup,
+} from "../types";
+import { VaultService } from "./vaultService";
 import { cacheService } from "./cacheService";
 
 export class ClipboardService {
@@ -21,7 +32,8 @@ export class ClipboardService {
       });
 
       // Determine if there are more results
-      const hasMore = results.length === (limit || 20);
+      // The first page also holds the pinned entries, on top of `limit`
+      const hasMore = results.length >= (limit || 20);
 
       // Cache the results
       cacheService.setList(results, hasMore, limit, afterTimestamp);
@@ -33,6 +45,19 @@ export class ClipboardService {
     }
   }
 
+  static async searchAllProfiles(
+    query: string,
+    limit?: number,
+    afterTimestamp?: number
+  ): Promise<{ results: SearchResult[]; hasMore: boolean; locked: string[] }> {
+    const { results, locked } = await invoke<ProfileSearch>("search_all_profiles", {
+      query,
+      limit,
+      afterTimestamp,
+    });
+    return { results, hasMore: results.length === (limit || 20), locked };
+  }
+
   static async searchClipboard(
     query: string,
     limit?: number,
@@ -65,6 +90,46 @@ export class ClipboardService {
     }
   }
 
+  /** Everything copied on `day` (YYYY-MM-DD, local time), by hour. */
+  static async getTimeline(day: string): Promise<TimelineGroup[]> {
+    try {
+      return await invoke<TimelineGroup[]>("get_timeline", { day });
+    } catch (error) {
+      console.error("Timeline failed:", error);
+      throw error;
+    }
+  }
+
+  /** Entries related to the one with `id`, most related first. */
+  static async getRelated(id: number): Promise<RelatedResult[]> {
+    try {
+      return await invoke<RelatedResult[]>("get_related", { id });
+    } catch (error) {
+      console.error("Related items failed:", error);
+      throw error;
+    }
+  }
+
+  /** When an entry was captured, copied again or copied back out. */
+  static async getCopyEvents(id: number): Promise<CopyEvent[]> {
+    try {
+      return await invoke<CopyEvent[]>("get_copy_events", { id });
+    } catch (error) {
+      console.error("Copy events failed:", error);
+      throw error;
+    }
+  }
+
+  /** The latest entries described in words, for screen readers. */
+  static async describeEntries(limit?: number): Promise<DescribedEntry[]> {
+    try {
+      return await invoke<DescribedEntry[]>("describe_entries", { limit });
+    } catch (error) {
+      console.error("Describing entries failed:", error);
+      throw error;
+    }
+  }
+
   /**
    * Invalidate cache when new clipboard item is added
    */
@@ -84,11 +149,35 @@ export class ClipboardService {
     }
   }
 
-  static async updateItem(oldContent: string, newContent: string): Promise<void> {
+  /** Copy content that must not enter the history, e.g. a generated password. */
+  static async copyWithoutHistory(content: string, contentType: string): Promise<void> {
+    try {
+      await invoke("copy_without_history", {
+        content,
+        contentType,
+      });
+    } catch (error) {
+      console.error("Copy without history failed:", error);
+      throw error;
+    }
+  }
+
+  /** Copy a text entry reshaped, e.g. as pretty JSON or a code block. */
+  static async copyAs(id: number, format: CopyFormat): Promise<void> {
+    try {
+      await invoke("copy_as", { id, format });
+    } catch (error) {
+      console.error(`Copy as ${format} failed:`, error);
+      throw error;
+    }
+  }
+
+  static async updateItem(entryId: number, newContent: string): Promise<void> {
     try {
       await invoke("update_item", {
-        oldContent,
+        id: entryId,
         newContent,
+        password: (await VaultService.passwordFor("edit")) ?? null,
       });
     } catch (error) {
       console.error("Failed to update item:", error);
@@ -96,15 +185,144 @@ export class ClipboardService {
     }
   }
 
-  static async deleteItem(content: string): Promise<void> {
+  /** Replace an image entry with an edited PNG, base64-encoded. */
+  static async updateImageItem(entryId: number, newContent: string): Promise<void> {
+    try {
+      await invoke("update_image_item", {
+        id: entryId,
+        newContent,
+        password: (await VaultService.passwordFor("edit")) ?? null,
+      });
+    } catch (error) {
+      console.error("Failed to update image:", error);
+      throw error;
+    }
+  }
+
+  static async deleteItem(entryId: number, password?: string): Promise<void> {
     try {
-      await invoke("delete_item", { content });
+      await invoke("delete_item", { id: entryId, password: password ?? null });
     } catch (error) {
       console.error("Failed to delete item:", error);
       throw error;
     }
   }
 
+  static async tagItem(id: number, tag: string, remove = false): Promise<string[]> {
+    try {
+      return await invoke<string[]>("tag_item", { id, tag, remove });
+    } catch (error) {
+      console.error("Failed to tag item:", error);
+      throw error;
+    }
+  }
+
+  static async getTags(id: number): Promise<string[]> {
+    try {
+      return await invoke<string[]>("get_tags", { id });
+    } catch (error) {
+      console.error("Failed to get tags:", error);
+      throw error;
+    }
+  }
+
+  /** Every tag in use, alphabetically. */
+  static async listTags(): Promise<string[]> {
+    try {
+      return await invoke<string[]>("list_tags");
+    } catch (error) {
+      console.error("Failed to list tags:", error);
+      return [];
+    }
+  }
+
+  /** Pin an entry, or unpin it if it is. Returns whether it's pinned now. */
+  static async togglePin(id: number): Promise<boolean> {
+    try {
+      return await invoke<boolean>("toggle_pin", { id });
+    } catch (error) {
+      console.error("Failed to toggle pin:", error);
+      throw error;
+    }
+  }
+
+  /** When the entry expires, as a timestamp like `SearchResult.timestamp`,
+   * or null if it doesn't. */
+  static async getItemExpiry(id: number): Promise<number | null> {
+    try {
+      return await invoke<number | null>("get_item_expiry", { id });
+    } catch (error) {
+      console.error("Failed to get expiry:", error);
+      throw error;
+    }
+  }
+
+  /** Delete the entry `ttlSecs` from now, or keep it with null. */
+  static async setItemExpiry(id: number, ttlSecs: number | null): Promise<number | null> {
+    try {
+      const password = ttlSecs === null ? null : await VaultService.passwordFor("set an expiry");
+      return await invoke<number | null>("set_item_expiry", {
+        id,
+        ttlSecs,
+        password: password ?? null,
+      });
+    } catch (error) {
+      console.error("Failed to set expiry:", error);
+      throw error;
+    }
+  }
+
+  /** The entry with `hash` as the results list shows it, or null. Only for
+   * the `item-*` events, which carry a hash; everything else goes by id. */
+  static async getItem(hash: string): Promise<SearchResult | null> {
+    try {
+      return await invoke<SearchResult | null>("get_item", { hash });
+    } catch (error) {
+      console.error("Failed to get item:", error);
+      throw error;
+    }
+  }
+
+  /** The full content of a result listed by its thumbnail, or null. */
+  static async getItemContent(id: number): Promise<string | null> {
+    try {
+      return await invoke<string | null>("get_item_content", { id });
+    } catch (error) {
+      console.error("Failed to get item content:", error);
+      throw error;
+    }
+  }
+
+  /** Like `getItem`, by the entry's id. */
+  static async getItemById(id: number): Promise<SearchResult | null> {
+    try {
+      return await invoke<SearchResult | null>("get_item_by_id", { id });
+    } catch (error) {
+      console.error("Failed to get item:", error);
+      throw error;
+    }
+  }
+
+  /** The note kept with the entry, or null. */
+  static async getItemNote(id: number): Promise<string | null> {
+    try {
+      return await invoke<string | null>("get_item_note", { id });
+    } catch (error) {
+      console.error("Failed to get note:", error);
+      throw error;
+    }
+  }
+
+  /** Keep a note with the entry; null or a blank note removes it. */
+  static async setItemNote(id: number, note: string | null): Promise<string | null> {
+    try {
+      return await invoke<string | null>("set_item_note", { id, note });
+    } catch (error) {
+      console.error("Failed to set note:", error);
+      throw error;
+    }
+  }
+
   static async vaultExists(): Promise<boolean> {
     try {
       return await invoke<boolean>("vault_exists");
@@ -114,9 +332,21 @@ export class ClipboardService {
     }
   }
 
-  static async createVault(password: string, settings: any): Promise<boolean> {
+  static async createVault(
+    password: string,
+    settings: any,
+    passwordHint: string | null,
+    recoveryKey: boolean,
+    securityProfile: SecurityProfile | null = null,
+  ): Promise<CreatedVault> {
     try {
-      return await invoke<boolean>("create_vault", { password, settings });
+      return await invoke<CreatedVault>("create_vault", {
+        password,
+        settings,
+        passwordHint,
+        recoveryKey,
+        securityProfile,
+      });
     } catch (error) {
       console.error("Failed to create vault:", error);
       throw error;
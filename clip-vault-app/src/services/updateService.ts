@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
+import type { UpdateChannel } from "../types";
 
 export class UpdateService {
   static async checkForUpdates(): Promise<string | null> {
@@ -20,6 +21,24 @@ export class UpdateService {
     }
   }
 
+  static async setUpdateChannel(channel: UpdateChannel): Promise<void> {
+    await invoke("set_update_channel", { channel });
+  }
+
+  /** Download the latest release now and install it when the app quits. */
+  static async scheduleInstallOnQuit(): Promise<string | null> {
+    try {
+      return await invoke<string | null>("schedule_update_install");
+    } catch (error) {
+      console.error("Failed to schedule update:", error);
+      throw error;
+    }
+  }
+
+  static async cancelScheduledInstall(): Promise<boolean> {
+    return await invoke<boolean>("cancel_scheduled_update");
+  }
+
   static async listenToUpdateProgress(callback: (progress: number) => void) {
     return await listen<number>("update-progress", (event) => {
       callback(event.payload);
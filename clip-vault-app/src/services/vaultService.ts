@@ -1,5 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings } from "../types";
+import type {
+  AppSettings,
+  DaemonStatus,
+  OnboardingStatus,
+  UnlockPolicy,
+  WindowBehavior,
+} from "../types";
 
 export class VaultService {
   static async checkVaultStatus(): Promise<boolean> {
@@ -12,6 +18,16 @@ export class VaultService {
     }
   }
 
+  /** Push back auto-lock after a search or copy. */
+  static async activityPing(): Promise<boolean> {
+    try {
+      return await invoke<boolean>("activity_ping");
+    } catch (error) {
+      console.error("Activity ping failed:", error);
+      return false;
+    }
+  }
+
   static async unlockVault(password: string): Promise<boolean> {
     try {
       const success = await invoke<boolean>("unlock_vault", { password });
@@ -22,6 +38,62 @@ export class VaultService {
     }
   }
 
+  static async getUnlockPolicy(): Promise<UnlockPolicy> {
+    try {
+      return await invoke<UnlockPolicy>("get_unlock_policy");
+    } catch (error) {
+      console.error("Failed to get unlock policy:", error);
+      return "password";
+    }
+  }
+
+  /**
+   * The password to send with a sensitive command (an edit, an expiry) if
+   * the unlock policy wants it entered again; undefined otherwise.
+   */
+  static async passwordFor(action: string): Promise<string | undefined> {
+    if ((await VaultService.getUnlockPolicy()) !== "keyring_confirm_sensitive") {
+      return undefined;
+    }
+    return window.prompt(`Vault password to ${action}`) ?? undefined;
+  }
+
+  static async getOnboardingStatus(): Promise<OnboardingStatus> {
+    try {
+      return await invoke<OnboardingStatus>("onboarding_status");
+    } catch (error) {
+      console.error("Failed to get onboarding status:", error);
+      throw error;
+    }
+  }
+
+  static async adoptVault(path: string, password: string): Promise<boolean> {
+    try {
+      return await invoke<boolean>("adopt_vault", { path, password });
+    } catch (error) {
+      console.error("Failed to adopt vault:", error);
+      return false;
+    }
+  }
+
+  static async registerDaemon(launchAtLogin: boolean): Promise<void> {
+    try {
+      await invoke("register_daemon", { launchAtLogin });
+    } catch (error) {
+      // Capturing still works for this session; only launch at login failed.
+      console.error("Failed to register daemon:", error);
+    }
+  }
+
+  static async getPasswordHint(): Promise<string | null> {
+    try {
+      return await invoke<string | null>("password_hint");
+    } catch (error) {
+      console.error("Failed to get password hint:", error);
+      return null;
+    }
+  }
+
   static async getSettings(): Promise<AppSettings> {
     try {
       const settings = await invoke<AppSettings>("get_settings");
@@ -41,6 +113,33 @@ export class VaultService {
     }
   }
 
+  static async exportSettings(path: string): Promise<void> {
+    try {
+      await invoke("export_settings", { path });
+    } catch (error) {
+      console.error("Failed to export settings:", error);
+      throw error;
+    }
+  }
+
+  static async importSettings(path: string): Promise<AppSettings> {
+    try {
+      return await invoke<AppSettings>("import_settings", { path });
+    } catch (error) {
+      console.error("Failed to import settings:", error);
+      throw error;
+    }
+  }
+
+  static async setWindowBehavior(behavior: WindowBehavior): Promise<void> {
+    try {
+      await invoke("set_window_behavior", { behavior });
+    } catch (error) {
+      console.error("Failed to set window behavior:", error);
+      throw error;
+    }
+  }
+
   static async startDaemon(): Promise<void> {
     try {
       await invoke("start_daemon");
@@ -59,13 +158,21 @@ export class VaultService {
     }
   }
 
-  static async getDaemonStatus(): Promise<boolean> {
+  static async takePendingSearch(): Promise<string | null> {
     try {
-      const isRunning = await invoke<boolean>("daemon_status");
-      return isRunning;
+      return await invoke<string | null>("take_pending_search");
+    } catch (error) {
+      console.error("Failed to get pending search:", error);
+      return null;
+    }
+  }
+
+  static async getDaemonStatus(): Promise<DaemonStatus | null> {
+    try {
+      return await invoke<DaemonStatus>("daemon_status");
     } catch (error) {
       console.error("Failed to get daemon status:", error);
-      return false;
+      return null;
     }
   }
 }
\ No newline at end of file
@@ -1,21 +1,272 @@
+/** A color, each channel and the alpha from 0 to 255. */
+export interface Rgba {
+  r: number;
+  g: number;
+  b: number;
+  a: number;
+}
+
+export interface ItemStats {
+  bytes: number;
+  lines: number;
+  words: number;
+}
+
 export interface SearchResult {
   id: string;
+  /** The vault's id for the entry, for deleting and editing it. Null for
+   * entries from other profiles or backups. */
+  entry_id?: number | null;
+  hash: string;
   content: string;
   timestamp: number;
   content_type: string;
+  /** What the entry holds: "text", "url", "email", "color", "json",
+   * "path", "code", "otp", "image" or "files". */
+  kind: string;
+  image_info?: string | null;
+  /** Base64 PNG shown in the list in place of a large image. */
+  thumbnail?: string | null;
+  /** The color a color entry spells, shown as a swatch. */
+  color?: Rgba | null;
+  /** Size, line and word counts recorded with the entry. */
+  stats: ItemStats;
+  /** The stats as "3.2 KB, 120 lines", formatted by the backend. */
+  stats_label: string;
+  /** Relative capture time ("5m ago") formatted by the backend. */
+  time_label: string;
+  /** The entry in words, for screen readers. */
+  description: string;
+  /** What the results list shows of a text entry, cut by the backend. */
+  preview?: TextPreview | null;
+  /** The app the entry was copied from, and its window title. */
+  source_app?: string | null;
+  window_title?: string | null;
+  /** The vault profile the entry came from, in searches across profiles. */
+  profile?: string;
+  /** Pinned above the history and out of pruning. */
+  pinned: boolean;
+}
+
+/** Result of `search_all_profiles`. */
+export interface ProfileSearch {
+  results: SearchResult[];
+  /** Profiles left out because they couldn't be unlocked. */
+  locked: string[];
+}
+
+/** The start of a text entry, cut at a word boundary. */
+export interface TextPreview {
+  lines: string[];
+  truncated: boolean;
+  total_lines: number;
+  total_chars: number;
+  /** Lines not shown at all, for a "+42 more lines" note. */
+  more_lines: number;
+}
+
+/** Payload of `search-selection-changed`. */
+export interface SelectionChanged {
+  index: number;
+  hash: string;
+}
+
+/** A screen-reader description from `describe_entries`. */
+export interface DescribedEntry {
+  hash: string;
+  kind: string;
+  size: string;
+  age: string | null;
+  first_line: string | null;
+  /** The whole description as one sentence. */
+  text: string;
+}
+
+/** One hour of the timeline tab, in local time. */
+export interface TimelineGroup {
+  hour: number;
+  items: SearchResult[];
+}
+
+/** Why `get_related` considers two entries related. */
+export type Relation = "same_domain" | "same_context" | "similar_text";
+
+export interface RelatedResult extends SearchResult {
+  relations: Relation[];
+}
+
+export interface WindowBehavior {
+  show_in_taskbar: boolean;
+  always_on_top: boolean;
+  hide_on_focus_loss: boolean;
 }
 
 export interface AppSettings {
   poll_interval_ms: number;
   vault_path: string;
   auto_lock_minutes: number;
+  search_window: WindowBehavior;
+  update_channel: UpdateChannel;
+  install_updates_on_quit: boolean;
+  speak_on_copy: boolean;
+  /** Record the app and window title each capture came from. */
+  record_source_app: boolean;
+  /** "whitespace" evens out line endings and trailing whitespace of
+   * captured text so copies from different editors are one entry. */
+}
+
+/** Rules `preview_retention` checks, as in the shared config's
+ * `retention`; `null` leaves a rule off. Pinned entries are never pruned. */
+export interface RetentionPolicy {
+  text_max_age_secs: number | null;
+  image_max_age_secs: number | null;
+  otp_max_age_secs: number | null;
+  max_items: number | null;
+  max_size_mb: number | null;
+  keep_tags?: Record<string, number | null>;
+}
+
+export type UpdateChannel = "stable" | "beta";
+
+export interface ExistingVault {
+  path: string;
+  size_bytes: number;
+  password_hint?: string | null;
+  has_recovery_key: boolean;
+}
+
+export interface PermissionCheck {
+  permission: "clipboard" | "accessibility" | "notifications";
+  status: "granted" | "denied" | "not_determined" | "not_applicable";
+  detail: string;
+  settings_url?: string | null;
+}
+
+export interface OnboardingStatus {
+  vault_path: string;
+  vault_exists: boolean;
+  existing_vaults: ExistingVault[];
+  permissions: PermissionCheck[];
+  launch_at_login: boolean;
+}
+
+/** Encryption settings picked when a vault is created; see `create_vault`. */
+export type SecurityProfile = "fast" | "balanced" | "paranoid";
+
+export interface CreatedVault {
+  created: boolean;
+  recovery_key?: string | null;
+}
+
+/** One time an entry was put on the clipboard; see `get_copy_events`. */
+export interface CopyEvent {
+  /** Nanoseconds since the Unix epoch. */
+  timestamp: number;
+  kind: "captured" | "recopied" | "copied_out";
+}
+
+/** Ways `copy_as` can reshape a text entry. */
+export type CopyFormat =
+  | "json_minified"
+  | "json_pretty"
+  | "shell_escaped"
+  | "markdown_code_block"
+  | "color_hex"
+  | "color_rgb"
+  | "color_hsl";
+
+/** Where the vault password comes from; see `get_unlock_policy`. */
+export type UnlockPolicy = "password" | "keyring" | "keyring_confirm_sensitive";
+
+export interface Action {
+  id: string;
+  title: string;
+  /** e.g. "Mod+Shift+M"; Mod is Cmd on macOS and Ctrl elsewhere. */
+  keys: string;
+  needs_item: boolean;
+  text_only: boolean;
+  /** The one kind of entry it applies to, e.g. "color". */
+  kind: string | null;
+}
+
+/** Payload of the `monitor-failed` event. */
+export interface MonitorFailure {
+  error: string;
+  consecutive_failures: number;
+  /** Null once the monitor has given up. */
+  retry_in_ms: number | null;
+}
+
+/** One run of clipboard polls that came to the same outcome. */
+export interface CaptureDecision {
+  timestamp: number;
+  outcome:
+    | "unchanged"
+    | "empty"
+    | "backend_error"
+    | "skipped"
+    | "duplicate"
+    | "captured"
+    | "failed";
+  /** Set for `backend_error` and `failed`. */
+  error?: string;
+  /** Set for `skipped`. */
+  reason?: string;
+  /** Set for `captured`. */
+  image?: boolean;
+  bytes?: number;
+  polls: number;
+  max_poll_micros: number;
+}
+
+/** Result of `daemon_status`. */
+export interface DaemonStatus {
+  running: boolean;
+  last_error: string | null;
+  /** Whether the app was started with `--trace-capture`. */
+  trace_capture: boolean;
+  /** Latest capture decisions, oldest first. */
+  decisions: CaptureDecision[];
+  /** Latest vault health notices, oldest first. */
+  health: VaultHealth[];
+}
+
+/** Payload of the `vault-health` event. */
+export interface VaultHealth {
+  level: "info" | "warning" | "error";
+  message: string;
+  /** Seconds since the Unix epoch. */
+  timestamp: number;
+  kind: "write_failed" | "write_recovered" | "unreadable_entries" | "pruned";
+  /** Set for `write_failed`. */
+  error?: string;
+  disk_full?: boolean;
+  /** Set for `unreadable_entries`. */
+  count?: number;
+  /** Set for `pruned`. */
+  removed?: number;
+  reason?: "retention" | "expired" | "size_cap" | "archived";
+}
+
+/** "confirm_delete" asks for the vault password before a delete. */
+export type PaletteMode = "actions" | "tag" | "confirm_delete";
+
+export interface CommandPaletteProps {
+  mode: PaletteMode | null;
+  selectedItem: SearchResult | null;
+  onRun: (action: Action) => void;
+  onTag: (tag: string) => void;
+  onConfirmDelete: (password: string) => void;
+  onClose: () => void;
 }
 
 export interface PreviewPaneProps {
   selectedItem: SearchResult | null;
   onCopy: (content: string, contentType: string) => void;
   onEdit?: () => void;
-  onDelete?: (content: string) => Promise<void>;
+  /** Bumped to start editing from the keyboard or the command palette. */
+  editRequest?: number;
+  onDelete?: (entryId: number) => Promise<void>;
 }
 
 export interface SearchInputProps {
@@ -25,6 +276,11 @@ export interface SearchInputProps {
   resultsCount: number;
   searching: boolean;
   setSearching: (searching: boolean) => void;
+  /** Search every vault profile, not just the vault. */
+  allProfiles: boolean;
+  onAllProfilesChange: (allProfiles: boolean) => void;
+  /** Profiles the last search across profiles couldn't unlock. */
+  lockedProfiles: string[];
 }
 
 export interface ResultsListProps {
@@ -36,7 +292,6 @@ export interface ResultsListProps {
   loadingMore: boolean;
   hasMore: boolean;
   onLoadMore: () => void;
-  formatTimestamp: (timestamp: number) => string;
   getWindowedContent: (content: string, query: string, maxLength?: number) => string;
   highlightText: (text: string, query: string) => React.ReactNode;
 }
@@ -44,6 +299,7 @@ export interface ResultsListProps {
 export interface PasswordPromptProps {
   isVisible: boolean;
   password: string;
+  hint?: string | null;
   onPasswordChange: (password: string) => void;
   onUnlock: () => void;
   onCancel: () => void;
@@ -0,0 +1,48 @@
+import type { Action, SearchResult } from "../types";
+import { isEditableText } from "./textUtils";
+
+const isMac = navigator.userAgent.includes("Mac");
+
+/** Whether a key event is the chord an action's `keys` describe. */
+export const matchesKeys = (e: KeyboardEvent, keys: string): boolean => {
+  // Split on "+" but keep a literal "+" key, e.g. "Mod++".
+  const parts = keys.split(/\+(?!$)/);
+  const key = parts.pop() ?? "";
+  const wants = new Set(parts);
+  const mod = isMac ? e.metaKey : e.ctrlKey;
+
+  return (
+    mod === wants.has("Mod") &&
+    e.shiftKey === wants.has("Shift") &&
+    e.altKey === wants.has("Alt") &&
+    e.key.toLowerCase() === key.toLowerCase()
+  );
+};
+
+/** `keys` as shown to the user, e.g. "⌘ ⇧ M" or "Ctrl+Shift+M". */
+export const formatKeys = (keys: string): string => {
+  const parts = keys.split(/\+(?!$)/);
+  if (!isMac) {
+    return parts.map((part) => (part === "Mod" ? "Ctrl" : part)).join("+");
+  }
+  const symbols: Record<string, string> = {
+    Mod: "⌘",
+    Shift: "⇧",
+    Alt: "⌥",
+    Enter: "↩",
+    Backspace: "⌫",
+    Escape: "Esc",
+  };
+  return parts.map((part) => symbols[part] ?? part).join(" ");
+};
+
+/** Whether `action` can run with `item` selected. */
+export const actionApplies = (
+  action: Action,
+  item: SearchResult | null,
+): boolean => {
+  if (!action.needs_item) return true;
+  if (!item) return false;
+  if (action.kind != null && item.kind !== action.kind) return false;
+  return !action.text_only || isEditableText(item.content_type);
+};
@@ -1,20 +1,5 @@
 import React from "react";
 
-export const formatTimestamp = (timestamp: number): string => {
-  const date = new Date(timestamp / 1_000_000);
-  const now = new Date();
-  const diffMs = now.getTime() - date.getTime();
-  const diffMins = Math.floor(diffMs / (1000 * 60));
-  const diffHours = Math.floor(diffMs / (1000 * 60 * 60));
-  const diffDays = Math.floor(diffMs / (1000 * 60 * 60 * 24));
-
-  if (diffMins < 1) return "just now";
-  if (diffMins < 60) return `${diffMins}m ago`;
-  if (diffHours < 24) return `${diffHours}h ago`;
-  if (diffDays < 7) return `${diffDays}d ago`;
-  return date.toLocaleDateString();
-};
-
 export const getWindowedContent = (content: string, query: string, maxLength: number = 100): string => {
   if (!query.trim()) {
     // No search query, just truncate normally
@@ -90,9 +75,10 @@ export const highlightText = (text: string, query: string): React.ReactNode => {
   );
 };
 
-export const getContentStats = (content: string) => {
-  const lines = content.split('\n').length;
-  const chars = content.length;
-  const words = content.trim().split(/\s+/).length;
-  return { lines, chars, words };
-};
\ No newline at end of file
+/** Content type of copied files, whose content is one path per line. */
+export const FILES_CONTENT_TYPE = "application/x-clip-vault-files";
+
+/** Whether an entry is text that can be edited or reshaped, rather than an
+ * image or copied files. */
+export const isEditableText = (contentType: string): boolean =>
+  contentType.startsWith("text") && contentType !== FILES_CONTENT_TYPE;
@@ -0,0 +1,86 @@
+use clip_vault_core::{osc52, ClipboardItem, Error, Representation, Result, RichText};
+use clip_vault_monitor::{ArboardClipboard, ClipboardError, ClipboardProvider};
+use std::io::{self, Write};
+
+/// Copy text to the clipboard. On a remote or display-less host this emits an
+/// OSC 52 sequence so the local terminal sets its clipboard instead.
+pub fn copy_text(text: &str) -> Result<()> {
+    if prefer_osc52() {
+        return copy_osc52(text);
+    }
+
+    let item = ClipboardItem::Text(text.to_string());
+    match ArboardClipboard::new().and_then(|mut clipboard| clipboard.set(&item)) {
+        Ok(()) => Ok(()),
+        // No usable system clipboard; the terminal may still have one.
+        Err(ClipboardError::Unsupported(_)) => copy_osc52(text),
+        Err(e) => Err(Error::Io(io::Error::other(e))),
+    }
+}
+
+/// Copy an entry to the clipboard: text as [`copy_text`] does, images
+/// decoded from their stored PNG. Images have no OSC 52 fallback.
+pub fn copy_item(item: &ClipboardItem) -> Result<()> {
+    copy_formatted(item, &RichText::default(), &[])
+}
+
+/// [`copy_item`], with the HTML and RTF flavours in `rich` and the other
+/// forms the copy came in put back alongside it. OSC 52 only carries the
+/// plain text.
+pub fn copy_formatted(
+    item: &ClipboardItem,
+    rich: &RichText,
+    representations: &[Representation],
+) -> Result<()> {
+    if let ClipboardItem::Text(text) = item {
+        if (rich.is_empty() && representations.is_empty()) || prefer_osc52() {
+            return copy_text(text);
+        }
+    }
+    ArboardClipboard::new()
+        .and_then(|mut clipboard| clipboard.set_bundle(item, rich, &[], representations))
+        .map_err(|e| Error::Io(io::Error::other(e)))
+}
+
+/// [`copy_formatted`], marked so clip-vault's monitors skip it rather than
+/// adding it to the history. An OSC 52 copy lands on another machine's
+/// clipboard, which the marker doesn't reach.
+pub fn copy_unrecorded(
+    item: &ClipboardItem,
+    rich: &RichText,
+    representations: &[Representation],
+) -> Result<()> {
+    clip_vault_monitor::suppress_capture(item)?;
+    copy_formatted(item, rich, representations)
+}
+
+/// Check that the system clipboard can be read, for `clip-vault doctor`.
+pub fn probe() -> std::result::Result<(), String> {
+    match ArboardClipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+        // An empty or image-only clipboard still proves access.
+        Ok(_) | Err(ClipboardError::Empty) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn copy_osc52(text: &str) -> Result<()> {
+    let seq = osc52::encode(text, std::env::var_os("TMUX").is_some());
+    let mut stdout = io::stdout();
+    stdout.write_all(seq.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// `CLIP_VAULT_OSC52=1` forces OSC 52, `=0` disables it; otherwise it is used
+/// inside SSH sessions and on Linux hosts without a display server.
+fn prefer_osc52() -> bool {
+    match std::env::var("CLIP_VAULT_OSC52").as_deref() {
+        Ok("1") => return true,
+        Ok("0") => return false,
+        _ => {}
+    }
+    let var = |name| std::env::var_os(name).is_some();
+    var("SSH_TTY")
+        || var("SSH_CONNECTION")
+        || (cfg!(target_os = "linux") && !var("DISPLAY") && !var("WAYLAND_DISPLAY"))
+}
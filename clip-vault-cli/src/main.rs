@@ -1,9 +1,21 @@
 use clap::{Parser, Subcommand};
-use clip_vault_core::{Error, Result, SqliteVault, Vault};
+use clip_vault_core::{
+    config_path, files_text, format_size, tr, tr_with, ClipboardItem, ClipboardItemWithTimestamp,
+    Config, DryRun, DuplicatePolicy, EntryDescription, Error, ExportFilter, ExportFormat,
+    HealthLevel, HistogramBucket, ImportSource, ItemKind, Language, LinePattern, Msg, MultiVault,
+    PermissionCheck, PermissionStatus, Remote, Result, RetentionPolicy, RichText, SearchQuery,
+    SecurityProfile, SensitiveAction, SourceApp, SqliteVault, Timestamp, UnlockPolicy, Vault,
+    VaultProfile, WeeklyDigest, DEFAULT_PROFILE,
+};
 use dialoguer::Password;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::time::{Duration as StdDuration, SystemTime, UNIX_EPOCH};
 
+mod clipboard;
+mod pick;
+mod replay;
+mod tmux;
 mod tui;
 
 #[derive(Parser)]
@@ -20,8 +32,21 @@ struct Cli {
     /// Forget any cached password and exit.
     #[arg(long)]
     forget: bool,
+
+    /// Incognito session: capture into a throwaway in-memory vault and browse
+    /// it in the TUI. Nothing touches disk and everything is gone on exit.
+    #[arg(long)]
+    ephemeral: bool,
+
+    /// Open this vault file read-only instead of the live vault, e.g. a
+    /// backup snapshot. Commands that would change it fail.
+    #[arg(long, global = true, value_name = "PATH")]
+    db: Option<std::path::PathBuf>,
 }
 
+/// The vault file given with `--db`, opened read-only by every command.
+static SNAPSHOT: std::sync::OnceLock<std::path::PathBuf> = std::sync::OnceLock::new();
+
 #[derive(Subcommand)]
 enum Commands {
     /// Print the latest clipboard entry
@@ -31,6 +56,18 @@ enum Commands {
         /// Number of entries to show (default: all)
         #[arg(short, long)]
         count: Option<usize>,
+        /// Only show entries copied while working in this repo or directory
+        #[arg(long)]
+        context: Option<String>,
+        /// Only show entries with this tag
+        #[arg(long, conflicts_with = "context")]
+        tag: Option<String>,
+        /// Print the entries as a JSON array, newest first
+        #[arg(long)]
+        json: bool,
+        /// Show everything copied on this day, oldest first by hour
+        #[arg(long, value_name = "YYYY-MM-DD", conflicts_with_all = ["count", "context", "tag"])]
+        on: Option<chrono::NaiveDate>,
     },
     /// Search clipboard entries for a text pattern
     Search {
@@ -39,9 +76,600 @@ enum Commands {
         /// Maximum number of results to show (default: all matches)
         #[arg(short, long)]
         count: Option<usize>,
+        /// Also search entries moved to the archive
+        #[arg(long)]
+        include_archive: bool,
+        /// Search every vault profile in the config too, unlocking each
+        #[arg(long, conflicts_with = "include_archive")]
+        all_profiles: bool,
+        /// Only show matches with this tag
+        #[arg(long, conflicts_with_all = ["include_archive", "all_profiles"])]
+        tag: Option<String>,
+    },
+    /// Print the lines inside entries that match a pattern, as `#entry:line:text`
+    Grep {
+        /// Regular expression to look for
+        pattern: String,
+        /// Match case-insensitively
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Treat the pattern as a plain string
+        #[arg(short = 'F', long)]
+        fixed_strings: bool,
+    },
+    /// Describe the latest entries in plain words, for screen readers
+    Describe {
+        /// Number of entries to describe
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
     },
     /// Launch interactive TUI (Terminal User Interface)
     Tui,
+    /// Print new clipboard entries as they are captured, and problems
+    /// storing them to stderr
+    Watch,
+    /// Fuzzy-pick an entry, ranked by frecency, and copy it
+    Pick {
+        /// Print the selection to stdout instead of copying it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Copy the entries captured in a time window again, oldest first and
+    /// one at a time, to repeat a sequence of pastes
+    Replay {
+        /// Start of the window: HH:MM today, or YYYY-MM-DD HH:MM
+        #[arg(long, value_parser = replay::parse_time)]
+        from: chrono::DateTime<chrono::Local>,
+        /// End of the window (default: now)
+        #[arg(long, value_parser = replay::parse_time)]
+        to: Option<chrono::DateTime<chrono::Local>>,
+        /// Move to the next entry after this long (e.g. 3s) instead of
+        /// waiting for a key
+        #[arg(long, value_parser = humantime::parse_duration)]
+        interval: Option<StdDuration>,
+    },
+    /// Copy an entry to the clipboard, images included
+    Copy {
+        /// Entry number as in `clip-vault list`, or the start of its hash.
+        /// Left out, text is read from stdin instead
+        entry: Option<String>,
+        /// Write the entry to this file instead (a PNG for images), for hosts
+        /// without a clipboard
+        #[arg(long, value_name = "FILE")]
+        out: Option<std::path::PathBuf>,
+        /// Keep the copy out of the history, e.g. for a generated password
+        #[arg(long)]
+        no_store: bool,
+    },
+    /// Load the latest entries into tmux paste buffers (clip-vault-0 is newest)
+    TmuxSync {
+        /// Number of entries to load
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
+        /// Keep running and resync when new entries are captured
+        #[arg(short, long)]
+        watch: bool,
+        /// How often to check for new entries when watching
+        #[arg(long, value_parser = humantime::parse_duration, default_value = "1s")]
+        interval: StdDuration,
+    },
+    /// Capture OSC 52 clipboard writes sent to a FIFO (for headless hosts)
+    Ingest {
+        /// FIFO to read from; created if it doesn't exist
+        fifo: std::path::PathBuf,
+    },
+    /// Move entries that can no longer be decoded out of the history
+    Quarantine,
+    /// Delete entries older than the given age, per content category
+    Prune(PruneArgs),
+    /// Show vault statistics
+    Stats {
+        /// Print capture counts per hour or day
+        #[arg(long, value_enum)]
+        histogram: Option<HistogramArg>,
+        /// How far back the histogram goes (default: 30d)
+        #[arg(long, value_parser = humantime::parse_duration)]
+        since: Option<StdDuration>,
+    },
+    /// Summarise recent copying: top domains, busiest hours, largest
+    /// entries and copies that look like secrets
+    Report {
+        /// Cover the last seven days, today included
+        #[arg(long, required = true)]
+        week: bool,
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: ReportFormat,
+        /// File to write to (default: stdout)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Shrink the vault file after deleting or pruning entries
+    Compact,
+    /// Move old entries to the archive, where only `search --include-archive`
+    /// finds them
+    Archive {
+        /// Age past which entries are archived, e.g. 90d (default: the
+        /// `settings archive` age)
+        #[arg(long, value_parser = humantime::parse_duration)]
+        older_than: Option<StdDuration>,
+    },
+    /// Report the active repo or directory for new captures (for shell hooks)
+    Context {
+        #[command(subcommand)]
+        action: ContextCommand,
+    },
+    /// Manage the tamper-evident audit log
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommand,
+    },
+    /// Show or set what re-copying an entry already in the history does
+    Duplicates {
+        /// `bump` moves it to the top; `keep` leaves it in place; `record`
+        /// leaves it in place and records when it was last copied
+        #[arg(value_enum)]
+        policy: Option<DuplicateArg>,
+    },
+    /// Export entries as JSON Lines, CSV or Markdown, oldest first
+    Export(ExportArgs),
+    /// Add or remove a tag on an entry (numbered as in `clip-vault list`)
+    Tag {
+        index: usize,
+        tag: String,
+        /// Remove the tag instead of adding it
+        #[arg(short, long)]
+        remove: bool,
+    },
+    /// Pin an entry so it stays at the top of the TUI and app and is never
+    /// pruned
+    Pin {
+        /// Entry number as in `clip-vault list`, or the start of its hash
+        entry: String,
+        /// Unpin it instead
+        #[arg(short, long)]
+        remove: bool,
+    },
+    /// Delete an entry once an age like 1h or 7d has passed, or keep it
+    /// again with `never`
+    Expire {
+        /// Entry number as in `clip-vault list`, or the start of its hash
+        entry: String,
+        #[arg(value_name = "AGE|never")]
+        after: String,
+    },
+    /// Keep a note with an entry, e.g. why it was saved, or print it.
+    /// Searches match notes too
+    Note {
+        /// Entry number as in `clip-vault list`, or the start of its hash
+        entry: String,
+        /// The note; print the current one when left out
+        note: Option<String>,
+        /// Remove the note
+        #[arg(short, long, conflicts_with = "note")]
+        clear: bool,
+    },
+    /// Print one entry in full, with its id, hash, kind, size, source, tags
+    /// and note
+    Show {
+        /// Entry number as in `clip-vault list`, or the start of its hash
+        #[arg(required_unless_present = "id")]
+        entry: Option<String>,
+        /// The entry's id instead, as `list --json` prints it
+        #[arg(long, conflicts_with = "entry")]
+        id: Option<i64>,
+        /// Print the entry as JSON, like `list --json`
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write one entry to an encrypted file to hand to someone, and print the
+    /// passphrase that opens it
+    Share {
+        /// Entry number as in `clip-vault list`, or the start of its hash
+        entry: String,
+        /// File to write (default: clip-vault-share-<hash>.cvshare)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Add the entry from a file made with `clip-vault share`
+    Receive { path: std::path::PathBuf },
+    /// Add the history of another clipboard manager, or of `clip-vault
+    /// export`, skipping entries already in the vault
+    Import {
+        #[arg(long, value_enum)]
+        from: ImportArg,
+        /// Ditto's Ditto.db, Clipy's data directory, Maccy's Storage.sqlite
+        /// or an export in JSON Lines
+        path: std::path::PathBuf,
+        /// Count what would be imported without storing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write an encrypted snapshot of the vault to a file or remote target
+    Backup {
+        /// File to write the snapshot to
+        #[arg(required_unless_present = "remote", conflicts_with = "remote")]
+        path: Option<std::path::PathBuf>,
+        /// Upload to an S3 bucket or a webdav share (s3://, webdav:// or https://
+        /// URL); credentials come from the shared config file
+        #[arg(long, value_name = "URL")]
+        remote: Option<String>,
+    },
+    /// Replace the vault with a snapshot from a file or remote target
+    Restore {
+        /// Snapshot file to restore
+        #[arg(required_unless_present = "remote", conflicts_with = "remote")]
+        path: Option<std::path::PathBuf>,
+        /// Download from an S3 bucket or a webdav share
+        #[arg(long, value_name = "URL")]
+        remote: Option<String>,
+    },
+    /// Create the vault (or update an existing one) with a password hint and
+    /// a recovery key
+    Setup {
+        /// Hint shown at the password prompt; stored unencrypted
+        #[arg(long)]
+        hint: Option<String>,
+        /// Don't generate a recovery key
+        #[arg(long)]
+        no_recovery_key: bool,
+        /// Encryption settings for a new vault; they can't be changed later
+        #[arg(long, value_enum)]
+        security_profile: Option<SecurityProfileArg>,
+    },
+    /// Set a new password using the recovery key
+    Recover,
+    /// Check the permissions capturing and pasting depend on
+    Doctor,
+    /// Print where the config, vault, caches, logs and spill files are kept
+    Paths,
+    /// Lock now: forget the cached password and ask the app to lock too
+    Lock,
+    /// Remove the app's launch at login entry, and forget the cached and
+    /// keyring passwords
+    Uninstall {
+        /// Also overwrite and delete the vault, its archive and sidecars
+        #[arg(long)]
+        shred: bool,
+        /// Don't ask before shredding
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Entries kept out of the history that need the password re-entered
+    Protected {
+        #[command(subcommand)]
+        action: ProtectedCommand,
+    },
+    /// Copy the shared settings (backup targets and app settings) to or from
+    /// a file, to set up another machine the same way
+    Settings {
+        #[command(subcommand)]
+        action: SettingsCommand,
+    },
+    /// Settle entries edited differently on machines sharing the vault
+    Sync {
+        #[command(subcommand)]
+        action: SyncCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum SyncCommand {
+    /// List entries edited into different versions on different devices
+    Conflicts,
+    /// Keep one version of a conflicting entry and delete the others
+    Resolve {
+        /// Start of the hash of the version to keep, as listed by `conflicts`
+        version: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SettingsCommand {
+    /// Write the settings to a file
+    Export { path: std::path::PathBuf },
+    /// Replace the settings with those from an exported file
+    Import { path: std::path::PathBuf },
+    /// Choose how the vault is unlocked, for the CLI and the app
+    Unlock { policy: UnlockArg },
+    /// Show or set the language of messages, for the CLI and the app
+    Language {
+        #[arg(value_enum)]
+        language: Option<LanguageArg>,
+    },
+    /// Show or set the age, e.g. 90d, past which the app moves entries to
+    /// the archive; `off` keeps them in the vault
+    Archive {
+        #[arg(value_name = "AGE|off")]
+        after: Option<String>,
+    },
+    /// Show or set the retention rules `prune` and `--ephemeral` apply
+    Retention {
+        /// Maximum age of text, files and images, e.g. 90d; one-time codes
+        /// keep a shorter age of their own
+        #[arg(long, value_name = "AGE|off")]
+        max_age: Option<String>,
+        /// Maximum number of entries
+        #[arg(long, value_name = "COUNT|off")]
+        max_items: Option<String>,
+        /// Maximum megabytes of entries
+        #[arg(long, value_name = "MB|off")]
+        max_size: Option<String>,
+    },
+    /// Show or set how long captures from an app, e.g. a password manager,
+    /// are kept before they're deleted; `off` keeps them like any other
+    AppTtl {
+        /// Part of the app's name, matched ignoring case
+        app: Option<String>,
+        #[arg(value_name = "AGE|off", requires = "app")]
+        ttl: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProtectedCommand {
+    /// Move an entry (numbered as in `clip-vault list`) into the protected area
+    Add { index: usize },
+    /// List protected entries
+    List,
+    /// Copy a protected entry (numbered as in `clip-vault protected list`)
+    Copy { index: usize },
+    /// Move a protected entry back into the history
+    Remove { index: usize },
+}
+
+#[derive(Subcommand)]
+enum ContextCommand {
+    /// Set the current context (default: the enclosing git repo, else the working directory)
+    Set { path: Option<String> },
+    /// Clear the current context
+    Clear,
+    /// Print the current context
+    Show,
+}
+
+#[derive(Subcommand)]
+enum AuditCommand {
+    /// Start recording inserts, updates, deletes and exports
+    Enable,
+    /// Stop recording (existing records are kept)
+    Disable,
+    /// Check that no audit record has been altered or removed
+    Verify,
+    /// Print the audit log
+    Log,
+}
+
+#[derive(clap::Args)]
+struct PruneArgs {
+    /// Maximum age of text entries (e.g. 90d)
+    #[arg(long, value_parser = humantime::parse_duration)]
+    text_max_age: Option<StdDuration>,
+    /// Maximum age of image entries (e.g. 7d)
+    #[arg(long, value_parser = humantime::parse_duration)]
+    image_max_age: Option<StdDuration>,
+    /// Maximum age of one-time codes (e.g. 10m)
+    #[arg(long, value_parser = humantime::parse_duration)]
+    otp_max_age: Option<StdDuration>,
+    /// Keep at most this many entries, deleting the oldest
+    #[arg(long, value_name = "COUNT")]
+    max_items: Option<usize>,
+    /// Keep at most this many megabytes of entries, deleting the oldest
+    #[arg(long, value_name = "MB")]
+    max_size: Option<u64>,
+    /// Never prune entries with this tag; `snippets=50` exempts only the
+    /// tag's 50 newest entries. Repeatable.
+    #[arg(long = "keep-tag", value_name = "TAG[=COUNT]", value_parser = parse_keep_tag)]
+    keep_tags: Vec<(String, Option<usize>)>,
+    /// List what would be pruned without deleting anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl PruneArgs {
+    /// The rules given on the command line, or without any, the configured
+    /// ones, still exempting the tags kept here.
+    fn policy(&self) -> Result<RetentionPolicy> {
+        let policy = RetentionPolicy {
+            text_max_age_secs: self.text_max_age.map(|d| d.as_secs()),
+            image_max_age_secs: self.image_max_age.map(|d| d.as_secs()),
+            otp_max_age_secs: self.otp_max_age.map(|d| d.as_secs()),
+            max_items: self.max_items,
+            max_size_mb: self.max_size,
+            keep_tags: self.keep_tags.iter().cloned().collect(),
+        };
+        if !policy.is_empty() {
+            return Ok(policy);
+        }
+        let mut configured = Config::load()?.retention;
+        configured.keep_tags.extend(policy.keep_tags);
+        Ok(configured)
+    }
+}
+
+#[derive(clap::Args)]
+struct ExportArgs {
+    /// File to write to (default: stdout)
+    #[arg(short, long)]
+    output: Option<std::path::PathBuf>,
+    #[arg(long, value_enum, default_value = "jsonl")]
+    format: ExportFormatArg,
+    /// Save images as PNG files in this directory and export their
+    /// paths instead of base64
+    #[arg(long, value_name = "DIR")]
+    images_dir: Option<std::path::PathBuf>,
+    /// Leave out one-time codes and entries containing detected secrets
+    #[arg(long)]
+    exclude_sensitive: bool,
+    /// Include one-time codes, which are left out by default
+    #[arg(long, conflicts_with = "exclude_sensitive")]
+    include_otp: bool,
+    /// Only export entries with this tag
+    #[arg(long, value_name = "TAG")]
+    only_tag: Option<String>,
+    /// Mask matching content in exported entries
+    #[arg(long, value_enum)]
+    redact: Option<RedactArg>,
+}
+
+impl ExportArgs {
+    fn filter(&self) -> ExportFilter {
+        ExportFilter {
+            exclude_sensitive: self.exclude_sensitive,
+            include_otp: self.include_otp,
+            only_tag: self.only_tag.clone(),
+            redact_secrets: matches!(self.redact, Some(RedactArg::Secrets)),
+            image_dir: self.images_dir.clone(),
+            output_dir: self.output.as_deref().map(|path| match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => std::path::PathBuf::from("."),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum RedactArg {
+    /// API tokens, private keys and password assignments
+    Secrets,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportFormatArg {
+    /// One JSON object per line
+    Jsonl,
+    Csv,
+    /// A document with a section per entry
+    Markdown,
+}
+
+impl From<ExportFormatArg> for ExportFormat {
+    fn from(arg: ExportFormatArg) -> Self {
+        match arg {
+            ExportFormatArg::Jsonl => Self::JsonLines,
+            ExportFormatArg::Csv => Self::Csv,
+            ExportFormatArg::Markdown => Self::Markdown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ImportArg {
+    Ditto,
+    Clipy,
+    Maccy,
+    /// JSON Lines written by `clip-vault export`
+    Json,
+}
+
+impl From<ImportArg> for ImportSource {
+    fn from(arg: ImportArg) -> Self {
+        match arg {
+            ImportArg::Ditto => Self::Ditto,
+            ImportArg::Clipy => Self::Clipy,
+            ImportArg::Maccy => Self::Maccy,
+            ImportArg::Json => Self::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum HistogramArg {
+    Hour,
+    Day,
+}
+
+impl From<HistogramArg> for HistogramBucket {
+    fn from(arg: HistogramArg) -> Self {
+        match arg {
+            HistogramArg::Hour => Self::Hour,
+            HistogramArg::Day => Self::Day,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum SecurityProfileArg {
+    /// Quicker to unlock, weaker against password guessing
+    Fast,
+    /// The standard settings
+    Balanced,
+    /// Four times the key derivation work of balanced
+    Paranoid,
+}
+
+impl From<SecurityProfileArg> for SecurityProfile {
+    fn from(arg: SecurityProfileArg) -> Self {
+        match arg {
+            SecurityProfileArg::Fast => Self::Fast,
+            SecurityProfileArg::Balanced => Self::Balanced,
+            SecurityProfileArg::Paranoid => Self::Paranoid,
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum DuplicateArg {
+    Bump,
+    Keep,
+    Record,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum UnlockArg {
+    /// Ask for the password every time
+    Password,
+    /// Use the password saved in the OS keyring
+    Keyring,
+    /// Use the keyring, but ask again before exports and deletions
+    KeyringConfirm,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum LanguageArg {
+    /// Follow the system locale
+    System,
+    En,
+    De,
+    Es,
+    Fr,
+}
+
+impl From<LanguageArg> for Option<Language> {
+    fn from(arg: LanguageArg) -> Self {
+        match arg {
+            LanguageArg::System => None,
+            LanguageArg::En => Some(Language::En),
+            LanguageArg::De => Some(Language::De),
+            LanguageArg::Es => Some(Language::Es),
+            LanguageArg::Fr => Some(Language::Fr),
+        }
+    }
+}
+
+impl From<UnlockArg> for UnlockPolicy {
+    fn from(arg: UnlockArg) -> Self {
+        match arg {
+            UnlockArg::Password => Self::Password,
+            UnlockArg::Keyring => Self::Keyring,
+            UnlockArg::KeyringConfirm => Self::KeyringConfirmSensitive,
+        }
+    }
+}
+
+impl From<DuplicateArg> for DuplicatePolicy {
+    fn from(arg: DuplicateArg) -> Self {
+        match arg {
+            DuplicateArg::Bump => Self::Bump,
+            DuplicateArg::Keep => Self::KeepPosition,
+            DuplicateArg::Record => Self::RecordLastSeen,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -51,47 +679,88 @@ struct Session {
 }
 
 fn cache_path() -> std::path::PathBuf {
-    dirs::cache_dir()
-        .unwrap_or_else(std::env::temp_dir)
-        .join("clip-vault")
-        .join("session.json")
+    clip_vault_core::cache_dir().join("session.json")
 }
 
-fn obtain_key(rem: Option<StdDuration>, forget: bool) -> Result<String> {
+/// The password from `CLIP_VAULT_KEY`, the keyring or the session cache,
+/// without prompting for it.
+fn saved_key(vault_path: &std::path::Path, policy: UnlockPolicy) -> Option<String> {
     use std::fs;
     let cache = cache_path();
 
-    // forget flag wipes cache
-    if forget && cache.exists() {
-        let _ = fs::remove_file(&cache);
-        println!("Password cache cleared.");
-        std::process::exit(0);
-    }
-
     // env var override
     if let Ok(ev) = std::env::var("CLIP_VAULT_KEY") {
-        return Ok(ev);
+        return Some(ev);
+    }
+
+    // try keyring
+    if policy.uses_keyring() {
+        match clip_vault_core::keyring_password(vault_path) {
+            Ok(Some(pass)) => return Some(pass),
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: {e}"),
+        }
     }
 
-    // try cache
-    if let Ok(text) = fs::read_to_string(&cache) {
+    // try cache, unless a lock was asked for since it was written
+    let locked_since = clip_vault_core::last_lock_request().is_some_and(|request| {
+        fs::metadata(&cache)
+            .and_then(|m| m.modified())
+            .is_ok_and(|cached| cached <= request)
+    });
+    if locked_since {
+        let _ = fs::remove_file(&cache);
+    } else if let Ok(text) = fs::read_to_string(&cache) {
         if let Ok(sess) = serde_json::from_str::<Session>(&text) {
-            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-            if now < sess.expires_at {
-                return Ok(sess.key);
+            if Timestamp::now().as_secs() < sess.expires_at {
+                return Some(sess.key);
             }
         }
     }
+    None
+}
+
+fn obtain_key(rem: Option<StdDuration>, forget: bool) -> Result<String> {
+    use std::fs;
+    let cache = cache_path();
+
+    let vault_path = clip_vault_core::default_db_path();
+
+    // forget flag wipes cache, and the keyring copy with it
+    if forget {
+        let saved = clip_vault_core::forget_keyring_password(&vault_path).unwrap_or(false);
+        if cache.exists() || saved {
+            let _ = fs::remove_file(&cache);
+            println!("Password cache cleared.");
+            std::process::exit(0);
+        }
+    }
+
+    let policy = Config::load().map(|c| c.unlock).unwrap_or_default();
+    if let Some(key) = saved_key(&vault_path, policy) {
+        return Ok(key);
+    }
 
     // prompt
+    if let Ok(Some(hint)) = clip_vault_core::password_hint(&vault_path) {
+        eprintln!("Hint: {hint}");
+    }
     let prompt = "Vault password";
     let pass = Password::new()
         .with_prompt(prompt)
         .interact()
         .map_err(|e| Error::Io(std::io::Error::other(e)))?;
 
+    // save to keyring once the password is known to open the vault
+    if policy.uses_keyring() {
+        open_store_with_key(&pass)?;
+        if let Err(e) = clip_vault_core::save_keyring_password(&vault_path, &pass) {
+            eprintln!("Warning: {e}");
+        }
+    }
+
     // write cache
-    let duration = rem.unwrap_or_else(|| StdDuration::from_secs(15 * 60));
+    let duration = rem.unwrap_or_else(|| StdDuration::from_mins(15));
     let expires = SystemTime::now() + duration;
     let sess = Session {
         key: pass.clone(),
@@ -106,46 +775,234 @@ fn obtain_key(rem: Option<StdDuration>, forget: bool) -> Result<String> {
 }
 
 fn main() -> Result<()> {
+    // A crash shouldn't leave the password cached for the next command.
+    clip_vault_core::install_panic_hook("cli", || match std::fs::remove_file(cache_path()) {
+        Ok(()) => vec!["password cache cleared".to_string()],
+        Err(_) => Vec::new(),
+    });
+
     let cli = Cli::parse();
+    if cli.ephemeral {
+        return cmd_ephemeral();
+    }
+    if let Some(path) = cli.db {
+        if !path.exists() {
+            eprintln!("No vault at {}", path.display());
+            std::process::exit(1);
+        }
+        SNAPSHOT.get_or_init(|| path);
+    }
 
     match cli.command.unwrap_or(Commands::Tui) {
-        Commands::Latest => {
-            let key = obtain_key(cli.remember, cli.forget)?;
-            cmd_latest(&key)?;
+        Commands::Context { action } => cmd_context(action),
+        Commands::Setup {
+            hint,
+            no_recovery_key,
+            security_profile,
+        } => cmd_setup(
+            hint.as_deref(),
+            !no_recovery_key,
+            security_profile.map(Into::into),
+        ),
+        Commands::Recover => cmd_recover(),
+        Commands::Settings { action } => cmd_settings(&action),
+        Commands::Doctor => {
+            cmd_doctor();
+            Ok(())
         }
-        Commands::List { count } => {
-            let key = obtain_key(cli.remember, cli.forget)?;
-            cmd_list(&key, count)?;
+        Commands::Paths => {
+            cmd_paths();
+            Ok(())
         }
-        Commands::Search { query, count } => {
+        Commands::Lock => cmd_lock(),
+        Commands::Uninstall { shred, yes } => cmd_uninstall(shred, yes),
+        Commands::Copy {
+            entry: None,
+            out,
+            no_store,
+        } => cmd_copy_stdin(out.as_deref(), no_store),
+        command => {
             let key = obtain_key(cli.remember, cli.forget)?;
-            cmd_search(&key, &query, count)?;
+            run(&key, command)
         }
-        Commands::Tui => {
-            let key = obtain_key(cli.remember, cli.forget)?;
-            cmd_tui(&key)?;
+    }
+}
+
+/// Dispatch a command that needs the unlocked vault.
+fn run(key: &str, command: Commands) -> Result<()> {
+    match command {
+        Commands::Latest => cmd_latest(key)?,
+        Commands::List {
+            json,
+            on: Some(day),
+            ..
+        } => cmd_timeline(key, day, json)?,
+        Commands::List {
+            count,
+            context,
+            tag,
+            json,
+            on: None,
+        } => cmd_list(key, count, context.as_deref(), tag.as_deref(), json)?,
+        Commands::Search {
+            query,
+            count,
+            include_archive,
+            all_profiles,
+            tag,
+        } => cmd_search(
+            key,
+            &query,
+            count,
+            include_archive,
+            all_profiles,
+            tag.as_deref(),
+        )?,
+        Commands::Grep {
+            pattern,
+            ignore_case,
+            fixed_strings,
+        } => cmd_grep(key, &pattern, fixed_strings, ignore_case)?,
+        Commands::Describe { count } => cmd_describe(key, count)?,
+        Commands::Tui => cmd_tui(key)?,
+        Commands::Watch => cmd_watch(key)?,
+        Commands::Pick { print } => cmd_pick(key, print)?,
+        Commands::Replay { from, to, interval } => cmd_replay(key, from, to, interval)?,
+        Commands::Copy {
+            entry: Some(entry),
+            out,
+            no_store,
+        } => cmd_copy(key, &entry, out.as_deref(), no_store)?,
+        Commands::TmuxSync {
+            count,
+            watch,
+            interval,
+        } => cmd_tmux_sync(key, count, watch, interval)?,
+        Commands::Ingest { fifo } => cmd_ingest(key, &fifo)?,
+        Commands::Quarantine => cmd_quarantine(key)?,
+        Commands::Prune(args) => cmd_prune(key, &args.policy()?, args.dry_run)?,
+        Commands::Stats { histogram, since } => cmd_stats(key, histogram.map(Into::into), since)?,
+        Commands::Report { format, output, .. } => cmd_report(key, format, output.as_deref())?,
+        Commands::Compact => cmd_compact(key)?,
+        Commands::Archive { older_than } => cmd_archive(key, older_than)?,
+        Commands::Audit { action } => cmd_audit(key, &action)?,
+        Commands::Duplicates { policy } => cmd_duplicates(key, policy.map(Into::into))?,
+        Commands::Context { .. }
+        | Commands::Setup { .. }
+        | Commands::Recover
+        | Commands::Settings { .. }
+        | Commands::Doctor
+        | Commands::Paths
+        | Commands::Lock
+        | Commands::Uninstall { .. }
+        | Commands::Copy { entry: None, .. } => {
+            unreachable!("handled before unlocking")
         }
+        Commands::Export(args) => {
+            cmd_export(
+                key,
+                args.output.as_deref(),
+                args.format.into(),
+                &args.filter(),
+            )?;
+        }
+        Commands::Import {
+            from,
+            path,
+            dry_run,
+        } => cmd_import(key, from.into(), &path, dry_run)?,
+        Commands::Tag { index, tag, remove } => cmd_tag(key, index, &tag, remove)?,
+        Commands::Pin { entry, remove } => cmd_pin(key, &entry, !remove)?,
+        Commands::Expire { entry, after } => cmd_expire(key, &entry, &after)?,
+        Commands::Note { entry, note, clear } => cmd_note(key, &entry, note.as_deref(), clear)?,
+        Commands::Show { entry, id, json } => cmd_show(key, entry.as_deref(), id, json)?,
+        Commands::Share { entry, output } => cmd_share(key, &entry, output)?,
+        Commands::Receive { path } => cmd_receive(key, &path)?,
+        Commands::Backup { path, remote } => cmd_backup(key, path.as_deref(), remote.as_deref())?,
+        Commands::Restore { path, remote } => cmd_restore(key, path.as_deref(), remote.as_deref())?,
+        Commands::Protected { action } => cmd_protected(key, &action)?,
+        Commands::Sync { action } => cmd_sync(key, &action)?,
     }
 
+    warn_storage_size();
     Ok(())
 }
 
+/// Warn once the vault file passes the configured size.
+fn warn_storage_size() {
+    let Some(limit_mb) = Config::load().unwrap_or_default().storage_warning_mb else {
+        return;
+    };
+    let size = clip_vault_core::vault_file_size(&clip_vault_core::default_db_path());
+    if let Some(warning) =
+        clip_vault_core::storage_warning(size, limit_mb.saturating_mul(1024 * 1024))
+    {
+        eprintln!(
+            "Warning: {warning}. `clip-vault prune --image-max-age 30d` removes old screenshots \
+             and `clip-vault compact` shrinks the file."
+        );
+    }
+}
+
 fn cmd_latest(key: &str) -> Result<()> {
     let store = open_store_with_key(key)?;
     if let Some(item) = store.latest()? {
-        println!("{item:?}");
+        println!("{}", content(&item));
     } else {
-        println!("No clipboard entries found.");
+        println!("{}", tr(Msg::NoEntries));
+    }
+    Ok(())
+}
+
+/// One line per entry, as "1. Text, 2 lines, 40 characters, copied 5 minutes
+/// ago: …", which reads better aloud than the listing.
+fn cmd_describe(key: &str, count: usize) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let now = Timestamp::now();
+    let entries = store.list(Some(count), None)?;
+    if entries.is_empty() {
+        println!("{}", tr(Msg::NoEntries));
     }
+    for (i, entry) in entries.iter().enumerate() {
+        println!("{}. {}", i + 1, EntryDescription::new(entry, now));
+    }
+    warn_skipped(&store);
     Ok(())
 }
 
-fn cmd_list(key: &str, count: Option<usize>) -> Result<()> {
+/// One entry of `list --json`. Images are base64-encoded, as in exports.
+#[derive(Serialize)]
+struct ListRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i64>,
+    timestamp: Timestamp,
+    content_type: String,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+}
+
+fn cmd_list(
+    key: &str,
+    count: Option<usize>,
+    context: Option<&str>,
+    tag: Option<&str>,
+    json: bool,
+) -> Result<()> {
     let store = open_store_with_key(key)?;
-    let items = store.list(count, None)?;
+    let items = match (context, tag) {
+        (Some(context), _) => store.list_in_context(context, count)?,
+        (None, Some(tag)) => store.list_by_tag(tag, count)?,
+        (None, None) => store.list(count, None)?,
+    };
+    warn_skipped(&store);
+
+    if json {
+        return print_json(items);
+    }
 
     if items.is_empty() {
-        println!("No clipboard entries found.");
+        println!("{}", tr(Msg::NoEntries));
         return Ok(());
     }
 
@@ -154,16 +1011,89 @@ fn cmd_list(key: &str, count: Option<usize>) -> Result<()> {
         None => println!("All {} clipboard entries:", items.len()),
     }
 
+    let pinned: HashSet<_> = store.pinned()?.iter().map(|e| e.item.hash()).collect();
     for (i, item) in items.iter().enumerate() {
-        println!("{}. {:?}", i + 1, item);
+        let mark = if pinned.contains(&item.item.hash()) {
+            " (pinned)"
+        } else {
+            ""
+        };
+        println!("{}. {}{mark}", i + 1, describe(item));
+    }
+
+    Ok(())
+}
+
+fn cmd_timeline(key: &str, day: chrono::NaiveDate, json: bool) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let hours = store.timeline(day)?;
+    warn_skipped(&store);
+
+    if json {
+        return print_json(hours.into_iter().flat_map(|h| h.entries).collect());
+    }
+
+    if hours.is_empty() {
+        println!("Nothing copied on {day}.");
+        return Ok(());
+    }
+
+    println!("Copied on {day}:");
+    for hour in &hours {
+        println!("\n{:02}:00", hour.hour);
+        for entry in &hour.entries {
+            let time = entry.timestamp.to_local().format("%H:%M");
+            println!("  {time}  {}", describe(entry));
+        }
     }
+    Ok(())
+}
 
+fn print_json(entries: Vec<ClipboardItemWithTimestamp>) -> Result<()> {
+    let records: Vec<ListRecord> = entries
+        .into_iter()
+        .map(|entry| {
+            let (data, content_type) = entry.item.into_parts();
+            ListRecord {
+                id: entry.id,
+                timestamp: entry.timestamp,
+                content_type,
+                content: data,
+                context: entry.context,
+            }
+        })
+        .collect();
+    let json =
+        serde_json::to_string_pretty(&records).map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    println!("{json}");
     Ok(())
 }
 
-fn cmd_search(key: &str, query: &str, count: Option<usize>) -> Result<()> {
+fn cmd_search(
+    key: &str,
+    query: &str,
+    count: Option<usize>,
+    include_archive: bool,
+    all_profiles: bool,
+    tag: Option<&str>,
+) -> Result<()> {
+    if all_profiles {
+        return cmd_search_all_profiles(key, query, count);
+    }
     let store = open_store_with_key(key)?;
-    let items = store.search(query, count, None)?;
+    let items = if include_archive {
+        store.search_with_archive(query, count)?
+    } else if let Some(tag) = tag {
+        // The same as typing `tag:<tag>` into the query.
+        store.search(
+            &format!("tag:{} {query}", SearchQuery::quote(tag)),
+            count,
+            None,
+        )?
+    } else {
+        store.search(query, count, None)?
+    };
+    warn_skipped(&store);
 
     if items.is_empty() {
         println!("No clipboard entries found matching '{query}'.");
@@ -181,24 +1111,1646 @@ fn cmd_search(key: &str, query: &str, count: Option<usize>) -> Result<()> {
     }
 
     for (i, item) in items.iter().enumerate() {
-        println!("{}. {:?}", i + 1, item);
+        println!("{}. {}", i + 1, describe(item));
     }
 
     Ok(())
 }
 
-fn cmd_tui(key: &str) -> Result<()> {
+/// `search --all-profiles`: the default vault and every configured profile
+/// that unlocks, matches newest first with the profile they came from.
+fn cmd_search_all_profiles(key: &str, query: &str, count: Option<usize>) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let default = open_store_with_key(key)?;
+    let mut profiles = Vec::new();
+    for profile in &config.profiles {
+        match open_profile(profile, key, config.unlock) {
+            Ok(vault) => profiles.push((profile.name.clone(), vault)),
+            Err(e) => eprintln!("warning: skipped profile {}: {e}", profile.name),
+        }
+    }
+
+    let mut vaults = MultiVault::new();
+    vaults.add(DEFAULT_PROFILE, &default);
+    for (name, vault) in &profiles {
+        vaults.add(name.clone(), vault);
+    }
+    let items = vaults.search(query, count, None)?;
+
+    if items.is_empty() {
+        println!("No clipboard entries found matching '{query}'.");
+        return Ok(());
+    }
+    println!(
+        "Found {} matches for '{}' in {} profiles:",
+        items.len(),
+        query,
+        vaults.profiles().count()
+    );
+    for (i, found) in items.iter().enumerate() {
+        println!("{}. [{}] {}", i + 1, found.profile, describe(&found.entry));
+    }
+    Ok(())
+}
+
+/// Open a profile's vault read-only with its password from the keyring or
+/// the default vault's, asking for it when neither works.
+fn open_profile(profile: &VaultProfile, key: &str, policy: UnlockPolicy) -> Result<SqliteVault> {
+    if !profile.path.exists() {
+        return Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no vault at {}", profile.path.display()),
+        )));
+    }
+    let saved = if policy.uses_keyring() {
+        clip_vault_core::keyring_password(&profile.path).unwrap_or(None)
+    } else {
+        None
+    };
+    for candidate in saved.iter().map(String::as_str).chain([key]) {
+        match SqliteVault::open_read_only(&profile.path, candidate) {
+            Err(Error::InvalidKey) => {}
+            result => return result,
+        }
+    }
+    let pass = Password::new()
+        .with_prompt(format!("Password for profile {}", profile.name))
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    SqliteVault::open_read_only(&profile.path, &pass)
+}
+
+/// Like `grep -n` across the history. Entries are numbered as in `list`;
+/// exits with status 1 when nothing matches.
+fn cmd_grep(key: &str, pattern: &str, fixed: bool, ignore_case: bool) -> Result<()> {
+    let pattern = LinePattern::new(pattern, fixed, ignore_case)?;
     let store = open_store_with_key(key)?;
-    let mut app = tui::App::new(store)?;
-    tui::ui::run_tui(&mut app)?;
+    let items = store.list(None, None)?;
+    warn_skipped(&store);
+
+    let mut matched = false;
+    for (i, entry) in items.iter().enumerate() {
+        if let ClipboardItem::Text(text) = &entry.item {
+            for (line, content) in pattern.matching_lines(text) {
+                println!("#{}:{line}:{content}", i + 1);
+                matched = true;
+            }
+        }
+    }
+    if !matched {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
-fn open_store_with_key(key: &str) -> Result<SqliteVault> {
-    let path = clip_vault_core::default_db_path();
-    std::fs::create_dir_all(path.parent().unwrap())?;
-    match SqliteVault::open(path, key) {
-        Ok(s) => Ok(s),
+/// One-line listing form: text and file paths with their line breaks shown
+/// as `\n`, images by their dimensions rather than a dump of their bytes.
+fn describe(entry: &ClipboardItemWithTimestamp) -> String {
+    content(&entry.item).replace('\n', "\\n")
+}
+
+/// An item's content as printed in full: text and file paths as they are,
+/// images by their dimensions.
+fn content(item: &ClipboardItem) -> String {
+    match item {
+        ClipboardItem::Text(text) => text.clone(),
+        ClipboardItem::Files(paths) => files_text(paths),
+        _ => item.image_info().map_or_else(
+            || format!("[Image: {}]", format_size(item.size())),
+            |info| format!("[Image: {info}]"),
+        ),
+    }
+}
+
+fn cmd_watch(key: &str) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let events = store.subscribe();
+    let health = store.subscribe_health();
+    let mut lock_requests = clip_vault_core::LockRequests::new();
+    let mut newest = store
+        .list(Some(1), None)?
+        .first()
+        .map_or(Timestamp::EPOCH, |e| e.timestamp);
+
+    loop {
+        if lock_requests.requested() {
+            eprintln!("{}", tr(Msg::LockedStopping));
+            return Ok(());
+        }
+        store.poll_changes()?;
+        if events.try_iter().count() > 0 {
+            let mut fresh: Vec<_> = store
+                .list(Some(50), None)?
+                .into_iter()
+                .take_while(|e| e.timestamp > newest)
+                .collect();
+            fresh.reverse();
+            for entry in fresh {
+                newest = entry.timestamp;
+                println!("{}  {}", entry.timestamp.absolute(), content(&entry.item));
+            }
+        }
+        for event in health.try_iter() {
+            eprintln!("{}: {event}", health_label(event.level()));
+        }
+        std::thread::sleep(StdDuration::from_millis(250));
+    }
+}
+
+fn cmd_pick(key: &str, print: bool) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let Some(item) = pick::Picker::new(&store)?.run()? else {
+        return Ok(());
+    };
+    store.record_use(item.hash())?;
+
+    match item {
+        ClipboardItem::Text(text) if print => println!("{text}"),
+        ClipboardItem::Files(paths) if print => println!("{}", files_text(&paths)),
+        ClipboardItem::Image(_) if print => {}
+        item => clipboard::copy_formatted(
+            &item,
+            &store.rich_text(item.hash())?,
+            &store.representations(item.hash())?,
+        )?,
+    }
+    Ok(())
+}
+
+fn cmd_copy(
+    key: &str,
+    reference: &str,
+    out: Option<&std::path::Path>,
+    no_store: bool,
+) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let entry = entry_or_exit(&store, reference)?;
+    store.record_use(entry.item.hash())?;
+
+    match out {
+        Some(path) => {
+            // Images are stored as PNG, so they go out as they are.
+            match &entry.item {
+                ClipboardItem::Text(text) => std::fs::write(path, text)?,
+                ClipboardItem::Image(data) => std::fs::write(path, data)?,
+                ClipboardItem::Files(paths) => std::fs::write(path, files_text(paths))?,
+                _ => {
+                    return Err(Error::Io(std::io::Error::other(
+                        "this kind of entry can't be written to a file",
+                    )))
+                }
+            }
+            println!("{}", tr_with(Msg::Wrote, &[("path", &path.display())]));
+        }
+        None if no_store => clipboard::copy_unrecorded(
+            &entry.item,
+            &store.rich_text(entry.item.hash())?,
+            &store.representations(entry.item.hash())?,
+        )?,
+        None => clipboard::copy_formatted(
+            &entry.item,
+            &store.rich_text(entry.item.hash())?,
+            &store.representations(entry.item.hash())?,
+        )?,
+    }
+    Ok(())
+}
+
+fn cmd_replay(
+    key: &str,
+    from: chrono::DateTime<chrono::Local>,
+    to: Option<chrono::DateTime<chrono::Local>>,
+    interval: Option<StdDuration>,
+) -> Result<()> {
+    let to = to.unwrap_or_else(chrono::Local::now);
+    if to <= from {
+        return Err(Error::Io(std::io::Error::other(
+            "--to must be later than --from",
+        )));
+    }
+    let store = open_store_with_key(key)?;
+    let entries =
+        store.copied_between(Timestamp::from_datetime(&from)..Timestamp::from_datetime(&to))?;
+    let window = format!(
+        "{} and {}",
+        from.format("%Y-%m-%d %H:%M"),
+        to.format("%H:%M")
+    );
+    if entries.is_empty() {
+        println!("Nothing was copied between {window}.");
+        return Ok(());
+    }
+    println!("Replaying {} copies made between {window}.", entries.len());
+    match interval {
+        Some(interval) => replay::timed(&entries, interval),
+        None => replay::step(&entries),
+    }
+}
+
+/// `clip-vault copy` without an entry: copy text piped in on stdin. One
+/// trailing newline is dropped, so `echo secret | clip-vault copy` copies
+/// just the secret.
+fn cmd_copy_stdin(out: Option<&std::path::Path>, no_store: bool) -> Result<()> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    let text = text
+        .strip_suffix('\n')
+        .map_or(text.as_str(), |t| t.strip_suffix('\r').unwrap_or(t));
+    if text.is_empty() {
+        return Err(Error::Io(std::io::Error::other(
+            "nothing to copy: stdin was empty",
+        )));
+    }
+    if let Some(path) = out {
+        std::fs::write(path, text)?;
+        println!("{}", tr_with(Msg::Wrote, &[("path", &path.display())]));
+        return Ok(());
+    }
+    let item = ClipboardItem::Text(text.to_string());
+    if no_store {
+        clipboard::copy_unrecorded(&item, &RichText::default(), &[])
+    } else {
+        clipboard::copy_item(&item)
+    }
+}
+
+/// Stand-in for the clipboard monitor on hosts without a clipboard: anything
+/// that writes OSC 52 sequences into the FIFO gets captured.
+fn cmd_ingest(key: &str, fifo: &std::path::Path) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    if !fifo.exists() {
+        let status = std::process::Command::new("mkfifo").arg(fifo).status()?;
+        if !status.success() {
+            return Err(Error::Io(std::io::Error::other(format!(
+                "mkfifo {} failed",
+                fifo.display()
+            ))));
+        }
+    }
+
+    // Reading the FIFO blocks until a writer shows up, so the lock is
+    // watched from a thread of its own.
+    std::thread::spawn(|| {
+        let mut lock_requests = clip_vault_core::LockRequests::new();
+        loop {
+            std::thread::sleep(StdDuration::from_secs(1));
+            if lock_requests.requested() {
+                eprintln!("{}", tr(Msg::LockedStopping));
+                std::process::exit(0);
+            }
+        }
+    });
+
+    let otp_ttl = Config::load()?.retention.max_age(ItemKind::Otp);
+    println!("Listening for OSC 52 writes on {}", fifo.display());
+    loop {
+        // Each open lasts until the writer closes its end.
+        let input = std::fs::read(fifo)?;
+        store.purge_expired()?;
+        for text in clip_vault_core::osc52::decode_all(&String::from_utf8_lossy(&input)) {
+            if text.is_empty() {
+                continue;
+            }
+            let item = ClipboardItem::Text(text);
+            let metadata = clip_vault_core::CaptureMetadata::current().expire_otp(&item, otp_ttl);
+            store.insert_with_metadata(item.hash(), &item, &metadata)?;
+        }
+    }
+}
+
+fn cmd_quarantine(key: &str) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    store.list(None, None)?;
+    let bad = store.diagnostics();
+
+    if bad.is_empty() {
+        println!("No undecodable entries found.");
+        return Ok(());
+    }
+
+    for diag in &bad {
+        store.quarantine(diag.hash)?;
+        println!(
+            "Quarantined {} ({})",
+            clip_vault_core::hash_to_hex(&diag.hash),
+            diag.error
+        );
+    }
+    println!("Moved {} entries to quarantine.", bad.len());
+    Ok(())
+}
+
+fn parse_keep_tag(arg: &str) -> std::result::Result<(String, Option<usize>), String> {
+    match arg.split_once('=') {
+        Some((tag, count)) => {
+            let count = count
+                .parse()
+                .map_err(|_| format!("invalid count '{count}'"))?;
+            Ok((tag.to_string(), Some(count)))
+        }
+        None => Ok((arg.to_string(), None)),
+    }
+}
+
+/// Whole days in an age like `90d`, or `None` for `off`.
+fn parse_archive_after(arg: &str) -> Result<Option<u64>> {
+    if arg == "off" {
+        return Ok(None);
+    }
+    let age = humantime::parse_duration(arg)
+        .map_err(|e| Error::Io(std::io::Error::other(format!("invalid age '{arg}': {e}"))))?;
+    match age.as_secs() / 86_400 {
+        0 => Err(Error::Io(std::io::Error::other(
+            "archive age must be at least a day",
+        ))),
+        days => Ok(Some(days)),
+    }
+}
+
+fn cmd_prune(key: &str, policy: &RetentionPolicy, dry_run: bool) -> Result<()> {
+    if policy.is_empty() {
+        println!(
+            "No retention rules given or configured (see `clip-vault settings retention`); nothing to prune."
+        );
+        return Ok(());
+    }
+
+    let store = open_store_with_key(key)?;
+    if dry_run {
+        print_dry_run("prune", &store.preview_prune(policy)?);
+        return Ok(());
+    }
+    authorize(&store, SensitiveAction::Delete)?;
+    let removed = store.prune(policy)?;
+    println!("Pruned {removed} entries.");
+    Ok(())
+}
+
+/// Report what `verb` would remove: a summary, then one line per entry,
+/// oldest first.
+fn print_dry_run(verb: &str, dry_run: &DryRun) {
+    let (Some(oldest), Some(newest)) = (dry_run.oldest(), dry_run.newest()) else {
+        println!("Dry run: nothing to {verb}.");
+        return;
+    };
+    let time = Timestamp::absolute;
+    println!(
+        "Dry run: would {verb} {} entries ({}), captured {} to {}:",
+        dry_run.count(),
+        format_size(dry_run.bytes()),
+        time(oldest),
+        time(newest)
+    );
+    for entry in &dry_run.entries {
+        println!(
+            "  {}  {:>9}  {}",
+            time(entry.timestamp),
+            format_size(entry.bytes),
+            entry.description
+        );
+    }
+}
+
+fn cmd_compact(key: &str) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let before = store.file_size();
+    store.compact()?;
+    let after = store.file_size();
+    println!(
+        "Compacted {} to {}.",
+        format_size(usize::try_from(before).unwrap_or(usize::MAX)),
+        format_size(usize::try_from(after).unwrap_or(usize::MAX))
+    );
+    Ok(())
+}
+
+fn cmd_archive(key: &str, older_than: Option<StdDuration>) -> Result<()> {
+    let Some(age) = older_than.or_else(|| {
+        Config::load()
+            .ok()?
+            .archive_after_days
+            .map(|days| StdDuration::from_secs(days * 86_400))
+    }) else {
+        println!("No age given and `settings archive` is off; nothing to archive.");
+        return Ok(());
+    };
+
+    let store = open_store_with_key(key)?;
+    let moved = store.archive_older_than(age)?;
+    match store.archive_path() {
+        Some(path) if moved > 0 => {
+            println!("Moved {moved} entries to {}.", path.display());
+        }
+        _ => println!("Nothing to archive."),
+    }
+    Ok(())
+}
+
+fn cmd_stats(
+    key: &str,
+    histogram: Option<HistogramBucket>,
+    since: Option<StdDuration>,
+) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let storage = store.storage_info()?;
+    let size = |bytes: u64| format_size(usize::try_from(bytes).unwrap_or(usize::MAX));
+    println!("Entries:     {}", storage.entries);
+    println!("Stored size: {} KB", storage.payload_bytes.div_ceil(1024));
+    println!("On disk:     {}", size(storage.file_bytes));
+    println!(
+        "Growth:      {}/day over the last {} days",
+        size(storage.bytes_per_day),
+        clip_vault_core::GROWTH_WINDOW_DAYS
+    );
+
+    let Some(bucket) = histogram else {
+        return Ok(());
+    };
+
+    let since = since.unwrap_or(StdDuration::from_hours(30 * 24));
+    let from = Timestamp::now().saturating_sub(since);
+    let buckets = store.activity_histogram(bucket, from..Timestamp::MAX)?;
+
+    let Some(max) = buckets.iter().map(|b| b.count).max() else {
+        println!("No captures in this period.");
+        return Ok(());
+    };
+
+    println!();
+    for b in &buckets {
+        let start = b.start.to_utc();
+        let label = match bucket {
+            HistogramBucket::Hour => start.format("%Y-%m-%d %H:00").to_string(),
+            HistogramBucket::Day => start.format("%Y-%m-%d").to_string(),
+        };
+        let width = usize::try_from(b.count * 40 / max).unwrap_or(40).max(1);
+        println!("{label:<16} {} {}", "█".repeat(width), b.count);
+    }
+    Ok(())
+}
+
+fn cmd_tmux_sync(key: &str, count: usize, watch: bool, interval: StdDuration) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    if watch {
+        tmux::watch(&store, count, interval)
+    } else {
+        tmux::sync(&store, count).map(drop)
+    }
+}
+
+fn cmd_report(key: &str, format: ReportFormat, output: Option<&std::path::Path>) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let digest = WeeklyDigest::week(&store, chrono::Local::now().date_naive())?;
+    let report = match format {
+        ReportFormat::Markdown => digest.to_markdown(),
+        ReportFormat::Html => digest.to_html(),
+    };
+    match output {
+        Some(path) => {
+            std::fs::write(path, report)?;
+            println!("{}", tr_with(Msg::Wrote, &[("path", &path.display())]));
+        }
+        None => print!("{report}"),
+    }
+    Ok(())
+}
+
+fn cmd_context(action: ContextCommand) -> Result<()> {
+    match action {
+        ContextCommand::Set { path } => {
+            let context = match path {
+                Some(path) => path,
+                None => git_toplevel().map_or_else(
+                    || std::env::current_dir().map(|d| d.display().to_string()),
+                    Ok,
+                )?,
+            };
+            clip_vault_core::set_current_context(Some(&context))?;
+        }
+        ContextCommand::Clear => clip_vault_core::set_current_context(None)?,
+        ContextCommand::Show => match clip_vault_core::current_context() {
+            Some(context) => println!("{context}"),
+            None => println!("No context set."),
+        },
+    }
+    Ok(())
+}
+
+fn cmd_settings(action: &SettingsCommand) -> Result<()> {
+    match action {
+        SettingsCommand::Export { path } => {
+            Config::load()?.save_to(path)?;
+            println!("Settings written to {}", path.display());
+            println!("The file includes backup credentials; keep it somewhere private.");
+        }
+        SettingsCommand::Import { path } => {
+            Config::load_from(path)?.save()?;
+            println!("Settings imported into {}", config_path().display());
+        }
+        SettingsCommand::Unlock { policy } => cmd_unlock_policy((*policy).into())?,
+        SettingsCommand::Language { language } => {
+            let mut config = Config::load()?;
+            if let Some(language) = language {
+                config.language = (*language).into();
+                config.save()?;
+            }
+            let current = clip_vault_core::set_language(config.language);
+            match config.language {
+                Some(_) => println!("Language: {current}"),
+                None => println!("Language: {current} (following the system locale)"),
+            }
+        }
+        SettingsCommand::Archive { after } => {
+            let mut config = Config::load()?;
+            if let Some(after) = after {
+                config.archive_after_days = parse_archive_after(after)?;
+                config.save()?;
+            }
+            match config.archive_after_days {
+                Some(days) => println!("Entries older than {days} days are archived"),
+                None => println!("Archiving is off"),
+            }
+        }
+        SettingsCommand::Retention {
+            max_age,
+            max_items,
+            max_size,
+        } => cmd_retention(
+            max_age.as_deref(),
+            max_items.as_deref(),
+            max_size.as_deref(),
+        )?,
+        SettingsCommand::AppTtl { app, ttl } => cmd_app_ttl(app.as_deref(), ttl.as_deref())?,
+    }
+    Ok(())
+}
+
+fn cmd_retention(
+    max_age: Option<&str>,
+    max_items: Option<&str>,
+    max_size: Option<&str>,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    let policy = &mut config.retention;
+    if let Some(age) = max_age {
+        let secs = parse_setting(age, |age| {
+            humantime::parse_duration(age).map(|age| age.as_secs().max(1))
+        })?;
+        policy.text_max_age_secs = secs;
+        policy.image_max_age_secs = secs;
+        // One-time codes keep their own, shorter age; a shorter general age
+        // only lowers it.
+        if let Some(secs) = secs {
+            policy.otp_max_age_secs =
+                Some(policy.otp_max_age_secs.map_or(secs, |otp| otp.min(secs)));
+        }
+    }
+    if let Some(count) = max_items {
+        policy.max_items = parse_setting(count, str::parse)?;
+    }
+    if let Some(mb) = max_size {
+        policy.max_size_mb = parse_setting(mb, str::parse)?;
+    }
+    if max_age.is_some() || max_items.is_some() || max_size.is_some() {
+        config.save()?;
+    }
+
+    let policy = &config.retention;
+    if policy.is_empty() {
+        println!("No retention rules; entries are kept until deleted");
+        return Ok(());
+    }
+    let age = |secs: Option<u64>| {
+        secs.map_or("forever".to_string(), |secs| {
+            format!("for {}", format_age(secs))
+        })
+    };
+    println!("Text and files are kept {}", age(policy.text_max_age_secs));
+    println!("Images are kept {}", age(policy.image_max_age_secs));
+    println!("One-time codes are kept {}", age(policy.otp_max_age_secs));
+    if let Some(count) = policy.max_items {
+        println!("At most {count} entries are kept");
+    }
+    if let Some(mb) = policy.max_size_mb {
+        println!("At most {mb} MB of entries are kept");
+    }
+    Ok(())
+}
+
+/// An age as it's usually given: whole days as `90d` rather than the
+/// months and hours `humantime` would make of them.
+fn format_age(secs: u64) -> String {
+    const DAY: u64 = 86_400;
+    if secs >= DAY && secs.is_multiple_of(DAY) {
+        format!("{}d", secs / DAY)
+    } else {
+        humantime::format_duration(StdDuration::from_secs(secs)).to_string()
+    }
+}
+
+/// `None` for `off`, otherwise `value` as `parse` reads it.
+fn parse_setting<T, E: std::fmt::Display>(
+    value: &str,
+    parse: impl Fn(&str) -> std::result::Result<T, E>,
+) -> Result<Option<T>> {
+    if value == "off" {
+        return Ok(None);
+    }
+    parse(value).map(Some).map_err(|e| {
+        Error::Io(std::io::Error::other(format!(
+            "invalid value '{value}': {e}"
+        )))
+    })
+}
+
+fn cmd_app_ttl(app: Option<&str>, ttl: Option<&str>) -> Result<()> {
+    let mut config = Config::load()?;
+    if let (Some(app), Some(ttl)) = (app, ttl) {
+        // An empty name would match captures from every app.
+        if app.trim().is_empty() {
+            return Err(Error::Io(std::io::Error::other("app name can't be empty")));
+        }
+        if ttl == "off" {
+            config.app_ttl_secs.remove(app);
+        } else {
+            let ttl = humantime::parse_duration(ttl).map_err(|e| {
+                Error::Io(std::io::Error::other(format!("invalid age '{ttl}': {e}")))
+            })?;
+            config
+                .app_ttl_secs
+                .insert(app.to_string(), ttl.as_secs().max(1));
+            if SourceApp::current().is_none() {
+                eprintln!(
+                    "Warning: the app a copy comes from can't be looked up here (e.g. on Wayland), so this rule won't apply to captures on this machine."
+                );
+            }
+        }
+        config.save()?;
+    }
+    let rules: Vec<_> = config
+        .app_ttl_secs
+        .iter()
+        .filter(|(name, _)| app.is_none_or(|app| *name == app))
+        .collect();
+    if rules.is_empty() {
+        println!(
+            "Captures from {} are kept like any other",
+            app.unwrap_or("all apps")
+        );
+    }
+    for (name, secs) in rules {
+        println!(
+            "Captures from {name} are deleted after {}",
+            format_age(*secs)
+        );
+    }
+    Ok(())
+}
+
+/// Switch unlock policies. Needs the password either way: to save it in the
+/// keyring, and so a weaker policy can't be chosen without it.
+fn cmd_unlock_policy(policy: UnlockPolicy) -> Result<()> {
+    let vault_path = clip_vault_core::default_db_path();
+    if !vault_path.exists() {
+        eprintln!("No vault yet; run `clip-vault setup` first.");
+        std::process::exit(1);
+    }
+    let password = Password::new()
+        .with_prompt("Vault password")
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    if open_store_with_key(&password).is_err() {
+        eprintln!("Wrong password; unlock policy unchanged.");
+        std::process::exit(1);
+    }
+    if policy.uses_keyring() {
+        clip_vault_core::save_keyring_password(&vault_path, &password)?;
+    } else {
+        clip_vault_core::forget_keyring_password(&vault_path)?;
+    }
+    let mut config = Config::load()?;
+    config.unlock = policy;
+    config.save()?;
+    println!(
+        "{}",
+        match policy {
+            UnlockPolicy::Password => "The password will be asked for every time.",
+            UnlockPolicy::Keyring => "The vault will unlock with the password in the keyring.",
+            UnlockPolicy::KeyringConfirmSensitive => {
+                "The vault will unlock with the password in the keyring; exports and deletions still ask for it."
+            }
+        }
+    );
+    Ok(())
+}
+
+/// Ask for the password again before `action`, if the unlock policy wants it.
+fn authorize(store: &SqliteVault, action: SensitiveAction) -> Result<()> {
+    let policy = Config::load()?.unlock;
+    let result = policy.authorize(action, store, |action| {
+        Password::new()
+            .with_prompt(format!("Vault password to {action}"))
+            .interact()
+            .ok()
+    });
+    if let Err(Error::InvalidKey) = result {
+        eprintln!("Wrong password; nothing was changed.");
+        std::process::exit(1);
+    }
+    result
+}
+
+/// Root of the git repo containing the working directory, if any.
+fn git_toplevel() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .output()
+        .ok()?;
+    let root = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !root.trim().is_empty()).then(|| root.trim().to_string())
+}
+
+fn cmd_duplicates(key: &str, policy: Option<DuplicatePolicy>) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    if let Some(policy) = policy {
+        store.set_duplicate_policy(policy)?;
+    }
+    let description = match store.duplicate_policy()? {
+        DuplicatePolicy::Bump => "moved to the top",
+        DuplicatePolicy::KeepPosition => "left in place",
+        DuplicatePolicy::RecordLastSeen => "left in place, recording when they were last copied",
+    };
+    println!("Re-copied entries are {description}.");
+    Ok(())
+}
+
+fn cmd_audit(key: &str, action: &AuditCommand) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    match action {
+        AuditCommand::Enable => {
+            store.set_audit_enabled(true)?;
+            println!("Audit log enabled.");
+        }
+        AuditCommand::Disable => {
+            store.set_audit_enabled(false)?;
+            println!("Audit log disabled.");
+        }
+        AuditCommand::Verify => {
+            let result = store.verify_audit_log()?;
+            if let Some(id) = result.first_broken {
+                eprintln!("Audit chain broken at record {id}.");
+                std::process::exit(1);
+            }
+            println!("Audit chain intact ({} records).", result.records);
+        }
+        AuditCommand::Log => {
+            let records = store.audit_log()?;
+            if records.is_empty() {
+                println!("Audit log is empty.");
+            }
+            for record in &records {
+                let when = record.timestamp.absolute();
+                let item = record
+                    .item_hash
+                    .as_ref()
+                    .map_or_else(|| "-".to_string(), clip_vault_core::hash_to_hex);
+                println!("{:>5} {when} {:<7} {item}", record.id, record.action);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn cmd_export(
+    key: &str,
+    output: Option<&std::path::Path>,
+    format: ExportFormat,
+    filter: &ExportFilter,
+) -> Result<()> {
+    use std::io::Write;
+
+    let store = open_store_with_key(key)?;
+    authorize(&store, SensitiveAction::Export)?;
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let written = store.export(&mut out, format, filter)?;
+    // Dropping the writer would flush it but ignore a failed write.
+    out.flush()?;
+    warn_skipped(&store);
+    eprintln!("Exported {written} entries.");
+    Ok(())
+}
+
+fn cmd_tag(key: &str, index: usize, tag: &str, remove: bool) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let Some(entry) = index
+        .checked_sub(1)
+        .and_then(|i| store.list(None, None).ok()?.into_iter().nth(i))
+    else {
+        eprintln!("No entry #{index}.");
+        std::process::exit(1);
+    };
+    let hash = entry.item.hash();
+    if remove {
+        store.remove_tag(hash, tag)?;
+    } else {
+        store.add_tag(hash, tag)?;
+    }
+    println!("Entry #{index} tags: {}", store.tags(hash)?.join(", "));
+    Ok(())
+}
+
+fn cmd_pin(key: &str, reference: &str, pinned: bool) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let entry = entry_or_exit(&store, reference)?;
+    store.pin(entry.item.hash(), pinned)?;
+    let state = if pinned { "Pinned" } else { "Unpinned" };
+    println!("{state} {}", describe(&entry));
+    Ok(())
+}
+
+fn cmd_expire(key: &str, reference: &str, after: &str) -> Result<()> {
+    let ttl =
+        match after {
+            "never" => None,
+            age => Some(humantime::parse_duration(age).map_err(|e| {
+                Error::Io(std::io::Error::other(format!("invalid age '{age}': {e}")))
+            })?),
+        };
+    let store = open_store_with_key(key)?;
+    let entry = entry_or_exit(&store, reference)?;
+    if ttl.is_some() {
+        authorize(&store, SensitiveAction::Delete)?;
+    }
+    let expires_at = ttl.map(|ttl| Timestamp::now().saturating_add(ttl));
+    store.set_expiry(entry.item.hash(), expires_at)?;
+    match ttl {
+        Some(ttl) => println!(
+            "{} expires in {}",
+            describe(&entry),
+            format_age(ttl.as_secs())
+        ),
+        None => println!("{} no longer expires", describe(&entry)),
+    }
+    Ok(())
+}
+
+fn cmd_note(key: &str, reference: &str, note: Option<&str>, clear: bool) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let entry = entry_or_exit(&store, reference)?;
+    let hash = entry.item.hash();
+    if note.is_none() && !clear {
+        match store.note(hash)? {
+            Some(note) => println!("{note}"),
+            None => eprintln!("{} has no note", describe(&entry)),
+        }
+        return Ok(());
+    }
+    store.set_note(hash, note)?;
+    match store.note(hash)? {
+        Some(_) => println!("Noted on {}", describe(&entry)),
+        None => println!("Removed the note from {}", describe(&entry)),
+    }
+    Ok(())
+}
+
+fn cmd_show(key: &str, reference: Option<&str>, id: Option<i64>, json: bool) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let entry = match (id, reference) {
+        (Some(id), _) => store.get_by_id(id)?,
+        (None, Some(reference)) => find_entry(&store, reference)?,
+        (None, None) => None,
+    };
+    let Some(entry) = entry else {
+        no_matching_entry(&id.map_or_else(|| format!("{reference:?}"), |id| format!("id {id}")));
+    };
+    if json {
+        return print_json(vec![entry]);
+    }
+
+    let hash = entry.item.hash();
+    if let Some(id) = entry.id {
+        println!("id:      {id}");
+    }
+    println!("hash:    {}", clip_vault_core::hash_to_hex(&hash));
+    // Past a week the relative form is just the date again.
+    let (absolute, relative) = (entry.timestamp.absolute(), entry.timestamp.relative());
+    if absolute.contains(&relative) {
+        println!("copied:  {absolute}");
+    } else {
+        println!("copied:  {absolute} ({relative})");
+    }
+    println!("kind:    {}", ItemKind::of(&entry.item).as_str());
+    if let Some(stats) = store.item_stats(hash)? {
+        println!("size:    {stats}");
+    }
+    if let Some(source) = &entry.source {
+        match &source.window_title {
+            Some(window) => println!("from:    {} — {window}", source.name),
+            None => println!("from:    {}", source.name),
+        }
+    }
+    if let Some(context) = &entry.context {
+        println!("context: {context}");
+    }
+    let tags = store.tags(hash)?;
+    if !tags.is_empty() {
+        println!("tags:    {}", tags.join(", "));
+    }
+    if let Some(note) = store.note(hash)? {
+        println!("note:    {note}");
+    }
+    if let Some(expiry) = store.expiry(hash)? {
+        println!("expires: {}", expiry.absolute());
+    }
+    println!();
+    println!("{}", content(&entry.item));
+    Ok(())
+}
+
+/// The entry `reference` points to: a number as in `list`, or a prefix of
+/// its hex hash that only one entry has.
+fn find_entry(store: &SqliteVault, reference: &str) -> Result<Option<ClipboardItemWithTimestamp>> {
+    // A whole hash needs no listing.
+    if let Some(hash) = clip_vault_core::hash_from_hex(&reference.to_ascii_lowercase()) {
+        return store.get(hash);
+    }
+    let entries = store.list(None, None)?;
+    if let Ok(index) = reference.parse::<usize>() {
+        return Ok(index
+            .checked_sub(1)
+            .and_then(|i| entries.into_iter().nth(i)));
+    }
+    let prefix = reference.to_ascii_lowercase();
+    let mut matches = entries
+        .into_iter()
+        .filter(|e| clip_vault_core::hash_to_hex(&e.item.hash()).starts_with(&prefix));
+    Ok(match (matches.next(), matches.next()) {
+        (Some(entry), None) => Some(entry),
+        _ => None,
+    })
+}
+
+/// [`find_entry`], exiting with an error when nothing matches.
+fn entry_or_exit(store: &SqliteVault, reference: &str) -> Result<ClipboardItemWithTimestamp> {
+    match find_entry(store, reference)? {
+        Some(entry) => Ok(entry),
+        None => no_matching_entry(&format!("{reference:?}")),
+    }
+}
+
+fn no_matching_entry(reference: &str) -> ! {
+    eprintln!(
+        "{}",
+        tr_with(Msg::NoMatchingEntry, &[("reference", &reference)])
+    );
+    std::process::exit(1);
+}
+
+fn cmd_share(key: &str, reference: &str, output: Option<std::path::PathBuf>) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let entry = entry_or_exit(&store, reference)?;
+    authorize(&store, SensitiveAction::Export)?;
+    let path = output.unwrap_or_else(|| {
+        let hash = clip_vault_core::hash_to_hex(&entry.item.hash());
+        format!("clip-vault-share-{}.cvshare", &hash[..8]).into()
+    });
+    let passphrase = clip_vault_core::share_entry(&entry, &path)?;
+    println!("{}", tr_with(Msg::Wrote, &[("path", &path.display())]));
+    println!("Passphrase: {passphrase}");
+    println!("Send the passphrase separately from the file; `clip-vault receive` opens it.");
+    Ok(())
+}
+
+fn cmd_receive(key: &str, path: &std::path::Path) -> Result<()> {
+    let passphrase = Password::new()
+        .with_prompt("Share passphrase")
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    let entry = match clip_vault_core::read_shared(path, &passphrase) {
+        Err(Error::InvalidKey) => {
+            eprintln!("Wrong passphrase, or not a clip-vault share file.");
+            std::process::exit(1);
+        }
+        result => result?,
+    };
+    let store = open_store_with_key(key)?;
+    let metadata = clip_vault_core::CaptureMetadata {
+        context: entry.context.clone(),
+        ttl: None,
+        source: entry.source.clone(),
+    };
+    store.insert_with_metadata(entry.item.hash(), &entry.item, &metadata)?;
+    println!("Added {}", describe(&entry));
+    Ok(())
+}
+
+/// Imported entries go through the CLI's capture settings, as if each had
+/// been copied at its recorded time: one-time codes and copies from apps
+/// with a TTL may be deleted right away.
+fn cmd_import(
+    key: &str,
+    source: ImportSource,
+    path: &std::path::Path,
+    dry_run: bool,
+) -> Result<()> {
+    let history = clip_vault_core::read_history(source, path)?;
+    let config = Config::load()?;
+    let options = clip_vault_monitor::CaptureOptions {
+        app_ttls: config.app_ttls(),
+        dedupe: config.dedupe,
+        normalize_text: config.text_normalization,
+        retention: config.retention,
+        ..Default::default()
+    };
+    let read = history.entries.len();
+    let entries: Vec<_> = history
+        .entries
+        .into_iter()
+        .filter_map(|entry| clip_vault_monitor::prepare_import(entry, &options))
+        .collect();
+    let secrets = entries
+        .iter()
+        .filter(|entry| matches!(&entry.item, ClipboardItem::Text(text) if clip_vault_core::contains_secret(text)))
+        .count();
+
+    let store = open_store_with_key(key)?;
+    let summary = if dry_run {
+        store.preview_import(&entries)?
+    } else {
+        store.import(&entries)?
+    };
+    println!(
+        "{} {} entries ({} already in the vault).",
+        if dry_run { "Would import" } else { "Imported" },
+        summary.added,
+        summary.duplicates
+    );
+    if entries.len() < read {
+        eprintln!(
+            "Skipped {} entries the capture settings leave out, such as text over the size limit.",
+            read - entries.len()
+        );
+    }
+    if secrets > 0 {
+        eprintln!("{secrets} of the entries read look like passwords, keys or tokens.");
+    }
+    if history.unsupported > 0 {
+        eprintln!(
+            "Skipped {} entries clip-vault can't store, such as unsupported formats.",
+            history.unsupported
+        );
+    }
+    Ok(())
+}
+
+/// Scratch directory for a snapshot and its sidecars on their way to or
+/// from a remote. It is made next to the vault under a random name, readable
+/// only by the current user, and removed with its contents when dropped.
+fn snapshot_tmp_dir() -> Result<tempfile::TempDir> {
+    let vault = clip_vault_core::default_db_path();
+    let parent = vault
+        .parent()
+        .map_or_else(std::env::temp_dir, std::path::Path::to_path_buf);
+    std::fs::create_dir_all(&parent)?;
+    Ok(tempfile::Builder::new()
+        .prefix(".clip-vault-snapshot-")
+        .tempdir_in(parent)?)
+}
+
+/// The files that go with the snapshot at `snapshot`, by the extension they
+/// are stored under next to it on a remote.
+fn snapshot_sidecars(snapshot: &std::path::Path) -> [(&'static str, std::path::PathBuf); 2] {
+    [
+        (
+            "archive.db",
+            clip_vault_core::snapshot_archive_path(snapshot),
+        ),
+        ("cipher", clip_vault_core::cipher_path(snapshot)),
+    ]
+}
+
+fn cmd_backup(key: &str, path: Option<&std::path::Path>, remote: Option<&str>) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    authorize(&store, SensitiveAction::Export)?;
+    match (path, remote) {
+        (_, Some(url)) => {
+            let target = Remote::parse(url)?;
+            let config = Config::load()?;
+            let tmp_dir = snapshot_tmp_dir()?;
+            let tmp = tmp_dir.path().join("snapshot.db");
+            let result = store
+                .backup_to(&tmp)
+                .and_then(|()| target.upload(&tmp, &config.backup))
+                .and_then(|()| {
+                    snapshot_sidecars(&tmp)
+                        .into_iter()
+                        .filter(|(_, path)| path.exists())
+                        .try_for_each(|(extension, path)| {
+                            target.sibling(extension).upload(&path, &config.backup)
+                        })
+                });
+            drop(tmp_dir);
+            result?;
+            println!("Encrypted snapshot uploaded to {url}");
+        }
+        (Some(path), None) => {
+            store.backup_to(path)?;
+            println!(
+                "Encrypted snapshot written to {} (keep {} with it)",
+                path.display(),
+                clip_vault_core::cipher_path(path).display()
+            );
+        }
+        (None, None) => unreachable!("clap requires a path or --remote"),
+    }
+    Ok(())
+}
+
+fn cmd_restore(key: &str, path: Option<&std::path::Path>, remote: Option<&str>) -> Result<()> {
+    let dest = clip_vault_core::default_db_path();
+    if dest.exists() {
+        // The snapshot replaces every entry in the vault.
+        authorize(&open_store_with_key(key)?, SensitiveAction::Delete)?;
+    }
+    let result = match (path, remote) {
+        (_, Some(url)) => {
+            let source = Remote::parse(url)?;
+            let config = Config::load()?;
+            let tmp_dir = snapshot_tmp_dir()?;
+            let tmp = tmp_dir.path().join("snapshot.db");
+            let result = source.download(&tmp, &config.backup).and_then(|()| {
+                // Vaults without an archive upload none, and older snapshots
+                // have no cipher settings; `restore_from` reports those
+                // that turn out to be needed.
+                for (extension, path) in snapshot_sidecars(&tmp) {
+                    source
+                        .sibling(extension)
+                        .download(&path, &config.backup)
+                        .ok();
+                }
+                SqliteVault::restore_from(&tmp, &dest, key)
+            });
+            drop(tmp_dir);
+            result
+        }
+        (Some(path), None) => SqliteVault::restore_from(path, &dest, key),
+        (None, None) => unreachable!("clap requires a path or --remote"),
+    };
+    match result {
+        Err(Error::InvalidKey) => {
+            eprintln!("The snapshot can't be opened with this vault's password.");
+            std::process::exit(1);
+        }
+        result => result?,
+    }
+    println!("Vault restored to {}", dest.display());
+    Ok(())
+}
+
+fn cmd_setup(
+    hint: Option<&str>,
+    recovery_key: bool,
+    profile: Option<SecurityProfile>,
+) -> Result<()> {
+    let path = clip_vault_core::default_db_path();
+    let exists = path.exists();
+    if exists && profile.is_some() {
+        eprintln!(
+            "The vault already exists; its security profile can only be picked when it's created."
+        );
+        std::process::exit(1);
+    }
+    let mut prompt = Password::new();
+    if exists {
+        println!("Updating the vault at {}", path.display());
+        prompt = prompt.with_prompt("Vault password");
+    } else {
+        prompt = prompt
+            .with_prompt("New vault password")
+            .with_confirmation("Confirm password", "Passwords don't match");
+    }
+    let password = prompt
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    let store = match profile {
+        Some(profile) => SqliteVault::create_with_options(&path, &password, &profile.options())?,
+        None => match open_store_with_key(&password) {
+            Err(_) if exists => {
+                eprintln!("{}", tr(Msg::InvalidPassword));
+                std::process::exit(1);
+            }
+            result => result?,
+        },
+    };
+
+    if hint.is_some() {
+        clip_vault_core::set_password_hint(&path, hint)?;
+        println!("Password hint saved.");
+    }
+    if recovery_key {
+        let key = store.create_recovery_key(&password)?;
+        println!();
+        println!("Recovery key:");
+        println!();
+        println!("    {key}");
+        println!();
+        println!("Write it down and keep it somewhere safe. It isn't stored anywhere and is");
+        println!("the only way back in if you forget your password (`clip-vault recover`).");
+        println!("Generating a new one replaces this key.");
+    }
+    Ok(())
+}
+
+fn cmd_recover() -> Result<()> {
+    let path = clip_vault_core::default_db_path();
+    let recovery_key = Password::new()
+        .with_prompt("Recovery key")
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    let password = Password::new()
+        .with_prompt("New vault password")
+        .with_confirmation("Confirm password", "Passwords don't match")
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    match SqliteVault::recover(&path, &recovery_key, &password) {
+        Err(Error::InvalidKey) => {
+            eprintln!("That recovery key doesn't match this vault.");
+            std::process::exit(1);
+        }
+        result => result?,
+    }
+    // The cached password no longer opens the vault.
+    let _ = std::fs::remove_file(cache_path());
+    println!("Password changed.");
+    Ok(())
+}
+
+/// Forget the cached password and ask every other clip-vault process to lock.
+/// A password saved in the keyring stays; `--forget` removes that.
+fn cmd_lock() -> Result<()> {
+    match std::fs::remove_file(cache_path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    clip_vault_core::request_lock()?;
+    println!("{}", tr(Msg::VaultLocked));
+    Ok(())
+}
+
+/// Remove what uninstalling the binaries leaves behind. The shared config
+/// and logs stay, and the vault unless `shred` is set.
+fn cmd_uninstall(shred: bool, yes: bool) -> Result<()> {
+    let vaults = if let Some(snapshot) = SNAPSHOT.get() {
+        // `--db` opens its vault read-only, so it can't be shredded.
+        if shred {
+            return Err(Error::ReadOnly);
+        }
+        vec![(DEFAULT_PROFILE.to_string(), snapshot.clone())]
+    } else {
+        // Profiles are vaults of their own, wherever they're kept.
+        let config = Config::load().unwrap_or_default();
+        let mut vaults = vec![(
+            DEFAULT_PROFILE.to_string(),
+            clip_vault_core::default_db_path(),
+        )];
+        vaults.extend(
+            config
+                .profiles
+                .into_iter()
+                .map(|profile| (profile.name, profile.path)),
+        );
+        vaults
+    };
+    let listed = vaults
+        .iter()
+        .map(|(_, path)| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if shred && !yes {
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!(
+                "Overwrite and delete {listed} and everything in them?"
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+        if !confirmed {
+            println!("Nothing removed.");
+            return Ok(());
+        }
+    }
+    if shred {
+        // Shredding can't be undone, so it takes the password typed again,
+        // not one cached or saved in the keyring.
+        let mut typed = None;
+        for (name, path) in vaults.iter().filter(|(_, path)| path.exists()) {
+            verify_password(name, path, &mut typed)?;
+            // The app or `watch` would keep writing to a vault shredded
+            // under it.
+            clip_vault_core::ensure_vault_closed(path, typed.as_deref().unwrap_or_default())?;
+        }
+    }
+    // Running copies shouldn't keep using a password that's being removed.
+    clip_vault_core::request_lock()?;
+
+    let paths: Vec<_> = vaults.into_iter().map(|(_, path)| path).collect();
+    let report = clip_vault_core::uninstall(&paths, shred);
+    for entry in &report.startup_entries {
+        println!("Removed startup entry {entry}");
+    }
+    for path in &report.removed_files {
+        println!("Removed {}", path.display());
+    }
+    if report.keyring_cleared {
+        println!("Removed the password saved in the keyring");
+    }
+    for path in &report.shredded {
+        println!("Shredded {}", path.display());
+    }
+    if !shred {
+        println!("Kept {listed}; run with --shred to delete them.");
+    }
+    for error in &report.errors {
+        eprintln!("Warning: {error}");
+    }
+    Ok(())
+}
+
+/// Check that the vault of profile `name` at `path` opens with the password
+/// typed for an earlier vault, or else with one asked for now and kept in
+/// `typed`. `CLIP_VAULT_KEY` stands in for typing it, for scripts.
+fn verify_password(name: &str, path: &std::path::Path, typed: &mut Option<String>) -> Result<()> {
+    if let Some(key) = typed.as_deref() {
+        if SqliteVault::open_read_only(path, key).is_ok() {
+            return Ok(());
+        }
+    }
+    let key = match std::env::var("CLIP_VAULT_KEY") {
+        Ok(key) => key,
+        Err(_) => Password::new()
+            .with_prompt(if name == DEFAULT_PROFILE {
+                "Vault password".to_string()
+            } else {
+                format!("Password for profile {name}")
+            })
+            .interact()
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?,
+    };
+    // Fails with `InvalidKey` on a wrong password.
+    SqliteVault::open_read_only(path, &key)?;
+    *typed = Some(key);
+    Ok(())
+}
+
+/// The locations the CLI and the app use, after every override. See the
+/// `paths` settings in the shared config.
+fn cmd_paths() {
+    let vault = clip_vault_core::default_db_path();
+    println!("config  {}", config_path().display());
+    println!("vault   {}", vault.display());
+    println!("cache   {}", clip_vault_core::cache_dir().display());
+    println!("logs    {}", clip_vault_core::log_dir().display());
+    match clip_vault_core::blob_dir() {
+        Some(dir) => println!("blobs   {}", dir.display()),
+        None => println!(
+            "blobs   {} (next to the vault)",
+            vault.parent().unwrap_or(&vault).display()
+        ),
+    }
+}
+
+fn cmd_doctor() {
+    let checks = [
+        PermissionCheck::clipboard(clipboard::probe()),
+        PermissionCheck::accessibility(),
+        PermissionCheck::notifications(PermissionStatus::NotApplicable),
+    ];
+    println!("Permissions");
+    for check in &checks {
+        let mark = match check.status {
+            PermissionStatus::Granted => "✓",
+            PermissionStatus::NotApplicable => "-",
+            PermissionStatus::Denied | PermissionStatus::NotDetermined => "✗",
+        };
+        println!(
+            "  {mark} {:<14} {} ({})",
+            check.permission.to_string(),
+            check.status,
+            check.detail
+        );
+        if let (true, Some(url)) = (check.is_problem(), check.settings_url) {
+            println!("      grant it in System Settings: open '{url}'");
+        }
+    }
+
+    println!("Vault");
+    let path = clip_vault_core::default_db_path();
+    if !path.exists() {
+        println!("  - no vault at {} yet", path.display());
+        return;
+    }
+    let policy = Config::load().map(|c| c.unlock).unwrap_or_default();
+    let Some(key) = saved_key(&path, policy) else {
+        println!("  - locked; run any command to unlock it, then `clip-vault doctor` again");
+        return;
+    };
+    match SqliteVault::inspect_schema(&path, &key) {
+        Ok(schema) if schema.is_compatible() => {
+            println!(
+                "  ✓ schema version {} (this release supports up to {})",
+                schema.found, schema.supported
+            );
+            for migration in &schema.pending {
+                println!(
+                    "      next open migrates to {}: {}",
+                    migration.version, migration.description
+                );
+            }
+        }
+        Ok(schema) => println!(
+            "  ✗ schema version {} is newer than this release supports ({}); update clip-vault",
+            schema.found, schema.supported
+        ),
+        Err(e) => println!("  ✗ can't read the vault: {e}"),
+    }
+}
+
+fn cmd_sync(key: &str, action: &SyncCommand) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let conflicts = store.sync_conflicts()?;
+    match action {
+        SyncCommand::Conflicts => {
+            if conflicts.is_empty() {
+                println!("No conflicting edits.");
+            }
+            for conflict in &conflicts {
+                let original = clip_vault_core::hash_to_hex(&conflict.original);
+                println!("Entry {} was edited on several devices:", &original[..12]);
+                for version in &conflict.versions {
+                    let hash = clip_vault_core::hash_to_hex(&version.entry.item.hash());
+                    println!(
+                        "  {}  {} (generation {}, {})  {}",
+                        &hash[..12],
+                        version.device,
+                        version.generation,
+                        version.entry.timestamp.relative(),
+                        describe(&version.entry)
+                    );
+                }
+            }
+        }
+        SyncCommand::Resolve { version } => {
+            let prefix = version.to_ascii_lowercase();
+            let mut matches = conflicts
+                .iter()
+                .flat_map(|c| &c.versions)
+                .map(|v| v.entry.item.hash())
+                .filter(|hash| clip_vault_core::hash_to_hex(hash).starts_with(&prefix));
+            let (Some(keep), None) = (matches.next(), matches.next()) else {
+                eprintln!("No single conflicting version starts with {version:?}.");
+                std::process::exit(1);
+            };
+            authorize(&store, SensitiveAction::Delete)?;
+            let removed = store.resolve_conflict(keep)?;
+            println!(
+                "Kept {}; deleted {removed} other version(s).",
+                &clip_vault_core::hash_to_hex(&keep)[..12]
+            );
+        }
+    }
+    Ok(())
+}
+
+fn cmd_protected(key: &str, action: &ProtectedCommand) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    match action {
+        ProtectedCommand::Add { index } => {
+            let Some(entry) = index
+                .checked_sub(1)
+                .and_then(|i| store.list(None, None).ok()?.into_iter().nth(i))
+            else {
+                eprintln!("No entry #{index}.");
+                std::process::exit(1);
+            };
+            store.set_protected(entry.item.hash(), true)?;
+            println!("Entry #{index} moved to the protected area.");
+        }
+        ProtectedCommand::List => {
+            let items = unlock_protected(&store)?;
+            if items.is_empty() {
+                println!("{}", tr(Msg::NoProtectedEntries));
+            }
+            for (i, item) in items.iter().enumerate() {
+                println!("{}. {}", i + 1, describe(item));
+            }
+        }
+        ProtectedCommand::Copy { index } => {
+            let entry = protected_entry(&store, *index)?;
+            clipboard::copy_item(&entry.item)?;
+            println!("{}", tr_with(Msg::CopiedProtected, &[("index", index)]));
+        }
+        ProtectedCommand::Remove { index } => {
+            let entry = protected_entry(&store, *index)?;
+            store.set_protected(entry.item.hash(), false)?;
+            println!("{}", tr_with(Msg::ProtectedMovedBack, &[("index", index)]));
+        }
+    }
+    Ok(())
+}
+
+/// The protected entries, once their password has been typed.
+fn unlock_protected(store: &SqliteVault) -> Result<Vec<ClipboardItemWithTimestamp>> {
+    // Deliberately not cached: reaching protected entries always takes the
+    // password, even while the vault is unlocked.
+    let password = Password::new()
+        .with_prompt("Password for protected entries")
+        .interact()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    match store.protected_items(&password) {
+        Err(Error::InvalidKey) => {
+            eprintln!("{}", tr(Msg::InvalidPassword));
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+/// Protected entry number `index`, as in `clip-vault protected list`.
+fn protected_entry(store: &SqliteVault, index: usize) -> Result<ClipboardItemWithTimestamp> {
+    let items = unlock_protected(store)?;
+    let Some(entry) = index.checked_sub(1).and_then(|i| items.into_iter().nth(i)) else {
+        eprintln!("No protected entry #{index}.");
+        std::process::exit(1);
+    };
+    Ok(entry)
+}
+
+fn health_label(level: HealthLevel) -> &'static str {
+    match level {
+        HealthLevel::Info => "note",
+        HealthLevel::Warning => "warning",
+        HealthLevel::Error => "error",
+    }
+}
+
+/// Report rows the last query had to skip so corruption doesn't go unnoticed.
+fn warn_skipped(store: &SqliteVault) {
+    let skipped = store.diagnostics();
+    if !skipped.is_empty() {
+        eprintln!(
+            "warning: skipped {} undecodable entries (run `clip-vault quarantine` to move them aside)",
+            skipped.len()
+        );
+    }
+}
+
+fn cmd_ephemeral() -> Result<()> {
+    let name = format!("clip-vault-ephemeral-{}", std::process::id());
+    let store = SqliteVault::open_in_memory(&name)?;
+
+    // The capture thread gets its own connection to the same in-memory DB.
+    let capture = SqliteVault::open_in_memory(&name)?;
+    std::thread::spawn(move || {
+        let config = Config::load().unwrap_or_default();
+        let options = clip_vault_monitor::CaptureOptions {
+            record_context: false,
+            app_ttls: config.app_ttls(),
+            dedupe: config.dedupe,
+            normalize_text: config.text_normalization,
+            retention: config.retention,
+            ..Default::default()
+        };
+        clip_vault_monitor::run(&capture, &options, StdDuration::from_millis(250), || true).ok();
+    });
+
+    let mut app = tui::App::new(store)?;
+    tui::ui::run_tui(&mut app)?;
+    Ok(())
+}
+
+fn cmd_tui(key: &str) -> Result<()> {
+    let store = open_store_with_key(key)?;
+    let mut app = tui::App::new(store)?;
+    tui::ui::run_tui(&mut app)?;
+    Ok(())
+}
+
+fn open_store_with_key(key: &str) -> Result<SqliteVault> {
+    if let Some(snapshot) = SNAPSHOT.get() {
+        return SqliteVault::open_read_only(snapshot, key);
+    }
+    let path = clip_vault_core::default_db_path();
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    match SqliteVault::open(path, key) {
+        Ok(s) => {
+            s.set_dedupe_rules(Config::load().unwrap_or_default().dedupe);
+            Ok(s)
+        }
         Err(err) => {
             if let Error::Sqlite(sql_err) = &err {
                 if sql_err.sqlite_error_code() == Some(rusqlite::ErrorCode::DatabaseBusy) {
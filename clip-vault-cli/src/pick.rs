@@ -0,0 +1,180 @@
+use clip_vault_core::{
+    files_text, ClipboardItem, ClipboardItemWithTimestamp, Result, SqliteVault, Vault,
+};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph},
+    Terminal, TerminalOptions, Viewport,
+};
+use std::io;
+
+/// Rows of results shown under the prompt.
+const VISIBLE_ROWS: u16 = 10;
+
+/// A minimal inline fuzzy picker: type to filter, Enter copies the selection.
+pub struct Picker {
+    entries: Vec<ClipboardItemWithTimestamp>,
+    /// Indices into `entries` that match the current query, in frecency order.
+    matches: Vec<usize>,
+    query: String,
+    state: ListState,
+}
+
+impl Picker {
+    /// Load text entries ranked by frecency.
+    pub fn new(store: &SqliteVault) -> Result<Self> {
+        let entries: Vec<_> = store
+            .frecent(None)?
+            .into_iter()
+            .filter(|e| matches!(e.item, ClipboardItem::Text(_)))
+            .collect();
+        let mut picker = Self {
+            matches: (0..entries.len()).collect(),
+            entries,
+            query: String::new(),
+            state: ListState::default(),
+        };
+        picker
+            .state
+            .select((!picker.matches.is_empty()).then_some(0));
+        Ok(picker)
+    }
+
+    /// Run the picker and return the chosen entry, or `None` if cancelled.
+    pub fn run(mut self) -> Result<Option<ClipboardItem>> {
+        enable_raw_mode()?;
+        let backend = CrosstermBackend::new(io::stdout());
+        let mut terminal = Terminal::with_options(
+            backend,
+            TerminalOptions {
+                viewport: Viewport::Inline(VISIBLE_ROWS + 1),
+            },
+        )?;
+
+        let res = self.event_loop(&mut terminal);
+
+        terminal.clear()?;
+        disable_raw_mode()?;
+        res
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<Option<ClipboardItem>> {
+        loop {
+            terminal.draw(|f| self.draw(f))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Char('c' | 'g') if ctrl => return Ok(None),
+                KeyCode::Enter => {
+                    return Ok(self
+                        .state
+                        .selected()
+                        .and_then(|i| self.matches.get(i))
+                        .map(|&i| self.entries[i].item.clone()));
+                }
+                KeyCode::Up => self.move_selection(-1),
+                KeyCode::Char('p' | 'k') if ctrl => self.move_selection(-1),
+                KeyCode::Down => self.move_selection(1),
+                KeyCode::Char('n' | 'j') if ctrl => self.move_selection(1),
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refilter();
+                }
+                KeyCode::Char('u') if ctrl => {
+                    self.query.clear();
+                    self.refilter();
+                }
+                KeyCode::Char(c) if !ctrl => {
+                    self.query.push(c);
+                    self.refilter();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let last = self.matches.len() - 1;
+        let current = self.state.selected().unwrap_or(0);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    fn refilter(&mut self) {
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| match &e.item {
+                ClipboardItem::Text(t) => fuzzy_match(&self.query, t),
+                ClipboardItem::Files(paths) => fuzzy_match(&self.query, &files_text(paths)),
+                _ => false,
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn draw(&mut self, f: &mut ratatui::Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(f.area());
+
+        let prompt = Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(&self.query),
+            Span::styled(
+                format!("  {}/{}", self.matches.len(), self.entries.len()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]);
+        f.render_widget(Paragraph::new(prompt), chunks[0]);
+
+        let width = usize::from(chunks[1].width.saturating_sub(2));
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&i| {
+                let text = match &self.entries[i].item {
+                    ClipboardItem::Text(t) => t.split_whitespace().collect::<Vec<_>>().join(" "),
+                    ClipboardItem::Files(paths) => files_text(paths).replace('\n', " "),
+                    _ => String::new(),
+                };
+                ListItem::new(text.chars().take(width).collect::<String>())
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        f.render_stateful_widget(list, chunks[1], &mut self.state);
+    }
+}
+
+/// Case-insensitive subsequence match, the usual fuzzy-finder rule.
+fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|q| chars.any(|c| c == q))
+}
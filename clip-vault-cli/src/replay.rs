@@ -0,0 +1,107 @@
+//! `clip-vault replay`: put entries captured in a time window back on the
+//! clipboard one at a time, oldest first, to paste a sequence again.
+
+use crate::clipboard;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use clip_vault_core::{ClipboardItemWithTimestamp, EntryDescription, Result, RichText};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::io::Write;
+use std::time::Duration;
+
+/// Parse `--from`/`--to`: a time today (`10:30`, `10:30:15`) or a date and
+/// time (`2024-05-01 10:30`), in local time.
+pub fn parse_time(arg: &str) -> std::result::Result<DateTime<Local>, String> {
+    let arg = arg.trim();
+    let naive = ["%H:%M", "%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(arg, format).ok())
+        .map(|time| Local::now().date_naive().and_time(time))
+        .or_else(|| {
+            ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(arg, format).ok())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(arg, "%Y-%m-%d")
+                .ok()
+                .map(|day| day.and_time(NaiveTime::MIN))
+        })
+        .ok_or_else(|| format!("expected HH:MM or YYYY-MM-DD HH:MM, got {arg:?}"))?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("{arg} doesn't exist in the local timezone"))
+}
+
+/// One line saying which entry is on the clipboard. Secrets are described
+/// rather than shown.
+fn describe(entries: &[ClipboardItemWithTimestamp], index: usize) -> String {
+    let entry = &entries[index];
+    format!(
+        "[{}/{}] {}  {}",
+        index + 1,
+        entries.len(),
+        entry.timestamp.absolute(),
+        EntryDescription::of_item(&entry.item)
+    )
+}
+
+/// Copy each entry in turn, waiting `interval` between them.
+pub fn timed(entries: &[ClipboardItemWithTimestamp], interval: Duration) -> Result<()> {
+    for index in 0..entries.len() {
+        if index > 0 {
+            std::thread::sleep(interval);
+        }
+        clipboard::copy_unrecorded(&entries[index].item, &RichText::default(), &[])?;
+        println!("{}", describe(entries, index));
+    }
+    Ok(())
+}
+
+/// Copy the first entry, then the next on each Enter or Space, until the
+/// last one or `q`. `b` goes back one.
+pub fn step(entries: &[ClipboardItemWithTimestamp]) -> Result<()> {
+    println!("Enter or Space: next   b: back   q: stop");
+    enable_raw_mode()?;
+    let res = step_loop(entries);
+    disable_raw_mode()?;
+    res
+}
+
+fn step_loop(entries: &[ClipboardItemWithTimestamp]) -> Result<()> {
+    let mut index = 0;
+    loop {
+        clipboard::copy_unrecorded(&entries[index].item, &RichText::default(), &[])?;
+        // Raw mode doesn't turn \n into a new line.
+        print!("{}\r\n", describe(entries, index));
+        std::io::stdout().flush()?;
+
+        loop {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter | KeyCode::Char(' ' | 'n') | KeyCode::Right => {
+                    if index + 1 == entries.len() {
+                        return Ok(());
+                    }
+                    index += 1;
+                    break;
+                }
+                KeyCode::Char('b' | 'p') | KeyCode::Left if index > 0 => {
+                    index -= 1;
+                    break;
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(());
+                }
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
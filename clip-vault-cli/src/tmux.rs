@@ -0,0 +1,74 @@
+use clip_vault_core::{
+    tr, ClipboardItem, Error, LockRequests, Msg, Result, SqliteVault, Timestamp, Vault,
+};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Prefix of the named buffers `tmux-sync` maintains (`clip-vault-0` is the
+/// newest entry).
+const BUFFER_PREFIX: &str = "clip-vault-";
+
+/// Load `text` into a tmux paste buffer. Without a name tmux creates a new
+/// automatically named buffer, which becomes the one `prefix + ]` pastes.
+/// The text goes through tmux's stdin rather than its arguments, where
+/// other users could read it with `ps`.
+pub fn set_buffer(name: Option<&str>, text: &str) -> Result<()> {
+    let mut cmd = Command::new("tmux");
+    cmd.arg("load-buffer");
+    if let Some(name) = name {
+        cmd.args(["-b", name]);
+    }
+    let mut child = cmd
+        .arg("-")
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Io(std::io::Error::other(format!("failed to run tmux: {e}"))))?;
+    // Dropping stdin closes it, which ends the buffer.
+    let written = child
+        .stdin
+        .take()
+        .map_or(Ok(()), |mut stdin| stdin.write_all(text.as_bytes()));
+    let status = child.wait()?;
+    written?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Io(std::io::Error::other(format!(
+            "tmux load-buffer exited with {status}"
+        ))))
+    }
+}
+
+/// Push the newest `count` text entries into `clip-vault-0..count` buffers.
+/// Returns the timestamp of the newest entry seen.
+pub fn sync(store: &SqliteVault, count: usize) -> Result<Option<Timestamp>> {
+    let entries = store.list(Some(count), None)?;
+    let texts = entries.iter().filter_map(|e| match &e.item {
+        ClipboardItem::Text(t) => Some(t),
+        _ => None,
+    });
+    for (i, text) in texts.enumerate() {
+        set_buffer(Some(&format!("{BUFFER_PREFIX}{i}")), text)?;
+    }
+    Ok(entries.first().map(|e| e.timestamp))
+}
+
+/// Resync whenever a new entry shows up. Runs until interrupted or until
+/// `clip-vault lock` runs.
+pub fn watch(store: &SqliteVault, count: usize, interval: Duration) -> Result<()> {
+    let mut lock_requests = LockRequests::new();
+    let mut newest = sync(store, count)?;
+    loop {
+        std::thread::sleep(interval);
+        if lock_requests.requested() {
+            eprintln!("{}", tr(Msg::LockedStopping));
+            return Ok(());
+        }
+        let latest = store.list(Some(1), None)?.first().map(|e| e.timestamp);
+        if latest != newest {
+            newest = sync(store, count)?;
+        }
+    }
+}
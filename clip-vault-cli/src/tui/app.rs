@@ -1,5 +1,8 @@
-use chrono_humanize::{Accuracy, HumanTime, Tense};
-use clip_vault_core::{ClipboardItem, ClipboardItemWithTimestamp, Result, SqliteVault, Vault};
+use clip_vault_core::{
+    files_text, tr, tr_with, ClipboardItem, ClipboardItemWithTimestamp, Config, CopyEvent,
+    CopyFormat, Error, ItemKind, ItemStats, LockRequests, Msg, PreviewOptions, Result, Rgba,
+    SensitiveAction, SqliteVault, TextPreview, UnlockPolicy, Vault,
+};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, MouseEvent, MouseEventKind};
 use crossterm::{
     cursor::{Hide, Show},
@@ -11,17 +14,20 @@ use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
+    text::{Line, Span, Text},
     widgets::{
         Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
         ScrollbarOrientation, ScrollbarState, Wrap,
     },
     Frame, Terminal,
 };
-use std::io;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use std::{fs, process::Command};
 
+/// How often the TUI checks for entries captured by other processes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Mode {
     Normal,
@@ -40,13 +46,38 @@ pub struct App {
     preview_text: String,
     preview_lines: Vec<ratatui::text::Line<'static>>,
     preview_offset: usize,
+    /// When the previewed entry was copied, newest first.
+    preview_events: Vec<CopyEvent>,
+    /// The previewed entry's note.
+    preview_note: Option<String>,
+    /// The note typed so far, while the previewed entry's note is edited.
+    note_draft: Option<String>,
     should_quit: bool,
     status_message: String,
     scrollbar_state: ScrollbarState,
+    /// The "copy as" menu, open over the list or preview with this format
+    /// highlighted.
+    copy_as_menu: Option<usize>,
+    /// Listing only the entries related to this one instead of the history.
+    related_to: Option<[u8; 32]>,
+    unlock_policy: UnlockPolicy,
+    /// How much of each text entry the list shows.
+    preview_options: PreviewOptions,
+    /// The password typed so far, while a delete waits for it.
+    delete_password: Option<String>,
+    /// Hashes of the pinned entries, listed first and marked.
+    pinned: HashSet<[u8; 32]>,
+    /// Size, line and word counts of the listed entries, as the vault
+    /// recorded them.
+    stats: HashMap<[u8; 32], ItemStats>,
+    lock_requests: LockRequests,
+    /// Whether the TUI closed because `clip-vault lock` ran.
+    stopped_by_lock: bool,
 }
 
 impl App {
     pub fn new(vault: SqliteVault) -> Result<Self> {
+        let config = Config::load().unwrap_or_default();
         let mut app = Self {
             vault,
             items: Vec::new(),
@@ -58,9 +89,21 @@ impl App {
             preview_text: String::new(),
             preview_lines: Vec::new(),
             preview_offset: 0,
+            preview_events: Vec::new(),
+            preview_note: None,
+            note_draft: None,
             should_quit: false,
-            status_message: "Welcome to Clip Vault! Press ? for help".to_string(),
+            status_message: tr(Msg::Welcome).to_string(),
             scrollbar_state: ScrollbarState::default(),
+            copy_as_menu: None,
+            related_to: None,
+            unlock_policy: config.unlock,
+            preview_options: config.previews.tui,
+            delete_password: None,
+            pinned: HashSet::new(),
+            stats: HashMap::new(),
+            lock_requests: LockRequests::new(),
+            stopped_by_lock: false,
         };
         app.load_items()?;
         if !app.items.is_empty() {
@@ -71,13 +114,36 @@ impl App {
     }
 
     pub fn load_items(&mut self) -> Result<()> {
-        self.items = self.vault.list(None, None)?;
+        self.items = self.vault.list_pinned_first(None)?;
+        self.pinned = self
+            .vault
+            .pinned()?
+            .iter()
+            .map(|entry| entry.item.hash())
+            .collect();
+        let skipped = self.vault.diagnostics().len();
+        if skipped > 0 {
+            self.status_message = tr_with(Msg::SkippedUndecodable, &[("count", &skipped)]);
+        }
+        self.stats.clear();
+        for entry in &self.items {
+            let hash = entry.item.hash();
+            if let Some(stats) = self.vault.item_stats(hash)? {
+                self.stats.insert(hash, stats);
+            }
+        }
         self.apply_filter();
         Ok(())
     }
 
     fn apply_filter(&mut self) {
-        if self.search_query.is_empty() {
+        if let Some(hash) = self.related_to {
+            self.filtered_items = self
+                .vault
+                .related(hash)
+                .map(|related| related.into_iter().map(|r| r.entry).collect())
+                .unwrap_or_default();
+        } else if self.search_query.is_empty() {
             self.filtered_items = self.items.clone();
         } else {
             // Use the vault's search functionality for consistency
@@ -96,6 +162,10 @@ impl App {
                                 // For images, search in the query for "image"
                                 self.search_query.to_lowercase().contains("image")
                             }
+                            ClipboardItem::Files(paths) => files_text(paths)
+                                .to_lowercase()
+                                .contains(&self.search_query.to_lowercase()),
+                            _ => false,
                         })
                         .cloned()
                         .collect();
@@ -122,19 +192,44 @@ impl App {
     }
 
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        let changes = self.vault.subscribe();
         loop {
             terminal.draw(|f| self.ui(f))?;
 
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match self.mode {
-                        Mode::Normal => self.handle_normal_input(key.code)?,
-                        Mode::Search => self.handle_search_input(key.code),
-                        Mode::Preview => self.handle_preview_input(key.code, terminal)?,
-                    }
+            // Wake up periodically to pick up entries captured elsewhere.
+            if !event::poll(WATCH_INTERVAL)? {
+                if self.lock_requests.requested() {
+                    self.stopped_by_lock = true;
+                    break;
+                }
+                self.vault.poll_changes()?;
+                if changes.try_iter().count() > 0 && self.mode == Mode::Normal {
+                    self.reload_keeping_selection()?;
+                }
+                continue;
+            }
+
+            match event::read()? {
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press && self.delete_password.is_some() =>
+                {
+                    self.handle_delete_password_input(key.code)?;
+                }
+                Event::Key(key)
+                    if key.kind == KeyEventKind::Press && self.copy_as_menu.is_some() =>
+                {
+                    self.handle_copy_as_input(key.code)?;
                 }
-            } else if let Event::Mouse(mouse) = event::read()? {
-                self.handle_mouse_input(mouse);
+                Event::Key(key) if key.kind == KeyEventKind::Press && self.note_draft.is_some() => {
+                    self.handle_note_input(key.code)?;
+                }
+                Event::Key(key) if key.kind == KeyEventKind::Press => match self.mode {
+                    Mode::Normal => self.handle_normal_input(key.code)?,
+                    Mode::Search => self.handle_search_input(key.code),
+                    Mode::Preview => self.handle_preview_input(key.code, terminal)?,
+                },
+                Event::Mouse(mouse) => self.handle_mouse_input(mouse),
+                _ => {}
             }
 
             if self.should_quit {
@@ -144,8 +239,14 @@ impl App {
         Ok(())
     }
 
+    /// Whether [`App::run`] returned because the vault was locked.
+    pub fn stopped_by_lock(&self) -> bool {
+        self.stopped_by_lock
+    }
+
     fn handle_normal_input(&mut self, key: KeyCode) -> Result<()> {
         match key {
+            KeyCode::Esc if self.related_to.is_some() => self.close_related(),
             KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
             KeyCode::Char('j') | KeyCode::Down => self.next_item(),
             KeyCode::Char('k') | KeyCode::Up => self.previous_item(),
@@ -155,9 +256,15 @@ impl App {
             KeyCode::PageUp => self.page_up(),
             KeyCode::Char('/') => self.enter_search_mode(),
             KeyCode::Char('c') => self.copy_selected_item()?,
-            KeyCode::Char('d') => self.delete_selected_item()?,
+            KeyCode::Char('t') => self.send_selected_to_tmux(),
+            KeyCode::Char('m') => self.copy_selected_as_markdown()?,
+            KeyCode::Char('y') => self.open_copy_as_menu(),
+            KeyCode::Char('p') => self.pin_selected_item()?,
+            KeyCode::Char('P') => self.protect_selected_item()?,
+            KeyCode::Char('d') => self.request_delete()?,
             KeyCode::Enter | KeyCode::Char(' ') => self.preview_selected_item(),
             KeyCode::Char('r') => self.refresh_items()?,
+            KeyCode::Char('R') => self.show_related(),
             KeyCode::Char('?') => self.show_help(),
             _ => {}
         }
@@ -184,6 +291,100 @@ impl App {
         }
     }
 
+    fn handle_copy_as_input(&mut self, key: KeyCode) -> Result<()> {
+        let formats = self.copy_as_formats();
+        let last = formats.len().saturating_sub(1);
+        match (key, self.copy_as_menu) {
+            (KeyCode::Esc | KeyCode::Char('q' | 'y'), _) => self.copy_as_menu = None,
+            (KeyCode::Char('j') | KeyCode::Down, Some(i)) => {
+                self.copy_as_menu = Some((i + 1).min(last));
+            }
+            (KeyCode::Char('k') | KeyCode::Up, Some(i)) => {
+                self.copy_as_menu = Some(i.saturating_sub(1));
+            }
+            (KeyCode::Enter | KeyCode::Char(' '), Some(i)) => {
+                if let Some(&format) = formats.get(i) {
+                    self.copy_selected_as(format)?;
+                }
+            }
+            (KeyCode::Char(c @ '1'..='9'), _) => {
+                if let Some(&format) = formats.get(c as usize - '1' as usize) {
+                    self.copy_selected_as(format)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_delete_password_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(password) = self.delete_password.as_mut() else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Esc => {
+                self.delete_password = None;
+                self.status_message = tr(Msg::DeleteCancelled).into();
+            }
+            KeyCode::Backspace => {
+                password.pop();
+            }
+            KeyCode::Char(c) => password.push(c),
+            KeyCode::Enter => {
+                let password = self.delete_password.take();
+                match self
+                    .unlock_policy
+                    .authorize(SensitiveAction::Delete, &self.vault, |_| password)
+                {
+                    Ok(()) => self.delete_selected_item()?,
+                    Err(Error::InvalidKey) => {
+                        self.status_message = tr(Msg::WrongPasswordNothingDeleted).into();
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_note_input(&mut self, key: KeyCode) -> Result<()> {
+        let Some(draft) = self.note_draft.as_mut() else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Esc => {
+                self.note_draft = None;
+                self.status_message = tr(Msg::NoteUnchanged).to_string();
+            }
+            KeyCode::Backspace => {
+                draft.pop();
+            }
+            KeyCode::Char(c) => draft.push(c),
+            KeyCode::Enter => {
+                let draft = self.note_draft.take().unwrap_or_default();
+                let Some(hash) = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.filtered_items.get(i))
+                    .map(|item_with_ts| item_with_ts.item.hash())
+                else {
+                    return Ok(());
+                };
+                self.vault.set_note(hash, Some(&draft))?;
+                self.preview_note = self.vault.note(hash)?;
+                self.status_message = tr(if self.preview_note.is_some() {
+                    Msg::NoteSaved
+                } else {
+                    Msg::NoteRemoved
+                })
+                .to_string();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     fn handle_preview_input<B: Backend>(
         &mut self,
         key: KeyCode,
@@ -192,17 +393,21 @@ impl App {
         match key {
             KeyCode::Esc | KeyCode::Char('q') => self.exit_preview_mode(),
             KeyCode::Char('c') => self.copy_selected_item()?,
-            KeyCode::Char('d') => self.delete_selected_item()?,
+            KeyCode::Char('t') => self.send_selected_to_tmux(),
+            KeyCode::Char('m') => self.copy_selected_as_markdown()?,
+            KeyCode::Char('y') => self.open_copy_as_menu(),
+            KeyCode::Char('d') => self.request_delete()?,
             KeyCode::Char('e') => self.edit_selected_item(terminal)?,
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.preview_offset > 0 {
-                    self.preview_offset -= 1;
-                }
+            KeyCode::Char('n') => {
+                self.note_draft = Some(self.preview_note.clone().unwrap_or_default());
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                if self.preview_offset + 1 < self.preview_lines.len() {
-                    self.preview_offset += 1;
-                }
+            KeyCode::Up | KeyCode::Char('k') if self.preview_offset > 0 => {
+                self.preview_offset -= 1;
+            }
+            KeyCode::Down | KeyCode::Char('j')
+                if self.preview_offset + 1 < self.preview_lines.len() =>
+            {
+                self.preview_offset += 1;
             }
             KeyCode::PageUp => {
                 self.preview_offset = self.preview_offset.saturating_sub(10);
@@ -288,12 +493,12 @@ impl App {
 
     fn enter_search_mode(&mut self) {
         self.mode = Mode::Search;
+        self.related_to = None;
         self.search_query.clear();
         self.search_cursor = 0;
         // Reset to show all items when entering search mode
         self.apply_filter();
-        self.status_message =
-            "Search mode - type to search, Enter to exit, Esc to cancel".to_string();
+        self.status_message = tr(Msg::SearchMode).to_string();
     }
 
     fn exit_search_mode(&mut self) {
@@ -301,7 +506,7 @@ impl App {
         self.search_query.clear();
         self.search_cursor = 0;
         self.apply_filter();
-        self.status_message = "Welcome to Clip Vault! Press ? for help".to_string();
+        self.status_message = tr(Msg::Welcome).to_string();
     }
 
     fn execute_search(&mut self) {
@@ -309,9 +514,12 @@ impl App {
         // Search is already applied, just exit search mode
         let count = self.filtered_items.len();
         self.status_message = if self.search_query.is_empty() {
-            "Showing all items".to_string()
+            tr(Msg::ShowingAll).to_string()
         } else {
-            format!("Found {} items matching '{}'", count, self.search_query)
+            tr_with(
+                Msg::Found,
+                &[("count", &count), ("query", &self.search_query)],
+            )
         };
     }
 
@@ -323,11 +531,11 @@ impl App {
             self.apply_filter();
             let count = self.filtered_items.len();
             self.status_message = if self.search_query.is_empty() {
-                "Search mode - type to search, Enter to exit, Esc to cancel".to_string()
+                tr(Msg::SearchMode).to_string()
             } else {
-                format!(
-                    "Found {} items matching '{}' - Enter to exit, Esc to cancel",
-                    count, self.search_query
+                tr_with(
+                    Msg::FoundWhileSearching,
+                    &[("count", &count), ("query", &self.search_query)],
                 )
             };
         }
@@ -350,11 +558,11 @@ impl App {
         self.apply_filter();
         let count = self.filtered_items.len();
         self.status_message = if self.search_query.is_empty() {
-            "Search mode - type to search, Enter to exit, Esc to cancel".to_string()
+            tr(Msg::SearchMode).to_string()
         } else {
-            format!(
-                "Found {} items matching '{}' - Enter to exit, Esc to cancel",
-                count, self.search_query
+            tr_with(
+                Msg::FoundWhileSearching,
+                &[("count", &count), ("query", &self.search_query)],
             )
         };
     }
@@ -365,10 +573,16 @@ impl App {
                 match &item_with_ts.item {
                     ClipboardItem::Text(text) => {
                         Self::copy_text_to_clipboard(&text.clone())?;
-                        self.status_message = "Copied to clipboard!".to_string();
+                        self.vault.record_use(item_with_ts.item.hash())?;
+                        self.status_message = tr(Msg::CopiedToClipboard).to_string();
+                    }
+                    ClipboardItem::Files(_) => {
+                        crate::clipboard::copy_item(&item_with_ts.item)?;
+                        self.vault.record_use(item_with_ts.item.hash())?;
+                        self.status_message = tr(Msg::CopiedToClipboard).to_string();
                     }
-                    ClipboardItem::Image(_) => {
-                        self.status_message = "Cannot copy images in CLI mode".to_string();
+                    _ => {
+                        self.status_message = tr(Msg::CannotCopyImages).to_string();
                     }
                 }
             }
@@ -376,31 +590,177 @@ impl App {
         Ok(())
     }
 
-    fn copy_text_to_clipboard(text: &str) -> Result<()> {
-        let mut clipboard = arboard::Clipboard::new()
-            .map_err(|e| clip_vault_core::Error::Io(io::Error::other(e)))?;
-        clipboard
-            .set_text(text)
-            .map_err(|e| clip_vault_core::Error::Io(io::Error::other(e)))?;
+    /// Copy the selected entry's HTML flavour converted to Markdown.
+    fn copy_selected_as_markdown(&mut self) -> Result<()> {
+        let Some(item_with_ts) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+        else {
+            return Ok(());
+        };
+        let hash = item_with_ts.item.hash();
+        match self.vault.html(hash)? {
+            Some(html) => {
+                Self::copy_text_to_clipboard(&clip_vault_core::html_to_markdown(&html))?;
+                self.vault.record_use(hash)?;
+                self.status_message = tr(Msg::CopiedAsMarkdown).to_string();
+            }
+            None => self.status_message = tr(Msg::NoHtmlVersion).to_string(),
+        }
+        Ok(())
+    }
+
+    /// The "copy as" formats for the selected entry's kind.
+    fn copy_as_formats(&self) -> Vec<CopyFormat> {
+        let Some(item_with_ts) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+        else {
+            return Vec::new();
+        };
+        let kind = ItemKind::of(&item_with_ts.item);
+        CopyFormat::ALL
+            .into_iter()
+            .filter(|format| format.applies_to(kind))
+            .collect()
+    }
+
+    fn open_copy_as_menu(&mut self) {
+        match self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+            .map(|item_with_ts| &item_with_ts.item)
+        {
+            Some(ClipboardItem::Text(_)) => self.copy_as_menu = Some(0),
+            Some(ClipboardItem::Image(_)) => {
+                self.status_message = tr(Msg::CannotCopyImages).to_string();
+            }
+            Some(ClipboardItem::Files(_)) => {
+                self.status_message = tr(Msg::CannotEditFiles).to_string();
+            }
+            Some(_) | None => {}
+        }
+    }
+
+    /// Copy the selected entry reshaped into `format`, closing the menu.
+    fn copy_selected_as(&mut self, format: CopyFormat) -> Result<()> {
+        self.copy_as_menu = None;
+        let Some(item_with_ts) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+        else {
+            return Ok(());
+        };
+        let ClipboardItem::Text(text) = &item_with_ts.item else {
+            return Ok(());
+        };
+        match clip_vault_core::copy_as(text, format) {
+            Ok(out) => {
+                Self::copy_text_to_clipboard(&out)?;
+                self.vault.record_use(item_with_ts.item.hash())?;
+                self.status_message = tr_with(Msg::CopiedAs, &[("format", &format.title())]);
+            }
+            Err(e) => {
+                self.status_message = tr_with(
+                    Msg::CannotCopyAs,
+                    &[("format", &format.title()), ("error", &e)],
+                );
+            }
+        }
         Ok(())
     }
 
+    /// Pin the selected entry above the history, or unpin it if it is.
+    fn pin_selected_item(&mut self) -> Result<()> {
+        let Some(hash) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+            .map(|item_with_ts| item_with_ts.item.hash())
+        else {
+            return Ok(());
+        };
+        let pin = !self.pinned.contains(&hash);
+        self.vault.pin(hash, pin)?;
+        self.reload_keeping_selection()?;
+        self.status_message = tr(if pin { Msg::Pinned } else { Msg::Unpinned }).to_string();
+        Ok(())
+    }
+
+    /// Move the selected entry into the protected area, out of the list.
+    fn protect_selected_item(&mut self) -> Result<()> {
+        let Some(item_with_ts) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+        else {
+            return Ok(());
+        };
+        self.vault.set_protected(item_with_ts.item.hash(), true)?;
+        self.reload_keeping_selection()?;
+        self.status_message = tr(Msg::MovedToProtected).to_string();
+        Ok(())
+    }
+
+    fn send_selected_to_tmux(&mut self) {
+        let Some(item_with_ts) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+        else {
+            return;
+        };
+        self.status_message = match &item_with_ts.item {
+            ClipboardItem::Text(text) => match crate::tmux::set_buffer(None, text) {
+                Ok(()) => tr(Msg::SentToTmux).to_string(),
+                Err(e) => format!("tmux: {e}"),
+            },
+            ClipboardItem::Files(paths) => {
+                match crate::tmux::set_buffer(None, &files_text(paths)) {
+                    Ok(()) => tr(Msg::SentToTmux).to_string(),
+                    Err(e) => format!("tmux: {e}"),
+                }
+            }
+            _ => tr(Msg::CannotSendImagesToTmux).to_string(),
+        };
+    }
+
+    fn copy_text_to_clipboard(text: &str) -> Result<()> {
+        crate::clipboard::copy_text(text)
+    }
+
     fn preview_selected_item(&mut self) {
         if let Some(selected) = self.list_state.selected() {
             if let Some(item_with_ts) = self.filtered_items.get(selected) {
                 // Extract text without holding the immutable borrow during mutable operations
                 let txt = match &item_with_ts.item {
                     ClipboardItem::Text(t) => Some(t.clone()),
-                    ClipboardItem::Image(_) => {
-                        Some("[Image content - not displayable in CLI]".to_string())
-                    }
+                    ClipboardItem::Files(paths) => Some(files_text(paths)),
+                    _ => Some(item_with_ts.item.image_info().map_or_else(
+                        || "[Image content - not displayable in CLI]".to_string(),
+                        |info| format!("[Image: {info} - not displayable in CLI]"),
+                    )),
                 };
 
+                let events = self
+                    .vault
+                    .copy_events(item_with_ts.item.hash())
+                    .unwrap_or_default();
+                let note = self
+                    .vault
+                    .note(item_with_ts.item.hash())
+                    .unwrap_or_default();
+
                 if let Some(t) = txt {
                     self.prepare_preview(&t);
+                    self.preview_events = events;
+                    self.preview_note = note;
                     self.mode = Mode::Preview;
-                    self.status_message =
-                        "Preview mode - press Esc to return, 'c' to copy".to_string();
+                    self.status_message = tr(Msg::PreviewMode).to_string();
                 }
             }
         }
@@ -411,7 +771,9 @@ impl App {
         self.preview_text.clear();
         self.preview_lines.clear();
         self.preview_offset = 0;
-        self.status_message = "Welcome to Clip Vault! Press ? for help".to_string();
+        self.preview_events.clear();
+        self.preview_note = None;
+        self.status_message = tr(Msg::Welcome).to_string();
     }
 
     /// Launch $EDITOR with the current item, save changes back to the vault.
@@ -426,8 +788,12 @@ impl App {
 
         let original_text = match &item_with_ts.item {
             ClipboardItem::Text(t) => t.clone(),
-            ClipboardItem::Image(_) => {
-                self.status_message = "Cannot edit images in CLI mode".to_string();
+            ClipboardItem::Files(_) => {
+                self.status_message = tr(Msg::CannotEditFiles).to_string();
+                return Ok(());
+            }
+            _ => {
+                self.status_message = tr(Msg::CannotEditImages).to_string();
                 return Ok(());
             }
         };
@@ -452,13 +818,13 @@ impl App {
         execute!(std::io::stdout(), EnableMouseCapture, Hide)?;
         enable_raw_mode()?;
         if !status.success() {
-            self.status_message = "Editor aborted".into();
+            self.status_message = tr(Msg::EditorAborted).into();
             return Ok(());
         }
 
         let new_text = fs::read_to_string(&path)?;
         if new_text == original_text {
-            self.status_message = "No changes made".into();
+            self.status_message = tr(Msg::NoChanges).into();
             return Ok(());
         }
 
@@ -469,81 +835,56 @@ impl App {
         self.load_items()?;
         Self::copy_text_to_clipboard(&new_text)?;
         self.prepare_preview(&new_text);
-        self.status_message = "Saved changes to vault".into();
+        self.status_message = tr(Msg::SavedChanges).into();
         Ok(())
     }
 
-    fn refresh_items(&mut self) -> Result<()> {
+    fn reload_keeping_selection(&mut self) -> Result<()> {
+        let selected = self.list_state.selected();
         self.load_items()?;
-        self.status_message = format!("Refreshed - {} items loaded", self.items.len());
+        if let Some(i) = selected {
+            if !self.filtered_items.is_empty() {
+                self.list_state
+                    .select(Some(i.min(self.filtered_items.len() - 1)));
+                self.update_scrollbar();
+            }
+        }
         Ok(())
     }
 
-    fn show_help(&mut self) {
-        self.status_message = "j/↓:down k/↑:up g:top G:bottom /:live-search c:copy Space/Enter:preview r:refresh q:quit".to_string();
-    }
-
-    fn format_timestamp(timestamp: u64) -> String {
-        let system_time = UNIX_EPOCH + Duration::from_nanos(timestamp);
-        let now = SystemTime::now();
-
-        // If more than 1 hour ago, show simple date/time format
-        if let Ok(duration) = now.duration_since(system_time) {
-            if duration.as_secs() > 3600 {
-                // 1 hour
-                let secs_since_epoch = system_time
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                let days_since_epoch = secs_since_epoch / 86400;
-                let remaining_secs = secs_since_epoch % 86400;
-                let hours = remaining_secs / 3600;
-                let minutes = (remaining_secs % 3600) / 60;
-
-                // Simple date calculation from epoch days
-                let mut year = 1970;
-                let mut days = days_since_epoch;
-                while days >= 365 {
-                    if (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0) {
-                        if days >= 366 {
-                            days -= 366;
-                            year += 1;
-                        } else {
-                            break;
-                        }
-                    } else {
-                        days -= 365;
-                        year += 1;
-                    }
-                }
-
-                let month_days = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-                let mut month = 1;
-                let mut day_in_month = days + 1;
-
-                for &month_length in &month_days {
-                    let adjusted_length = if month == 2
-                        && ((year % 4 == 0 && year % 100 != 0) || (year % 400 == 0))
-                    {
-                        29
-                    } else {
-                        month_length
-                    };
+    /// List the entries related to the selected one, e.g. copied in the same
+    /// repo around the same time.
+    fn show_related(&mut self) {
+        let Some(item_with_ts) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_items.get(i))
+        else {
+            return;
+        };
+        self.related_to = Some(item_with_ts.item.hash());
+        self.search_query.clear();
+        self.apply_filter();
+        self.status_message = tr_with(
+            Msg::RelatedEntries,
+            &[("count", &self.filtered_items.len())],
+        );
+    }
 
-                    if day_in_month <= adjusted_length {
-                        break;
-                    }
-                    day_in_month -= adjusted_length;
-                    month += 1;
-                }
+    fn close_related(&mut self) {
+        self.related_to = None;
+        self.apply_filter();
+        self.status_message = tr(Msg::ShowingAll).to_string();
+    }
 
-                return format!("{month:02}/{day_in_month:02} {hours:02}:{minutes:02}");
-            }
-        }
+    fn refresh_items(&mut self) -> Result<()> {
+        self.load_items()?;
+        self.status_message = tr_with(Msg::Refreshed, &[("count", &self.items.len())]);
+        Ok(())
+    }
 
-        // Otherwise use relative time
-        let human_time = HumanTime::from(system_time);
-        human_time.to_text_en(Accuracy::Rough, Tense::Past)
+    fn show_help(&mut self) {
+        self.status_message = tr(Msg::TuiHelp).to_string();
     }
 
     pub fn ui(&mut self, f: &mut Frame) {
@@ -573,6 +914,43 @@ impl App {
 
         // Footer
         self.render_footer(f, chunks[2]);
+
+        if let Some(selected) = self.copy_as_menu {
+            Self::render_copy_as_menu(f, chunks[1], &self.copy_as_formats(), selected);
+        }
+    }
+
+    /// The "copy as" popup, centred over `area`.
+    fn render_copy_as_menu(
+        f: &mut Frame,
+        area: ratatui::layout::Rect,
+        formats: &[CopyFormat],
+        selected: usize,
+    ) {
+        let items: Vec<ListItem> = formats
+            .iter()
+            .enumerate()
+            .map(|(i, format)| ListItem::new(format!("{} {}", i + 1, format.title())))
+            .collect();
+        let height = u16::try_from(items.len() + 2).map_or(area.height, |h| h.min(area.height));
+        let width = 30.min(area.width);
+        let popup = ratatui::layout::Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Copy as"))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::LightBlue)
+                    .fg(Color::Black)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let mut state = ListState::default().with_selected(Some(selected));
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, popup, &mut state);
     }
 
     fn render_list(&mut self, f: &mut Frame, area: ratatui::layout::Rect) {
@@ -624,16 +1002,43 @@ impl App {
         self.render_scrollbar(f, area);
     }
 
-    /// Build `ListItem`s for the timestamp column.
+    /// Build `ListItem`s for the timestamp column, with the app each entry
+    /// was copied from below its time where it has room, or after it.
     fn build_timestamp_items(&self) -> Vec<ListItem<'static>> {
         self.filtered_items
             .iter()
             .map(|item_with_ts| {
-                let timestamp_str = Self::format_timestamp(item_with_ts.timestamp);
-                ListItem::new(Line::from(Span::styled(
-                    timestamp_str,
-                    Style::default().fg(Color::DarkGray),
-                )))
+                let mut timestamp_str = item_with_ts.timestamp.relative();
+                if self.pinned.contains(&item_with_ts.item.hash()) {
+                    timestamp_str.insert_str(0, "📌 ");
+                }
+                let height = self.item_height(&item_with_ts.item);
+                let source = item_with_ts.source.as_ref().map(|s| s.name.as_str());
+                let mut lines = match source {
+                    Some(app) if height > 1 => vec![
+                        Line::from(Span::styled(
+                            timestamp_str,
+                            Style::default().fg(Color::DarkGray),
+                        )),
+                        Line::from(Span::styled(
+                            app.chars().take(18).collect::<String>(),
+                            Style::default().fg(Color::Cyan),
+                        )),
+                    ],
+                    Some(app) => vec![Line::from(Span::styled(
+                        format!("{timestamp_str} {app}")
+                            .chars()
+                            .take(18)
+                            .collect::<String>(),
+                        Style::default().fg(Color::DarkGray),
+                    ))],
+                    None => vec![Line::from(Span::styled(
+                        timestamp_str,
+                        Style::default().fg(Color::DarkGray),
+                    ))],
+                };
+                lines.resize(height, Line::default());
+                ListItem::new(lines)
             })
             .collect()
     }
@@ -646,49 +1051,49 @@ impl App {
             .map(|(i, item_with_ts)| {
                 let content = match &item_with_ts.item {
                     ClipboardItem::Text(text) => {
-                        let preview = if text.len() > 80 {
-                            format!("{}...", &text[..80])
-                        } else {
-                            text.clone()
-                        };
-
-                        // Replace newlines with ↵ symbol for better display
-                        let preview = preview.replace('\n', "↵").replace('\r', "");
-
-                        let mut spans = vec![Span::styled(
-                            format!("{:>3}. ", i + 1),
-                            Style::default().fg(Color::DarkGray),
-                        )];
-
-                        // Add search highlighting if in search mode
-                        if self.search_query.is_empty() {
-                            spans.push(Span::raw(preview));
-                        } else {
-                            let search_lower = self.search_query.to_lowercase();
-                            let preview_lower = preview.to_lowercase();
-
-                            if let Some(pos) = preview_lower.find(&search_lower) {
-                                // Text before match
-                                if pos > 0 {
-                                    spans.push(Span::raw(preview[..pos].to_string()));
-                                }
-                                // Highlighted match
+                        let preview = TextPreview::new(text, self.preview_options);
+                        let last = preview.lines.len().saturating_sub(1);
+                        let mut lines = Vec::with_capacity(preview.lines.len());
+                        for (n, shown) in preview.lines.iter().enumerate() {
+                            let mut spans = if n == 0 {
+                                vec![Span::styled(
+                                    format!("{:>3}. ", i + 1),
+                                    Style::default().fg(Color::DarkGray),
+                                )]
+                            } else {
+                                vec![Span::raw("     ")]
+                            };
+                            if n == 0 && clip_vault_core::is_otp_like(text) {
                                 spans.push(Span::styled(
-                                    preview[pos..pos + self.search_query.len()].to_string(),
-                                    Style::default().bg(Color::Yellow).fg(Color::Black),
+                                    "[OTP] ",
+                                    Style::default().fg(Color::Magenta),
+                                ));
+                            }
+                            if let Some(color) = (n == 0).then(|| Rgba::parse(text)).flatten() {
+                                spans.push(Span::styled(
+                                    "██ ",
+                                    Style::default().fg(Color::Rgb(color.r, color.g, color.b)),
                                 ));
-                                // Text after match
-                                if pos + self.search_query.len() < preview.len() {
-                                    spans.push(Span::raw(
-                                        preview[pos + self.search_query.len()..].to_string(),
-                                    ));
-                                }
-                            } else {
-                                spans.push(Span::raw(preview.clone()));
                             }
+                            spans.extend(self.highlight_matches(shown));
+                            if n == last && preview.more_lines > 0 {
+                                spans.push(Span::styled(
+                                    format!("  +{} more lines", preview.more_lines),
+                                    Style::default().fg(Color::DarkGray),
+                                ));
+                            }
+                            if let Some(stats) = (n == last)
+                                .then(|| self.stats.get(&item_with_ts.item.hash()))
+                                .flatten()
+                            {
+                                spans.push(Span::styled(
+                                    format!("  {stats}"),
+                                    Style::default().fg(Color::DarkGray),
+                                ));
+                            }
+                            lines.push(Line::from(spans));
                         }
-
-                        Line::from(spans)
+                        Text::from(lines)
                     }
                     ClipboardItem::Image(data) => {
                         let mut spans = vec![Span::styled(
@@ -696,22 +1101,86 @@ impl App {
                             Style::default().fg(Color::DarkGray),
                         )];
 
+                        let label = item_with_ts.item.image_info().map_or_else(
+                            || format!("{} bytes", data.len()),
+                            |info| info.to_string(),
+                        );
                         spans.push(Span::styled(
-                            format!("📷 [Image: {} bytes]", data.len()),
+                            format!("📷 [Image: {label}]"),
                             Style::default().fg(Color::Blue),
                         ));
 
-                        Line::from(spans)
+                        Text::from(Line::from(spans))
                     }
+                    ClipboardItem::Files(paths) => {
+                        let names: Vec<_> = paths
+                            .iter()
+                            .map(|path| {
+                                path.file_name()
+                                    .unwrap_or(path.as_os_str())
+                                    .to_string_lossy()
+                            })
+                            .collect();
+                        let mut spans = vec![
+                            Span::styled(
+                                format!("{:>3}. ", i + 1),
+                                Style::default().fg(Color::DarkGray),
+                            ),
+                            Span::styled("📁 ", Style::default().fg(Color::Blue)),
+                        ];
+                        spans.extend(self.highlight_matches(&names.join(", ")));
+                        Text::from(Line::from(spans))
+                    }
+                    _ => Text::from(format!("{:>3}. ?", i + 1)),
                 };
                 ListItem::new(content)
             })
             .collect()
     }
 
+    /// `line` as spans, with the first match of the search query
+    /// highlighted.
+    fn highlight_matches(&self, line: &str) -> Vec<Span<'static>> {
+        if self.search_query.is_empty() {
+            return vec![Span::raw(line.to_string())];
+        }
+        let search_lower = self.search_query.to_lowercase();
+        let line_lower = line.to_lowercase();
+        let Some(pos) = line_lower.find(&search_lower) else {
+            return vec![Span::raw(line.to_string())];
+        };
+        let end = pos + self.search_query.len();
+        let mut spans = Vec::new();
+        // Text before match
+        if pos > 0 {
+            spans.push(Span::raw(line[..pos].to_string()));
+        }
+        // Highlighted match
+        spans.push(Span::styled(
+            line[pos..end].to_string(),
+            Style::default().bg(Color::Yellow).fg(Color::Black),
+        ));
+        // Text after match
+        if end < line.len() {
+            spans.push(Span::raw(line[end..].to_string()));
+        }
+        spans
+    }
+
+    /// Rows an entry takes up in the list, so the timestamp column can keep
+    /// level with multi-line previews.
+    fn item_height(&self, item: &ClipboardItem) -> usize {
+        match item {
+            ClipboardItem::Text(text) => TextPreview::new(text, self.preview_options).lines.len(),
+            _ => 1,
+        }
+    }
+
     /// Title for the content list depending on search state.
     fn list_title(&self) -> String {
-        if self.search_query.is_empty() {
+        if self.related_to.is_some() {
+            format!("Related Entries ({} items)", self.filtered_items.len())
+        } else if self.search_query.is_empty() {
             format!("Clipboard History ({} items)", self.filtered_items.len())
         } else {
             format!(
@@ -744,10 +1213,48 @@ impl App {
     }
 
     fn render_preview(&mut self, f: &mut Frame, area: ratatui::layout::Rect) {
-        let title = String::from("Preview (Esc to close, 'c' to copy, 'e' to edit)");
+        let title =
+            String::from("Preview (Esc to close, 'c' to copy, 'e' to edit, 'n' for a note)");
 
         let block = Block::default().title(title).borders(Borders::ALL);
 
+        f.render_widget(Clear, area);
+        let area = match &self.preview_note {
+            Some(note) => {
+                // The note above the content, at most a third of the pane
+                let rows = u16::try_from(note.lines().count())
+                    .unwrap_or(u16::MAX)
+                    .min(area.height / 3)
+                    .saturating_add(2);
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(rows), Constraint::Min(3)])
+                    .split(area);
+                let note = Paragraph::new(note.clone())
+                    .block(Block::default().title("Note").borders(Borders::ALL))
+                    .style(Style::default().fg(Color::Cyan))
+                    .wrap(Wrap { trim: false });
+                f.render_widget(note, chunks[0]);
+                chunks[1]
+            }
+            None => area,
+        };
+        let area = if self.preview_events.is_empty() {
+            area
+        } else {
+            // Copy history below the content, at most a third of the pane
+            let rows = u16::try_from(self.preview_events.len())
+                .unwrap_or(u16::MAX)
+                .min(area.height / 3)
+                .saturating_add(2);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(rows)])
+                .split(area);
+            self.render_copy_events(f, chunks[1]);
+            chunks[0]
+        };
+
         // Determine visible lines
         let height = area.height.saturating_sub(2) as usize; // border padding
         let end = (self.preview_offset + height).min(self.preview_lines.len());
@@ -758,10 +1265,32 @@ impl App {
             .style(Style::default().fg(Color::White))
             .wrap(Wrap { trim: false });
 
-        f.render_widget(Clear, area);
         f.render_widget(paragraph, area);
     }
 
+    fn render_copy_events(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let lines: Vec<Line> = self
+            .preview_events
+            .iter()
+            .map(|event| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>12}  ", event.timestamp.relative()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(event.kind.to_string()),
+                ])
+            })
+            .collect();
+        let block = Block::default()
+            .title(tr_with(
+                Msg::CopyHistory,
+                &[("count", &self.preview_events.len())],
+            ))
+            .borders(Borders::ALL);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
     fn render_footer(&self, f: &mut Frame, area: ratatui::layout::Rect) {
         let footer_chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -769,8 +1298,23 @@ impl App {
             .split(area);
 
         // Status message
-        let status = match self.mode {
-            Mode::Search => {
+        let status = match (&self.delete_password, &self.mode) {
+            (Some(typed), _) => Paragraph::new(format!(
+                "Password to delete: {}",
+                "*".repeat(typed.chars().count())
+            ))
+            .style(Style::default().fg(Color::Yellow))
+            .block(Block::default().borders(Borders::ALL)),
+            (None, _) if self.note_draft.is_some() => {
+                let draft = self.note_draft.as_deref().unwrap_or_default();
+                Paragraph::new(Line::from(vec![
+                    Span::raw(format!("Note (Enter to save, empty to remove): {draft}")),
+                    Span::styled("│", Style::default().fg(Color::Yellow)),
+                ]))
+                .style(Style::default().fg(Color::Yellow))
+                .block(Block::default().borders(Borders::ALL))
+            }
+            (None, Mode::Search) => {
                 let search_text = format!("Search: {}", self.search_query);
                 let mut spans = vec![Span::raw(search_text)];
 
@@ -787,7 +1331,7 @@ impl App {
         f.render_widget(status, footer_chunks[0]);
 
         // Help text
-        let help = Paragraph::new("Press ? for help")
+        let help = Paragraph::new(tr(Msg::PressForHelp))
             .style(Style::default().fg(Color::DarkGray))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(help, footer_chunks[1]);
@@ -827,6 +1371,23 @@ impl App {
         }
     }
 
+    /// Delete the selected entry, first asking for the password if the
+    /// unlock policy wants it.
+    fn request_delete(&mut self) -> Result<()> {
+        if self.list_state.selected().is_none() {
+            return Ok(());
+        }
+        if self
+            .unlock_policy
+            .requires_password(SensitiveAction::Delete)
+        {
+            self.delete_password = Some(String::new());
+            Ok(())
+        } else {
+            self.delete_selected_item()
+        }
+    }
+
     fn delete_selected_item(&mut self) -> Result<()> {
         let Some(selected) = self.list_state.selected() else {
             return Ok(());
@@ -837,8 +1398,7 @@ impl App {
         let hash = item_with_ts.item.hash();
         self.vault.delete(hash)?;
         self.load_items()?;
-        self.status_message = "Item deleted".into();
+        self.status_message = tr(Msg::ItemDeleted).into();
         Ok(())
     }
 }
-
@@ -1,5 +1,5 @@
 use crate::tui::App;
-use clip_vault_core::Result;
+use clip_vault_core::{tr, Msg, Result};
 use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -24,5 +24,8 @@ pub fn run_tui(app: &mut App) -> Result<()> {
     execute!(terminal.backend_mut(), LeaveAlternateScreen).map_err(clip_vault_core::Error::Io)?;
     terminal.show_cursor().map_err(clip_vault_core::Error::Io)?;
 
+    if app.stopped_by_lock() {
+        eprintln!("{}", tr(Msg::LockedStopping));
+    }
     res
 }
@@ -0,0 +1,149 @@
+//! Vault throughput and latency. Run with `cargo bench -p clip-vault-core`.
+//!
+//! List and search are measured against prefilled vaults of 10k, 100k and
+//! 1M entries. Filling the larger ones takes a while; set
+//! `CLIP_VAULT_BENCH_SIZES=10000` (comma-separated) for a quick run.
+
+use clip_vault_core::{ClipboardItem, SqliteVault, Vault};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tempfile::TempDir;
+
+const DEFAULT_SIZES: &[usize] = &[10_000, 100_000, 1_000_000];
+const PAGE: usize = 50;
+
+fn sizes() -> Vec<usize> {
+    std::env::var("CLIP_VAULT_BENCH_SIZES").map_or_else(
+        |_| DEFAULT_SIZES.to_vec(),
+        |sizes| {
+            sizes
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect()
+        },
+    )
+}
+
+fn open_vault() -> (TempDir, SqliteVault) {
+    let dir = TempDir::new().expect("temp dir");
+    let vault = SqliteVault::open(dir.path().join("bench.db"), "bench").expect("open vault");
+    (dir, vault)
+}
+
+/// A line of text shaped like a typical copy, unique per `i`.
+fn text_item(i: u64) -> ClipboardItem {
+    ClipboardItem::Text(format!(
+        "entry {i}: let value = config.get(\"key_{i}\").unwrap_or_default(); // copied snippet"
+    ))
+}
+
+/// Incompressible bytes behind a PNG signature, the size of a screenshot.
+fn image_item(seed: u64, len: usize) -> ClipboardItem {
+    let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut state = seed | 1;
+    data.extend((0..len).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state.to_le_bytes()[0]
+    }));
+    ClipboardItem::Image(data)
+}
+
+fn insert(vault: &SqliteVault, item: &ClipboardItem) {
+    vault.insert(item.hash(), item).expect("insert");
+}
+
+fn prefilled(entries: usize) -> (TempDir, SqliteVault) {
+    let (dir, vault) = open_vault();
+    for i in 0..entries as u64 {
+        insert(&vault, &text_item(i));
+    }
+    (dir, vault)
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let (_dir, vault) = open_vault();
+    let next = AtomicU64::new(0);
+    let mut group = c.benchmark_group("insert");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("text", |b| {
+        b.iter(|| insert(&vault, &text_item(next.fetch_add(1, Ordering::Relaxed))));
+    });
+    group.bench_function("duplicate_text", |b| {
+        let item = text_item(u64::MAX);
+        b.iter(|| insert(&vault, &item));
+    });
+    group.finish();
+}
+
+fn bench_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("query");
+    group.sample_size(20);
+    for entries in sizes() {
+        let (_dir, vault) = prefilled(entries);
+        group.bench_with_input(BenchmarkId::new("list", entries), &vault, |b, vault| {
+            b.iter(|| black_box(vault.list(Some(PAGE), None).expect("list")));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("search_hit", entries),
+            &vault,
+            |b, vault| {
+                b.iter(|| black_box(vault.search("key_4", Some(PAGE), None).expect("search")));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("search_previews", entries),
+            &vault,
+            |b, vault| {
+                b.iter(|| {
+                    black_box(
+                        vault
+                            .search_previews("key_4", Some(PAGE), None)
+                            .expect("search"),
+                    )
+                });
+            },
+        );
+        // Nothing matches, so the whole history is scanned.
+        group.bench_with_input(
+            BenchmarkId::new("search_miss", entries),
+            &vault,
+            |b, vault| {
+                b.iter(|| {
+                    black_box(
+                        vault
+                            .search("no such text", Some(PAGE), None)
+                            .expect("search"),
+                    )
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_images(c: &mut Criterion) {
+    let mut group = c.benchmark_group("image");
+    group.sample_size(20);
+    for len in [256 * 1024, 4 * 1024 * 1024] {
+        let (_dir, vault) = open_vault();
+        let next = AtomicU64::new(0);
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("insert", len), &len, |b, &len| {
+            b.iter_batched(
+                || image_item(next.fetch_add(1, Ordering::Relaxed), len),
+                |item| insert(&vault, &item),
+                BatchSize::LargeInput,
+            );
+        });
+        group.bench_with_input(BenchmarkId::new("latest", len), &vault, |b, vault| {
+            b.iter(|| black_box(vault.latest().expect("latest")));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert, bench_queries, bench_images);
+criterion_main!(benches);
@@ -0,0 +1,204 @@
+//! Entry descriptions meant to be heard rather than seen, for screen readers
+//! and spoken feedback: words instead of symbols and abbreviations ("5
+//! minutes ago", "800 by 600 pixels"), and nothing read out that shouldn't be
+//! said aloud.
+
+use crate::{
+    contains_secret, format_size, is_otp_like, ClipboardItem, ClipboardItemWithTimestamp, Error,
+    ImageInfo, ListedEntry, Result, Timestamp,
+};
+use serde::Serialize;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Characters of the first line read out before it is cut short.
+const FIRST_LINE_CHARS: usize = 80;
+
+/// What an entry is, in words.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntryDescription {
+    /// "Text", "Image", "Files", "One-time code" or "Possible password or
+    /// API key".
+    pub kind: String,
+    /// e.g. "3 lines, 120 characters", "800 by 600 pixels, 45.2 KB" or "2
+    /// files".
+    pub size: String,
+    /// e.g. "5 minutes ago"; `None` for something just copied.
+    pub age: Option<String>,
+    /// Left out for images, one-time codes and text that looks like a
+    /// password or API key.
+    pub first_line: Option<String>,
+}
+
+impl EntryDescription {
+    /// Describe a stored entry as of `now`.
+    #[must_use]
+    pub fn new(entry: &ClipboardItemWithTimestamp, now: Timestamp) -> Self {
+        let secs = now.duration_since(entry.timestamp).as_secs();
+        Self {
+            age: Some(spoken_age(secs)),
+            ..Self::of_item(&entry.item)
+        }
+    }
+
+    /// Describe a listed entry as of `now`, giving an image listed by its
+    /// thumbnail the stored image's size.
+    #[must_use]
+    pub fn of_listed(listed: &ListedEntry, now: Timestamp) -> Self {
+        let mut description = Self::new(&listed.entry, now);
+        if listed.thumbnail {
+            description.size = listed.image.as_ref().map_or_else(
+                || format_size(usize::try_from(listed.stats.bytes).unwrap_or(usize::MAX)),
+                pixels_and_size,
+            );
+        }
+        description
+    }
+
+    /// Describe an item without saying when it was copied.
+    #[must_use]
+    pub fn of_item(item: &ClipboardItem) -> Self {
+        match item {
+            ClipboardItem::Text(text) => {
+                let lines = text.lines().count().max(1);
+                let size = format!(
+                    "{}, {}",
+                    plural(lines, "line"),
+                    plural(text.chars().count(), "character")
+                );
+                if is_otp_like(text) {
+                    Self::new_text("One-time code", size, None)
+                } else if contains_secret(text) {
+                    Self::new_text("Possible password or API key", size, None)
+                } else {
+                    Self::new_text("Text", size, Some(first_line(text)))
+                }
+            }
+            ClipboardItem::Image(data) => Self {
+                kind: "Image".to_string(),
+                size: image_size(data),
+                age: None,
+                first_line: None,
+            },
+            ClipboardItem::Files(paths) => Self {
+                kind: "Files".to_string(),
+                size: plural(paths.len(), "file"),
+                age: None,
+                first_line: paths
+                    .first()
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().into_owned()),
+            },
+        }
+    }
+
+    fn new_text(kind: &str, size: String, first_line: Option<String>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            size,
+            age: None,
+            first_line,
+        }
+    }
+}
+
+impl fmt::Display for EntryDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.kind, self.size)?;
+        if let Some(age) = &self.age {
+            write!(f, ", copied {age}")?;
+        }
+        if let Some(line) = &self.first_line {
+            write!(f, ": {line}")?;
+        }
+        Ok(())
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("1 {word}")
+    } else {
+        format!("{count} {word}s")
+    }
+}
+
+fn spoken_age(secs: u64) -> String {
+    let count = |n: u64| usize::try_from(n).unwrap_or(usize::MAX);
+    match secs {
+        ..60 => "just now".to_string(),
+        60..3_600 => format!("{} ago", plural(count(secs / 60), "minute")),
+        3_600..86_400 => format!("{} ago", plural(count(secs / 3_600), "hour")),
+        _ => format!("{} ago", plural(count(secs / 86_400), "day")),
+    }
+}
+
+fn first_line(text: &str) -> String {
+    let line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let line = line.trim();
+    if line.chars().count() > FIRST_LINE_CHARS {
+        let cut: String = line.chars().take(FIRST_LINE_CHARS).collect();
+        format!("{}, and more", cut.trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+fn image_size(data: &[u8]) -> String {
+    ImageInfo::from_bytes(data)
+        .map_or_else(|| format_size(data.len()), |info| pixels_and_size(&info))
+}
+
+fn pixels_and_size(info: &ImageInfo) -> String {
+    format!(
+        "{} by {} pixels, {}",
+        info.width,
+        info.height,
+        format_size(usize::try_from(info.bytes).unwrap_or(usize::MAX))
+    )
+}
+
+/// Read `text` aloud with the OS speech synthesizer: `say` on macOS,
+/// `System.Speech` through `PowerShell` on Windows, and `spd-say` (falling
+/// back to `espeak`) elsewhere. Blocks until it has been spoken.
+pub fn speak(text: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("say").arg(text).status()
+    } else if cfg!(windows) {
+        powershell_speak(text)
+    } else {
+        match Command::new("spd-say").args(["--wait", text]).status() {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Command::new("espeak").arg(text).status()
+            }
+            result => result,
+        }
+    }
+    .map_err(|e| Error::Io(std::io::Error::other(format!("text to speech: {e}"))))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Io(std::io::Error::other(format!(
+            "text to speech failed ({status})"
+        ))))
+    }
+}
+
+/// The text goes in on stdin, so it needs no quoting.
+fn powershell_speak(text: &str) -> std::io::Result<std::process::ExitStatus> {
+    let mut child = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    child.wait()
+}
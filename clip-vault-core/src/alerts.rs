@@ -0,0 +1,166 @@
+//! Alerts for captures worth a second look: huge copies, binary data pasted
+//! as text, credentials. They are reported natively by the desktop app and
+//! can be posted to a webhook. Alerts never carry the copied content.
+
+use crate::{contains_secret, format_size, Capture, ClipboardItem, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Share of control characters above which text looks like binary data.
+const BINARY_CONTROL_RATIO: f64 = 0.1;
+
+/// Something unusual about a capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureAlert {
+    /// At least [`AlertConfig::large_bytes`] in size.
+    Large { bytes: usize },
+    /// Text with NUL bytes or many other control characters, as when a
+    /// binary file is copied as text.
+    Binary,
+    /// Text matching the patterns in [`find_secrets`](crate::find_secrets).
+    Secret,
+}
+
+impl fmt::Display for CaptureAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Large { bytes } => write!(f, "Copied a large entry ({})", format_size(*bytes)),
+            Self::Binary => f.write_str("Copied something that looks like binary data"),
+            Self::Secret => f.write_str("Copied what looks like a password or API key"),
+        }
+    }
+}
+
+/// Which captures raise alerts, and where they go besides the app. All
+/// off by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Alert on entries of at least this many bytes.
+    pub large_bytes: Option<usize>,
+    pub binary: bool,
+    pub secrets: bool,
+    /// POST each alert here as JSON.
+    pub webhook: Option<String>,
+}
+
+impl AlertConfig {
+    /// The alerts `item` raises.
+    #[must_use]
+    pub fn check(&self, item: &ClipboardItem) -> Vec<CaptureAlert> {
+        self.check_parts(item.size(), item_text(item).into_iter())
+    }
+
+    /// The alerts `capture` raises. What was copied alongside its item
+    /// counts towards its size, and text kept with an image is checked
+    /// like copied text.
+    #[must_use]
+    pub fn check_capture(&self, capture: &Capture) -> Vec<CaptureAlert> {
+        let bytes = capture.item.size()
+            + capture
+                .representations
+                .iter()
+                .map(|representation| representation.data.len())
+                .sum::<usize>();
+        let texts = item_text(&capture.item).into_iter().chain(
+            capture
+                .representations
+                .iter()
+                .filter_map(crate::Representation::as_text),
+        );
+        self.check_parts(bytes, texts)
+    }
+
+    fn check_parts<'a>(
+        &self,
+        bytes: usize,
+        mut texts: impl Iterator<Item = &'a str> + Clone,
+    ) -> Vec<CaptureAlert> {
+        let mut alerts = Vec::new();
+        if self.large_bytes.is_some_and(|limit| bytes >= limit) {
+            alerts.push(CaptureAlert::Large { bytes });
+        }
+        if self.binary && texts.clone().any(looks_binary) {
+            alerts.push(CaptureAlert::Binary);
+        }
+        if self.secrets && texts.any(contains_secret) {
+            alerts.push(CaptureAlert::Secret);
+        }
+        alerts
+    }
+}
+
+fn item_text(item: &ClipboardItem) -> Option<&str> {
+    match item {
+        ClipboardItem::Text(text) => Some(text),
+        _ => None,
+    }
+}
+
+fn looks_binary(text: &str) -> bool {
+    let mut chars = 0usize;
+    let mut control = 0usize;
+    for c in text.chars() {
+        if c == '\0' {
+            return true;
+        }
+        chars += 1;
+        if c.is_control() && !matches!(c, '\n' | '\r' | '\t') {
+            control += 1;
+        }
+    }
+    // Counts are far below 2^52.
+    #[allow(clippy::cast_precision_loss)]
+    let ratio = control as f64 / chars.max(1) as f64;
+    ratio > BINARY_CONTROL_RATIO
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    /// The vault's id for the entry, as `clip-vault show --id` takes it.
+    /// Unlike its hash, it says nothing about the content.
+    entry_id: i64,
+    alert: &'a CaptureAlert,
+    message: String,
+}
+
+/// POST `alert` about the entry with id `entry_id` to `url` as JSON,
+/// through `curl` as backups are. Only `http(s)://` URLs are sent to.
+pub fn send_webhook(url: &str, entry_id: i64, alert: &CaptureAlert) -> Result<()> {
+    let scheme = url
+        .split_once("://")
+        .map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if !matches!(scheme.as_deref(), Some("http" | "https")) {
+        return Err(Error::Io(std::io::Error::other(format!(
+            "webhook {url} isn't an http(s) URL"
+        ))));
+    }
+    let payload = WebhookPayload {
+        entry_id,
+        alert,
+        message: alert.to_string(),
+    };
+    let body = serde_json::to_vec(&payload).map_err(|e| Error::Io(std::io::Error::other(e)))?;
+    let mut child = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--max-time", "10"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", "--", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| Error::Io(std::io::Error::other(format!("failed to run curl: {e}"))))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&body)?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Io(std::io::Error::other(format!(
+            "webhook {url} failed ({status})"
+        ))))
+    }
+}
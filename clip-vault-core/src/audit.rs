@@ -0,0 +1,167 @@
+//! Optional append-only audit log. Each record stores the hash of the
+//! previous record, so editing or removing a record breaks the chain.
+
+use crate::{Result, Timestamp};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Insert,
+    Update,
+    Delete,
+    Export,
+    /// Moved out of the vault into its archive.
+    Archive,
+}
+
+impl AuditAction {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Insert => "insert",
+            Self::Update => "update",
+            Self::Delete => "delete",
+            Self::Export => "export",
+            Self::Archive => "archive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub timestamp: Timestamp,
+    pub action: String,
+    pub item_hash: Option<[u8; 32]>,
+    pub prev_hash: [u8; 32],
+    pub record_hash: [u8; 32],
+}
+
+/// Outcome of walking the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditVerification {
+    pub records: usize,
+    /// Id of the first record whose hash or back-link doesn't match.
+    pub first_broken: Option<i64>,
+}
+
+impl AuditVerification {
+    #[must_use]
+    pub fn is_intact(&self) -> bool {
+        self.first_broken.is_none()
+    }
+}
+
+pub(crate) const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS audit_log (
+        id           INTEGER PRIMARY KEY,
+        ts           INTEGER NOT NULL,
+        action       TEXT    NOT NULL,
+        item_hash    BLOB,
+        prev_hash    BLOB    NOT NULL,
+        record_hash  BLOB    NOT NULL
+    );
+
+    CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+    BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END;
+
+    CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+    BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END;
+";
+
+fn record_hash(
+    prev_hash: &[u8; 32],
+    timestamp: Timestamp,
+    action: &str,
+    item_hash: Option<&[u8]>,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash);
+    hasher.update(timestamp.as_nanos().to_le_bytes());
+    hasher.update(action.as_bytes());
+    if let Some(hash) = item_hash {
+        hasher.update(hash);
+    }
+    hasher.finalize().into()
+}
+
+pub(crate) fn append(
+    conn: &Connection,
+    timestamp: Timestamp,
+    action: AuditAction,
+    item_hash: Option<&[u8; 32]>,
+) -> Result<()> {
+    let prev_hash = conn
+        .query_row(
+            "SELECT record_hash FROM audit_log ORDER BY id DESC LIMIT 1;",
+            [],
+            |row| row.get::<_, [u8; 32]>(0),
+        )
+        .optional()?
+        .unwrap_or([0; 32]);
+    let item_hash = item_hash.map(|h| &h[..]);
+    let hash = record_hash(&prev_hash, timestamp, action.as_str(), item_hash);
+
+    conn.execute(
+        "INSERT INTO audit_log (ts, action, item_hash, prev_hash, record_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5);",
+        params![
+            timestamp,
+            action.as_str(),
+            item_hash,
+            &prev_hash[..],
+            &hash[..]
+        ],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn records(conn: &Connection) -> Result<Vec<AuditRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, ts, action, item_hash, prev_hash, record_hash
+         FROM audit_log ORDER BY id;",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(AuditRecord {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            action: row.get(2)?,
+            item_hash: row.get(3)?,
+            prev_hash: row.get(4)?,
+            record_hash: row.get(5)?,
+        })
+    })?;
+
+    let mut records = Vec::new();
+    for row in rows {
+        records.push(row?);
+    }
+    Ok(records)
+}
+
+pub(crate) fn verify(records: &[AuditRecord]) -> AuditVerification {
+    let mut expected_prev = [0u8; 32];
+    for record in records {
+        let recomputed = record_hash(
+            &record.prev_hash,
+            record.timestamp,
+            &record.action,
+            record.item_hash.as_ref().map(|h| &h[..]),
+        );
+        if record.prev_hash != expected_prev || record.record_hash != recomputed {
+            return AuditVerification {
+                records: records.len(),
+                first_broken: Some(record.id),
+            };
+        }
+        expected_prev = record.record_hash;
+    }
+
+    AuditVerification {
+        records: records.len(),
+        first_broken: None,
+    }
+}
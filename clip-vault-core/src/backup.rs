@@ -0,0 +1,206 @@
+//! Remote targets for vault snapshots. Snapshots are vault databases,
+//! encrypted with the vault password before they leave the machine, so the
+//! remote only ever sees ciphertext. Transfers go through `curl`, which
+//! handles S3 request signing and plain HTTP PUT/GET for `WebDAV`.
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// File name used when a remote URL names a directory (ends in `/`).
+const DEFAULT_SNAPSHOT_NAME: &str = "clip_vault.db";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub s3: S3Config,
+    pub webdav: WebDavConfig,
+}
+
+/// Credentials fall back to `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    pub region: String,
+    /// S3-compatible endpoint, addressed path-style; AWS is used when unset.
+    pub endpoint: Option<String>,
+    pub access_key_id: Option<String>,
+    pub secret_access_key: Option<String>,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            region: "us-east-1".to_string(),
+            endpoint: None,
+            access_key_id: None,
+            secret_access_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebDavConfig {
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// Where a snapshot is uploaded to or restored from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remote {
+    /// `s3://bucket/key`
+    S3 { bucket: String, key: String },
+    /// `webdav://host/path` (HTTPS) or a plain `https://`/`http://` URL.
+    WebDav { url: String },
+}
+
+impl Remote {
+    pub fn parse(url: &str) -> Result<Self> {
+        let with_name = |path: &str| {
+            if path.is_empty() || path.ends_with('/') {
+                format!("{path}{DEFAULT_SNAPSHOT_NAME}")
+            } else {
+                path.to_string()
+            }
+        };
+
+        if let Some(rest) = url.strip_prefix("s3://") {
+            let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+            if bucket.is_empty() {
+                return Err(invalid_url(url));
+            }
+            Ok(Self::S3 {
+                bucket: bucket.to_string(),
+                key: with_name(key),
+            })
+        } else if let Some(rest) = url.strip_prefix("webdav://") {
+            Ok(Self::WebDav {
+                url: with_name(&format!("https://{rest}")),
+            })
+        } else if url.starts_with("https://") || url.starts_with("http://") {
+            Ok(Self::WebDav {
+                url: with_name(url),
+            })
+        } else {
+            Err(invalid_url(url))
+        }
+    }
+
+    /// The remote next to this one named as `Path::with_extension` would
+    /// name it, for the files that go with a snapshot.
+    #[must_use]
+    pub fn sibling(&self, extension: &str) -> Self {
+        let rename = |path: &str| {
+            let with_extension = |name: &str| {
+                Path::new(name)
+                    .with_extension(extension)
+                    .display()
+                    .to_string()
+            };
+            match path.rsplit_once('/') {
+                Some((dir, name)) => format!("{dir}/{}", with_extension(name)),
+                None => with_extension(path),
+            }
+        };
+        match self {
+            Self::S3 { bucket, key } => Self::S3 {
+                bucket: bucket.clone(),
+                key: rename(key),
+            },
+            Self::WebDav { url } => Self::WebDav { url: rename(url) },
+        }
+    }
+
+    /// Upload the snapshot at `file`.
+    pub fn upload(&self, file: &Path, config: &BackupConfig) -> Result<()> {
+        let mut cmd = self.curl(config);
+        cmd.arg("--upload-file").arg(file);
+        self.run(cmd, config)
+    }
+
+    /// Download the snapshot to `dest`.
+    pub fn download(&self, dest: &Path, config: &BackupConfig) -> Result<()> {
+        let mut cmd = self.curl(config);
+        cmd.arg("--output").arg(dest);
+        self.run(cmd, config)
+    }
+
+    fn http_url(&self, config: &BackupConfig) -> String {
+        match self {
+            Self::S3 { bucket, key } => match &config.s3.endpoint {
+                Some(endpoint) => format!("{}/{bucket}/{key}", endpoint.trim_end_matches('/')),
+                None => format!(
+                    "https://{bucket}.s3.{}.amazonaws.com/{key}",
+                    config.s3.region
+                ),
+            },
+            Self::WebDav { url } => url.clone(),
+        }
+    }
+
+    fn curl(&self, config: &BackupConfig) -> Command {
+        let mut cmd = Command::new("curl");
+        cmd.args(["--fail", "--silent", "--show-error", "--config", "-"]);
+        if let Self::S3 { .. } = self {
+            cmd.arg("--aws-sigv4")
+                .arg(format!("aws:amz:{}:s3", config.s3.region));
+        }
+        cmd.arg(self.http_url(config));
+        cmd
+    }
+
+    /// Credentials go through curl's stdin config rather than its argv, which
+    /// other users on the machine could read.
+    fn credentials(&self, config: &BackupConfig) -> Option<(String, String)> {
+        match self {
+            Self::S3 { .. } => Some((
+                config
+                    .s3
+                    .access_key_id
+                    .clone()
+                    .or_else(|| std::env::var("AWS_ACCESS_KEY_ID").ok())?,
+                config
+                    .s3
+                    .secret_access_key
+                    .clone()
+                    .or_else(|| std::env::var("AWS_SECRET_ACCESS_KEY").ok())?,
+            )),
+            Self::WebDav { .. } => Some((
+                config.webdav.username.clone()?,
+                config.webdav.password.clone().unwrap_or_default(),
+            )),
+        }
+    }
+
+    fn run(&self, mut cmd: Command, config: &BackupConfig) -> Result<()> {
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Io(std::io::Error::other(format!("failed to run curl: {e}"))))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Some((user, password)) = self.credentials(config) {
+                let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+                writeln!(stdin, "user = \"{}:{}\"", escape(&user), escape(&password))?;
+            }
+        }
+        let status = child.wait()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(Error::Io(std::io::Error::other(format!(
+                "transfer to {} failed ({status})",
+                self.http_url(config)
+            ))))
+        }
+    }
+}
+
+fn invalid_url(url: &str) -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("unsupported backup target {url:?} (use s3://bucket/key or a WebDAV URL)"),
+    ))
+}
@@ -0,0 +1,160 @@
+//! Buffering between reading the clipboard and writing to the vault.
+//! Automation tools can change the clipboard dozens of times a second; the
+//! queue lets a capture loop collect those changes without touching the
+//! vault and store them in one transaction, and bounds what a runaway
+//! burst can pile up.
+
+use crate::{
+    CaptureMetadata, ClipboardItem, CustomFormat, DedupePolicy, DedupeRules, Representation,
+    Timestamp,
+};
+use serde::{Deserialize, Serialize};
+
+/// One clipboard change waiting to be stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    pub hash: [u8; 32],
+    pub item: ClipboardItem,
+    pub metadata: CaptureMetadata,
+    /// When the change was seen. Stored
+    /// as the entry's timestamp, so batching doesn't shift capture times.
+    pub captured_at: Timestamp,
+    /// HTML flavour copied alongside text.
+    pub html: Option<String>,
+    /// RTF flavour copied alongside text.
+    pub rtf: Option<String>,
+    /// Full text of an item that was truncated before storing.
+    pub spill: Option<String>,
+    /// Application-specific formats copied alongside the item.
+    pub custom_formats: Vec<CustomFormat>,
+    /// Other standard forms the copy came in, like the text copied with an
+    /// image.
+    pub representations: Vec<Representation>,
+    /// How the item is matched against stored entries, resolved from its
+    /// category.
+    pub dedupe: DedupePolicy,
+}
+
+impl Capture {
+    /// A capture of `item` happening now.
+    #[must_use]
+    pub fn new(item: ClipboardItem, metadata: CaptureMetadata) -> Self {
+        let captured_at = Timestamp::now();
+        Self {
+            hash: item.hash(),
+            dedupe: DedupeRules::default().resolve(&item),
+            item,
+            metadata,
+            captured_at,
+            html: None,
+            rtf: None,
+            spill: None,
+            custom_formats: Vec::new(),
+            representations: Vec::new(),
+        }
+    }
+}
+
+/// A capture a batch couldn't store, while the rest of the batch was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedCapture {
+    pub hash: [u8; 32],
+    pub captured_at: Timestamp,
+    pub error: String,
+}
+
+/// What a full queue gives up to make room.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Keep the newest changes; the burst's earliest ones are lost.
+    #[default]
+    DropOldest,
+    /// Keep what was queued first and ignore changes until it's stored.
+    DropNewest,
+    /// Collapse the queue to the newest change, as if only the end of the
+    /// burst had been copied.
+    KeepLatest,
+}
+
+/// A bounded, order-preserving queue of captures. Queueing an item that is
+/// already waiting moves it to the end instead of storing it twice.
+#[derive(Debug)]
+pub struct CaptureQueue {
+    pending: Vec<Capture>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: usize,
+}
+
+impl CaptureQueue {
+    #[must_use]
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            pending: Vec::with_capacity(capacity),
+            capacity: capacity.max(1),
+            policy,
+            dropped: 0,
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
+    /// Queue `capture`, applying the overflow policy if the queue is full.
+    /// Returns whether it was queued.
+    pub fn push(&mut self, capture: Capture) -> bool {
+        if let Some(i) = self.pending.iter().position(|c| c.hash == capture.hash) {
+            self.pending.remove(i);
+        } else if self.is_full() {
+            match self.policy {
+                OverflowPolicy::DropOldest => {
+                    self.pending.remove(0);
+                    self.dropped += 1;
+                }
+                OverflowPolicy::DropNewest => {
+                    self.dropped += 1;
+                    return false;
+                }
+                OverflowPolicy::KeepLatest => {
+                    self.dropped += self.pending.len();
+                    self.pending.clear();
+                }
+            }
+        }
+        self.pending.push(capture);
+        true
+    }
+
+    /// Waiting captures, oldest first.
+    #[must_use]
+    pub fn pending(&self) -> &[Capture] {
+        &self.pending
+    }
+
+    /// Forget the waiting captures once they're stored.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.pending.len() >= self.capacity
+    }
+
+    /// Captures lost to the overflow policy since the last call.
+    pub fn take_dropped(&mut self) -> usize {
+        std::mem::take(&mut self.dropped)
+    }
+}
@@ -0,0 +1,172 @@
+//! `SQLCipher` settings picked when a vault is created. `SQLCipher` doesn't
+//! record them in the database, so settings other than its defaults are
+//! kept in a small file next to the vault and applied on every open.
+
+use crate::{Error, Result};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Ready-made [`CipherOptions`] for common threat models.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityProfile {
+    /// Fewer key derivation rounds and larger pages: quicker to unlock and
+    /// to read big entries, at the cost of weaker brute-force resistance.
+    Fast,
+    /// `SQLCipher` 4's defaults.
+    #[default]
+    Balanced,
+    /// Four times the key derivation rounds of `Balanced`, so each
+    /// password guess costs an attacker that much more.
+    Paranoid,
+}
+
+impl SecurityProfile {
+    #[must_use]
+    pub fn options(self) -> CipherOptions {
+        match self {
+            Self::Fast => CipherOptions {
+                kdf_iter: 64_000,
+                page_size: 16_384,
+                hmac: HmacAlgorithm::Sha256,
+            },
+            Self::Balanced => CipherOptions::default(),
+            Self::Paranoid => CipherOptions {
+                kdf_iter: 1_024_000,
+                ..CipherOptions::default()
+            },
+        }
+    }
+}
+
+/// The hash behind page authentication and, as PBKDF2-HMAC, key
+/// derivation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HmacAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl HmacAlgorithm {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA1",
+            Self::Sha256 => "SHA256",
+            Self::Sha512 => "SHA512",
+        }
+    }
+}
+
+/// How a vault file is encrypted. Pages are always AES-256-CBC; these are
+/// the parameters `SQLCipher` lets vary around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherOptions {
+    /// PBKDF2 rounds turning the password into the key.
+    pub kdf_iter: u32,
+    /// Bytes per encrypted page: a power of two from 512 to 65536.
+    pub page_size: u32,
+    pub hmac: HmacAlgorithm,
+}
+
+impl Default for CipherOptions {
+    fn default() -> Self {
+        Self {
+            kdf_iter: 256_000,
+            page_size: 4_096,
+            hmac: HmacAlgorithm::Sha512,
+        }
+    }
+}
+
+impl CipherOptions {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.kdf_iter == 0 {
+            return Err(invalid("kdf_iter must be at least 1"));
+        }
+        if !self.page_size.is_power_of_two() || !(512..=65_536).contains(&self.page_size) {
+            return Err(invalid(
+                "page_size must be a power of two from 512 to 65536",
+            ));
+        }
+        Ok(())
+    }
+
+    /// The settings the vault at `vault` was created with.
+    pub(crate) fn for_vault(vault: &Path) -> Result<Self> {
+        match std::fs::read(cipher_path(vault)) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| invalid(&e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The settings saved with the snapshot at `snapshot`, or `None` for
+    /// snapshots taken before they were.
+    pub(crate) fn for_snapshot(snapshot: &Path) -> Result<Option<Self>> {
+        if cipher_path(snapshot).exists() {
+            Self::for_vault(snapshot).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Record these as the settings of the vault at `vault`. Defaults need
+    /// no file.
+    pub(crate) fn save_for(&self, vault: &Path) -> Result<()> {
+        if *self == Self::default() {
+            return match std::fs::remove_file(cipher_path(vault)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        self.save_for_snapshot(vault)
+    }
+
+    /// Record these as the settings of the snapshot at `snapshot`, defaults
+    /// included, so it can be opened away from the vault it was taken of.
+    pub(crate) fn save_for_snapshot(&self, snapshot: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(|e| invalid(&e.to_string()))?;
+        std::fs::write(cipher_path(snapshot), json)?;
+        Ok(())
+    }
+
+    /// Key `conn` with `key` under these settings. Must come before
+    /// anything else touches the database.
+    pub(crate) fn apply(&self, conn: &Connection, key: &str) -> Result<()> {
+        conn.pragma_update(None, "key", key)?;
+        self.apply_to_schema(conn, None)
+    }
+
+    /// Use these settings for the new database attached as `schema`, which
+    /// otherwise gets the main database's key but `SQLCipher`'s defaults
+    /// for everything else. Must come before anything is written to it.
+    pub(crate) fn apply_to_schema(&self, conn: &Connection, schema: Option<&str>) -> Result<()> {
+        if *self == Self::default() {
+            return Ok(());
+        }
+        let hmac = self.hmac.name();
+        conn.pragma_update(schema, "kdf_iter", self.kdf_iter)?;
+        conn.pragma_update(schema, "cipher_page_size", self.page_size)?;
+        conn.pragma_update(schema, "cipher_hmac_algorithm", format!("HMAC_{hmac}"))?;
+        conn.pragma_update(
+            schema,
+            "cipher_kdf_algorithm",
+            format!("PBKDF2_HMAC_{hmac}"),
+        )?;
+        Ok(())
+    }
+}
+
+/// Where the settings of the vault at `vault` are kept, when they aren't
+/// `SQLCipher`'s defaults.
+#[must_use]
+pub fn cipher_path(vault: &Path) -> PathBuf {
+    vault.with_extension("cipher")
+}
+
+fn invalid(message: &str) -> Error {
+    Error::Io(std::io::Error::other(format!("cipher options: {message}")))
+}
@@ -0,0 +1,156 @@
+//! Content categories used by retention, capture rules and the `kind:`
+//! search filter. Text is classified when it's stored.
+
+use crate::{detect_language, normalize_url, ClipboardItem, Rgba, FILES_MIME, OLD_FILES_MIME};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long captured one-time codes are kept unless configured otherwise.
+pub const DEFAULT_OTP_TTL: Duration = Duration::from_mins(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Text,
+    Image,
+    /// One-time passcodes copied from authenticators or SMS.
+    Otp,
+    /// Files copied in a file manager.
+    Files,
+    /// A single `http(s)` link.
+    Url,
+    Email,
+    /// A CSS color such as `#1e90ff`, `rgb(30 144 255)` or
+    /// `hsl(210, 100%, 56%)` (see [`Rgba`]).
+    Color,
+    /// A JSON object or array.
+    Json,
+    /// A file system path typed or copied as text.
+    Path,
+    /// A snippet of source code or shell commands.
+    Code,
+}
+
+impl ItemKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Image => "image",
+            Self::Otp => "otp",
+            Self::Files => "files",
+            Self::Url => "url",
+            Self::Email => "email",
+            Self::Color => "color",
+            Self::Json => "json",
+            Self::Path => "path",
+            Self::Code => "code",
+        }
+    }
+
+    /// The kind named `name`, as written by [`as_str`](Self::as_str).
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            Self::Text,
+            Self::Image,
+            Self::Otp,
+            Self::Files,
+            Self::Url,
+            Self::Email,
+            Self::Color,
+            Self::Json,
+            Self::Path,
+            Self::Code,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// Categorise an entry.
+    #[must_use]
+    pub fn of(item: &ClipboardItem) -> Self {
+        match item {
+            ClipboardItem::Text(text) => Self::of_text(text),
+            ClipboardItem::Image(_) => Self::Image,
+            ClipboardItem::Files(_) => Self::Files,
+        }
+    }
+
+    /// Categorise a stored row from its MIME type and text column.
+    #[must_use]
+    pub fn from_row(mime: &str, text: Option<&str>) -> Self {
+        if mime.starts_with("image/") {
+            Self::Image
+        } else if mime == FILES_MIME || mime == OLD_FILES_MIME {
+            Self::Files
+        } else {
+            text.map_or(Self::Text, Self::of_text)
+        }
+    }
+
+    fn of_text(text: &str) -> Self {
+        if is_otp_like(text) {
+            Self::Otp
+        } else if normalize_url(text).is_some() {
+            Self::Url
+        } else if is_email(text) {
+            Self::Email
+        } else if Rgba::parse(text).is_some() {
+            Self::Color
+        } else if is_path(text) {
+            Self::Path
+        } else {
+            match detect_language(text) {
+                Some("json") => Self::Json,
+                Some(_) => Self::Code,
+                None => Self::Text,
+            }
+        }
+    }
+}
+
+/// A 6–8 digit code, optionally split in two halves by a space or dash
+/// (`123456`, `123 456`, `1234-5678`).
+#[must_use]
+pub fn is_otp_like(text: &str) -> bool {
+    let text = text.trim();
+    let digits: String = match text.split_once([' ', '-']) {
+        Some((a, b)) if a.len() == b.len() => format!("{a}{b}"),
+        Some(_) => return false,
+        None => text.to_string(),
+    };
+    (6..=8).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// A single address like `name@example.com`.
+fn is_email(text: &str) -> bool {
+    let text = text.trim();
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !text
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '<' | '>' | ',' | ';'))
+        && !domain.contains('@')
+}
+
+/// One line starting like an absolute, home-relative or relative path, or
+/// a Windows drive: `/etc/hosts`, `~/notes.md`, `./src`, `C:\Users`.
+fn is_path(text: &str) -> bool {
+    let text = text.trim();
+    if text.len() < 2 || text.contains('\n') {
+        return false;
+    }
+    let bytes = text.as_bytes();
+    let windows_drive = bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == b":\\";
+    windows_drive
+        || (["/", "~/", "./", "../"]
+            .iter()
+            .any(|prefix| text.starts_with(prefix))
+            && !text.starts_with("//"))
+}
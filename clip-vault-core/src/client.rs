@@ -0,0 +1,139 @@
+//! A small, stable API for embedding vault access in other Rust tools
+//! (window managers, launchers, daemons) instead of shelling out to the CLI.
+//!
+//! Everything in this module follows semver: nothing here changes or goes
+//! away without a major version bump. [`Entry`] only grows new fields, and
+//! [`ClipboardItem`], [`VaultEvent`] and [`Error`] only grow new variants,
+//! so match them with a `_` arm. Methods added to the
+//! [`Vault`](crate::Vault) trait come with a default, so implementations
+//! outside the crate keep compiling. The rest of the crate moves with the
+//! CLI and app and may change in any release; reach it through
+//! [`Client::vault`] at your own risk.
+//!
+//! ```no_run
+//! use clip_vault_core::client::Client;
+//!
+//! let client = Client::open("vault password")?;
+//! client.insert_text("hello")?;
+//! for entry in client.search("hel", 10)? {
+//!     println!("{}", entry.preview());
+//! }
+//! # Ok::<(), clip_vault_core::Error>(())
+//! ```
+
+use crate::{ClipboardItemWithTimestamp, SqliteVault, Timestamp, Vault};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
+pub use crate::{ClipboardItem, Error, Result, VaultEvent};
+
+/// One stored clipboard entry, newest-first in query results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Entry {
+    /// Identifies the entry in [`VaultEvent`]s.
+    pub hash: [u8; 32],
+    pub item: ClipboardItem,
+    /// Capture time.
+    pub timestamp: Timestamp,
+}
+
+impl Entry {
+    /// The text or file paths, or a short description for images.
+    #[must_use]
+    pub fn preview(&self) -> String {
+        match &self.item {
+            ClipboardItem::Text(text) => text.clone(),
+            ClipboardItem::Image(data) => format!("[image, {}]", crate::format_size(data.len())),
+            ClipboardItem::Files(paths) => crate::files_text(paths),
+        }
+    }
+}
+
+impl From<ClipboardItemWithTimestamp> for Entry {
+    fn from(entry: ClipboardItemWithTimestamp) -> Self {
+        Self {
+            hash: entry.item.hash(),
+            item: entry.item,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+/// An unlocked vault.
+pub struct Client {
+    vault: SqliteVault,
+}
+
+impl Client {
+    /// Unlock the vault the CLI and app use (see
+    /// [`default_db_path`](crate::default_db_path)). A wrong password fails
+    /// with [`Error::InvalidKey`].
+    pub fn open(password: &str) -> Result<Self> {
+        Self::open_at(crate::default_db_path(), password)
+    }
+
+    /// Unlock, or create, the vault at `path`.
+    pub fn open_at<P: AsRef<Path>>(path: P, password: &str) -> Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        match SqliteVault::open(path, password) {
+            Err(Error::Sqlite(e))
+                if e.sqlite_error_code() == Some(rusqlite::ErrorCode::NotADatabase) =>
+            {
+                Err(Error::InvalidKey)
+            }
+            result => result.map(|vault| Self { vault }),
+        }
+    }
+
+    /// Store `item`, or bump it to the top if it is already stored.
+    pub fn insert(&self, item: &ClipboardItem) -> Result<()> {
+        self.vault.insert(item.hash(), item)
+    }
+
+    pub fn insert_text(&self, text: &str) -> Result<()> {
+        self.insert(&ClipboardItem::Text(text.to_string()))
+    }
+
+    pub fn latest(&self) -> Result<Option<Entry>> {
+        Ok(self.recent(1)?.into_iter().next())
+    }
+
+    /// The `limit` newest entries.
+    pub fn recent(&self, limit: usize) -> Result<Vec<Entry>> {
+        Ok(entries(self.vault.list(Some(limit), None)?))
+    }
+
+    /// Text entries containing `query`, newest first. The query accepts the
+    /// same filters as the search window.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Entry>> {
+        Ok(entries(self.vault.search(query, Some(limit), None)?))
+    }
+
+    /// Receive an event for every change. Changes made by other processes
+    /// (the app's monitor, the CLI) only arrive after
+    /// [`poll_changes`](Self::poll_changes) notices them.
+    #[must_use]
+    pub fn subscribe(&self) -> Receiver<VaultEvent> {
+        self.vault.subscribe()
+    }
+
+    /// Check for writes from other processes, notifying subscribers.
+    /// Returns whether there were any. Cheap enough to call often.
+    pub fn poll_changes(&self) -> Result<bool> {
+        self.vault.poll_changes()
+    }
+
+    /// The underlying vault, for everything this module doesn't cover. Not
+    /// covered by the stability promise.
+    #[must_use]
+    pub fn vault(&self) -> &SqliteVault {
+        &self.vault
+    }
+}
+
+fn entries(items: Vec<ClipboardItemWithTimestamp>) -> Vec<Entry> {
+    items.into_iter().map(Entry::from).collect()
+}
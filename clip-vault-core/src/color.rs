@@ -0,0 +1,228 @@
+//! Colors copied as CSS: `#1e90ff`, `rgb(30 144 255)`, `hsl(210, 100%, 56%)`.
+//! They're stored as text of the color kind; [`Rgba`] reads the value back
+//! out so lists can show a swatch and copying can switch notation.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 255 is opaque.
+    pub a: u8,
+}
+
+/// Length of the longest `rgb()`/`hsl()` spelling worth parsing, e.g.
+/// `hsla(359.999deg, 100.000%, 100.000%, 0.999)` with room to spare.
+const MAX_FUNCTION_LEN: usize = 64;
+
+impl Rgba {
+    /// The color `text` spells, in hex (`#rgb`, `#rgba`, `#rrggbb`,
+    /// `#rrggbbaa`) or as `rgb()`/`rgba()` or `hsl()`/`hsla()`, with commas
+    /// or spaces between the values. `None` for anything else.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+        // Check the shape first, so long text that can't be a color isn't
+        // copied just to find that out.
+        if text.len() > MAX_FUNCTION_LEN {
+            return None;
+        }
+        let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+        let args = args.to_ascii_lowercase();
+        let (values, alpha) = split_args(&args)?;
+        let alpha = alpha.map_or(Some(255), parse_alpha)?;
+        let [first, second, third] = values.as_slice() else {
+            return None;
+        };
+        let [r, g, b] = match name.to_ascii_lowercase().as_str() {
+            "rgb" | "rgba" => [
+                parse_channel(first)?,
+                parse_channel(second)?,
+                parse_channel(third)?,
+            ],
+            "hsl" | "hsla" => hsl_to_rgb(
+                parse_hue(first)?,
+                parse_percent(second)?,
+                parse_percent(third)?,
+            ),
+            _ => return None,
+        };
+        Some(Self { r, g, b, a: alpha })
+    }
+
+    #[must_use]
+    pub fn is_opaque(self) -> bool {
+        self.a == 255
+    }
+
+    /// `#1e90ff`, with the alpha as a fourth byte unless opaque.
+    #[must_use]
+    pub fn to_hex(self) -> String {
+        let Self { r, g, b, a } = self;
+        if self.is_opaque() {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    /// `rgb(30, 144, 255)`, or `rgba(…)` when not opaque.
+    #[must_use]
+    pub fn to_rgb(self) -> String {
+        let Self { r, g, b, .. } = self;
+        if self.is_opaque() {
+            format!("rgb({r}, {g}, {b})")
+        } else {
+            format!("rgba({r}, {g}, {b}, {})", self.alpha())
+        }
+    }
+
+    /// `hsl(210, 100%, 56%)`, or `hsla(…)` when not opaque. Rounded to
+    /// whole degrees and percents, so converting back may be off by one.
+    #[must_use]
+    pub fn to_hsl(self) -> String {
+        let (h, s, l) = rgb_to_hsl(self.r, self.g, self.b);
+        if self.is_opaque() {
+            format!("hsl({h:.0}, {s:.0}%, {l:.0}%)")
+        } else {
+            format!("hsla({h:.0}, {s:.0}%, {l:.0}%, {})", self.alpha())
+        }
+    }
+
+    /// The alpha from 0 to 1, to two decimals without trailing zeros.
+    fn alpha(self) -> String {
+        let alpha = format!("{:.2}", f64::from(self.a) / 255.0);
+        alpha
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// [`Rgba::to_hex`].
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok();
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 | 4 => Some(Rgba {
+            r: digit(0)? * 17,
+            g: digit(1)? * 17,
+            b: digit(2)? * 17,
+            a: if hex.len() == 4 { digit(3)? * 17 } else { 255 },
+        }),
+        6 | 8 => Some(Rgba {
+            r: byte(0)?,
+            g: byte(2)?,
+            b: byte(4)?,
+            a: if hex.len() == 8 { byte(6)? } else { 255 },
+        }),
+        _ => None,
+    }
+}
+
+/// The three values and the alpha of `1, 2, 3, 0.5` or `1 2 3 / 50%`.
+fn split_args(args: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    let mut values: Vec<&str> = if args.contains(',') {
+        args.split(',').map(str::trim).collect()
+    } else {
+        let (values, alpha) = match args.split_once('/') {
+            Some((values, alpha)) => (values, Some(alpha.trim())),
+            None => (args, None),
+        };
+        values.split_whitespace().chain(alpha).collect()
+    };
+    let alpha = if values.len() == 4 {
+        values.pop()
+    } else {
+        None
+    };
+    (values.len() == 3).then_some((values, alpha))
+}
+
+/// A number, or a percentage when it ends in `%`, scaled so `max` (or
+/// 100%) is 1. `None` outside 0..=1.
+fn parse_fraction(value: &str, max: f64) -> Option<f64> {
+    let fraction = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+        None => value.parse::<f64>().ok()? / max,
+    };
+    (0.0..=1.0).contains(&fraction).then_some(fraction)
+}
+
+fn parse_channel(value: &str) -> Option<u8> {
+    parse_fraction(value, 255.0).map(to_byte)
+}
+
+fn parse_alpha(value: &str) -> Option<u8> {
+    parse_fraction(value, 1.0).map(to_byte)
+}
+
+fn parse_percent(value: &str) -> Option<f64> {
+    parse_fraction(value, 100.0)
+}
+
+/// Degrees, wrapped into 0..360.
+fn parse_hue(value: &str) -> Option<f64> {
+    let degrees: f64 = value.strip_suffix("deg").unwrap_or(value).parse().ok()?;
+    degrees.is_finite().then(|| degrees.rem_euclid(360.0))
+}
+
+/// `fraction` (0..=1) of 255, rounded.
+fn to_byte(fraction: f64) -> u8 {
+    // In range by construction.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let byte = (fraction.clamp(0.0, 1.0) * 255.0).round() as u8;
+    byte
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> [u8; 3] {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let rgb = match sector {
+        s if s < 1.0 => [chroma, second, 0.0],
+        s if s < 2.0 => [second, chroma, 0.0],
+        s if s < 3.0 => [0.0, chroma, second],
+        s if s < 4.0 => [0.0, second, chroma],
+        s if s < 5.0 => [second, 0.0, chroma],
+        _ => [chroma, 0.0, second],
+    };
+    let offset = lightness - chroma / 2.0;
+    rgb.map(|channel| to_byte(channel + offset))
+}
+
+/// Hue in degrees, saturation and lightness in percent.
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let [red, green, blue] = [r, g, b].map(|c| f64::from(c) / 255.0);
+    let (high, low) = (f64::from(max) / 255.0, f64::from(min) / 255.0);
+    let lightness = f64::midpoint(high, low);
+    if max == min {
+        return (0.0, 0.0, lightness * 100.0);
+    }
+    let delta = high - low;
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((green - blue) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+    (hue.round() % 360.0, saturation * 100.0, lightness * 100.0)
+}
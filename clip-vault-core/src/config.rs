@@ -0,0 +1,214 @@
+//! Settings shared by the CLI and the desktop app, kept in `config.json` in
+//! the clip-vault config directory.
+
+use crate::{
+    BackupConfig, DedupeRules, Error, Language, PathsConfig, PreviewSettings, Result,
+    RetentionPolicy, TextNormalization, UnlockPolicy, VaultProfile,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Vault size past which the CLI and the app start warning, unless
+/// configured otherwise.
+pub const DEFAULT_STORAGE_WARNING_MB: u64 = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub backup: BackupConfig,
+    /// How the CLI and the app unlock the vault.
+    pub unlock: UnlockPolicy,
+    /// Warn once the vault file is larger than this; `None` never warns.
+    pub storage_warning_mb: Option<u64>,
+    /// Named queries offered as quick filters, e.g. in the app's tray menu.
+    pub saved_searches: Vec<SavedSearch>,
+    /// Language for messages from the CLI and the app; `None` follows the
+    /// system locale.
+    pub language: Option<Language>,
+    /// Where the vault, caches, logs and spill files go (see
+    /// [`data_dir`](crate::data_dir) and its siblings).
+    pub paths: PathsConfig,
+    /// Move entries older than this many days to the archive (see
+    /// [`SqliteVault::archive_older_than`](crate::SqliteVault::archive_older_than));
+    /// `None` keeps everything in the vault.
+    pub archive_after_days: Option<u64>,
+    /// Ages and caps past which `clip-vault prune`, `clip-vault
+    /// --ephemeral` and the desktop app's monitor delete entries. Captured
+    /// one-time codes also expire after the one-time code age, which is
+    /// [`DEFAULT_OTP_TTL`](crate::DEFAULT_OTP_TTL) unless configured.
+    pub retention: RetentionPolicy,
+    /// How much of each text entry the TUI and the app show in result
+    /// lists.
+    pub previews: PreviewSettings,
+    /// Which captures count as copies of stored entries, by content
+    /// category.
+    pub dedupe: DedupeRules,
+    /// How captured text is tidied before it's hashed, by the app and the
+    /// CLI alike.
+    pub text_normalization: TextNormalization,
+    /// Vaults besides the default one that `clip-vault search
+    /// --all-profiles` and the app's "All profiles" search include.
+    pub profiles: Vec<VaultProfile>,
+    /// Delete captures from these apps this many seconds after they were
+    /// copied, keyed by part of the app's name, e.g. `"1Password": 60`.
+    /// Only applies where the source app can be looked up, so not on
+    /// Wayland or headless hosts.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub app_ttl_secs: BTreeMap<String, u64>,
+    /// Desktop app settings and shortcuts, carried along by settings
+    /// exports. The CLI passes them through untouched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<serde_json::Value>,
+}
+
+/// A search kept under a name, like "Work" for `tag:work`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    /// In the search window's syntax (see [`SearchQuery`](crate::SearchQuery)).
+    pub query: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            backup: BackupConfig::default(),
+            unlock: UnlockPolicy::default(),
+            storage_warning_mb: Some(DEFAULT_STORAGE_WARNING_MB),
+            saved_searches: Vec::new(),
+            language: None,
+            paths: PathsConfig::default(),
+            archive_after_days: None,
+            retention: RetentionPolicy {
+                otp_max_age_secs: Some(crate::DEFAULT_OTP_TTL.as_secs()),
+                ..RetentionPolicy::default()
+            },
+            previews: PreviewSettings::default(),
+            dedupe: DedupeRules::default(),
+            text_normalization: TextNormalization::default(),
+            profiles: Vec::new(),
+            app_ttl_secs: BTreeMap::new(),
+            app: None,
+        }
+    }
+}
+
+impl Config {
+    /// [`app_ttl_secs`](Self::app_ttl_secs) as capture options take them
+    /// (see [`CaptureMetadata::expire_from_app`](crate::CaptureMetadata::expire_from_app)).
+    #[must_use]
+    pub fn app_ttls(&self) -> BTreeMap<String, Duration> {
+        self.app_ttl_secs
+            .iter()
+            .map(|(app, secs)| (app.clone(), Duration::from_secs(*secs)))
+            .collect()
+    }
+
+    /// Read the config file. A missing file yields the defaults.
+    pub fn load() -> Result<Self> {
+        match Self::load_from(&config_path()) {
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&config_path())
+    }
+
+    /// Read settings exported with [`Config::save_to`].
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut config: Self = serde_json::from_str(&text).map_err(invalid)?;
+        config.migrate();
+        Ok(config)
+    }
+
+    /// Bring settings written by older versions up to date: the app's own
+    /// `max_db_size_mb` became [`RetentionPolicy::max_size_mb`] and its
+    /// `text_normalization` moved here. Values already set here take
+    /// precedence.
+    fn migrate(&mut self) {
+        let Some(app) = self.app.as_mut().and_then(serde_json::Value::as_object_mut) else {
+            return;
+        };
+        if let Some(mb) = app.remove("max_db_size_mb") {
+            if self.retention.max_size_mb.is_none() {
+                self.retention.max_size_mb = mb.as_u64();
+            }
+        }
+        if let Some(normalization) = app.remove("text_normalization") {
+            if self.text_normalization == TextNormalization::default() {
+                self.text_normalization = serde_json::from_value(normalization).unwrap_or_default();
+            }
+        }
+    }
+
+    /// Write the settings to `path`, e.g. to carry them to another machine.
+    /// Backup credentials are included, so the file is only readable by the
+    /// current user.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(invalid)?;
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        let mut file = {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            let file = options.open(path)?;
+            // An existing file keeps its mode otherwise.
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            file
+        };
+        #[cfg(not(unix))]
+        let mut file = options.open(path)?;
+        file.write_all(text.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// The shared config for loops that consult it on every tick, read again
+/// only when the file changes. A file that can't be read counts as the
+/// defaults, as with `Config::load().unwrap_or_default()`.
+#[derive(Debug, Default)]
+pub struct CachedConfig {
+    config: Config,
+    loaded: bool,
+    /// The file's modification time and length when `config` was read;
+    /// `None` if there was no file.
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl CachedConfig {
+    /// The config as of the file's last change.
+    pub fn get(&mut self) -> &Config {
+        let stamp = std::fs::metadata(config_path())
+            .and_then(|meta| Ok((meta.modified()?, meta.len())))
+            .ok();
+        if !self.loaded || self.stamp != stamp {
+            self.config = Config::load().unwrap_or_default();
+            self.loaded = true;
+            self.stamp = stamp;
+        }
+        &self.config
+    }
+}
+
+#[must_use]
+pub fn config_path() -> PathBuf {
+    if let Ok(path) = std::env::var("CLIP_VAULT_CONFIG") {
+        PathBuf::from(path)
+    } else {
+        crate::config_dir().join("config.json")
+    }
+}
+
+fn invalid(e: serde_json::Error) -> Error {
+    Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
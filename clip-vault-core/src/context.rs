@@ -0,0 +1,101 @@
+//! The "current context" a capture is tagged with: usually the git repo or
+//! working directory a shell hook last reported via `clip-vault context set`.
+
+use crate::{is_otp_like, ClipboardItem, Result, SourceApp};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Extra facts recorded alongside a captured item.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureMetadata {
+    pub context: Option<String>,
+    /// Delete the entry this long after capture (see
+    /// [`Vault::purge_expired`](crate::Vault::purge_expired)), unless it's
+    /// pinned by then.
+    pub ttl: Option<Duration>,
+    /// The application the item was copied from.
+    pub source: Option<SourceApp>,
+}
+
+impl CaptureMetadata {
+    /// Metadata for a capture happening right now.
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            context: current_context(),
+            ttl: None,
+            source: None,
+        }
+    }
+
+    /// Expire `item` after `ttl` if it looks like a one-time code.
+    #[must_use]
+    pub fn expire_otp(mut self, item: &ClipboardItem, ttl: Option<Duration>) -> Self {
+        if let ClipboardItem::Text(text) = item {
+            if is_otp_like(text) {
+                self.ttl = ttl.or(self.ttl);
+            }
+        }
+        self
+    }
+
+    /// Expire the capture after the TTL of the app it was copied from, e.g.
+    /// a password manager. `ttls` is keyed by part of the app's name,
+    /// matched ignoring case; the shortest matching TTL wins. Does nothing
+    /// without a [`source`](Self::source), which is the case wherever
+    /// [`SourceApp::current`] can't tell the frontmost app, e.g. on Wayland.
+    #[must_use]
+    pub fn expire_from_app(mut self, ttls: &BTreeMap<String, Duration>) -> Self {
+        let Some(source) = &self.source else {
+            return self;
+        };
+        let name = source.name.to_lowercase();
+        let app_ttl = ttls
+            .iter()
+            .filter(|(app, _)| name.contains(&app.to_lowercase()))
+            .map(|(_, ttl)| *ttl)
+            .min();
+        self.ttl = match (self.ttl, app_ttl) {
+            (Some(ttl), Some(app_ttl)) => Some(ttl.min(app_ttl)),
+            (ttl, app_ttl) => ttl.or(app_ttl),
+        };
+        self
+    }
+}
+
+#[must_use]
+pub fn context_path() -> PathBuf {
+    if let Ok(path) = std::env::var("CLIP_VAULT_CONTEXT_PATH") {
+        PathBuf::from(path)
+    } else {
+        crate::cache_dir().join("context")
+    }
+}
+
+/// The last context reported by the shell hook, if any.
+#[must_use]
+pub fn current_context() -> Option<String> {
+    let text = std::fs::read_to_string(context_path()).ok()?;
+    let context = text.trim();
+    (!context.is_empty()).then(|| context.to_string())
+}
+
+/// Record (or clear, with `None`) the current context.
+pub fn set_current_context(context: Option<&str>) -> Result<()> {
+    let path = context_path();
+    match context {
+        Some(context) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, context)?;
+        }
+        None => {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+    }
+    Ok(())
+}
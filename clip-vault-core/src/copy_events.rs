@@ -0,0 +1,59 @@
+//! Each time an entry was put on the clipboard, for
+//! [`Vault::copy_events`](crate::Vault::copy_events): its first capture,
+//! later copies of the same content, and copies back out of the vault. A
+//! credential's events show when it was last used.
+
+use crate::{tr, Msg, Timestamp};
+use serde::Serialize;
+use std::fmt;
+
+/// Events kept per entry; older ones are dropped.
+pub const MAX_COPY_EVENTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyEventKind {
+    /// Copied for the first time, creating the entry.
+    Captured,
+    /// Copied again from somewhere else while already in the history.
+    Recopied,
+    /// Copied from the vault, e.g. picked in the TUI or the app.
+    CopiedOut,
+}
+
+#[cfg(feature = "sqlcipher")]
+impl CopyEventKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Captured => "captured",
+            Self::Recopied => "recopied",
+            Self::CopiedOut => "copied_out",
+        }
+    }
+
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "captured" => Some(Self::Captured),
+            "recopied" => Some(Self::Recopied),
+            "copied_out" => Some(Self::CopiedOut),
+            _ => None,
+        }
+    }
+}
+
+/// In the current [`language`](crate::language).
+impl fmt::Display for CopyEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(tr(match self {
+            Self::Captured => Msg::CopyCaptured,
+            Self::Recopied => Msg::CopyRecopied,
+            Self::CopiedOut => Msg::CopyCopiedOut,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CopyEvent {
+    pub timestamp: Timestamp,
+    pub kind: CopyEventKind,
+}
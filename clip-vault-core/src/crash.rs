@@ -0,0 +1,83 @@
+//! Crash handling for long-running processes. A panic may leave a thread
+//! holding the vault mid-operation, so the hook lets the caller drop key
+//! material and end the session before anything else runs, then leaves a
+//! report behind for bug reports. Panic messages can quote whatever was
+//! being handled, clipboard contents included, so the report only says
+//! where the panic happened.
+
+use crate::{log_dir, Result};
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Install a panic hook for `component` (e.g. `app`). `on_panic` runs first
+/// and should clear secrets; the notes it returns go into the crash report.
+/// The previous hook still runs afterwards, so the panic is printed as usual.
+pub fn install_panic_hook<F>(component: &'static str, on_panic: F)
+where
+    F: Fn() -> Vec<String> + Send + Sync + 'static,
+{
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let notes = on_panic();
+        let report = crash_report(component, info, &notes);
+        match write_crash_report(&log_dir(), component, &report) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {e}"),
+        }
+        previous(info);
+    }));
+}
+
+fn crash_report(component: &str, info: &PanicHookInfo<'_>, notes: &[String]) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.chars().count())
+        .or_else(|| {
+            info.payload()
+                .downcast_ref::<String>()
+                .map(|message| message.chars().count())
+        })
+        .map_or_else(
+            || "(no message)".to_string(),
+            |chars| format!("(redacted, {chars} characters)"),
+        );
+    let location = info
+        .location()
+        .map_or_else(|| "unknown".to_string(), ToString::to_string);
+
+    let mut report = format!(
+        "clip-vault {component} {} crashed\nthread: {}\nmessage: {message}\nlocation: {location}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::thread::current().name().unwrap_or("unnamed"),
+    );
+    for note in notes {
+        let _ = writeln!(report, "note: {note}");
+    }
+    let _ = write!(report, "\nbacktrace:\n{}", Backtrace::force_capture());
+    report
+}
+
+/// Save `report` as a new file in `dir`, readable only by the current user,
+/// returning its path.
+pub fn write_crash_report(dir: &Path, component: &str, report: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let path = dir.join(format!("crash-{component}-{stamp}.log"));
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&path)?.write_all(report.as_bytes())?;
+    Ok(path)
+}
@@ -0,0 +1,18 @@
+//! Formats an application registers for its own copies, like a
+//! spreadsheet's cell ranges or an image editor's layers. clip-vault can't
+//! interpret them, so they are kept as opaque bytes next to the entry and
+//! put back with it, letting a paste into the same application keep what
+//! plain text or pixels lose.
+
+use serde::{Deserialize, Serialize};
+
+/// Custom formats larger than this are left out of a capture.
+pub const MAX_CUSTOM_FORMAT_BYTES: usize = 8 * 1024 * 1024;
+
+/// One application-specific clipboard format and its contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomFormat {
+    /// The name the application registered it under, e.g. `Biff12`.
+    pub name: String,
+    pub data: Vec<u8>,
+}
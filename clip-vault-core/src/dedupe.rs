@@ -0,0 +1,153 @@
+//! What counts as a copy of something already in the history, by content
+//! category.
+
+use crate::{is_otp_like, ClipboardItem};
+use serde::{Deserialize, Serialize};
+
+/// How a capture is matched against what's already stored, chosen per
+/// content category by [`DedupeRules`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupePolicy {
+    /// Only identical content is a duplicate.
+    #[default]
+    Exact,
+    /// Links are duplicates when they differ only in their fragment,
+    /// tracking parameters or the case of the scheme and host (see
+    /// [`normalize_url`]).
+    NormalizedUrl,
+    /// Every copy is stored as an entry of its own, whatever the
+    /// [`DuplicatePolicy`](crate::DuplicatePolicy); earlier copies stay
+    /// where they were.
+    Never,
+}
+
+impl DedupePolicy {
+    /// The key duplicates of `item` share beyond its hash, if any.
+    #[must_use]
+    pub fn key(self, item: &ClipboardItem) -> Option<String> {
+        match (self, item) {
+            (Self::NormalizedUrl, ClipboardItem::Text(text)) => normalize_url(text),
+            _ => None,
+        }
+    }
+}
+
+/// The [`DedupePolicy`] for each content category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupeRules {
+    /// Text that is a single `http(s)` link.
+    pub urls: DedupePolicy,
+    /// One-time passcodes (see [`is_otp_like`]).
+    pub otp: DedupePolicy,
+    /// Everything else, code snippets included.
+    pub other: DedupePolicy,
+}
+
+impl Default for DedupeRules {
+    fn default() -> Self {
+        Self {
+            urls: DedupePolicy::NormalizedUrl,
+            otp: DedupePolicy::Never,
+            other: DedupePolicy::Exact,
+        }
+    }
+}
+
+impl DedupeRules {
+    /// The policy for `item`'s category.
+    #[must_use]
+    pub fn resolve(&self, item: &ClipboardItem) -> DedupePolicy {
+        match item {
+            ClipboardItem::Text(text) if is_otp_like(text) => self.otp,
+            ClipboardItem::Text(text) if normalize_url(text).is_some() => self.urls,
+            _ => self.other,
+        }
+    }
+}
+
+/// How captured text is tidied before it's hashed, so the "same" text
+/// copied from editors that end lines differently is one entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextNormalization {
+    /// Text is stored as copied.
+    #[default]
+    Off,
+    /// `\r\n` and lone `\r` line endings become `\n`, and whitespace at
+    /// the end of each line and of the text is dropped.
+    Whitespace,
+}
+
+impl TextNormalization {
+    #[must_use]
+    pub fn apply(self, text: String) -> String {
+        match self {
+            Self::Off => text,
+            Self::Whitespace => text
+                .replace("\r\n", "\n")
+                .replace('\r', "\n")
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim_end()
+                .to_string(),
+        }
+    }
+}
+
+/// Query parameters that only track where a link was shared from.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "igshid", "yclid", "ref_src",
+];
+
+/// `text` as a canonical link: the scheme and host lowercased, the
+/// fragment and `utm_*`-style tracking parameters dropped. `None` unless
+/// `text` is a single `http(s)` URL.
+#[must_use]
+pub fn normalize_url(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.chars().any(char::is_whitespace) {
+        return None;
+    }
+    let (scheme, rest) = text.split_once("://")?;
+    let scheme = scheme.to_ascii_lowercase();
+    if scheme != "http" && scheme != "https" {
+        return None;
+    }
+    let rest = rest.split_once('#').map_or(rest, |(before, _)| before);
+    let (rest, query) = match rest.split_once('?') {
+        Some((before, query)) => (before, Some(query)),
+        None => (rest, None),
+    };
+    let (host, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    if host.is_empty() {
+        return None;
+    }
+
+    let mut url = format!("{scheme}://{}{path}", host.to_ascii_lowercase());
+    let kept: Vec<&str> = query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .filter(|param| {
+            let name = param
+                .split('=')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            !param.is_empty()
+                && !name.starts_with("utm_")
+                && !TRACKING_PARAMS.contains(&name.as_str())
+        })
+        .collect();
+    if !kept.is_empty() {
+        url.push('?');
+        url.push_str(&kept.join("&"));
+    }
+    Some(url)
+}
@@ -0,0 +1,234 @@
+//! A digest of a week of copying, as Markdown or HTML: where copied links
+//! point, when the clipboard is busiest, the biggest entries and how many
+//! copies looked like passwords, stored or skipped. Built from
+//! [`Vault::for_each_captured_between`], so protected entries are left out.
+
+use crate::{
+    contains_secret, format_size, local_day_range, url_domains, ClipboardItem, EntryDescription,
+    Result, Vault,
+};
+use chrono::{NaiveDate, Timelike};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+/// Days a weekly digest covers, ending with the day it's made for.
+pub const DIGEST_DAYS: u64 = 7;
+/// Rows in each of the digest's lists.
+const TOP_N: usize = 5;
+
+/// What was copied over a run of days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeeklyDigest {
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    pub captures: usize,
+    /// Hosts of copied links with the number of entries linking to them,
+    /// most first.
+    pub top_domains: Vec<(String, usize)>,
+    /// Hours of the day (local time, 0–23) with their captures, most first.
+    pub busiest_hours: Vec<(u32, usize)>,
+    /// Biggest entries first.
+    pub largest: Vec<DigestEntry>,
+    /// Text entries that look like a password or API key.
+    pub secrets: usize,
+    /// Copies that looked like one and were skipped instead of stored.
+    pub skipped_secrets: usize,
+}
+
+/// One of a [`WeeklyDigest`]'s largest entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestEntry {
+    pub bytes: usize,
+    /// As [`EntryDescription::of_item`] gives it, so what looks like a
+    /// secret isn't shown.
+    pub description: String,
+}
+
+impl WeeklyDigest {
+    /// The [`DIGEST_DAYS`] days up to and including `last_day`.
+    pub fn week(vault: &(impl Vault + ?Sized), last_day: NaiveDate) -> Result<Self> {
+        let first_day = last_day - chrono::Days::new(DIGEST_DAYS - 1);
+        let range = local_day_range(first_day).start..local_day_range(last_day).end;
+        let mut digest = Self {
+            first_day,
+            last_day,
+            captures: 0,
+            top_domains: Vec::new(),
+            busiest_hours: Vec::new(),
+            largest: Vec::new(),
+            secrets: 0,
+            skipped_secrets: vault.skipped_secrets_between(range.clone())?,
+        };
+        let mut domains: HashMap<String, usize> = HashMap::new();
+        let mut hours: HashMap<u32, usize> = HashMap::new();
+        // Entries are read one at a time and only the largest kept, so a
+        // busy week isn't held in memory.
+        let mut largest: Vec<DigestEntry> = Vec::new();
+        vault.for_each_captured_between(range, &mut |entry| {
+            digest.captures += 1;
+            *hours.entry(entry.timestamp.to_local().hour()).or_default() += 1;
+            let bytes = match &entry.item {
+                ClipboardItem::Text(text) => {
+                    for domain in url_domains(text) {
+                        *domains.entry(domain).or_default() += 1;
+                    }
+                    if contains_secret(text) {
+                        digest.secrets += 1;
+                    }
+                    text.len()
+                }
+                item => item.size(),
+            };
+            // Of entries the same size, the earliest stays.
+            if largest.len() < TOP_N || largest.last().is_some_and(|last| bytes > last.bytes) {
+                largest.push(DigestEntry {
+                    bytes,
+                    description: EntryDescription::of_item(&entry.item).to_string(),
+                });
+                largest.sort_by_key(|entry| std::cmp::Reverse(entry.bytes));
+                largest.truncate(TOP_N);
+            }
+            Ok(())
+        })?;
+
+        digest.top_domains = top(domains);
+        digest.busiest_hours = top(hours);
+        digest.largest = largest;
+        Ok(digest)
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "Clipboard digest, {} to {}",
+            self.first_day.format("%Y-%m-%d"),
+            self.last_day.format("%Y-%m-%d")
+        )
+    }
+
+    fn summary(&self) -> String {
+        let copies = plural(self.captures, "copy", "copies");
+        let mut summary = if self.secrets == 0 {
+            format!("{copies}, none of them looking like a password or API key.")
+        } else {
+            format!(
+                "{copies}, {} looking like a password or API key. Copy those with \
+                 \"clip-vault copy --no-store\" or from a password manager to keep \
+                 them out of the history.",
+                self.secrets
+            )
+        };
+        if self.skipped_secrets > 0 {
+            let _ = write!(
+                summary,
+                " {} looking like one {} skipped without being stored.",
+                plural(self.skipped_secrets, "copy", "copies"),
+                if self.skipped_secrets == 1 {
+                    "was"
+                } else {
+                    "were"
+                }
+            );
+        }
+        summary
+    }
+
+    /// Each section's heading, its lines, and what to say when it's empty.
+    fn sections(&self) -> [(&'static str, Vec<String>, &'static str); 3] {
+        [
+            (
+                "Top domains",
+                self.top_domains
+                    .iter()
+                    .map(|(domain, count)| {
+                        format!("{domain}: {}", plural(*count, "copy", "copies"))
+                    })
+                    .collect(),
+                "No links copied.",
+            ),
+            (
+                "Busiest hours",
+                self.busiest_hours
+                    .iter()
+                    .map(|(hour, count)| {
+                        format!(
+                            "{hour:02}:00–{:02}:00: {}",
+                            (hour + 1) % 24,
+                            plural(*count, "copy", "copies")
+                        )
+                    })
+                    .collect(),
+                "Nothing copied.",
+            ),
+            (
+                "Largest entries",
+                self.largest
+                    .iter()
+                    .map(|entry| format!("{}: {}", format_size(entry.bytes), entry.description))
+                    .collect(),
+                "Nothing copied.",
+            ),
+        ]
+    }
+
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{}\n", self.title(), self.summary());
+        for (heading, lines, empty) in self.sections() {
+            let _ = write!(out, "\n## {heading}\n\n");
+            if lines.is_empty() {
+                let _ = writeln!(out, "{empty}");
+            }
+            for line in lines {
+                let _ = writeln!(out, "- {line}");
+            }
+        }
+        out
+    }
+
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+             <body>\n<h1>{title}</h1>\n<p>{}</p>\n",
+            escape_html(&self.summary())
+        );
+        for (heading, lines, empty) in self.sections() {
+            let _ = writeln!(out, "<h2>{heading}</h2>");
+            if lines.is_empty() {
+                let _ = writeln!(out, "<p>{empty}</p>");
+                continue;
+            }
+            out.push_str("<ul>\n");
+            for line in lines {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(&line));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+/// The [`TOP_N`] keys with the highest counts, ties broken by key.
+fn top<K: Ord>(counts: HashMap<K, usize>) -> Vec<(K, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().filter(|(_, n)| *n > 0).collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP_N);
+    counts
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    if count == 1 {
+        format!("1 {one}")
+    } else {
+        format!("{count} {many}")
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,67 @@
+//! What a destructive operation would do, for `--dry-run` and the app's
+//! previews. Each preview picks its entries with the same code as the
+//! operation itself, so it can't drift from what actually gets deleted.
+
+use crate::{ClipboardItem, EntryDescription, Timestamp};
+
+/// One entry an operation would remove.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffectedEntry {
+    pub hash: [u8; 32],
+    /// When it was captured.
+    pub timestamp: Timestamp,
+    /// Text or image bytes.
+    pub bytes: usize,
+    /// As [`EntryDescription::of_item`] gives it, so what looks like a
+    /// secret isn't shown.
+    pub description: String,
+}
+
+impl AffectedEntry {
+    #[must_use]
+    pub fn new(hash: [u8; 32], timestamp: Timestamp, item: &ClipboardItem) -> Self {
+        let bytes = item.size();
+        Self {
+            hash,
+            timestamp,
+            bytes,
+            description: EntryDescription::of_item(item).to_string(),
+        }
+    }
+}
+
+/// The entries an operation would remove, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DryRun {
+    pub entries: Vec<AffectedEntry>,
+}
+
+impl DryRun {
+    #[must_use]
+    pub fn new(mut entries: Vec<AffectedEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.timestamp);
+        Self { entries }
+    }
+
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn bytes(&self) -> usize {
+        self.entries.iter().map(|entry| entry.bytes).sum()
+    }
+
+    /// Capture time of the oldest affected entry.
+    #[must_use]
+    pub fn oldest(&self) -> Option<Timestamp> {
+        self.entries.first().map(|entry| entry.timestamp)
+    }
+
+    /// Capture time of the newest affected entry.
+    #[must_use]
+    pub fn newest(&self) -> Option<Timestamp> {
+        self.entries.last().map(|entry| entry.timestamp)
+    }
+}
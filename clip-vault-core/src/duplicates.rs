@@ -0,0 +1,46 @@
+//! What re-copying something already in the history does.
+
+use serde::{Deserialize, Serialize};
+
+/// How an insert treats an item that is already stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Move it to the top with the new copy's time, context and source
+    /// app, as if copied for the first time. An expiry already set on it
+    /// is kept unless the new copy's runs out later.
+    #[default]
+    Bump,
+    /// Leave it where it was first copied, for a strictly chronological
+    /// history.
+    KeepPosition,
+    /// Leave it where it was first copied but remember when it was last
+    /// copied (see [`SqliteVault::last_seen`](crate::SqliteVault::last_seen)).
+    RecordLastSeen,
+}
+
+impl DuplicatePolicy {
+    /// The upsert clause for an insert binding the timestamp, context and
+    /// expiry as `?5`, `?6` and `?7`, and the source app as `?9` and `?10`.
+    pub(crate) fn on_conflict(self) -> &'static str {
+        match self {
+            Self::Bump => "DO UPDATE SET ts = ?5, context = ?6, expires_at = COALESCE(MAX(expires_at, ?7), expires_at, ?7), source_app = ?9, source_window = ?10",
+            Self::KeepPosition => "DO NOTHING",
+            Self::RecordLastSeen => "DO UPDATE SET last_seen = ?5",
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Bump => "bump",
+            Self::KeepPosition => "keep_position",
+            Self::RecordLastSeen => "record_last_seen",
+        }
+    }
+
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        [Self::Bump, Self::KeepPosition, Self::RecordLastSeen]
+            .into_iter()
+            .find(|p| p.as_str() == s)
+    }
+}
@@ -2,11 +2,40 @@ use std::fmt;
 use std::io;
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     Io(std::io::Error),
     BincodeEncode(bincode::error::EncodeError),
     BincodeDecode(bincode::error::DecodeError),
+    #[cfg(feature = "sqlcipher")]
     Sqlite(rusqlite::Error),
+    /// A password re-entered to reach protected entries didn't match.
+    InvalidKey,
+    /// A change was asked of a vault opened with
+    /// [`SqliteVault::open_read_only`](crate::SqliteVault::open_read_only).
+    ReadOnly,
+    /// The vault was written by a newer release, with a schema this one
+    /// doesn't know.
+    IncompatibleSchema {
+        found: u32,
+        supported: u32,
+    },
+}
+
+impl Error {
+    /// Whether this failed because the disk (or the user's quota) is full.
+    #[must_use]
+    pub fn is_disk_full(&self) -> bool {
+        match self {
+            Error::Io(e) => matches!(
+                e.kind(),
+                io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded
+            ),
+            #[cfg(feature = "sqlcipher")]
+            Error::Sqlite(e) => e.sqlite_error_code() == Some(rusqlite::ErrorCode::DiskFull),
+            _ => false,
+        }
+    }
 }
 
 impl std::error::Error for Error {
@@ -15,7 +44,9 @@ impl std::error::Error for Error {
             Error::Io(e) => Some(e),
             Error::BincodeEncode(e) => Some(e),
             Error::BincodeDecode(e) => Some(e),
+            #[cfg(feature = "sqlcipher")]
             Error::Sqlite(e) => Some(e),
+            Error::InvalidKey | Error::ReadOnly | Error::IncompatibleSchema { .. } => None,
         }
     }
 }
@@ -26,7 +57,15 @@ impl fmt::Display for Error {
             Error::Io(e) => write!(f, "IO error: {e}"),
             Error::BincodeEncode(e) => write!(f, "bincode encode error: {e}"),
             Error::BincodeDecode(e) => write!(f, "bincode decode error: {e}"),
+            #[cfg(feature = "sqlcipher")]
             Error::Sqlite(e) => write!(f, "sqlite error: {e}"),
+            Error::InvalidKey => write!(f, "invalid password"),
+            Error::ReadOnly => write!(f, "the vault is open read-only"),
+            Error::IncompatibleSchema { found, supported } => write!(
+                f,
+                "the vault has schema version {found}, but this release only supports up to \
+                 {supported}; update clip-vault to open it"
+            ),
         }
     }
 }
@@ -49,6 +88,7 @@ impl From<bincode::error::DecodeError> for Error {
     }
 }
 
+#[cfg(feature = "sqlcipher")]
 impl From<rusqlite::Error> for Error {
     fn from(e: rusqlite::Error) -> Self {
         Self::Sqlite(e)
@@ -0,0 +1,107 @@
+//! Change notifications for [`Vault::subscribe`](crate::Vault::subscribe),
+//! and vault health for [`Vault::subscribe_health`](crate::Vault::subscribe_health).
+
+use serde::Serialize;
+use std::fmt;
+
+/// A change to the vault's entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VaultEvent {
+    Added {
+        hash: [u8; 32],
+    },
+    Updated {
+        old_hash: [u8; 32],
+        hash: [u8; 32],
+    },
+    Deleted {
+        hash: [u8; 32],
+    },
+    /// Another connection (e.g. a different process) wrote to the vault;
+    /// the specific entries aren't known, so reload.
+    Changed,
+}
+
+/// A problem with the vault itself, or housekeeping worth telling the user
+/// about, for [`Vault::subscribe_health`](crate::Vault::subscribe_health).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HealthEvent {
+    /// Storing new entries failed; copies aren't being saved until a
+    /// [`WriteRecovered`](HealthEvent::WriteRecovered) follows. Sent once
+    /// per run of failures, not for every retry.
+    WriteFailed { error: String, disk_full: bool },
+    /// Entries are being stored again.
+    WriteRecovered,
+    /// Entries that couldn't be decrypted or decoded were left out of
+    /// results. `count` only covers ones not reported before.
+    UnreadableEntries { count: usize },
+    /// Entries were removed from the vault by housekeeping rather than by
+    /// the user.
+    Pruned { removed: usize, reason: PruneReason },
+}
+
+/// Why [`HealthEvent::Pruned`] entries were deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PruneReason {
+    /// The retention policy.
+    Retention,
+    /// Their own expiry, e.g. one-time codes.
+    Expired,
+    /// The vault size cap.
+    SizeCap,
+    /// Their age: they were moved to the archive, not deleted.
+    Archived,
+}
+
+/// How much a [`HealthEvent`] matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl HealthEvent {
+    #[must_use]
+    pub fn level(&self) -> HealthLevel {
+        match self {
+            HealthEvent::WriteFailed { .. } => HealthLevel::Error,
+            HealthEvent::UnreadableEntries { .. } => HealthLevel::Warning,
+            HealthEvent::WriteRecovered | HealthEvent::Pruned { .. } => HealthLevel::Info,
+        }
+    }
+}
+
+impl fmt::Display for HealthEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthEvent::WriteFailed {
+                disk_full: true, ..
+            } => write!(f, "The disk is full; new copies aren't being saved"),
+            HealthEvent::WriteFailed { error, .. } => {
+                write!(f, "New copies aren't being saved: {error}")
+            }
+            HealthEvent::WriteRecovered => write!(f, "New copies are being saved again"),
+            HealthEvent::UnreadableEntries { count } => write!(
+                f,
+                "{count} {} couldn't be decrypted and {} hidden; `clip-vault quarantine` moves them aside",
+                if *count == 1 { "entry" } else { "entries" },
+                if *count == 1 { "is" } else { "are" },
+            ),
+            HealthEvent::Pruned { removed, reason } => match reason {
+                PruneReason::Retention => {
+                    write!(f, "Removed {removed} entries by the retention policy")
+                }
+                PruneReason::Expired => write!(f, "Removed {removed} entries as they expired"),
+                PruneReason::SizeCap => {
+                    write!(f, "Removed {removed} entries to stay under the size cap")
+                }
+                PruneReason::Archived => write!(f, "Moved {removed} old entries to the archive"),
+            },
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Exporting entries as JSON Lines, CSV or Markdown, with filters for
+//! sharing a dump without leaking credentials.
+// Only `SqliteVault` writes exports so far.
+#![cfg_attr(not(feature = "sqlcipher"), allow(dead_code))]
+
+use crate::secrets::{contains_secret, redact_secrets};
+use crate::{hash_to_hex, ClipboardItem, ClipboardItemWithTimestamp, Error, Result, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// How [`Vault::export`](crate::Vault::export) writes entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// One JSON object per line, with the timestamp in nanoseconds.
+    #[default]
+    JsonLines,
+    /// A header row, then one row per entry, with RFC 3339 timestamps and
+    /// tags separated by `;`.
+    Csv,
+    /// A document with a section per entry: its time, type, tags and
+    /// context, then the content in a code block or as an image.
+    Markdown,
+}
+
+/// What [`Vault::export`](crate::Vault::export) leaves out or rewrites.
+/// Protected entries are never exported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportFilter {
+    /// Leave out one-time codes and entries containing detected secrets.
+    pub exclude_sensitive: bool,
+    /// One-time codes are left out unless this is set.
+    pub include_otp: bool,
+    /// Only export entries carrying this tag.
+    pub only_tag: Option<String>,
+    /// Replace detected secrets with `[REDACTED]`.
+    pub redact_secrets: bool,
+    /// Write images to PNG files in this directory and export their paths
+    /// instead of the base64-encoded image.
+    pub image_dir: Option<PathBuf>,
+    /// The directory the export is written to. Image paths are written
+    /// relative to it, so the export and its images can be moved together;
+    /// without it they're written as they are under
+    /// [`image_dir`](Self::image_dir).
+    pub output_dir: Option<PathBuf>,
+}
+
+impl ExportFilter {
+    pub(crate) fn skips_otp(&self) -> bool {
+        self.exclude_sensitive || !self.include_otp
+    }
+}
+
+#[derive(Serialize)]
+struct ExportRecord<'a> {
+    timestamp: Timestamp,
+    content_type: String,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
+
+/// Start an export: the CSV header row or the Markdown title.
+pub(crate) fn write_header(writer: &mut dyn Write, format: ExportFormat) -> Result<()> {
+    match format {
+        ExportFormat::JsonLines => {}
+        ExportFormat::Csv => writeln!(writer, "timestamp,content_type,content,context,tags")?,
+        ExportFormat::Markdown => writeln!(writer, "# Clipboard history")?,
+    }
+    Ok(())
+}
+
+/// Write one entry in `format` unless the filter drops it. Images are
+/// written base64-encoded, or saved to the filter's image directory.
+/// Returns whether the entry was written.
+pub(crate) fn write_entry(
+    writer: &mut dyn Write,
+    format: ExportFormat,
+    entry: &ClipboardItemWithTimestamp,
+    tags: Vec<String>,
+    filter: &ExportFilter,
+) -> Result<bool> {
+    if let ClipboardItem::Text(text) = &entry.item {
+        if filter.exclude_sensitive && contains_secret(text) {
+            return Ok(false);
+        }
+    }
+
+    let (mut content, content_type) = match (&entry.item, &filter.image_dir) {
+        (ClipboardItem::Image(data), Some(dir)) => {
+            let mut path = save_image(dir, entry.item.hash(), data)?;
+            if let Some(output_dir) = &filter.output_dir {
+                path = relative_path(&path, output_dir)?;
+            }
+            (path.to_string_lossy().into_owned(), "image/png".to_string())
+        }
+        _ => entry.item.clone().into_parts(),
+    };
+    if filter.redact_secrets && matches!(entry.item, ClipboardItem::Text(_)) {
+        content = redact_secrets(&content);
+    }
+    let record = ExportRecord {
+        timestamp: entry.timestamp,
+        content_type,
+        content,
+        context: entry.context.as_deref(),
+        tags,
+    };
+    match format {
+        ExportFormat::JsonLines => {
+            serde_json::to_writer(&mut *writer, &record)
+                .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Csv => write_csv_row(writer, &record)?,
+        ExportFormat::Markdown => {
+            let image = matches!(entry.item, ClipboardItem::Image(_));
+            let inline = image && filter.image_dir.is_none();
+            write_markdown_section(writer, &record, image, inline)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Save a PNG named after its hash in `dir`, returning its path.
+fn save_image(dir: &Path, hash: [u8; 32], data: &[u8]) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.png", &hash_to_hex(&hash)[..16]));
+    std::fs::write(&path, data)?;
+    Ok(path)
+}
+
+/// `path` as reached from `base`, both resolved first.
+fn relative_path(path: &Path, base: &Path) -> Result<PathBuf> {
+    let path = path.canonicalize()?;
+    let base = base.canonicalize()?;
+    let common = path
+        .components()
+        .zip(base.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative = PathBuf::new();
+    for _ in base.components().skip(common) {
+        relative.push("..");
+    }
+    relative.extend(path.components().skip(common));
+    Ok(relative)
+}
+
+fn write_csv_row(writer: &mut dyn Write, record: &ExportRecord) -> Result<()> {
+    let fields = [
+        record.timestamp.to_string(),
+        record.content_type.clone(),
+        record.content.clone(),
+        record.context.unwrap_or_default().to_string(),
+        record.tags.join(";"),
+    ];
+    let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    writeln!(writer, "{}", row.join(","))?;
+    Ok(())
+}
+
+/// `field` quoted if it holds a separator, quote or line break. One that
+/// starts like a formula gets a leading `'`, so a spreadsheet opening the
+/// export shows it rather than running it.
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{field}")
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// A section headed by the entry's time. `image` content is a path, or
+/// base64 when `inline`.
+fn write_markdown_section(
+    writer: &mut dyn Write,
+    record: &ExportRecord,
+    image: bool,
+    inline: bool,
+) -> Result<()> {
+    writeln!(writer, "\n## {}\n", record.timestamp)?;
+    let mut details = vec![format!("`{}`", record.content_type)];
+    if !record.tags.is_empty() {
+        details.push(format!("tags: {}", record.tags.join(", ")));
+    }
+    if let Some(context) = record.context {
+        details.push(format!("context: {context}"));
+    }
+    writeln!(writer, "{}\n", details.join(" · "))?;
+
+    if inline {
+        writeln!(writer, "![image](data:image/png;base64,{})", record.content)?;
+    } else if image {
+        writeln!(writer, "![image](<{}>)", record.content)?;
+    } else {
+        // Longer than any run of backticks in the content, so it can't
+        // close the block early.
+        let longest = record
+            .content
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+        writeln!(writer, "{fence}\n{}\n{fence}", record.content)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,24 @@
+//! Frecency ranking: entries that were copied or picked often and recently
+//! rank first, in the spirit of shell directory jumpers.
+
+const HOUR: u64 = 3_600 * 1_000_000_000;
+
+/// Score an entry from how often it was used and when it was last seen
+/// (captured or picked, whichever is newer).
+#[must_use]
+pub fn frecency_score(use_count: u64, last_seen: u64, now: u64) -> f64 {
+    let age = now.saturating_sub(last_seen);
+    let weight = if age < HOUR {
+        4.0
+    } else if age < 24 * HOUR {
+        2.0
+    } else if age < 7 * 24 * HOUR {
+        1.0
+    } else {
+        0.25
+    };
+    // Counts beyond 2^32 are not a concern for clipboard history.
+    #[allow(clippy::cast_precision_loss)]
+    let uses = (use_count + 1) as f64;
+    uses * weight
+}
@@ -0,0 +1,34 @@
+//! Line-level search inside entries. Entry search says which copies mention
+//! something; for long logs and configs it also matters where.
+
+use crate::{Error, Result};
+use regex::{Regex, RegexBuilder};
+use std::io;
+
+/// A compiled pattern for [`LinePattern::matching_lines`].
+#[derive(Debug, Clone)]
+pub struct LinePattern(Regex);
+
+impl LinePattern {
+    /// `pattern` is a regular expression, or a plain string with `fixed`.
+    pub fn new(pattern: &str, fixed: bool, ignore_case: bool) -> Result<Self> {
+        let pattern = if fixed {
+            regex::escape(pattern)
+        } else {
+            pattern.to_string()
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map(Self)
+            .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidInput, e)))
+    }
+
+    /// The lines of `text` that match, with their 1-based line numbers.
+    pub fn matching_lines<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (usize, &'a str)> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| self.0.is_match(line))
+            .map(|(i, line)| (i + 1, line))
+    }
+}
@@ -0,0 +1,658 @@
+//! Translations of the messages the Rust side shows people: the TUI status
+//! line, the app's tray menu and notifications, and the CLI's everyday
+//! messages. Longer CLI output (reports, `doctor`, errors) isn't covered
+//! yet. Messages are looked up by [`Msg`] in tables compiled into the
+//! binary; one missing from a table falls back to English.
+//!
+//! The language comes from [`Config::language`](crate::Config::language),
+//! or the system locale when that isn't set.
+
+use crate::{system_locale, Config};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+}
+
+impl Language {
+    pub const ALL: [Self; 4] = [Self::En, Self::De, Self::Es, Self::Fr];
+
+    /// ISO 639-1 code, as stored in the config.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+            Self::Es => "es",
+            Self::Fr => "fr",
+        }
+    }
+
+    /// The language a locale name like `de_AT.UTF-8` or `fr-CA` is in, if
+    /// there is a catalog for it.
+    #[must_use]
+    pub fn from_locale(name: &str) -> Option<Self> {
+        let code = name
+            .split(['_', '-', '.', '@'])
+            .next()?
+            .to_ascii_lowercase();
+        Self::ALL.into_iter().find(|l| l.code() == code)
+    }
+
+    fn from_index(index: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(index)).copied()
+    }
+}
+
+impl fmt::Display for Language {
+    /// The language's own name for itself.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::En => "English",
+            Self::De => "Deutsch",
+            Self::Es => "Español",
+            Self::Fr => "Français",
+        })
+    }
+}
+
+/// Index into [`Language::ALL`] of the language in use, or [`UNRESOLVED`].
+static CURRENT: AtomicU8 = AtomicU8::new(UNRESOLVED);
+const UNRESOLVED: u8 = u8::MAX;
+
+/// The language messages are shown in. Worked out from the config and the
+/// system locale on first use.
+#[must_use]
+pub fn language() -> Language {
+    if let Some(language) = Language::from_index(CURRENT.load(Ordering::Relaxed)) {
+        return language;
+    }
+    let configured = Config::load().ok().and_then(|config| config.language);
+    set_language(configured)
+}
+
+/// Switch languages, e.g. after the setting changed. `None` follows the
+/// system locale. Returns the language now in use.
+pub fn set_language(language: Option<Language>) -> Language {
+    let language = language
+        .or_else(|| Language::from_locale(&system_locale()))
+        .unwrap_or_default();
+    let index = Language::ALL.iter().position(|l| *l == language);
+    CURRENT.store(
+        index
+            .and_then(|i| u8::try_from(i).ok())
+            .unwrap_or(UNRESOLVED),
+        Ordering::Relaxed,
+    );
+    language
+}
+
+/// A translatable message. Placeholders in braces are filled in by
+/// [`tr_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    NoEntries,
+    /// `{reference}`
+    NoMatchingEntry,
+    InvalidPassword,
+    NoProtectedEntries,
+    /// `{index}`
+    CopiedProtected,
+    /// `{index}`
+    ProtectedMovedBack,
+    /// `{path}`
+    Wrote,
+    Welcome,
+    CopiedToClipboard,
+    CopiedAsMarkdown,
+    NoHtmlVersion,
+    CannotCopyImages,
+    CannotEditImages,
+    CannotEditFiles,
+    DeleteCancelled,
+    WrongPasswordNothingDeleted,
+    ItemDeleted,
+    EditorAborted,
+    NoChanges,
+    SavedChanges,
+    ShowingAll,
+    /// `{count}`
+    Refreshed,
+    SearchMode,
+    /// `{count}`, `{query}`
+    Found,
+    /// `{count}`, `{query}`
+    FoundWhileSearching,
+    SentToTmux,
+    /// `{count}`
+    SkippedUndecodable,
+    NoteUnchanged,
+    NoteSaved,
+    NoteRemoved,
+    /// `{format}`
+    CopiedAs,
+    /// `{format}`, `{error}`
+    CannotCopyAs,
+    Pinned,
+    Unpinned,
+    MovedToProtected,
+    CannotSendImagesToTmux,
+    PreviewMode,
+    /// `{count}`
+    RelatedEntries,
+    /// `{count}`
+    CopyHistory,
+    CopyCaptured,
+    CopyRecopied,
+    CopyCopiedOut,
+    TuiHelp,
+    PressForHelp,
+    TrayRecording,
+    TrayIncognito,
+    TrayPaused,
+    TrayLocked,
+    TrayError,
+    MenuSearch,
+    MenuQuickFilters,
+    MenuNoSavedSearches,
+    MenuStartDaemon,
+    MenuStopDaemon,
+    MenuIncognito,
+    MenuLockNow,
+    MenuSettings,
+    MenuQuit,
+    StorageWarningHint,
+    VaultLocked,
+    LockedStopping,
+}
+
+/// `msg` in the current [`language`].
+#[must_use]
+pub fn tr(msg: Msg) -> &'static str {
+    translate(language(), msg)
+}
+
+/// `msg` in the current [`language`], with each `{name}` replaced by its
+/// value from `args`.
+#[must_use]
+pub fn tr_with(msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> String {
+    args.iter()
+        .fold(tr(msg).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+/// `msg` in `language`, or in English if it hasn't been translated.
+#[must_use]
+pub fn translate(language: Language, msg: Msg) -> &'static str {
+    let table = match language {
+        Language::En => return english(msg),
+        Language::De => GERMAN,
+        Language::Es => SPANISH,
+        Language::Fr => FRENCH,
+    };
+    table
+        .iter()
+        .find(|(m, _)| *m == msg)
+        .map_or_else(|| english(msg), |(_, text)| text)
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::NoEntries => "No clipboard entries found.",
+        Msg::NoMatchingEntry => "No single entry matches {reference}.",
+        Msg::InvalidPassword => "Invalid password.",
+        Msg::NoProtectedEntries => "No protected entries.",
+        Msg::CopiedProtected => "Copied protected entry #{index}.",
+        Msg::ProtectedMovedBack => "Protected entry #{index} moved back into the history.",
+        Msg::Wrote => "Wrote {path}",
+        Msg::Welcome => "Welcome to Clip Vault! Press ? for help",
+        Msg::CopiedToClipboard => "Copied to clipboard!",
+        Msg::CopiedAsMarkdown => "Copied as Markdown!",
+        Msg::NoHtmlVersion => "No HTML version of this entry",
+        Msg::CannotCopyImages => "Cannot copy images in CLI mode",
+        Msg::CannotEditImages => "Cannot edit images in CLI mode",
+        Msg::CannotEditFiles => "Copied files can't be edited or reshaped",
+        Msg::DeleteCancelled => "Delete cancelled",
+        Msg::WrongPasswordNothingDeleted => "Wrong password - nothing deleted",
+        Msg::ItemDeleted => "Item deleted",
+        Msg::EditorAborted => "Editor aborted",
+        Msg::NoChanges => "No changes made",
+        Msg::SavedChanges => "Saved changes to vault",
+        Msg::ShowingAll => "Showing all items",
+        Msg::Refreshed => "Refreshed - {count} items loaded",
+        Msg::SearchMode => "Search mode - type to search, Enter to exit, Esc to cancel",
+        Msg::Found => "Found {count} items matching '{query}'",
+        Msg::FoundWhileSearching => {
+            "Found {count} items matching '{query}' - Enter to exit, Esc to cancel"
+        }
+        Msg::SentToTmux => "Sent to tmux buffer",
+        Msg::SkippedUndecodable => {
+            "Skipped {count} undecodable entries - run `clip-vault quarantine` to move them aside"
+        }
+        Msg::NoteUnchanged => "Note unchanged",
+        Msg::NoteSaved => "Note saved",
+        Msg::NoteRemoved => "Note removed",
+        Msg::CopiedAs => "Copied as {format}!",
+        Msg::CannotCopyAs => "Cannot copy as {format}: {error}",
+        Msg::Pinned => "Pinned",
+        Msg::Unpinned => "Unpinned",
+        Msg::MovedToProtected => "Moved to protected area (see `clip-vault protected list`)",
+        Msg::CannotSendImagesToTmux => "Cannot send images to tmux",
+        Msg::PreviewMode => "Preview mode - press Esc to return, 'c' to copy, 'n' to add a note",
+        Msg::RelatedEntries => "{count} related entries - Esc to go back",
+        Msg::CopyHistory => "Copy history ({count})",
+        Msg::CopyCaptured => "captured",
+        Msg::CopyRecopied => "copied again",
+        Msg::CopyCopiedOut => "copied from the vault",
+        Msg::TuiHelp => {
+            "j/↓:down k/↑:up g:top G:bottom /:live-search c:copy m:markdown y:copy-as p:pin P:protect t:tmux R:related Space/Enter:preview r:refresh q:quit"
+        }
+        Msg::PressForHelp => "Press ? for help",
+        Msg::TrayRecording => "Clip Vault: recording",
+        Msg::TrayIncognito => "Clip Vault: incognito, not saving history",
+        Msg::TrayPaused => "Clip Vault: paused",
+        Msg::TrayLocked => "Clip Vault: locked",
+        Msg::TrayError => "Clip Vault: capture failing, see logs",
+        Msg::MenuSearch => "Search Clipboard",
+        Msg::MenuQuickFilters => "Quick Filters",
+        Msg::MenuNoSavedSearches => "Save searches in Settings",
+        Msg::MenuStartDaemon => "Start Daemon",
+        Msg::MenuStopDaemon => "Stop Daemon",
+        Msg::MenuIncognito => "Incognito Mode",
+        Msg::MenuLockNow => "Lock Vault Now",
+        Msg::MenuSettings => "Settings...",
+        Msg::MenuQuit => "Quit Clip Vault",
+        Msg::StorageWarningHint => "Shorter retention times in Settings will keep it down.",
+        Msg::VaultLocked => {
+            "Vault locked. The cached password is gone and the app has been asked to lock."
+        }
+        Msg::LockedStopping => "The vault was locked; stopping.",
+    }
+}
+
+const GERMAN: &[(Msg, &str)] = &[
+    (
+        Msg::NoEntries,
+        "Keine Einträge in der Zwischenablage gefunden.",
+    ),
+    (
+        Msg::NoMatchingEntry,
+        "Kein eindeutiger Eintrag passt zu {reference}.",
+    ),
+    (Msg::InvalidPassword, "Ungültiges Passwort."),
+    (Msg::NoProtectedEntries, "Keine geschützten Einträge."),
+    (
+        Msg::CopiedProtected,
+        "Geschützten Eintrag #{index} kopiert.",
+    ),
+    (
+        Msg::ProtectedMovedBack,
+        "Geschützter Eintrag #{index} ist wieder im Verlauf.",
+    ),
+    (Msg::Wrote, "{path} geschrieben"),
+    (
+        Msg::Welcome,
+        "Willkommen bei Clip Vault! Drücke ? für die Hilfe",
+    ),
+    (Msg::CopiedToClipboard, "In die Zwischenablage kopiert!"),
+    (Msg::CopiedAsMarkdown, "Als Markdown kopiert!"),
+    (Msg::NoHtmlVersion, "Keine HTML-Version dieses Eintrags"),
+    (
+        Msg::CannotCopyImages,
+        "Bilder können im CLI-Modus nicht kopiert werden",
+    ),
+    (
+        Msg::CannotEditImages,
+        "Bilder können im CLI-Modus nicht bearbeitet werden",
+    ),
+    (
+        Msg::CannotEditFiles,
+        "Kopierte Dateien können nicht bearbeitet werden",
+    ),
+    (Msg::DeleteCancelled, "Löschen abgebrochen"),
+    (
+        Msg::WrongPasswordNothingDeleted,
+        "Falsches Passwort - nichts gelöscht",
+    ),
+    (Msg::ItemDeleted, "Eintrag gelöscht"),
+    (Msg::EditorAborted, "Editor abgebrochen"),
+    (Msg::NoChanges, "Keine Änderungen"),
+    (Msg::SavedChanges, "Änderungen im Tresor gespeichert"),
+    (Msg::ShowingAll, "Alle Einträge werden angezeigt"),
+    (Msg::Refreshed, "Aktualisiert - {count} Einträge geladen"),
+    (
+        Msg::SearchMode,
+        "Suchmodus - tippen zum Suchen, Enter zum Beenden, Esc zum Abbrechen",
+    ),
+    (Msg::Found, "{count} Einträge passen zu '{query}'"),
+    (
+        Msg::FoundWhileSearching,
+        "{count} Einträge passen zu '{query}' - Enter zum Beenden, Esc zum Abbrechen",
+    ),
+    (Msg::SentToTmux, "In den tmux-Puffer gesendet"),
+    (
+        Msg::SkippedUndecodable,
+        "{count} nicht lesbare Einträge übersprungen - `clip-vault quarantine` legt sie beiseite",
+    ),
+    (Msg::NoteUnchanged, "Notiz unverändert"),
+    (Msg::NoteSaved, "Notiz gespeichert"),
+    (Msg::NoteRemoved, "Notiz entfernt"),
+    (Msg::CopiedAs, "Als {format} kopiert!"),
+    (Msg::CannotCopyAs, "Kopieren als {format} nicht möglich: {error}"),
+    (Msg::Pinned, "Angeheftet"),
+    (Msg::Unpinned, "Nicht mehr angeheftet"),
+    (
+        Msg::MovedToProtected,
+        "In den geschützten Bereich verschoben (siehe `clip-vault protected list`)",
+    ),
+    (
+        Msg::CannotSendImagesToTmux,
+        "Bilder können nicht an tmux gesendet werden",
+    ),
+    (
+        Msg::PreviewMode,
+        "Vorschau - Esc zum Zurückkehren, 'c' zum Kopieren, 'n' für eine Notiz",
+    ),
+    (
+        Msg::RelatedEntries,
+        "{count} verwandte Einträge - Esc zum Zurückkehren",
+    ),
+    (
+        Msg::TuiHelp,
+        "j/↓:runter k/↑:hoch g:Anfang G:Ende /:Live-Suche c:kopieren m:Markdown y:kopieren-als p:anheften P:schützen t:tmux R:verwandte Leertaste/Enter:Vorschau r:neu laden q:beenden",
+    ),
+    (Msg::PressForHelp, "Drücke ? für die Hilfe"),
+    (Msg::TrayRecording, "Clip Vault: zeichnet auf"),
+    (
+        Msg::TrayIncognito,
+        "Clip Vault: inkognito, Verlauf wird nicht gespeichert",
+    ),
+    (Msg::TrayPaused, "Clip Vault: pausiert"),
+    (Msg::TrayLocked, "Clip Vault: gesperrt"),
+    (
+        Msg::TrayError,
+        "Clip Vault: Erfassung schlägt fehl, siehe Protokoll",
+    ),
+    (Msg::MenuSearch, "Zwischenablage durchsuchen"),
+    (Msg::MenuQuickFilters, "Schnellfilter"),
+    (
+        Msg::MenuNoSavedSearches,
+        "Suchen in den Einstellungen speichern",
+    ),
+    (Msg::MenuStartDaemon, "Dienst starten"),
+    (Msg::MenuStopDaemon, "Dienst beenden"),
+    (Msg::MenuIncognito, "Inkognito-Modus"),
+    (Msg::MenuLockNow, "Tresor jetzt sperren"),
+    (Msg::MenuSettings, "Einstellungen..."),
+    (Msg::MenuQuit, "Clip Vault beenden"),
+    (
+        Msg::StorageWarningHint,
+        "Kürzere Aufbewahrungszeiten in den Einstellungen halten ihn klein.",
+    ),
+    (
+        Msg::VaultLocked,
+        "Tresor gesperrt. Das zwischengespeicherte Passwort ist gelöscht und die App wurde zum Sperren aufgefordert.",
+    ),
+    (
+        Msg::LockedStopping,
+        "Der Tresor wurde gesperrt; wird beendet.",
+    ),
+    (Msg::CopyHistory, "Kopierverlauf ({count})"),
+    (Msg::CopyCaptured, "erfasst"),
+    (Msg::CopyRecopied, "erneut kopiert"),
+    (Msg::CopyCopiedOut, "aus dem Tresor kopiert"),
+];
+
+const SPANISH: &[(Msg, &str)] = &[
+    (
+        Msg::NoEntries,
+        "No se encontraron entradas del portapapeles.",
+    ),
+    (
+        Msg::NoMatchingEntry,
+        "Ninguna entrada única coincide con {reference}.",
+    ),
+    (Msg::InvalidPassword, "Contraseña no válida."),
+    (Msg::NoProtectedEntries, "No hay entradas protegidas."),
+    (Msg::CopiedProtected, "Entrada protegida #{index} copiada."),
+    (
+        Msg::ProtectedMovedBack,
+        "La entrada protegida #{index} volvió al historial.",
+    ),
+    (Msg::Wrote, "Se escribió {path}"),
+    (
+        Msg::Welcome,
+        "¡Bienvenido a Clip Vault! Pulsa ? para ver la ayuda",
+    ),
+    (Msg::CopiedToClipboard, "¡Copiado al portapapeles!"),
+    (Msg::CopiedAsMarkdown, "¡Copiado como Markdown!"),
+    (Msg::NoHtmlVersion, "Esta entrada no tiene versión HTML"),
+    (
+        Msg::CannotCopyImages,
+        "No se pueden copiar imágenes en modo CLI",
+    ),
+    (
+        Msg::CannotEditImages,
+        "No se pueden editar imágenes en modo CLI",
+    ),
+    (
+        Msg::CannotEditFiles,
+        "No se pueden editar archivos copiados",
+    ),
+    (Msg::DeleteCancelled, "Eliminación cancelada"),
+    (
+        Msg::WrongPasswordNothingDeleted,
+        "Contraseña incorrecta - no se eliminó nada",
+    ),
+    (Msg::ItemDeleted, "Entrada eliminada"),
+    (Msg::EditorAborted, "Editor cancelado"),
+    (Msg::NoChanges, "No se hicieron cambios"),
+    (Msg::SavedChanges, "Cambios guardados en la bóveda"),
+    (Msg::ShowingAll, "Mostrando todas las entradas"),
+    (Msg::Refreshed, "Actualizado - {count} entradas cargadas"),
+    (
+        Msg::SearchMode,
+        "Modo búsqueda - escribe para buscar, Enter para salir, Esc para cancelar",
+    ),
+    (Msg::Found, "{count} entradas coinciden con '{query}'"),
+    (
+        Msg::FoundWhileSearching,
+        "{count} entradas coinciden con '{query}' - Enter para salir, Esc para cancelar",
+    ),
+    (Msg::SentToTmux, "Enviado al búfer de tmux"),
+    (
+        Msg::SkippedUndecodable,
+        "Se omitieron {count} entradas ilegibles - ejecuta `clip-vault quarantine` para apartarlas",
+    ),
+    (Msg::NoteUnchanged, "Nota sin cambios"),
+    (Msg::NoteSaved, "Nota guardada"),
+    (Msg::NoteRemoved, "Nota eliminada"),
+    (Msg::CopiedAs, "¡Copiado como {format}!"),
+    (Msg::CannotCopyAs, "No se puede copiar como {format}: {error}"),
+    (Msg::Pinned, "Fijada"),
+    (Msg::Unpinned, "Desfijada"),
+    (
+        Msg::MovedToProtected,
+        "Movida al área protegida (ver `clip-vault protected list`)",
+    ),
+    (Msg::CannotSendImagesToTmux, "No se pueden enviar imágenes a tmux"),
+    (
+        Msg::PreviewMode,
+        "Vista previa - Esc para volver, 'c' para copiar, 'n' para añadir una nota",
+    ),
+    (
+        Msg::RelatedEntries,
+        "{count} entradas relacionadas - Esc para volver",
+    ),
+    (
+        Msg::TuiHelp,
+        "j/↓:bajar k/↑:subir g:inicio G:final /:búsqueda c:copiar m:markdown y:copiar-como p:fijar P:proteger t:tmux R:relacionadas Espacio/Enter:vista previa r:recargar q:salir",
+    ),
+    (Msg::PressForHelp, "Pulsa ? para ver la ayuda"),
+    (Msg::TrayRecording, "Clip Vault: grabando"),
+    (
+        Msg::TrayIncognito,
+        "Clip Vault: incógnito, no se guarda el historial",
+    ),
+    (Msg::TrayPaused, "Clip Vault: en pausa"),
+    (Msg::TrayLocked, "Clip Vault: bloqueado"),
+    (
+        Msg::TrayError,
+        "Clip Vault: la captura falla, consulta los registros",
+    ),
+    (Msg::MenuSearch, "Buscar en el portapapeles"),
+    (Msg::MenuQuickFilters, "Filtros rápidos"),
+    (Msg::MenuNoSavedSearches, "Guarda búsquedas en Ajustes"),
+    (Msg::MenuStartDaemon, "Iniciar servicio"),
+    (Msg::MenuStopDaemon, "Detener servicio"),
+    (Msg::MenuIncognito, "Modo incógnito"),
+    (Msg::MenuLockNow, "Bloquear la bóveda ahora"),
+    (Msg::MenuSettings, "Ajustes..."),
+    (Msg::MenuQuit, "Salir de Clip Vault"),
+    (
+        Msg::StorageWarningHint,
+        "Acortar la retención en Ajustes lo mantendrá más pequeño.",
+    ),
+    (
+        Msg::VaultLocked,
+        "Bóveda bloqueada. La contraseña en caché se ha borrado y se ha pedido a la app que se bloquee.",
+    ),
+    (Msg::LockedStopping, "La bóveda se ha bloqueado; deteniendo."),
+    (Msg::CopyHistory, "Historial de copias ({count})"),
+    (Msg::CopyCaptured, "capturado"),
+    (Msg::CopyRecopied, "copiado de nuevo"),
+    (Msg::CopyCopiedOut, "copiado desde la bóveda"),
+];
+
+const FRENCH: &[(Msg, &str)] = &[
+    (Msg::NoEntries, "Aucune entrée du presse-papiers trouvée."),
+    (
+        Msg::NoMatchingEntry,
+        "Aucune entrée unique ne correspond à {reference}.",
+    ),
+    (Msg::InvalidPassword, "Mot de passe invalide."),
+    (Msg::NoProtectedEntries, "Aucune entrée protégée."),
+    (Msg::CopiedProtected, "Entrée protégée n°{index} copiée."),
+    (
+        Msg::ProtectedMovedBack,
+        "L'entrée protégée n°{index} est revenue dans l'historique.",
+    ),
+    (Msg::Wrote, "{path} écrit"),
+    (
+        Msg::Welcome,
+        "Bienvenue dans Clip Vault ! Appuyez sur ? pour l'aide",
+    ),
+    (Msg::CopiedToClipboard, "Copié dans le presse-papiers !"),
+    (Msg::CopiedAsMarkdown, "Copié en Markdown !"),
+    (Msg::NoHtmlVersion, "Pas de version HTML pour cette entrée"),
+    (
+        Msg::CannotCopyImages,
+        "Impossible de copier des images en mode CLI",
+    ),
+    (
+        Msg::CannotEditImages,
+        "Impossible de modifier des images en mode CLI",
+    ),
+    (
+        Msg::CannotEditFiles,
+        "Impossible de modifier des fichiers copiés",
+    ),
+    (Msg::DeleteCancelled, "Suppression annulée"),
+    (
+        Msg::WrongPasswordNothingDeleted,
+        "Mot de passe incorrect - rien n'a été supprimé",
+    ),
+    (Msg::ItemDeleted, "Entrée supprimée"),
+    (Msg::EditorAborted, "Éditeur interrompu"),
+    (Msg::NoChanges, "Aucune modification"),
+    (
+        Msg::SavedChanges,
+        "Modifications enregistrées dans le coffre",
+    ),
+    (Msg::ShowingAll, "Affichage de toutes les entrées"),
+    (Msg::Refreshed, "Actualisé - {count} entrées chargées"),
+    (
+        Msg::SearchMode,
+        "Mode recherche - tapez pour chercher, Entrée pour quitter, Échap pour annuler",
+    ),
+    (Msg::Found, "{count} entrées correspondent à '{query}'"),
+    (
+        Msg::FoundWhileSearching,
+        "{count} entrées correspondent à '{query}' - Entrée pour quitter, Échap pour annuler",
+    ),
+    (Msg::SentToTmux, "Envoyé dans le tampon tmux"),
+    (
+        Msg::SkippedUndecodable,
+        "{count} entrées illisibles ignorées - lancez `clip-vault quarantine` pour les mettre de côté",
+    ),
+    (Msg::NoteUnchanged, "Note inchangée"),
+    (Msg::NoteSaved, "Note enregistrée"),
+    (Msg::NoteRemoved, "Note supprimée"),
+    (Msg::CopiedAs, "Copié en {format} !"),
+    (Msg::CannotCopyAs, "Impossible de copier en {format} : {error}"),
+    (Msg::Pinned, "Épinglée"),
+    (Msg::Unpinned, "Désépinglée"),
+    (
+        Msg::MovedToProtected,
+        "Déplacée dans la zone protégée (voir `clip-vault protected list`)",
+    ),
+    (
+        Msg::CannotSendImagesToTmux,
+        "Impossible d'envoyer des images à tmux",
+    ),
+    (
+        Msg::PreviewMode,
+        "Aperçu - Échap pour revenir, 'c' pour copier, 'n' pour ajouter une note",
+    ),
+    (Msg::RelatedEntries, "{count} entrées liées - Échap pour revenir"),
+    (
+        Msg::TuiHelp,
+        "j/↓:bas k/↑:haut g:début G:fin /:recherche c:copier m:markdown y:copier-en p:épingler P:protéger t:tmux R:liées Espace/Entrée:aperçu r:actualiser q:quitter",
+    ),
+    (Msg::PressForHelp, "Appuyez sur ? pour l'aide"),
+    (Msg::TrayRecording, "Clip Vault : enregistrement"),
+    (
+        Msg::TrayIncognito,
+        "Clip Vault : navigation privée, historique non enregistré",
+    ),
+    (Msg::TrayPaused, "Clip Vault : en pause"),
+    (Msg::TrayLocked, "Clip Vault : verrouillé"),
+    (
+        Msg::TrayError,
+        "Clip Vault : la capture échoue, voir les journaux",
+    ),
+    (Msg::MenuSearch, "Rechercher dans le presse-papiers"),
+    (Msg::MenuQuickFilters, "Filtres rapides"),
+    (
+        Msg::MenuNoSavedSearches,
+        "Enregistrez des recherches dans les Réglages",
+    ),
+    (Msg::MenuStartDaemon, "Démarrer le service"),
+    (Msg::MenuStopDaemon, "Arrêter le service"),
+    (Msg::MenuIncognito, "Mode privé"),
+    (Msg::MenuLockNow, "Verrouiller le coffre maintenant"),
+    (Msg::MenuSettings, "Réglages..."),
+    (Msg::MenuQuit, "Quitter Clip Vault"),
+    (
+        Msg::StorageWarningHint,
+        "Des durées de conservation plus courtes dans les Réglages le limiteront.",
+    ),
+    (
+        Msg::VaultLocked,
+        "Coffre verrouillé. Le mot de passe en cache est effacé et l'app a été invitée à se verrouiller.",
+    ),
+    (Msg::LockedStopping, "Le coffre a été verrouillé ; arrêt."),
+    (Msg::CopyHistory, "Historique des copies ({count})"),
+    (Msg::CopyCaptured, "capturé"),
+    (Msg::CopyRecopied, "copié à nouveau"),
+    (Msg::CopyCopiedOut, "copié depuis le coffre"),
+];
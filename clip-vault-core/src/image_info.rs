@@ -0,0 +1,149 @@
+//! Dimensions and format of image entries, read straight from the file
+//! header so listing doesn't need to decode pixels.
+
+use crate::format_size;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Upper-case format name, e.g. `PNG`.
+    pub format: String,
+    pub bytes: u64,
+}
+
+impl ImageInfo {
+    /// Parse the header of a PNG, JPEG or GIF. Returns `None` for anything
+    /// else or a truncated header.
+    #[must_use]
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let (width, height, format) = if data.starts_with(PNG_SIGNATURE) {
+            // IHDR is always the first chunk.
+            let ihdr = data.get(16..24)?;
+            (be_u32(&ihdr[..4]), be_u32(&ihdr[4..]), "PNG")
+        } else if data.starts_with(b"GIF8") {
+            let dims = data.get(6..10)?;
+            (
+                u32::from(u16::from_le_bytes([dims[0], dims[1]])),
+                u32::from(u16::from_le_bytes([dims[2], dims[3]])),
+                "GIF",
+            )
+        } else if data.starts_with(&[0xFF, 0xD8]) {
+            let (w, h) = jpeg_dimensions(data)?;
+            (w, h, "JPEG")
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            width,
+            height,
+            format: format.to_string(),
+            bytes: data.len() as u64,
+        })
+    }
+}
+
+impl fmt::Display for ImageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}×{} {}, {}",
+            self.width,
+            self.height,
+            self.format,
+            format_size(usize::try_from(self.bytes).unwrap_or(usize::MAX))
+        )
+    }
+}
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+}
+
+/// Walk JPEG segments until a start-of-frame marker.
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        let len = usize::from(u16::from_be_bytes([
+            *data.get(pos + 2)?,
+            *data.get(pos + 3)?,
+        ]));
+        // SOF0..SOF15, except DHT (C4), JPG (C8) and DAC (CC).
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let sof = data.get(pos + 5..pos + 9)?;
+            let height = u16::from_be_bytes([sof[0], sof[1]]);
+            let width = u16::from_be_bytes([sof[2], sof[3]]);
+            return Some((u32::from(width), u32::from(height)));
+        }
+        pos += 2 + len;
+    }
+}
+
+/// Remove EXIF, GPS, text and timestamp metadata from a PNG or JPEG while
+/// leaving the pixel data alone. Other formats and malformed files are
+/// returned unchanged.
+#[must_use]
+pub fn strip_image_metadata(data: &[u8]) -> Vec<u8> {
+    let stripped = if data.starts_with(PNG_SIGNATURE) {
+        strip_png(data)
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(data)
+    } else {
+        None
+    };
+    stripped.unwrap_or_else(|| data.to_vec())
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+fn strip_png(data: &[u8]) -> Option<Vec<u8>> {
+    const DROPPED: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut pos = PNG_SIGNATURE.len();
+    while pos < data.len() {
+        let len = usize::try_from(be_u32(data.get(pos..pos + 4)?)).ok()?;
+        // length + type + data + CRC
+        let chunk = data.get(pos..pos + 12 + len)?;
+        if !DROPPED.iter().any(|t| chunk[4..8] == t[..]) {
+            out.extend_from_slice(chunk);
+        }
+        pos += chunk.len();
+    }
+    Some(out)
+}
+
+fn strip_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = vec![0xFF, 0xD8];
+    let mut pos = 2;
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        let len = usize::from(u16::from_be_bytes([
+            *data.get(pos + 2)?,
+            *data.get(pos + 3)?,
+        ]));
+        let segment = data.get(pos..pos + 2 + len)?;
+        // Start of scan: the rest is entropy-coded image data.
+        if marker == 0xDA {
+            out.extend_from_slice(&data[pos..]);
+            return Some(out);
+        }
+        // APP1-APP13 and APP15 carry EXIF/XMP/IPTC; COM is free text. APP0
+        // (JFIF) and APP14 (Adobe colour transform) affect decoding.
+        let metadata = matches!(marker, 0xE1..=0xED | 0xEF | 0xFE);
+        if !metadata {
+            out.extend_from_slice(segment);
+        }
+        pos += segment.len();
+    }
+}
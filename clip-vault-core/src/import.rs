@@ -0,0 +1,383 @@
+//! Histories from other clipboard managers, read into entries that
+//! [`Vault::import`](crate::Vault::import) stores. Ditto and Maccy keep
+//! theirs in `SQLite` databases, Clipy in keyed archives, one file per clip;
+//! `json` is clip-vault's own JSON Lines export.
+
+use crate::{ClipboardItem, DedupePolicy, DedupeRules, SourceApp, Timestamp};
+#[cfg(feature = "import")]
+use crate::{Error, Result, FILES_MIME, OLD_FILES_MIME};
+#[cfg(feature = "import")]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where a history to import comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    /// Ditto's `Ditto.db`. Groups become tags.
+    Ditto,
+    /// Clipy's data directory, or a single `.data` file from it. Clipy
+    /// keeps copy times in a Realm database, so the files' modification
+    /// times stand in for them.
+    Clipy,
+    /// Maccy's `Storage.sqlite`. Pinned items stay pinned.
+    Maccy,
+    /// A `clip-vault export` in JSON Lines, with images base64-encoded or
+    /// extracted next to it.
+    Json,
+}
+
+/// One entry read from another clipboard manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedEntry {
+    pub item: ClipboardItem,
+    /// When it was copied; `None` if the source doesn't say, in which case
+    /// it is imported as copied now.
+    pub timestamp: Option<Timestamp>,
+    pub context: Option<String>,
+    pub source: Option<SourceApp>,
+    pub tags: Vec<String>,
+    pub pinned: bool,
+    /// Delete it this long after it was copied, like a capture's
+    /// [`ttl`](crate::CaptureMetadata::ttl).
+    pub ttl: Option<Duration>,
+    /// The full text of an entry cut short by the text limit.
+    pub spill: Option<String>,
+    /// How it's matched against stored entries.
+    pub dedupe: DedupePolicy,
+}
+
+impl ImportedEntry {
+    /// The entry as read, before any capture settings; see
+    /// `clip_vault_monitor::prepare_import` for those.
+    #[must_use]
+    pub fn new(item: ClipboardItem, timestamp: Option<Timestamp>) -> Self {
+        Self {
+            dedupe: DedupeRules::default().resolve(&item),
+            item,
+            timestamp,
+            context: None,
+            source: None,
+            tags: Vec::new(),
+            pinned: false,
+            ttl: None,
+            spill: None,
+        }
+    }
+}
+
+/// Entries read by [`read_history`], oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportedHistory {
+    pub entries: Vec<ImportedEntry>,
+    /// Records clip-vault can't store, such as Clipy's images or formats
+    /// other than text, PNG images and files.
+    pub unsupported: usize,
+}
+
+/// What [`Vault::import`](crate::Vault::import) did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    /// Entries already in the vault, or repeated in the import.
+    pub duplicates: usize,
+}
+
+/// Read the history at `path` as `source` wrote it.
+#[cfg(feature = "import")]
+pub fn read_history(source: ImportSource, path: &Path) -> Result<ImportedHistory> {
+    let mut history = match source {
+        ImportSource::Ditto => read_ditto(path)?,
+        ImportSource::Clipy => read_clipy(path)?,
+        ImportSource::Maccy => read_maccy(path)?,
+        ImportSource::Json => read_json(path)?,
+    };
+    history.entries.sort_by_key(|entry| entry.timestamp);
+    Ok(history)
+}
+
+#[cfg(feature = "import")]
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message.into(),
+    ))
+}
+
+/// Opened read-only, so a manager that's running isn't disturbed.
+#[cfg(feature = "import")]
+fn open_database(path: &Path) -> Result<rusqlite::Connection> {
+    if !path.is_file() {
+        return Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} not found", path.display()),
+        )));
+    }
+    Ok(rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?)
+}
+
+/// Ditto keeps each clip's text in `Main` and its formats in `Data`; the
+/// clip's group, if any, is another `Main` row.
+#[cfg(feature = "import")]
+fn read_ditto(path: &Path) -> Result<ImportedHistory> {
+    let conn = open_database(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT m.lDate, m.mText,
+                EXISTS(SELECT 1 FROM Data d WHERE d.lParentID = m.lID
+                       AND d.strClipBoardFormat IN ('CF_UNICODETEXT', 'CF_TEXT')),
+                (SELECT d.ooData FROM Data d WHERE d.lParentID = m.lID
+                 AND d.strClipBoardFormat = 'PNG' LIMIT 1),
+                g.mText
+         FROM Main m LEFT JOIN Main g ON g.lID = m.lParentID AND g.bIsGroup = 1
+         WHERE m.bIsGroup = 0;",
+    )?;
+    let mut rows = stmt.query([])?;
+    let mut history = ImportedHistory::default();
+    while let Some(row) = rows.next()? {
+        let secs: i64 = row.get(0)?;
+        let text: Option<String> = row.get(1)?;
+        let has_text: bool = row.get(2)?;
+        let png: Option<Vec<u8>> = row.get(3)?;
+        let group: Option<String> = row.get(4)?;
+        let item = match (text, png) {
+            (Some(text), _) if has_text && !text.is_empty() => ClipboardItem::Text(text),
+            (_, Some(png)) => ClipboardItem::Image(png),
+            _ => {
+                history.unsupported += 1;
+                continue;
+            }
+        };
+        let mut entry =
+            ImportedEntry::new(item, u64::try_from(secs).ok().map(Timestamp::from_secs));
+        entry.tags.extend(group);
+        history.entries.push(entry);
+    }
+    Ok(history)
+}
+
+/// Seconds between the Unix epoch and Core Data's, 2001-01-01.
+#[cfg(feature = "import")]
+const CORE_DATA_EPOCH: f64 = 978_307_200.0;
+
+/// Maccy keeps one `ZHISTORYITEM` per clip with a `ZHISTORYITEMCONTENT`
+/// row per pasteboard type.
+#[cfg(feature = "import")]
+fn read_maccy(path: &Path) -> Result<ImportedHistory> {
+    let conn = open_database(path)?;
+    let mut items = conn
+        .prepare("SELECT Z_PK, ZLASTCOPIEDAT, ZAPPLICATION, ZPIN IS NOT NULL FROM ZHISTORYITEM;")?;
+    let mut contents =
+        conn.prepare("SELECT ZTYPE, ZVALUE FROM ZHISTORYITEMCONTENT WHERE ZITEM = ?1;")?;
+    let mut rows = items.query([])?;
+    let mut history = ImportedHistory::default();
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let copied_at: Option<f64> = row.get(1)?;
+        let app: Option<String> = row.get(2)?;
+        let pinned: bool = row.get(3)?;
+
+        let mut text = None;
+        let mut png = None;
+        let mut files = Vec::new();
+        let mut values = contents.query([id])?;
+        while let Some(value) = values.next()? {
+            let kind: String = value.get(0)?;
+            let Some(data) = value.get::<_, Option<Vec<u8>>>(1)? else {
+                continue;
+            };
+            match kind.as_str() {
+                "public.utf8-plain-text" => text = String::from_utf8(data).ok(),
+                "public.png" => png = Some(data),
+                "public.file-url" => {
+                    files.extend(
+                        String::from_utf8(data)
+                            .ok()
+                            .and_then(|url| file_url_path(&url)),
+                    );
+                }
+                _ => {}
+            }
+        }
+        let item = if !files.is_empty() {
+            ClipboardItem::Files(files)
+        } else if let Some(text) = text.filter(|t| !t.is_empty()) {
+            ClipboardItem::Text(text)
+        } else if let Some(png) = png {
+            ClipboardItem::Image(png)
+        } else {
+            history.unsupported += 1;
+            continue;
+        };
+
+        // Times are seconds since 2001, with a fraction.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let timestamp = copied_at
+            .map(|secs| secs + CORE_DATA_EPOCH)
+            .filter(|secs| *secs >= 0.0)
+            .map(|secs| Timestamp::from_nanos((secs * 1e9) as u64));
+        let mut entry = ImportedEntry::new(item, timestamp);
+        entry.source = app.map(|name| SourceApp {
+            name,
+            window_title: None,
+        });
+        entry.pinned = pinned;
+        history.entries.push(entry);
+    }
+    Ok(history)
+}
+
+/// The path of a `file://` URL, percent-decoded.
+#[cfg(feature = "import")]
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    let path = url.trim().strip_prefix("file://")?;
+    // A host, usually `localhost`, may come before the path.
+    let path = &path[path.find('/')?..];
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        if let Some(decoded) = escaped {
+            bytes.push(decoded);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+/// Every `.data` file in Clipy's directory, or the one file given.
+#[cfg(feature = "import")]
+fn read_clipy(path: &Path) -> Result<ImportedHistory> {
+    let files = if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let file = entry?.path();
+            if file.extension().is_some_and(|ext| ext == "data") {
+                files.push(file);
+            }
+        }
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut history = ImportedHistory::default();
+    for file in files {
+        let archive = plist::Value::from_file(&file)
+            .map_err(|e| invalid(format!("{}: {e}", file.display())))?;
+        let Some(item) = clipy_item(&archive) else {
+            history.unsupported += 1;
+            continue;
+        };
+        let modified = std::fs::metadata(&file)?.modified().ok();
+        history
+            .entries
+            .push(ImportedEntry::new(item, modified.map(Timestamp::from)));
+    }
+    Ok(history)
+}
+
+/// The text or files in a keyed archive of Clipy's `CPYClipData`. `None`
+/// for images and anything else.
+#[cfg(feature = "import")]
+fn clipy_item(archive: &plist::Value) -> Option<ClipboardItem> {
+    let objects = archive.as_dictionary()?.get("$objects")?.as_array()?;
+    let resolve = |value: &plist::Value| -> Option<&plist::Value> {
+        let index = usize::try_from(value.as_uid()?.get()).ok()?;
+        objects
+            .get(index)
+            .filter(|object| object.as_string() != Some("$null"))
+    };
+    let top = archive.as_dictionary()?.get("$top")?.as_dictionary()?;
+    let clip = resolve(top.get("root")?)?.as_dictionary()?;
+
+    let files: Vec<PathBuf> = clip
+        .get("fileNames")
+        .and_then(resolve)
+        .and_then(|names| names.as_dictionary()?.get("NS.objects")?.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|name| resolve(name)?.as_string().map(PathBuf::from))
+        .collect();
+    if !files.is_empty() {
+        return Some(ClipboardItem::Files(files));
+    }
+    let text = clip.get("stringValue").and_then(resolve)?.as_string()?;
+    (!text.is_empty()).then(|| ClipboardItem::Text(text.to_string()))
+}
+
+/// A record of `clip-vault export`.
+#[cfg(feature = "import")]
+#[derive(serde::Deserialize)]
+struct ExportedRecord {
+    timestamp: Timestamp,
+    content_type: String,
+    content: String,
+    #[serde(default)]
+    context: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// The image at `relative` under the export's directory `dir`, if that's
+/// where it is, so an export can't have any file on disk read in.
+#[cfg(feature = "import")]
+fn exported_image(dir: &Path, relative: &str) -> Option<PathBuf> {
+    let dir = dir.canonicalize().ok()?;
+    let path = dir.join(relative).canonicalize().ok()?;
+    path.starts_with(&dir).then_some(path)
+}
+
+#[cfg(feature = "import")]
+fn read_json(path: &Path) -> Result<ImportedHistory> {
+    use base64::Engine as _;
+
+    let text = std::fs::read_to_string(path)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut history = ImportedHistory::default();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: ExportedRecord =
+            serde_json::from_str(line).map_err(|e| invalid(format!("line {}: {e}", number + 1)))?;
+        let item = match record.content_type.as_str() {
+            "text/plain" => ClipboardItem::Text(record.content),
+            FILES_MIME | OLD_FILES_MIME => {
+                ClipboardItem::Files(crate::files_from_text(&record.content))
+            }
+            // Base64, or the path of an image extracted by `--images-dir`,
+            // which must be inside the export's directory.
+            "image/png" => ClipboardItem::Image(
+                match base64::engine::general_purpose::STANDARD.decode(&record.content) {
+                    Ok(png) => png,
+                    Err(_) => {
+                        std::fs::read(exported_image(dir, &record.content).ok_or_else(|| {
+                            invalid(format!(
+                                "line {}: image {} is outside the export's directory",
+                                number + 1,
+                                record.content
+                            ))
+                        })?)?
+                    }
+                },
+            ),
+            _ => {
+                history.unsupported += 1;
+                continue;
+            }
+        };
+        let mut entry = ImportedEntry::new(item, Some(record.timestamp));
+        entry.context = record.context;
+        entry.tags = record.tags;
+        history.entries.push(entry);
+    }
+    Ok(history)
+}
@@ -0,0 +1,57 @@
+//! Size, line and word counts of an entry, recorded when it's stored so a
+//! list can say "3.2 KB, 120 lines" without loading the payload.
+
+use crate::{format_size, ClipboardItem};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStats {
+    /// See [`ClipboardItem::size`].
+    pub bytes: u64,
+    /// Lines of text, or paths of a file entry; 0 for images.
+    pub lines: u64,
+    /// Whitespace-separated words of text; 0 for images and files.
+    pub words: u64,
+}
+
+impl ItemStats {
+    #[must_use]
+    pub fn of(item: &ClipboardItem) -> Self {
+        let bytes = item.size() as u64;
+        match item {
+            ClipboardItem::Text(text) => Self::of_text(text),
+            ClipboardItem::Image(_) => Self {
+                bytes,
+                ..Self::default()
+            },
+            ClipboardItem::Files(paths) => Self {
+                bytes,
+                lines: paths.len() as u64,
+                words: 0,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn of_text(text: &str) -> Self {
+        Self {
+            bytes: text.len() as u64,
+            lines: text.lines().count() as u64,
+            words: text.split_whitespace().count() as u64,
+        }
+    }
+}
+
+/// The size, then the line count where there's more than one, e.g.
+/// `3.2 KB, 120 lines`.
+impl fmt::Display for ItemStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = format_size(usize::try_from(self.bytes).unwrap_or(usize::MAX));
+        if self.lines > 1 {
+            write!(f, "{size}, {} lines", self.lines)
+        } else {
+            f.write_str(&size)
+        }
+    }
+}
@@ -1,4 +1,10 @@
 //! Core data types shared by daemon & CLI.
+//!
+//! The `sqlcipher` feature (on by default) provides [`SqliteVault`] and
+//! vendors `SQLCipher` and `OpenSSL`; `images` (also default) decodes images
+//! to make thumbnails; `keyring` (also default) saves the vault password in the OS
+//! keyring for [`UnlockPolicy::Keyring`]. With all three off the crate is
+//! just the types, the [`Vault`] trait and the pure-Rust helpers.
 
 use base64::{engine::general_purpose, Engine as _};
 use bincode::{Decode, Encode};
@@ -6,10 +12,19 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::path::PathBuf;
 
+/// MIME type of file entries; their text is one path per line, not the
+/// `file://` URIs `text/uri-list` would hold.
+pub const FILES_MIME: &str = "application/x-clip-vault-files";
+/// What [`FILES_MIME`] was in vaults and exports before schema version 12.
+pub(crate) const OLD_FILES_MIME: &str = "text/uri-list";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Encode, Decode)]
+#[non_exhaustive]
 pub enum ClipboardItem {
     Text(String),
     Image(Vec<u8>),
+    /// Files copied in Finder, Explorer or a file manager.
+    Files(Vec<PathBuf>),
 }
 
 impl ClipboardItem {
@@ -22,10 +37,30 @@ impl ClipboardItem {
             ClipboardItem::Image(data) => {
                 hasher.update(data);
             }
+            // Led by a byte UTF-8 text never starts with, and each path
+            // length-prefixed, so the same paths copied as text are another
+            // entry.
+            ClipboardItem::Files(paths) => {
+                hasher.update([0xff, b'F']);
+                for path in paths {
+                    let path = path.to_string_lossy();
+                    hasher.update((path.len() as u64).to_le_bytes());
+                    hasher.update(path.as_bytes());
+                }
+            }
         }
         hasher.finalize().into()
     }
 
+    /// Dimensions and format for image entries whose header could be read.
+    #[must_use]
+    pub fn image_info(&self) -> Option<ImageInfo> {
+        match self {
+            ClipboardItem::Text(_) | ClipboardItem::Files(_) => None,
+            ClipboardItem::Image(data) => ImageInfo::from_bytes(data),
+        }
+    }
+
     #[must_use]
     pub fn into_parts(self) -> (String, String) {
         match self {
@@ -37,28 +72,263 @@ impl ClipboardItem {
                     "image/png".to_string(),
                 )
             }
+            ClipboardItem::Files(paths) => (files_text(&paths), FILES_MIME.to_string()),
+        }
+    }
+
+    /// Size of the payload in bytes: text and image bytes, or the length
+    /// of the paths of a file entry.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        match self {
+            ClipboardItem::Text(text) => text.len(),
+            ClipboardItem::Image(data) => data.len(),
+            ClipboardItem::Files(paths) => files_text(paths).len(),
         }
     }
 }
 
+/// The paths of a file entry, one per line, as searched and shown.
+#[must_use]
+pub fn files_text(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parse [`files_text`] back into paths, skipping blank lines.
+#[must_use]
+pub fn files_from_text(text: &str) -> Vec<PathBuf> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ClipboardItemWithTimestamp {
     pub item: ClipboardItem,
-    pub timestamp: u64,
+    pub timestamp: Timestamp,
+    /// Repo or directory that was active when the item was copied.
+    #[serde(default)]
+    pub context: Option<String>,
+    /// The application it was copied from.
+    #[serde(default)]
+    pub source: Option<SourceApp>,
+    /// The vault's id for the entry. It stays the same through edits and
+    /// copies of the same content, unlike the hash. `None` for entries not
+    /// read from a vault, e.g. received shares or archived entries.
+    #[serde(default)]
+    pub id: Option<i64>,
+}
+
+/// A stored row that was skipped because its blob could not be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowDiagnostic {
+    pub hash: [u8; 32],
+    pub timestamp: Timestamp,
+    pub error: String,
+}
+
+/// A search hit before its payload is loaded: what a result list needs to
+/// show it. Load the full entry with [`Vault::get`] once it is picked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPreview {
+    pub hash: [u8; 32],
+    /// The text of a text entry; `None` for images and files.
+    pub text: Option<String>,
+    pub timestamp: Timestamp,
+    pub context: Option<String>,
+    pub source: Option<SourceApp>,
+    /// See [`ClipboardItemWithTimestamp::id`].
+    pub id: i64,
+    pub stats: ItemStats,
+}
+
+/// An entry as [`Vault::list_thumbnails`] lists it, with a large image's
+/// thumbnail standing in for the image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedEntry {
+    /// The stored entry's hash, which `entry.item` doesn't have when it
+    /// holds a thumbnail.
+    pub hash: [u8; 32],
+    pub entry: ClipboardItemWithTimestamp,
+    /// Whether `entry.item` is the image's thumbnail rather than the image.
+    pub thumbnail: bool,
+    /// The stored image's dimensions, format and size; `None` for other
+    /// entries and images whose header couldn't be read.
+    pub image: Option<ImageInfo>,
+    pub stats: ItemStats,
+}
+
+/// Lowercase hex encoding of an item hash, used when printing entry references.
+#[must_use]
+pub fn hash_to_hex(hash: &[u8; 32]) -> String {
+    use std::fmt::Write as _;
+    hash.iter().fold(String::with_capacity(64), |mut out, b| {
+        let _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+/// Parse the output of [`hash_to_hex`] back into an item hash.
+#[must_use]
+pub fn hash_from_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut hash = [0u8; 32];
+    for (byte, pair) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(hash)
 }
 
+mod accessibility;
+mod alerts;
+#[cfg(feature = "sqlcipher")]
+mod audit;
+mod backup;
+mod capture_queue;
+#[cfg(feature = "sqlcipher")]
+mod cipher;
+mod classify;
+#[cfg(feature = "sqlcipher")]
+pub mod client;
+mod color;
+mod config;
+mod context;
+mod copy_events;
+mod crash;
+mod custom_format;
+mod dedupe;
+mod digest;
+mod dry_run;
+#[cfg(feature = "sqlcipher")]
+mod duplicates;
 mod error;
+mod events;
+mod export;
+mod frecency;
+mod grep;
+mod i18n;
+mod image_info;
+mod import;
+mod item_stats;
+mod markdown;
+mod navigation;
+pub mod osc52;
+mod paths;
+mod permissions;
+mod preview;
+mod profiles;
+mod provenance;
+mod query;
+#[cfg(feature = "sqlcipher")]
+mod recovery;
+mod related;
+mod representation;
+mod retention;
+mod rich_text;
+mod schema;
+mod secrets;
+#[cfg(feature = "sqlcipher")]
+mod share;
+mod source_app;
+mod stats;
+#[cfg(feature = "sqlcipher")]
 mod store;
+mod text_limit;
+#[cfg(feature = "images")]
+mod thumbnail;
+mod time_format;
+mod timestamp;
+mod transform;
+#[cfg(feature = "sqlcipher")]
+mod uninstall;
+mod unlock;
+mod vault;
 
+pub use accessibility::{speak, EntryDescription};
+pub use alerts::{send_webhook, AlertConfig, CaptureAlert};
+#[cfg(feature = "sqlcipher")]
+pub use audit::{AuditAction, AuditRecord, AuditVerification};
+pub use backup::{BackupConfig, Remote, S3Config, WebDavConfig};
+pub use capture_queue::{Capture, CaptureQueue, FailedCapture, OverflowPolicy};
+#[cfg(feature = "sqlcipher")]
+pub use cipher::{cipher_path, CipherOptions, HmacAlgorithm, SecurityProfile};
+pub use classify::{is_otp_like, ItemKind, DEFAULT_OTP_TTL};
+pub use color::Rgba;
+pub use config::{config_path, CachedConfig, Config, SavedSearch, DEFAULT_STORAGE_WARNING_MB};
+pub use context::{context_path, current_context, set_current_context, CaptureMetadata};
+pub use copy_events::{CopyEvent, CopyEventKind, MAX_COPY_EVENTS};
+pub use crash::{install_panic_hook, write_crash_report};
+pub use custom_format::{CustomFormat, MAX_CUSTOM_FORMAT_BYTES};
+pub use dedupe::{normalize_url, DedupePolicy, DedupeRules, TextNormalization};
+pub use digest::{DigestEntry, WeeklyDigest, DIGEST_DAYS};
+pub use dry_run::{AffectedEntry, DryRun};
+#[cfg(feature = "sqlcipher")]
+pub use duplicates::DuplicatePolicy;
 pub use error::{Error, Result};
-pub use store::{SqliteVault, Vault};
+pub use events::{HealthEvent, HealthLevel, PruneReason, VaultEvent};
+pub use export::{ExportFilter, ExportFormat};
+pub use frecency::frecency_score;
+pub use grep::LinePattern;
+pub use i18n::{language, set_language, tr, tr_with, translate, Language, Msg};
+pub use image_info::{strip_image_metadata, ImageInfo};
+#[cfg(feature = "import")]
+pub use import::read_history;
+pub use import::{ImportSource, ImportSummary, ImportedEntry, ImportedHistory};
+pub use item_stats::ItemStats;
+pub use markdown::html_to_markdown;
+pub use navigation::{ListNavigation, Selection};
+pub use paths::{blob_dir, cache_dir, config_dir, data_dir, log_dir, PathsConfig};
+pub use permissions::{Permission, PermissionCheck, PermissionStatus};
+pub use preview::{truncate_at_word, PreviewOptions, PreviewSettings, TextPreview};
+pub use profiles::{MultiVault, ProfileEntry, VaultProfile, DEFAULT_PROFILE};
+pub use provenance::{device_name, ConflictVersion, Provenance, SyncConflict};
+pub use query::{Cmp, Filter, SearchQuery};
+#[cfg(feature = "sqlcipher")]
+pub use recovery::{password_hint, set_password_hint};
+pub use related::{relations, text_similarity, url_domains, RelatedItem, Relation, RELATED_WINDOW};
+pub use representation::Representation;
+pub use retention::RetentionPolicy;
+pub use rich_text::{RichText, HTML_MIME, RTF_MIME};
+pub use schema::{Migration, SchemaInfo, MIGRATIONS, SCHEMA_VERSION};
+pub use secrets::{contains_secret, find_secrets, redact_secrets, REDACTED};
+#[cfg(feature = "sqlcipher")]
+pub use share::{read_shared, share_entry};
+pub use source_app::SourceApp;
+pub use stats::{
+    local_day_range, storage_warning, ActivityBucket, HistogramBucket, StorageInfo, TimelineHour,
+    GROWTH_WINDOW_DAYS,
+};
+#[cfg(feature = "sqlcipher")]
+pub use store::{
+    ensure_vault_closed, snapshot_archive_path, vault_file_size, vault_files, SqliteVault,
+};
+pub use text_limit::{format_size, Limited, OversizeAction, TextLimit};
+#[cfg(feature = "images")]
+pub use thumbnail::{thumbnail, THUMBNAIL_SIZE};
+pub use time_format::{format_timestamp, system_locale, TimestampStyle};
+pub use timestamp::Timestamp;
+pub use transform::{
+    apply_capture_transforms, copy_as, detect_language, CaptureTransform, CopyFormat,
+};
+#[cfg(feature = "sqlcipher")]
+pub use uninstall::{shred_vault, uninstall, UninstallReport};
+#[cfg(feature = "keyring")]
+pub use unlock::{forget_keyring_password, keyring_password, save_keyring_password};
+pub use unlock::{last_lock_request, request_lock, LockRequests, SensitiveAction, UnlockPolicy};
+pub use vault::Vault;
 
 #[must_use]
 pub fn default_db_path() -> PathBuf {
     if let Ok(path) = std::env::var("CLIP_VAULT_DB_PATH") {
         PathBuf::from(path)
     } else {
-        let base = dirs::data_dir().unwrap_or_else(std::env::temp_dir);
-        base.join("clip-vault").join("clip_vault.db")
+        data_dir().join("clip_vault.db")
     }
 }
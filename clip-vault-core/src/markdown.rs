@@ -0,0 +1,283 @@
+//! Convert the HTML flavour of a copy into Markdown, so web content pastes
+//! into notes apps as clean text. Covers the structure browsers put on the
+//! clipboard (headings, emphasis, links, lists, code, quotes); anything else
+//! is reduced to its text.
+
+use std::fmt::Write;
+
+/// Convert an HTML fragment to Markdown.
+#[must_use]
+pub fn html_to_markdown(html: &str) -> String {
+    let mut w = Writer::default();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+        } else if let Some(after) = rest.strip_prefix('<') {
+            // An unterminated `<` isn't a tag; the rest is literal text.
+            let Some(end) = after.find('>') else {
+                w.text(&decode_entities(rest));
+                break;
+            };
+            w.tag(&after[..end]);
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            w.text(&decode_entities(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    while !w.quotes.is_empty() {
+        w.close_quote();
+    }
+    w.out.trim().to_string()
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    /// One entry per open list: `None` for bullets, the next number for
+    /// ordered lists.
+    lists: Vec<Option<usize>>,
+    links: Vec<Option<String>>,
+    /// Output preceding each open `<blockquote>`; quoted content is written
+    /// to a fresh buffer and prefixed with `> ` when the quote closes.
+    quotes: Vec<String>,
+    in_pre: bool,
+    /// Inside `<script>`, `<style>` or `<head>`, whose text isn't content.
+    skip_depth: usize,
+}
+
+impl Writer {
+    fn tag(&mut self, raw: &str) {
+        let raw = raw.trim_end_matches('/').trim();
+        let (closing, raw) = match raw.strip_prefix('/') {
+            Some(raw) => (true, raw),
+            None => (false, raw),
+        };
+        let name_end = raw
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(raw.len());
+        let name = raw[..name_end].to_ascii_lowercase();
+        let attrs = &raw[name_end..];
+
+        if matches!(name.as_str(), "script" | "style" | "head") {
+            if closing {
+                self.skip_depth = self.skip_depth.saturating_sub(1);
+            } else {
+                self.skip_depth += 1;
+            }
+            return;
+        }
+        if self.skip_depth > 0 {
+            return;
+        }
+
+        match (name.as_str(), closing) {
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                self.block();
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            ("p" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "table", _) => self.block(),
+            ("tr", true) | ("br", _) => self.newline(),
+            ("td" | "th", true) => self.out.push(' '),
+            ("hr", _) => {
+                self.block();
+                self.out.push_str("---");
+                self.block();
+            }
+            ("strong" | "b", _) => self.out.push_str("**"),
+            ("em" | "i", _) => self.out.push('*'),
+            ("del" | "s", _) => self.out.push_str("~~"),
+            ("code", _) if !self.in_pre => self.out.push('`'),
+            ("pre", _) => self.pre(closing),
+            ("blockquote", false) => {
+                self.block();
+                self.quotes.push(std::mem::take(&mut self.out));
+            }
+            ("blockquote", true) => self.close_quote(),
+            ("ul", false) => self.open_list(None),
+            ("ol", false) => self.open_list(Some(1)),
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block();
+                }
+            }
+            ("li", false) => self.list_item(),
+            ("a", false) => {
+                let href = attr(attrs, "href");
+                if href.is_some() {
+                    self.out.push('[');
+                }
+                self.links.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = self.links.pop() {
+                    let _ = write!(self.out, "]({href})");
+                }
+            }
+            ("img", false) => {
+                if let Some(src) = attr(attrs, "src") {
+                    let alt = attr(attrs, "alt").unwrap_or_default();
+                    let _ = write!(self.out, "![{alt}]({src})");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        if self.in_pre {
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    self.newline();
+                }
+                self.out.push_str(line);
+            }
+            return;
+        }
+        // Collapse whitespace runs the way a browser renders them.
+        for c in text.chars() {
+            if c.is_whitespace() {
+                if !(self.out.is_empty() || self.out.ends_with([' ', '\n'])) {
+                    self.out.push(' ');
+                }
+            } else {
+                self.out.push(c);
+            }
+        }
+    }
+
+    fn pre(&mut self, closing: bool) {
+        if closing {
+            self.in_pre = false;
+            if !self.out.ends_with('\n') {
+                self.newline();
+            }
+            self.out.push_str("```");
+            self.block();
+        } else {
+            self.block();
+            self.out.push_str("```");
+            self.newline();
+            self.in_pre = true;
+        }
+    }
+
+    fn close_quote(&mut self) {
+        let Some(before) = self.quotes.pop() else {
+            return;
+        };
+        let quoted = std::mem::replace(&mut self.out, before);
+        for line in quoted.trim().lines() {
+            self.out.push_str(if line.is_empty() { ">" } else { "> " });
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+        self.block();
+    }
+
+    fn list_item(&mut self) {
+        if !(self.out.is_empty() || self.out.ends_with('\n')) {
+            self.newline();
+        }
+        let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+        self.out.push_str(&indent);
+        match self.lists.last_mut() {
+            Some(Some(n)) => {
+                let _ = write!(self.out, "{n}. ");
+                *n += 1;
+            }
+            _ => self.out.push_str("- "),
+        }
+    }
+
+    fn open_list(&mut self, start: Option<usize>) {
+        if self.lists.is_empty() {
+            self.block();
+        }
+        self.lists.push(start);
+    }
+
+    fn newline(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        self.out.push('\n');
+    }
+
+    /// End the current block with a blank line, unless one is already there.
+    fn block(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\n']).len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() {
+            self.out.push_str("\n\n");
+        }
+    }
+}
+
+/// Value of attribute `name` in a tag's attribute text.
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let lower = attrs.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(name) {
+        let start = from + found;
+        from = start + name.len();
+        // Skip matches inside longer names, e.g. `data-href`.
+        if !lower[..start].ends_with(|c: char| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let Some(value) = attrs[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let value = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => value[1..].split(q).next().unwrap_or_default(),
+            _ => value.split_ascii_whitespace().next().unwrap_or_default(),
+        };
+        return Some(decode_entities(value));
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                _ => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+        if let Some((c, end)) = decoded {
+            out.push(c);
+            rest = &rest[end + 1..];
+        } else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,67 @@
+//! Which entry of a result list is selected, kept outside any one frontend
+//! so the search window and whatever else drives it (a remote control, an
+//! assistive tech bridge) move through the same list.
+
+use serde::Serialize;
+
+/// The selected entry of a result list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Selection {
+    /// Position in the list, from 0.
+    pub index: usize,
+    pub hash: [u8; 32],
+}
+
+/// A result list and the entry selected in it. Moving stops at either end
+/// instead of wrapping, as arrow keys in the search window do.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListNavigation {
+    entries: Vec<[u8; 32]>,
+    selected: usize,
+}
+
+impl ListNavigation {
+    /// Replace the list, e.g. after a new search or another page of
+    /// results. The selection stays on the same entry if it is still
+    /// listed and goes back to the first one otherwise.
+    pub fn set_entries(&mut self, entries: Vec<[u8; 32]>) {
+        let current = self.entries.get(self.selected).copied();
+        self.selected = current
+            .and_then(|hash| entries.iter().position(|e| *e == hash))
+            .unwrap_or(0);
+        self.entries = entries;
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `None` while the list is empty.
+    #[must_use]
+    pub fn selected(&self) -> Option<Selection> {
+        self.entries.get(self.selected).map(|&hash| Selection {
+            index: self.selected,
+            hash,
+        })
+    }
+
+    /// Select the entry at `index`, or the last one if the list is shorter.
+    pub fn select(&mut self, index: usize) -> Option<Selection> {
+        self.selected = index.min(self.entries.len().saturating_sub(1));
+        self.selected()
+    }
+
+    pub fn select_next(&mut self) -> Option<Selection> {
+        self.select(self.selected + 1)
+    }
+
+    pub fn select_previous(&mut self) -> Option<Selection> {
+        self.select(self.selected.saturating_sub(1))
+    }
+}
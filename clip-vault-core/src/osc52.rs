@@ -0,0 +1,49 @@
+//! OSC 52 clipboard escape sequences, used to reach the local terminal's
+//! clipboard over SSH and to accept clipboard writes on headless hosts.
+
+use base64::{engine::general_purpose, Engine as _};
+
+const PREFIX: &str = "\x1b]52;";
+
+/// Build the sequence that asks the terminal to set its clipboard to `text`.
+/// Inside tmux the sequence is wrapped in a passthrough so it reaches the
+/// outer terminal.
+#[must_use]
+pub fn encode(text: &str, tmux_passthrough: bool) -> String {
+    let seq = format!("{PREFIX}c;{}\x07", general_purpose::STANDARD.encode(text));
+    if tmux_passthrough {
+        format!("\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b"))
+    } else {
+        seq
+    }
+}
+
+/// Extract the text of every clipboard write in `input`. Sequences may end
+/// with BEL or ST; queries (`?`) and undecodable payloads are ignored.
+#[must_use]
+pub fn decode_all(input: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = input;
+    while let Some(start) = rest.find(PREFIX) {
+        rest = &rest[start + PREFIX.len()..];
+        let end = rest.find(['\x07', '\x1b']).unwrap_or(rest.len());
+        let body = &rest[..end];
+        rest = &rest[end..];
+
+        // body is "<selection>;<base64>"
+        let Some((_, payload)) = body.split_once(';') else {
+            continue;
+        };
+        if payload == "?" {
+            continue;
+        }
+        if let Some(text) = general_purpose::STANDARD
+            .decode(payload.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+        {
+            out.push(text);
+        }
+    }
+    out
+}
@@ -0,0 +1,143 @@
+//! Where clip-vault keeps its files, resolved the same way by the CLI and
+//! the app. Each directory is, in order of precedence:
+//!
+//! 1. its own environment variable (`CLIP_VAULT_DATA_DIR`, ...);
+//! 2. the matching entry under `paths` in the shared config (not for the
+//!    config directory itself);
+//! 3. a subdirectory of `CLIP_VAULT_HOME`, to keep everything in one place;
+//! 4. the platform default, which on Linux follows the XDG base directory
+//!    variables.
+//!
+//! The older single-file overrides (`CLIP_VAULT_DB_PATH`, `CLIP_VAULT_CONFIG`,
+//! `CLIP_VAULT_CONTEXT_PATH`) still take precedence over all of these.
+//!
+//! The `paths` entries are read from the config once per process, so a
+//! change to them takes effect when the CLI or the app next starts.
+
+use crate::Config;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const APP_DIR: &str = "clip-vault";
+
+/// Directory overrides kept in the shared config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathsConfig {
+    /// The vault.
+    pub data_dir: Option<PathBuf>,
+    /// The CLI's password cache and the shell hook's current context.
+    pub cache_dir: Option<PathBuf>,
+    /// Logs and crash reports.
+    pub log_dir: Option<PathBuf>,
+    /// Spill files holding the full originals of truncated entries.
+    pub blob_dir: Option<PathBuf>,
+}
+
+/// One of the directories clip-vault resolves.
+struct Dir {
+    env: &'static str,
+    configured: fn(&PathsConfig) -> Option<PathBuf>,
+    /// Name under `CLIP_VAULT_HOME`.
+    home_subdir: &'static str,
+}
+
+impl Dir {
+    /// The override for this directory, if anything sets one.
+    fn overridden(&self) -> Option<PathBuf> {
+        if let Some(dir) = env_path(self.env) {
+            return Some(dir);
+        }
+        if let Some(dir) = (self.configured)(configured_paths()) {
+            return Some(dir);
+        }
+        env_path("CLIP_VAULT_HOME").map(|home| home.join(self.home_subdir))
+    }
+}
+
+const DATA: Dir = Dir {
+    env: "CLIP_VAULT_DATA_DIR",
+    configured: |paths| paths.data_dir.clone(),
+    home_subdir: "data",
+};
+const CACHE: Dir = Dir {
+    env: "CLIP_VAULT_CACHE_DIR",
+    configured: |paths| paths.cache_dir.clone(),
+    home_subdir: "cache",
+};
+const LOG: Dir = Dir {
+    env: "CLIP_VAULT_LOG_DIR",
+    configured: |paths| paths.log_dir.clone(),
+    home_subdir: "logs",
+};
+const BLOB: Dir = Dir {
+    env: "CLIP_VAULT_BLOB_DIR",
+    configured: |paths| paths.blob_dir.clone(),
+    home_subdir: "blobs",
+};
+
+/// The `paths` entries of the shared config, loaded on first use.
+fn configured_paths() -> &'static PathsConfig {
+    static PATHS: OnceLock<PathsConfig> = OnceLock::new();
+    PATHS.get_or_init(|| Config::load().unwrap_or_default().paths)
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The clip-vault directory under a platform default, or under the temp
+/// directory where there is none.
+fn platform_dir(base: Option<PathBuf>) -> PathBuf {
+    base.unwrap_or_else(std::env::temp_dir).join(APP_DIR)
+}
+
+/// Holds `config.json`. Set with `CLIP_VAULT_CONFIG_DIR` or
+/// `CLIP_VAULT_HOME`; it can't come from the config it holds.
+#[must_use]
+pub fn config_dir() -> PathBuf {
+    env_path("CLIP_VAULT_CONFIG_DIR")
+        .or_else(|| env_path("CLIP_VAULT_HOME"))
+        .unwrap_or_else(|| platform_dir(dirs::config_dir()))
+}
+
+/// Holds the vault.
+#[must_use]
+pub fn data_dir() -> PathBuf {
+    DATA.overridden()
+        .unwrap_or_else(|| platform_dir(dirs::data_dir()))
+}
+
+/// Holds files that can be lost without losing history.
+#[must_use]
+pub fn cache_dir() -> PathBuf {
+    CACHE
+        .overridden()
+        .unwrap_or_else(|| platform_dir(dirs::cache_dir()))
+}
+
+/// Where logs and crash reports go.
+#[must_use]
+pub fn log_dir() -> PathBuf {
+    if let Some(dir) = LOG.overridden() {
+        return dir;
+    }
+    if cfg!(target_os = "macos") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join("Library").join("Logs").join(APP_DIR);
+        }
+    }
+    platform_dir(dirs::data_local_dir()).join("logs")
+}
+
+/// Where spill files go. `None` when nothing overrides it: each spill file
+/// then sits next to its vault. Spill files in this directory are named
+/// after their vault's full path, so vaults of different profiles can share
+/// it.
+#[must_use]
+pub fn blob_dir() -> Option<PathBuf> {
+    BLOB.overridden()
+}
@@ -0,0 +1,155 @@
+//! Preflight checks for the OS permissions capturing and pasting depend on.
+//! Probing the clipboard needs a clipboard backend, which the CLI and the app
+//! each bring, so callers run that probe and hand the outcome over here.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Clipboard,
+    /// macOS: lets Clip Vault paste into the frontmost app.
+    Accessibility,
+    Notifications,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionStatus {
+    Granted,
+    Denied,
+    /// The OS hasn't asked the user yet.
+    NotDetermined,
+    /// This platform or binary doesn't need it.
+    NotApplicable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PermissionCheck {
+    pub permission: Permission,
+    pub status: PermissionStatus,
+    /// What the permission is for, or why the check failed.
+    pub detail: String,
+    /// Where the user can grant it, when the OS has such a page.
+    pub settings_url: Option<&'static str>,
+}
+
+impl PermissionCheck {
+    /// `access` is the outcome of reading the clipboard.
+    #[must_use]
+    pub fn clipboard(access: std::result::Result<(), String>) -> Self {
+        let (status, detail) = match access {
+            Ok(()) => (
+                PermissionStatus::Granted,
+                "clipboard can be read".to_string(),
+            ),
+            Err(reason) => (PermissionStatus::Denied, reason),
+        };
+        Self {
+            permission: Permission::Clipboard,
+            status,
+            detail,
+            settings_url: Permission::Clipboard.settings_url(),
+        }
+    }
+
+    #[must_use]
+    pub fn accessibility() -> Self {
+        let status = match accessibility_trusted() {
+            Some(true) => PermissionStatus::Granted,
+            Some(false) => PermissionStatus::Denied,
+            None => PermissionStatus::NotApplicable,
+        };
+        let detail = if status == PermissionStatus::NotApplicable {
+            "only macOS asks for this"
+        } else {
+            "needed to paste into other apps"
+        };
+        Self {
+            permission: Permission::Accessibility,
+            status,
+            detail: detail.to_string(),
+            settings_url: Permission::Accessibility.settings_url(),
+        }
+    }
+
+    #[must_use]
+    pub fn notifications(status: PermissionStatus) -> Self {
+        let detail = if status == PermissionStatus::NotApplicable {
+            "only the desktop app sends notifications"
+        } else {
+            "used to confirm copies and report problems"
+        };
+        Self {
+            permission: Permission::Notifications,
+            status,
+            detail: detail.to_string(),
+            settings_url: Permission::Notifications.settings_url(),
+        }
+    }
+
+    /// Whether this check needs the user's attention.
+    #[must_use]
+    pub fn is_problem(&self) -> bool {
+        matches!(
+            self.status,
+            PermissionStatus::Denied | PermissionStatus::NotDetermined
+        )
+    }
+}
+
+impl Permission {
+    /// The System Settings page where this permission is granted (macOS).
+    #[must_use]
+    pub fn settings_url(self) -> Option<&'static str> {
+        if !cfg!(target_os = "macos") {
+            return None;
+        }
+        match self {
+            Self::Clipboard => None,
+            Self::Accessibility => Some(
+                "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility",
+            ),
+            Self::Notifications => {
+                Some("x-apple.systempreferences:com.apple.preference.notifications")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Clipboard => "clipboard",
+            Self::Accessibility => "accessibility",
+            Self::Notifications => "notifications",
+        })
+    }
+}
+
+impl fmt::Display for PermissionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Granted => "granted",
+            Self::Denied => "denied",
+            Self::NotDetermined => "not requested yet",
+            Self::NotApplicable => "not needed",
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn accessibility_trusted() -> Option<bool> {
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+    // SAFETY: takes no arguments and only reads this process's trust state.
+    Some(unsafe { AXIsProcessTrusted() })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn accessibility_trusted() -> Option<bool> {
+    None
+}
@@ -0,0 +1,131 @@
+//! The few lines of a text entry a result list shows. Built here so the
+//! TUI and the app cut text the same way: at a word boundary where there
+//! is one, and with counts of what was left out.
+
+use serde::{Deserialize, Serialize};
+
+/// How much of a text entry a result list shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewOptions {
+    /// Characters across all shown lines, before the rest is cut.
+    pub max_chars: usize,
+    /// Lines shown; 1 flattens the entry onto a single line.
+    pub max_lines: usize,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        Self {
+            max_chars: 80,
+            max_lines: 1,
+        }
+    }
+}
+
+/// Preview settings for each surface that lists entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewSettings {
+    /// Rows of the TUI list.
+    pub tui: PreviewOptions,
+    /// Results in the app's search window.
+    pub app: PreviewOptions,
+}
+
+impl Default for PreviewSettings {
+    fn default() -> Self {
+        Self {
+            tui: PreviewOptions::default(),
+            app: PreviewOptions {
+                max_chars: 200,
+                max_lines: 3,
+            },
+        }
+    }
+}
+
+/// The start of a text entry as a result list shows it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextPreview {
+    /// The lines to show. With `max_lines` of 1, line breaks in what is
+    /// shown become `↵`.
+    pub lines: Vec<String>,
+    /// Whether anything was left out.
+    pub truncated: bool,
+    /// Lines in the whole entry.
+    pub total_lines: usize,
+    /// Characters in the whole entry.
+    pub total_chars: usize,
+    /// Lines of the entry not shown at all, for a "+42 more lines" note.
+    pub more_lines: usize,
+}
+
+impl TextPreview {
+    /// Preview `text`, cutting it to `options`.
+    #[must_use]
+    pub fn new(text: &str, options: PreviewOptions) -> Self {
+        let total_chars = text.chars().count();
+        let all_lines: Vec<&str> = text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        let total_lines = all_lines.len().max(1);
+        let max_lines = options.max_lines.max(1);
+
+        let mut budget = options.max_chars.max(1);
+        let mut truncated = false;
+        let mut lines = Vec::new();
+        if max_lines == 1 {
+            let flat = all_lines.join("↵");
+            let (line, cut) = truncate_at_word(&flat, budget);
+            truncated = cut;
+            lines.push(line);
+        } else {
+            for line in all_lines.iter().take(max_lines) {
+                if budget == 0 {
+                    truncated = true;
+                    break;
+                }
+                let (shown, cut) = truncate_at_word(line, budget);
+                budget = budget.saturating_sub(line.chars().count());
+                lines.push(shown);
+                if cut {
+                    truncated = true;
+                    break;
+                }
+            }
+        }
+
+        // Lines reached at all, even if only partly shown.
+        let shown = if max_lines == 1 {
+            lines[0].matches('↵').count() + 1
+        } else {
+            lines.len()
+        };
+        let more_lines = total_lines.saturating_sub(shown);
+        Self {
+            lines,
+            truncated: truncated || more_lines > 0,
+            total_lines,
+            total_chars,
+            more_lines,
+        }
+    }
+}
+
+/// The first `max_chars` characters of `text`, cut back to the last word
+/// boundary unless that would lose more than half of them, with `…` when
+/// anything was cut. Returns whether it was.
+#[must_use]
+pub fn truncate_at_word(text: &str, max_chars: usize) -> (String, bool) {
+    let Some((end, _)) = text.char_indices().nth(max_chars) else {
+        return (text.to_string(), false);
+    };
+    let head = &text[..end];
+    let cut = match head.rfind(char::is_whitespace) {
+        Some(space) if head[..space].chars().count() * 2 >= max_chars => &head[..space],
+        _ => head,
+    };
+    (format!("{}…", cut.trim_end()), true)
+}
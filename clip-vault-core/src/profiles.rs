@@ -0,0 +1,108 @@
+//! Searching several vaults at once. Each profile is a vault of its own,
+//! with its own password; [`MultiVault`] runs the read-only queries across
+//! whichever of them are unlocked and merges the results newest first.
+
+use crate::{ClipboardItemWithTimestamp, Result, Vault};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What the vault at [`default_db_path`](crate::default_db_path) is called
+/// next to the configured profiles.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A vault searched alongside the default one, e.g. a work history kept
+/// apart from a personal one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultProfile {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// An entry and the profile it was found in.
+#[derive(Debug, Clone)]
+pub struct ProfileEntry {
+    pub profile: String,
+    pub entry: ClipboardItemWithTimestamp,
+}
+
+/// The query side of [`Vault`] over several vaults, each labelled with its
+/// profile name. Writes stay with the individual vaults.
+#[derive(Default)]
+pub struct MultiVault<'a> {
+    vaults: Vec<(String, &'a dyn Vault)>,
+}
+
+impl<'a> MultiVault<'a> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include `vault` in queries, its entries labelled `profile`.
+    pub fn add(&mut self, profile: impl Into<String>, vault: &'a dyn Vault) {
+        self.vaults.push((profile.into(), vault));
+    }
+
+    /// The vault included as `profile`.
+    #[must_use]
+    pub fn get(&self, profile: &str) -> Option<&'a dyn Vault> {
+        self.vaults
+            .iter()
+            .find(|(name, _)| name == profile)
+            .map(|(_, vault)| *vault)
+    }
+
+    /// Names of the included profiles, in the order they were added.
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.vaults.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Entries of every vault, newest first.
+    pub fn list(
+        &self,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ProfileEntry>> {
+        self.merge(limit, |vault| vault.list(limit, after_timestamp))
+    }
+
+    /// [`Vault::search`] in every vault, matches newest first.
+    pub fn search(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ProfileEntry>> {
+        self.merge(limit, |vault| vault.search(query, limit, after_timestamp))
+    }
+
+    /// Entries across all vaults.
+    pub fn len(&self) -> Result<usize> {
+        self.vaults.iter().map(|(_, vault)| vault.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Run `query` on each vault and keep the newest `limit` entries. Each
+    /// vault is asked for `limit` too, which is all a merge can use.
+    fn merge(
+        &self,
+        limit: Option<usize>,
+        query: impl Fn(&dyn Vault) -> Result<Vec<ClipboardItemWithTimestamp>>,
+    ) -> Result<Vec<ProfileEntry>> {
+        let mut merged = Vec::new();
+        for (profile, vault) in &self.vaults {
+            merged.extend(query(*vault)?.into_iter().map(|entry| ProfileEntry {
+                profile: profile.clone(),
+                entry,
+            }));
+        }
+        merged.sort_by_key(|found| std::cmp::Reverse(found.entry.timestamp));
+        if let Some(limit) = limit {
+            merged.truncate(limit);
+        }
+        Ok(merged)
+    }
+}
@@ -0,0 +1,64 @@
+//! Which device wrote an entry, for vaults shared between machines (a
+//! data directory in a synced folder, say). Edits remember the entry they
+//! started from, so two devices editing the same entry differently show up
+//! as a [`SyncConflict`] to resolve rather than as two unrelated entries.
+
+use crate::ClipboardItemWithTimestamp;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Where and when an entry was last written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// `None` for entries written before provenance was recorded.
+    pub device: Option<String>,
+    /// Position in the vault's sequence of writes, across all devices.
+    pub generation: Option<u64>,
+    /// The entry this one was edited from, if it is an edit.
+    pub edited_from: Option<[u8; 32]>,
+}
+
+/// One entry edited into different versions on different devices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncConflict {
+    /// Hash of the entry before it was edited.
+    pub original: [u8; 32],
+    /// Newest first.
+    pub versions: Vec<ConflictVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictVersion {
+    pub entry: ClipboardItemWithTimestamp,
+    pub device: String,
+    pub generation: u64,
+}
+
+/// The name entries written on this machine are marked with: its host
+/// name, or `CLIP_VAULT_DEVICE` if set.
+#[must_use]
+pub fn device_name() -> String {
+    static NAME: OnceLock<String> = OnceLock::new();
+    NAME.get_or_init(|| {
+        std::env::var("CLIP_VAULT_DEVICE")
+            .ok()
+            .or_else(host_name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    })
+    .clone()
+}
+
+fn host_name() -> Option<String> {
+    if let Some(name) = ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+    {
+        return Some(name);
+    }
+    let output = Command::new("hostname").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,210 @@
+//! Search query syntax: free text plus `size:`, `dim:`, `tag:`, `app:`,
+//! `kind:`, `since:` and `before:` filters, e.g. `size:>1mb dim:>=1920x1080`,
+//! `tag:work deploy`, `app:slack`, `kind:url since:today` or
+//! `before:2024-06-01`. Tag and app names with spaces go in double quotes,
+//! e.g. `tag:"side project"`, with `\"` and `\\` for a quote or backslash.
+
+use crate::{local_day_range, ItemKind, Timestamp};
+use chrono::{Local, NaiveDate};
+use std::time::Duration;
+
+/// Comparison used by a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Cmp {
+    fn split(s: &str) -> (Self, &str) {
+        for (prefix, cmp) in [
+            (">=", Self::Ge),
+            ("<=", Self::Le),
+            (">", Self::Gt),
+            ("<", Self::Lt),
+            ("=", Self::Eq),
+        ] {
+            if let Some(rest) = s.strip_prefix(prefix) {
+                return (cmp, rest);
+            }
+        }
+        (Self::Eq, s)
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    pub(crate) fn as_sql(self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Eq => "=",
+            Self::Ge => ">=",
+            Self::Gt => ">",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// Stored payload size in bytes.
+    Size(Cmp, u64),
+    /// Image width and height; both must satisfy the comparison.
+    Dim(Cmp, u32, u32),
+    /// Entries with this tag.
+    Tag(String),
+    /// Entries copied from an app whose name contains this, ignoring case.
+    App(String),
+    /// Entries of this kind, e.g. links or code.
+    Kind(ItemKind),
+    /// Entries last copied at or after this time.
+    Since(Timestamp),
+    /// Entries last copied before this time.
+    Before(Timestamp),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Free text, matched as a substring.
+    pub text: String,
+    pub filters: Vec<Filter>,
+}
+
+impl SearchQuery {
+    /// Split filter tokens out of `query`. Tokens that look like filters but
+    /// don't parse are kept as text.
+    #[must_use]
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut text = Vec::new();
+        for token in tokens(query) {
+            match parse_filter(token) {
+                Some(filter) => parsed.filters.push(filter),
+                None => text.push(token),
+            }
+        }
+        parsed.text = text.join(" ");
+        parsed
+    }
+
+    /// `value` in double quotes, so it reads back as one filter value
+    /// whatever spaces or quotes it contains.
+    #[must_use]
+    pub fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// Split on spaces outside double quotes. Tokens are returned as written, so
+/// text reads back the same when joined.
+fn tokens(query: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (i, c) in query.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ' ' if !quoted => {
+                tokens.push(&query[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    tokens.push(&query[start..]);
+    tokens
+}
+
+/// A tag or app name, taken from inside double quotes with their escapes
+/// undone if it's quoted. `None` if it's empty.
+fn name(value: &str) -> Option<String> {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return (!value.is_empty()).then(|| value.to_string());
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        unquoted.push(if c == '\\' {
+            chars.next().unwrap_or(c)
+        } else {
+            c
+        });
+    }
+    (!unquoted.is_empty()).then_some(unquoted)
+}
+
+fn parse_filter(token: &str) -> Option<Filter> {
+    let (key, value) = token.split_once(':')?;
+    let (cmp, value) = Cmp::split(value);
+    match key.to_ascii_lowercase().as_str() {
+        "size" => Some(Filter::Size(cmp, parse_size(value)?)),
+        "dim" => {
+            let (w, h) = value
+                .to_ascii_lowercase()
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))?;
+            Some(Filter::Dim(cmp, w, h))
+        }
+        "tag" if cmp == Cmp::Eq => name(value).map(Filter::Tag),
+        "app" if cmp == Cmp::Eq => name(value).map(Filter::App),
+        "kind" if cmp == Cmp::Eq => ItemKind::parse(value).map(Filter::Kind),
+        "since" if cmp == Cmp::Eq => parse_time(value).map(Filter::Since),
+        "before" if cmp == Cmp::Eq => parse_time(value).map(Filter::Before),
+        _ => None,
+    }
+}
+
+/// `today` and `yesterday` or a date like `2024-06-01`, meaning the start of
+/// that day in local time, or an age like `30m`, `2h`, `7d` or `2w`, meaning
+/// that long ago.
+fn parse_time(value: &str) -> Option<Timestamp> {
+    let value = value.to_ascii_lowercase();
+    let today = Local::now().date_naive();
+    let day = match value.as_str() {
+        "today" => Some(today),
+        "yesterday" => today.pred_opt(),
+        _ => NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok(),
+    };
+    if let Some(day) = day {
+        return Some(local_day_range(day).start);
+    }
+
+    let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+    let secs = match unit {
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return None,
+    };
+    let age = Duration::from_secs(number.parse::<u64>().ok()?.checked_mul(secs)?);
+    Some(Timestamp::now().saturating_sub(age))
+}
+
+/// `512`, `10kb`, `1.5mb`, `2gb` (binary units).
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.to_ascii_lowercase();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit {
+        "" | "b" => 1,
+        "k" | "kb" => 1024,
+        "m" | "mb" => 1024 * 1024,
+        "g" | "gb" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    Some((number * multiplier as f64) as u64)
+}
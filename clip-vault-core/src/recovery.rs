@@ -0,0 +1,126 @@
+//! Recovery keys for forgotten passwords, and the optional password hint.
+//!
+//! A recovery key is 32 random bytes shown to the user once, as hex. Clip
+//! Vault never stores it: it encrypts a small sidecar database next to the
+//! vault that holds the vault password, so whoever has the key can unlock
+//! the vault and set a new password. The hint is kept in plain text beside
+//! the vault so it can be shown before unlocking.
+
+use crate::{Error, Result};
+use rusqlite::{params, Connection, OpenFlags};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const KEY_BYTES: usize = 32;
+
+/// Generate a new recovery key, printed as 16 dash-separated groups of four
+/// hex digits.
+pub fn generate_recovery_key() -> Result<String> {
+    random_key(KEY_BYTES)
+}
+
+/// `len` random bytes as dash-separated groups of four hex digits.
+pub(crate) fn random_key(len: usize) -> Result<String> {
+    let mut bytes = vec![0u8; len];
+    getrandom::fill(&mut bytes).map_err(|e| Error::Io(std::io::Error::other(e.to_string())))?;
+    let mut key = String::with_capacity(len * 2 + len / 2);
+    for (i, pair) in bytes.chunks(2).enumerate() {
+        if i > 0 {
+            key.push('-');
+        }
+        let _ = write!(key, "{:02x}{:02x}", pair[0], pair[1]);
+    }
+    Ok(key)
+}
+
+/// Sidecar holding the vault password, encrypted with the recovery key.
+#[must_use]
+pub fn recovery_path(vault: &Path) -> PathBuf {
+    vault.with_extension("recovery.db")
+}
+
+#[must_use]
+pub fn hint_path(vault: &Path) -> PathBuf {
+    vault.with_extension("hint")
+}
+
+/// The hint set for the vault at `vault`, if any.
+pub fn password_hint(vault: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(hint_path(vault)) {
+        Ok(hint) => Ok(Some(hint.trim().to_string()).filter(|h| !h.is_empty())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Set or clear the hint for the vault at `vault`. The hint is not
+/// encrypted, so it must not give the password away.
+pub fn set_password_hint(vault: &Path, hint: Option<&str>) -> Result<()> {
+    let path = hint_path(vault);
+    match hint.map(str::trim).filter(|h| !h.is_empty()) {
+        Some(hint) => std::fs::write(path, hint)?,
+        None => match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        },
+    }
+    Ok(())
+}
+
+/// The raw-key form of a recovery key, accepting any case and any
+/// spacing or dashes the user typed it with.
+fn raw_key(recovery_key: &str) -> Result<String> {
+    let hex: String = recovery_key
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if hex.len() != KEY_BYTES * 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidKey);
+    }
+    // A raw key skips SQLCipher's key derivation, which a random key
+    // doesn't need.
+    Ok(format!("x'{hex}'"))
+}
+
+/// Replace the sidecar at `sidecar` with one holding `password` under
+/// `recovery_key`.
+pub(crate) fn write_password(sidecar: &Path, recovery_key: &str, password: &str) -> Result<()> {
+    let staged = stage_password(sidecar, recovery_key, password)?;
+    std::fs::rename(staged, sidecar)?;
+    Ok(())
+}
+
+/// Write a sidecar holding `password` under `recovery_key` beside
+/// `sidecar`, returning its path. Moving it into place replaces the old
+/// one, which keeps working until then.
+pub(crate) fn stage_password(
+    sidecar: &Path,
+    recovery_key: &str,
+    password: &str,
+) -> Result<PathBuf> {
+    let raw = raw_key(recovery_key)?;
+    let staged = sidecar.with_extension("new.db");
+    // Left over from an attempt that didn't finish.
+    match std::fs::remove_file(&staged) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    let conn = Connection::open(&staged)?;
+    conn.pragma_update(None, "key", &raw)?;
+    conn.execute_batch("CREATE TABLE recovery (password TEXT NOT NULL);")?;
+    conn.execute(
+        "INSERT INTO recovery (password) VALUES (?1);",
+        params![password],
+    )?;
+    conn.close().map_err(|(_, e)| e)?;
+    Ok(staged)
+}
+
+pub(crate) fn read_password(sidecar: &Path, recovery_key: &str) -> Result<String> {
+    let raw = raw_key(recovery_key)?;
+    let conn = Connection::open_with_flags(sidecar, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.pragma_update(None, "key", &raw)?;
+    conn.query_row("SELECT password FROM recovery;", [], |row| row.get(0))
+        .map_err(|_| Error::InvalidKey)
+}
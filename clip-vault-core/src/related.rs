@@ -0,0 +1,102 @@
+//! Links between entries, for piecing together a workflow that spanned
+//! several copies: a URL and the page title copied next to it, a command and
+//! its output, two revisions of the same snippet.
+
+use crate::{ClipboardItem, ClipboardItemWithTimestamp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Entries in the same context count as related when copied at most this
+/// far apart.
+pub const RELATED_WINDOW: Duration = Duration::from_mins(15);
+
+/// Share of words two texts must have in common to count as similar.
+const SIMILARITY_THRESHOLD: f64 = 0.5;
+
+/// Why two entries are related.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    /// Both contain links to the same site.
+    SameDomain,
+    /// Copied in the same context (see
+    /// [`current_context`](crate::current_context)) within
+    /// [`RELATED_WINDOW`] of each other.
+    SameContext,
+    /// Mostly the same words.
+    SimilarText,
+}
+
+/// An entry related to another, with every reason it is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelatedItem {
+    pub entry: ClipboardItemWithTimestamp,
+    pub relations: Vec<Relation>,
+}
+
+/// How `candidate` relates to `target`; empty if it doesn't.
+#[must_use]
+pub fn relations(
+    target: &ClipboardItemWithTimestamp,
+    candidate: &ClipboardItemWithTimestamp,
+) -> Vec<Relation> {
+    let texts = match (&target.item, &candidate.item) {
+        (ClipboardItem::Text(a), ClipboardItem::Text(b)) => Some((a, b)),
+        _ => None,
+    };
+    let same_domain = texts.is_some_and(|(a, b)| {
+        let domains = url_domains(a);
+        url_domains(b).iter().any(|d| domains.contains(d))
+    });
+    let same_context = target.context.is_some()
+        && target.context == candidate.context
+        && target.timestamp.abs_diff(candidate.timestamp) <= RELATED_WINDOW;
+    let similar = texts.is_some_and(|(a, b)| text_similarity(a, b) >= SIMILARITY_THRESHOLD);
+
+    [
+        (same_domain, Relation::SameDomain),
+        (same_context, Relation::SameContext),
+        (similar, Relation::SimilarText),
+    ]
+    .into_iter()
+    .filter_map(|(holds, relation)| holds.then_some(relation))
+    .collect()
+}
+
+/// Hosts of the `http(s)://` links in `text`, lowercased and without `www.`.
+#[must_use]
+pub fn url_domains(text: &str) -> HashSet<String> {
+    text.match_indices("://")
+        .filter(|(i, _)| text[..*i].ends_with("http") || text[..*i].ends_with("https"))
+        .filter_map(|(i, _)| {
+            let rest = &text[i + 3..];
+            let end = rest
+                .find(|c: char| matches!(c, '/' | '?' | '#' | ':') || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let host = rest[..end].to_lowercase();
+            let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+            (!host.is_empty()).then_some(host)
+        })
+        .collect()
+}
+
+/// Jaccard similarity of the texts' sets of words, from 0 (nothing in
+/// common) to 1 (the same words).
+#[must_use]
+pub fn text_similarity(a: &str, b: &str) -> f64 {
+    let words = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    // Word counts stay far below 2^52.
+    #[allow(clippy::cast_precision_loss)]
+    let score = a.intersection(&b).count() as f64 / a.union(&b).count() as f64;
+    score
+}
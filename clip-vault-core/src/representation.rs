@@ -0,0 +1,50 @@
+//! The other forms a copy came in. Apps often put several on the clipboard
+//! at once: a spreadsheet copies a range as a picture and as text, a
+//! browser an image with its address. An entry is stored as one of them,
+//! the image when there is one; the rest are kept next to it, like the
+//! HTML and RTF flavours, so copying the entry puts them all back.
+
+use serde::{Deserialize, Serialize};
+
+const TEXT_MIME: &str = "text/plain";
+const PNG_MIME: &str = "image/png";
+
+/// One form of a copy, by MIME type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Representation {
+    pub mime: String,
+    pub data: Vec<u8>,
+}
+
+impl Representation {
+    #[must_use]
+    pub fn text(text: &str) -> Self {
+        Self {
+            mime: TEXT_MIME.to_string(),
+            data: text.as_bytes().to_vec(),
+        }
+    }
+
+    /// An image, as PNG bytes like [`ClipboardItem::Image`](crate::ClipboardItem::Image).
+    #[must_use]
+    pub fn png(data: Vec<u8>) -> Self {
+        Self {
+            mime: PNG_MIME.to_string(),
+            data,
+        }
+    }
+
+    /// The text of a plain-text representation.
+    #[must_use]
+    pub fn as_text(&self) -> Option<&str> {
+        (self.mime == TEXT_MIME)
+            .then(|| std::str::from_utf8(&self.data).ok())
+            .flatten()
+    }
+
+    /// The PNG bytes of an image representation.
+    #[must_use]
+    pub fn as_png(&self) -> Option<&[u8]> {
+        (self.mime == PNG_MIME).then_some(self.data.as_slice())
+    }
+}
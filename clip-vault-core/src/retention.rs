@@ -0,0 +1,59 @@
+//! Retention rules enforced by [`Vault::prune`](crate::Vault::prune): how
+//! old entries may get, and how many of them and how much data the vault
+//! keeps.
+
+use crate::ItemKind;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Maximum age per content category, plus caps on the whole vault. `None`
+/// leaves that rule off. Copied files follow the text rule. Pinned,
+/// protected and kept-tag entries are never removed and are left out of
+/// the caps, which only count what the rules can remove.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub text_max_age_secs: Option<u64>,
+    pub image_max_age_secs: Option<u64>,
+    pub otp_max_age_secs: Option<u64>,
+    /// Keep at most this many entries, removing the oldest first.
+    pub max_items: Option<usize>,
+    /// Keep at most this many megabytes of entry data, counting other
+    /// representations, custom formats and spilled originals, removing the
+    /// oldest images first and then the oldest of the rest.
+    pub max_size_mb: Option<u64>,
+    /// Tags whose entries the rules never remove, e.g. `keep`. With a
+    /// count, only that many of the tag's newest entries are exempt and
+    /// older ones are removed as usual.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keep_tags: BTreeMap<String, Option<usize>>,
+}
+
+impl RetentionPolicy {
+    #[must_use]
+    pub fn max_age(&self, kind: ItemKind) -> Option<Duration> {
+        let secs = match kind {
+            ItemKind::Image => self.image_max_age_secs,
+            ItemKind::Otp => self.otp_max_age_secs,
+            _ => self.text_max_age_secs,
+        };
+        secs.map(Duration::from_secs)
+    }
+
+    /// [`max_size_mb`](Self::max_size_mb) in bytes.
+    #[must_use]
+    pub fn max_bytes(&self) -> Option<u64> {
+        self.max_size_mb.map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+
+    /// True when no rule would ever remove anything.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text_max_age_secs.is_none()
+            && self.image_max_age_secs.is_none()
+            && self.otp_max_age_secs.is_none()
+            && self.max_items.is_none()
+            && self.max_size_mb.is_none()
+    }
+}
@@ -0,0 +1,42 @@
+//! Formatting copied alongside plain text. Browsers put HTML on the
+//! clipboard next to the text, word processors RTF too. An entry keeps
+//! them as flavours of its text, so search and dedupe still see one text
+//! entry, and copying it back puts the formatting back with it.
+//!
+//! There are no `ClipboardItem` variants for HTML or RTF: a rich copy
+//! always comes with its plain text, and an entry per flavour would list
+//! and dedupe the same copy two or three times. The flavours live in the
+//! `html` and `rtf` columns of the entry's row instead, read with
+//! `SqliteVault::rich_text`. RTF is only read and written on Windows,
+//! where arboard has no API for it; other platforms keep the HTML.
+
+use serde::{Deserialize, Serialize};
+
+pub const HTML_MIME: &str = "text/html";
+pub const RTF_MIME: &str = "text/rtf";
+
+/// The formatted flavours kept with a text entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RichText {
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+impl RichText {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.html.is_none() && self.rtf.is_none()
+    }
+
+    /// MIME types of the flavours present, HTML first.
+    #[must_use]
+    pub fn mimes(&self) -> Vec<&'static str> {
+        [
+            self.html.as_ref().map(|_| HTML_MIME),
+            self.rtf.as_ref().map(|_| RTF_MIME),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
@@ -0,0 +1,108 @@
+//! Versions of the vault's database schema, so a release can tell a vault
+//! it knows how to upgrade from one written by a newer release that it
+//! would only damage.
+
+use serde::Serialize;
+
+/// Schema version written by this release. Bump it with every change to
+/// the schema and describe the change in [`MIGRATIONS`].
+pub const SCHEMA_VERSION: u32 = 13;
+
+/// A step that opening a vault applies to bring its schema up to date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Migration {
+    /// The schema version the vault has afterwards.
+    pub version: u32,
+    pub description: &'static str,
+}
+
+/// Every migration, oldest first. Vaults from before schema versions were
+/// recorded have version 0.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "add the columns and tables of earlier releases and record the schema version",
+    },
+    Migration {
+        version: 2,
+        description: "record a dedupe key for links so variants of a URL are stored once",
+    },
+    Migration {
+        version: 3,
+        description: "record the app and window each entry was copied from",
+    },
+    Migration {
+        version: 4,
+        description: "keep the RTF flavour copied alongside text",
+    },
+    Migration {
+        version: 5,
+        description: "let entries be pinned above the history and out of pruning",
+    },
+    Migration {
+        version: 6,
+        description: "store a thumbnail with each large image",
+    },
+    Migration {
+        version: 7,
+        description: "classify links, emails, colors, JSON, paths and code",
+    },
+    Migration {
+        version: 8,
+        description: "keep the other forms a copy came in, like text copied with an image",
+    },
+    Migration {
+        version: 9,
+        description: "keep a note with each entry",
+    },
+    Migration {
+        version: 10,
+        description: "record the line and word counts of each entry",
+    },
+    Migration {
+        version: 11,
+        description: "classify rgb() and hsl() colors",
+    },
+    Migration {
+        version: 12,
+        description:
+            "hash file entries apart from text of the same paths and give them their own MIME type",
+    },
+    Migration {
+        version: 13,
+        description: "count copies that looked like secrets and were skipped",
+    },
+];
+
+/// The schema version of a vault and what opening it would do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaInfo {
+    /// The version recorded in the vault.
+    pub found: u32,
+    /// The newest version this release can open.
+    pub supported: u32,
+    /// Migrations the next open applies, oldest first.
+    pub pending: Vec<Migration>,
+}
+
+impl SchemaInfo {
+    #[must_use]
+    pub fn new(found: u32) -> Self {
+        Self {
+            found,
+            supported: SCHEMA_VERSION,
+            pending: MIGRATIONS
+                .iter()
+                .filter(|m| m.version > found)
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Whether this release can open the vault; `false` for one written by
+    /// a newer release.
+    #[must_use]
+    pub fn is_compatible(&self) -> bool {
+        self.found <= self.supported
+    }
+}
@@ -0,0 +1,78 @@
+//! Detection of credentials in copied text: API tokens, private keys and
+//! `password=...` style assignments.
+
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Shown in place of a redacted secret.
+pub const REDACTED: &str = "[REDACTED]";
+
+static PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        // Private key blocks
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+        // AWS access key IDs
+        r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+        // GitHub tokens
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+        r"\bgithub_pat_[A-Za-z0-9_]{22,}\b",
+        // Slack tokens
+        r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+        // Stripe, OpenAI and similar `sk-`/`sk_` keys
+        r"\b[rs]k_(?:live|test)_[A-Za-z0-9]{16,}\b",
+        r"\bsk-[A-Za-z0-9_-]{20,}",
+        // Google API keys
+        r"\bAIza[0-9A-Za-z_-]{35}\b",
+        // JSON Web Tokens
+        r"\beyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+        // `password = hunter2`, `API_KEY: abc`; only the value is secret
+        r#"(?i)\b(?:password|passwd|pwd|secret|token|api[_-]?key)\b["']?\s*[:=]\s*["']?([^\s"',;]+)"#,
+    ]
+    .iter()
+    .map(|p| Regex::new(p).expect("secret patterns are valid"))
+    .collect()
+});
+
+/// Byte ranges of secrets in `text`, sorted and non-overlapping.
+#[must_use]
+pub fn find_secrets(text: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = PATTERNS
+        .iter()
+        .flat_map(|re| {
+            re.captures_iter(text).filter_map(|caps| {
+                // Assignment patterns capture just the value.
+                caps.get(1).or_else(|| caps.get(0)).map(|m| m.range())
+            })
+        })
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[must_use]
+pub fn contains_secret(text: &str) -> bool {
+    PATTERNS.iter().any(|re| re.is_match(text))
+}
+
+/// `text` with every detected secret replaced by [`REDACTED`].
+#[must_use]
+pub fn redact_secrets(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in find_secrets(text) {
+        out.push_str(&text[last..range.start]);
+        out.push_str(REDACTED);
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
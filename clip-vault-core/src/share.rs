@@ -0,0 +1,72 @@
+//! Single-entry share files, for handing one copy to someone else without a
+//! server. A share file is a tiny `SQLCipher` database holding just the entry,
+//! encrypted with a random passphrase that is passed on separately.
+
+use crate::recovery::random_key;
+use crate::{ClipboardItem, ClipboardItemWithTimestamp, Error, Result, Timestamp};
+use rusqlite::{params, Connection, OpenFlags};
+use std::fs::OpenOptions;
+use std::path::Path;
+
+/// Random bytes in a share passphrase.
+const PASSPHRASE_BYTES: usize = 16;
+
+/// Write `entry` to a new share file at `path` and return the passphrase
+/// that opens it. Only the content and when it was copied go in; the
+/// context, like the source app, stays on this machine. Fails if `path`
+/// already exists.
+pub fn share_entry(entry: &ClipboardItemWithTimestamp, path: &Path) -> Result<String> {
+    // Created here rather than by SQLite, so an existing file is never
+    // written to, even one that appears after a check.
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => Error::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            )),
+            _ => Error::Io(e),
+        })?;
+    let passphrase = random_key(PASSPHRASE_BYTES)?;
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "key", &passphrase)?;
+    conn.execute_batch(
+        "CREATE TABLE shared (
+            data    BLOB    NOT NULL,
+            ts      INTEGER NOT NULL
+        );",
+    )?;
+    conn.execute(
+        "INSERT INTO shared (data, ts) VALUES (?1, ?2);",
+        params![
+            bincode::encode_to_vec(&entry.item, bincode::config::standard())?,
+            entry.timestamp
+        ],
+    )?;
+    Ok(passphrase)
+}
+
+/// Read the entry from a share file. A wrong passphrase, or a file that
+/// isn't a share file, is [`Error::InvalidKey`].
+pub fn read_shared(path: &Path, passphrase: &str) -> Result<ClipboardItemWithTimestamp> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.pragma_update(None, "key", passphrase.trim())?;
+    let (blob, timestamp): (Vec<u8>, Timestamp) = conn
+        .query_row("SELECT data, ts FROM shared LIMIT 1;", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|_| Error::InvalidKey)?;
+    let (item, _): (ClipboardItem, usize) =
+        bincode::decode_from_slice(&blob, bincode::config::standard())?;
+    Ok(ClipboardItemWithTimestamp {
+        item,
+        timestamp,
+        // Where it was copied stays on the sharing machine, even if an
+        // older share file has it.
+        context: None,
+        source: None,
+        id: None,
+    })
+}
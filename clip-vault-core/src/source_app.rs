@@ -0,0 +1,134 @@
+//! The application a copy came from: whichever app was frontmost, and its
+//! window title, when the capture loop saw the clipboard change.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceApp {
+    /// e.g. "Safari" or "firefox".
+    pub name: String,
+    pub window_title: Option<String>,
+}
+
+impl SourceApp {
+    /// The frontmost application, asked of System Events on macOS, the
+    /// window manager on Windows and `xdotool` elsewhere. `None` where it
+    /// can't be told, e.g. on Wayland or a headless host.
+    /// `CLIP_VAULT_SOURCE_APP` (and `CLIP_VAULT_SOURCE_WINDOW`) name it
+    /// instead, for scripted captures.
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        if let Ok(name) = std::env::var("CLIP_VAULT_SOURCE_APP") {
+            return Self::new(
+                &name,
+                std::env::var("CLIP_VAULT_SOURCE_WINDOW").ok().as_deref(),
+            );
+        }
+        if cfg!(windows) {
+            return foreground_window();
+        }
+        let output = if cfg!(target_os = "macos") {
+            Command::new("osascript")
+                .args(["-e", FRONTMOST_SCRIPT_MACOS])
+                .output()
+        } else {
+            Command::new("xdotool")
+                .args(["getactivewindow", "getwindowclassname", "getwindowname"])
+                .output()
+        }
+        .ok()
+        .filter(|output| output.status.success())?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut lines = text.lines();
+        Self::new(lines.next()?, lines.next())
+    }
+
+    /// `None` without a name; a blank title is left out.
+    #[must_use]
+    pub fn new(name: &str, window_title: Option<&str>) -> Option<Self> {
+        let name = name.trim();
+        (!name.is_empty()).then(|| Self {
+            name: name.to_string(),
+            window_title: window_title
+                .map(str::trim)
+                .filter(|title| !title.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+/// Prints the app name and its front window's title on separate lines.
+const FRONTMOST_SCRIPT_MACOS: &str = r#"
+tell application "System Events"
+    set p to first application process whose frontmost is true
+    set t to ""
+    try
+        set t to name of front window of p
+    end try
+    return (name of p) & linefeed & t
+end tell
+"#;
+
+/// The foreground window's title and the name of the executable that owns
+/// it, without its extension (`firefox` for `firefox.exe`).
+#[cfg(windows)]
+fn foreground_window() -> Option<SourceApp> {
+    use std::ffi::c_void;
+
+    type Handle = *mut c_void;
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> Handle;
+        fn GetWindowThreadProcessId(window: Handle, process_id: *mut u32) -> u32;
+        fn GetWindowTextW(window: Handle, text: *mut u16, max_count: i32) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit_handle: i32, process_id: u32) -> Handle;
+        fn QueryFullProcessImageNameW(
+            process: Handle,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    let mut title = [0u16; 512];
+    let mut path = [0u16; 1024];
+    let mut path_len = u32::try_from(path.len()).ok()?;
+    // SAFETY: every buffer outlives the call it's passed to and its length
+    // is passed with it, and the process handle is closed once read.
+    let (title_len, found) = unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return None;
+        }
+        let mut process_id: u32 = 0;
+        GetWindowThreadProcessId(window, &mut process_id);
+        let title_len =
+            GetWindowTextW(window, title.as_mut_ptr(), i32::try_from(title.len()).ok()?);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+        if process.is_null() {
+            return None;
+        }
+        let found = QueryFullProcessImageNameW(process, 0, path.as_mut_ptr(), &mut path_len);
+        CloseHandle(process);
+        (title_len, found != 0)
+    };
+    if !found {
+        return None;
+    }
+    let path = String::from_utf16_lossy(path.get(..usize::try_from(path_len).ok()?)?);
+    let title = String::from_utf16_lossy(title.get(..usize::try_from(title_len).ok()?)?);
+    let name = std::path::Path::new(&path).file_stem()?.to_string_lossy();
+    SourceApp::new(&name, Some(&title))
+}
+
+#[cfg(not(windows))]
+fn foreground_window() -> Option<SourceApp> {
+    None
+}
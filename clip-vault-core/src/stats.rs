@@ -0,0 +1,94 @@
+//! Aggregate views over the history, such as capture activity over time and
+//! the entries of a single day.
+
+use crate::{ClipboardItemWithTimestamp, Timestamp};
+use chrono::{Local, NaiveDate, NaiveTime, TimeDelta, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const NANOS_PER_HOUR: u64 = 3_600 * 1_000_000_000;
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Width of one histogram bucket. Buckets are aligned to UTC boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistogramBucket {
+    Hour,
+    Day,
+}
+
+impl HistogramBucket {
+    #[must_use]
+    pub fn as_nanos(self) -> u64 {
+        match self {
+            Self::Hour => NANOS_PER_HOUR,
+            Self::Day => 24 * NANOS_PER_HOUR,
+        }
+    }
+}
+
+/// Number of captures whose timestamp falls in `[start, start + bucket)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityBucket {
+    pub start: Timestamp,
+    pub count: u64,
+}
+
+/// The entries captured during one hour of a
+/// [`Vault::timeline`](crate::Vault::timeline) day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineHour {
+    /// Hour of the day in local time, 0–23.
+    pub hour: u32,
+    /// Oldest first.
+    pub entries: Vec<ClipboardItemWithTimestamp>,
+}
+
+/// Timestamps covering `day` from local midnight to the next,
+/// so a day across a DST change is 23 or 25 hours long.
+#[must_use]
+pub fn local_day_range(day: NaiveDate) -> Range<Timestamp> {
+    let midnight = |day: NaiveDate| {
+        let naive = day.and_time(NaiveTime::MIN);
+        // Where midnight is repeated the day starts at the first one, and
+        // where it's skipped, at the first minute the clock shows after it.
+        (0..MINUTES_PER_DAY)
+            .find_map(|minute| {
+                Local
+                    .from_local_datetime(&(naive + TimeDelta::minutes(minute)))
+                    .earliest()
+            })
+            .map_or(Timestamp::EPOCH, |start| Timestamp::from_datetime(&start))
+    };
+    let next = day.succ_opt().map_or(Timestamp::MAX, midnight);
+    midnight(day)..next
+}
+
+/// Days of captures [`StorageInfo::bytes_per_day`] averages over.
+pub const GROWTH_WINDOW_DAYS: u64 = 7;
+
+/// How much room the vault takes up and how fast it is growing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageInfo {
+    /// Size on disk: the database, its write-ahead log and the spill file.
+    pub file_bytes: u64,
+    /// Stored entries, before encryption and page overhead.
+    pub payload_bytes: u64,
+    pub entries: usize,
+    /// Entry bytes captured per day, averaged over the last
+    /// [`GROWTH_WINDOW_DAYS`] days.
+    pub bytes_per_day: u64,
+}
+
+/// A warning when a vault of `file_bytes` has grown past `limit_bytes`.
+#[must_use]
+pub fn storage_warning(file_bytes: u64, limit_bytes: u64) -> Option<String> {
+    let size = |bytes: u64| crate::format_size(usize::try_from(bytes).unwrap_or(usize::MAX));
+    (file_bytes > limit_bytes).then(|| {
+        format!(
+            "The vault is {}, over the {} warning size",
+            size(file_bytes),
+            size(limit_bytes)
+        )
+    })
+}
@@ -1,103 +1,2853 @@
-use crate::{ClipboardItem, ClipboardItemWithTimestamp, Result};
+use crate::audit::{self, AuditAction};
+use crate::export;
+use crate::recovery;
+use crate::{
+    frecency_score, local_day_range, ActivityBucket, AffectedEntry, AuditRecord, AuditVerification,
+    Capture, CaptureMetadata, CipherOptions, ClipboardItem, ClipboardItemWithTimestamp,
+    ConflictVersion, CopyEvent, CopyEventKind, CustomFormat, DedupePolicy, DedupeRules, DryRun,
+    DuplicatePolicy, Error, ExportFilter, ExportFormat, FailedCapture, Filter, HealthEvent,
+    HistogramBucket, ImageInfo, ImportSummary, ImportedEntry, ItemKind, ItemStats, ListedEntry,
+    Provenance, PruneReason, RelatedItem, Representation, Result, RetentionPolicy, RichText,
+    RowDiagnostic, SchemaInfo, SearchPreview, SearchQuery, SourceApp, StorageInfo, SyncConflict,
+    TimelineHour, Timestamp, Vault, VaultEvent, FILES_MIME, GROWTH_WINDOW_DAYS, MAX_COPY_EVENTS,
+    OLD_FILES_MIME, SCHEMA_VERSION, THUMBNAIL_SIZE,
+};
+use chrono::{NaiveDate, Timelike};
+use rusqlite::trace::{TraceEvent, TraceEventCodes};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
 
-pub trait Vault {
-    fn insert(&self, hash: [u8; 32], item: &ClipboardItem) -> Result<()>;
-    fn latest(&self) -> Result<Option<ClipboardItem>>;
-    fn list(
+/// The `items` columns an archived entry keeps. Searches filter on
+/// `protected`, so it is kept too, though it is always 0.
+const ARCHIVE_COLUMNS: &str =
+    "hash, mime, text, data, ts, context, size, width, height, format, kind, html, rtf, \
+     protected, source_app, source_window, note, thumbnail, lines, words";
+const ARCHIVE_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS archive.items (
+        hash        BLOB    PRIMARY KEY,
+        mime        TEXT    NOT NULL,
+        text        TEXT,
+        data        BLOB    NOT NULL,
+        ts          INTEGER NOT NULL,
+        context     TEXT,
+        size        INTEGER,
+        width       INTEGER,
+        height      INTEGER,
+        format      TEXT,
+        kind        TEXT    NOT NULL DEFAULT 'text',
+        html        TEXT,
+        rtf         TEXT,
+        protected   INTEGER NOT NULL DEFAULT 0,
+        source_app      TEXT,
+        source_window   TEXT,
+        note        TEXT,
+        thumbnail   BLOB,
+        lines       INTEGER NOT NULL DEFAULT 0,
+        words       INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS archive.idx_ts ON items (ts);
+    CREATE TABLE IF NOT EXISTS archive.tags (
+        hash    BLOB NOT NULL,
+        tag     TEXT NOT NULL,
+        PRIMARY KEY (hash, tag)
+    );
+    CREATE TABLE IF NOT EXISTS archive.custom_formats (
+        hash        BLOB    NOT NULL,
+        position    INTEGER NOT NULL,
+        name        TEXT    NOT NULL,
+        data        BLOB    NOT NULL,
+        PRIMARY KEY (hash, position)
+    );
+    CREATE TABLE IF NOT EXISTS archive.representations (
+        hash    BLOB    NOT NULL,
+        mime    TEXT    NOT NULL,
+        data    BLOB    NOT NULL,
+        PRIMARY KEY (hash, mime)
+    );
+    CREATE TABLE IF NOT EXISTS archive.copy_events (
+        id      INTEGER PRIMARY KEY,
+        hash    BLOB    NOT NULL,
+        ts      INTEGER NOT NULL,
+        kind    TEXT    NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS archive.spill (
+        hash    BLOB PRIMARY KEY,
+        text    TEXT NOT NULL
+    );
+";
+
+/// Tables besides `items` and `tags` that an archived entry's rows are
+/// copied from. Deleting the entry from the vault drops them through its
+/// `_follow_delete` triggers.
+const ARCHIVE_CHILD_TABLES: [(&str, &str); 3] = [
+    ("custom_formats", "hash, position, name, data"),
+    ("representations", "hash, mime, data"),
+    ("copy_events", "hash, ts, kind"),
+];
+
+/// Custom formats follow their entry through edits and deletes, like tags.
+const CUSTOM_FORMATS_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS custom_formats (
+        hash        BLOB    NOT NULL,
+        position    INTEGER NOT NULL,
+        name        TEXT    NOT NULL,
+        data        BLOB    NOT NULL,
+        PRIMARY KEY (hash, position)
+    );
+    CREATE TRIGGER IF NOT EXISTS custom_formats_follow_update AFTER UPDATE OF hash ON items
+    BEGIN
+        UPDATE custom_formats SET hash = NEW.hash WHERE hash = OLD.hash;
+    END;
+    CREATE TRIGGER IF NOT EXISTS custom_formats_follow_delete AFTER DELETE ON items
+    BEGIN
+        DELETE FROM custom_formats WHERE hash = OLD.hash;
+    END;
+";
+
+/// Representations follow their entry the same way.
+const REPRESENTATIONS_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS representations (
+        hash    BLOB    NOT NULL,
+        mime    TEXT    NOT NULL,
+        data    BLOB    NOT NULL,
+        PRIMARY KEY (hash, mime)
+    );
+    CREATE TRIGGER IF NOT EXISTS representations_follow_update AFTER UPDATE OF hash ON items
+    BEGIN
+        UPDATE representations SET hash = NEW.hash WHERE hash = OLD.hash;
+    END;
+    CREATE TRIGGER IF NOT EXISTS representations_follow_delete AFTER DELETE ON items
+    BEGIN
+        DELETE FROM representations WHERE hash = OLD.hash;
+    END;
+";
+
+/// Copy events follow their entry the same way.
+const COPY_EVENTS_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS copy_events (
+        id      INTEGER PRIMARY KEY,
+        hash    BLOB    NOT NULL,
+        ts      INTEGER NOT NULL,
+        kind    TEXT    NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_copy_events_hash ON copy_events (hash, ts);
+    CREATE INDEX IF NOT EXISTS idx_copy_events_ts ON copy_events (ts);
+    CREATE TRIGGER IF NOT EXISTS copy_events_follow_update AFTER UPDATE OF hash ON items
+    BEGIN
+        UPDATE copy_events SET hash = NEW.hash WHERE hash = OLD.hash;
+    END;
+    CREATE TRIGGER IF NOT EXISTS copy_events_follow_delete AFTER DELETE ON items
+    BEGIN
+        DELETE FROM copy_events WHERE hash = OLD.hash;
+    END;
+";
+
+/// When captures that looked like a password or API key were skipped,
+/// for the weekly digest. Only the time is kept.
+const SKIPPED_SECRETS_SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS skipped_secrets (
+        ts  INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_skipped_secrets_ts ON skipped_secrets (ts);
+";
+/// Skipped secrets are counted for this many days.
+const SKIPPED_SECRETS_KEPT_DAYS: u64 = 28;
+
+/// The `thumbnail` column, with the empty marker
+/// [`SqliteVault::backfill_thumbnails`] leaves on images that can't have one
+/// read as none.
+const THUMBNAIL: &str = "NULLIF(thumbnail, X'')";
+
+/// Entries nearest in time to compare in [`Vault::related`].
+const RELATED_CANDIDATES: usize = 1_000;
+/// Most entries [`Vault::related`] returns.
+const RELATED_LIMIT: usize = 20;
+
+pub struct SqliteVault {
+    conn: Connection,
+    diagnostics: Mutex<Vec<RowDiagnostic>>,
+    audit_enabled: AtomicBool,
+    /// Whether images are stored without their metadata; see
+    /// [`set_strip_image_metadata`](Self::set_strip_image_metadata).
+    strip_metadata: AtomicBool,
+    /// Database file, `None` for in-memory vaults.
+    path: Option<PathBuf>,
+    spill_attached: AtomicBool,
+    subscribers: Mutex<Vec<Sender<VaultEvent>>>,
+    health_subscribers: Mutex<Vec<Sender<HealthEvent>>>,
+    /// Whether the last insert failed, so a run of failures is reported
+    /// once and its end is reported too.
+    write_failing: AtomicBool,
+    /// Unreadable rows already reported to health subscribers.
+    reported_unreadable: Mutex<HashSet<[u8; 32]>>,
+    /// Recorded on every entry this connection writes.
+    device: Mutex<String>,
+    /// What counts as a duplicate for single inserts and edits; see
+    /// [`set_dedupe_rules`](Self::set_dedupe_rules).
+    dedupe: Mutex<DedupeRules>,
+    data_version: AtomicI64,
+    /// The settings the vault was opened with, which backups are written with
+    /// too.
+    cipher: CipherOptions,
+    /// Opened with [`open_read_only`](Self::open_read_only).
+    read_only: bool,
+    /// Where [`open_read_only`](Self::open_read_only) upgraded a copy of a
+    /// snapshot from an older release; removed with the vault.
+    upgraded_copy: Option<tempfile::TempDir>,
+}
+
+impl SqliteVault {
+    pub fn open<P: AsRef<std::path::Path>>(path: P, key: &str) -> Result<Self> {
+        let options = CipherOptions::for_vault(path.as_ref())?;
+        Self::open_with(path, key, options)
+    }
+
+    /// Create a vault at `path` encrypted with `options` instead of
+    /// `SQLCipher`'s defaults. They are kept in a file next to the vault
+    /// (see [`cipher_path`](crate::cipher_path)) and used by every later
+    /// [`open`](Self::open). Fails if there is already a vault at `path`.
+    pub fn create_with_options<P: AsRef<std::path::Path>>(
+        path: P,
+        key: &str,
+        options: &CipherOptions,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        options.validate()?;
+        if path.exists() {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("there is already a vault at {}", path.display()),
+            )));
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        options.save_for(path)?;
+        Self::open_with(path, key, *options).inspect_err(|_| {
+            std::fs::remove_file(crate::cipher_path(path)).ok();
+        })
+    }
+
+    fn open_with<P: AsRef<std::path::Path>>(
+        path: P,
+        key: &str,
+        options: CipherOptions,
+    ) -> Result<Self> {
+        let conn = Connection::open(&path)?;
+        options.apply(&conn, key)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let mut vault = Self::init(conn, Some(path.as_ref().to_path_buf()))?;
+        vault.cipher = options;
+        move_spill_file(path.as_ref())?;
+        if vault.spill_path().is_some_and(|p| p.exists()) {
+            vault.attach_spill()?;
+        }
+        Ok(vault)
+    }
+
+    /// Open the vault file at `path` without writing to it, e.g. a backup
+    /// snapshot to browse next to the live vault. Nothing is written: no
+    /// WAL, no schema upgrades, no usage or audit records, and changing
+    /// entries fails with [`Error::ReadOnly`]. A snapshot from an older
+    /// release is upgraded in a private temporary copy instead. Cipher
+    /// settings are read from next to `path`, as for [`open`](Self::open).
+    pub fn open_read_only<P: AsRef<std::path::Path>>(path: P, key: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let options = CipherOptions::for_vault(path)?;
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        options.apply(&conn, key)?;
+        if conn
+            .query_row("SELECT COUNT(*) FROM items;", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .is_err()
+        {
+            return Err(Error::InvalidKey);
+        }
+        let mut vault = if schema_version(&conn)? < SCHEMA_VERSION {
+            drop(conn);
+            Self::open_upgraded_copy(path, key, &options)?
+        } else {
+            Self::from_connection(conn, Some(path.to_path_buf()))?
+        };
+        vault.cipher = options;
+        vault.read_only = true;
+        Ok(vault)
+    }
+
+    /// Copy the vault at `path` (with its WAL, if any) into a new owner-only
+    /// temporary directory and bring the copy's schema up to date, for
+    /// [`open_read_only`](Self::open_read_only).
+    fn open_upgraded_copy(
+        path: &std::path::Path,
+        key: &str,
+        options: &CipherOptions,
+    ) -> Result<Self> {
+        let dir = tempfile::TempDir::new()?;
+        let copy = dir.path().join("snapshot.db");
+        std::fs::copy(path, &copy)?;
+        let mut wal = path.as_os_str().to_owned();
+        wal.push("-wal");
+        if Path::new(&wal).exists() {
+            std::fs::copy(&wal, dir.path().join("snapshot.db-wal"))?;
+        }
+        let conn = Connection::open(&copy)?;
+        options.apply(&conn, key)?;
+        let mut vault = Self::init(conn, Some(path.to_path_buf()))?;
+        vault.upgraded_copy = Some(dir);
+        Ok(vault)
+    }
+
+    /// Open a vault that lives only in memory and is never written to disk.
+    /// Connections opened with the same `name` in this process share one
+    /// database, which is freed when the last of them is dropped.
+    pub fn open_in_memory(name: &str) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            format!("file:{name}?mode=memory&cache=shared"),
+            OpenFlags::default() | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_SHARED_CACHE,
+        )?;
+        // Shared-cache readers would otherwise hit table locks while another
+        // connection is writing.
+        conn.pragma_update(None, "read_uncommitted", true)?;
+        Self::init(conn, None)
+    }
+
+    fn init(conn: Connection, path: Option<PathBuf>) -> Result<Self> {
+        let found = schema_version(&conn)?;
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS items (
+                id      INTEGER PRIMARY KEY,
+                hash    BLOB    UNIQUE NOT NULL,
+                mime    TEXT    NOT NULL,
+                text    TEXT,
+                data    BLOB    NOT NULL,
+                ts      INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_ts ON items (ts);
+
+            CREATE TABLE IF NOT EXISTS quarantine (
+                id              INTEGER PRIMARY KEY,
+                hash            BLOB    NOT NULL,
+                mime            TEXT    NOT NULL,
+                data            BLOB    NOT NULL,
+                ts              INTEGER NOT NULL,
+                quarantined_at  INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS meta (
+                key     TEXT PRIMARY KEY,
+                value   TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS tags (
+                hash    BLOB NOT NULL,
+                tag     TEXT NOT NULL,
+                PRIMARY KEY (hash, tag)
+            );
+
+            -- Tags follow their entry through edits and go with it on delete.
+            CREATE TRIGGER IF NOT EXISTS tags_follow_update AFTER UPDATE OF hash ON items
+            BEGIN
+                UPDATE tags SET hash = NEW.hash WHERE hash = OLD.hash;
+            END;
+            CREATE TRIGGER IF NOT EXISTS tags_follow_delete AFTER DELETE ON items
+            BEGIN
+                DELETE FROM tags WHERE hash = OLD.hash;
+            END;
+            ",
+        )?;
+        conn.execute_batch(audit::SCHEMA)?;
+        conn.execute_batch(CUSTOM_FORMATS_SCHEMA)?;
+        conn.execute_batch(REPRESENTATIONS_SCHEMA)?;
+        conn.execute_batch(COPY_EVENTS_SCHEMA)?;
+        conn.execute_batch(SKIPPED_SECRETS_SCHEMA)?;
+        if found < 12 {
+            rehash_files(&conn, "main")?;
+        }
+        add_item_columns(&conn)?;
+        if found < 11 {
+            reclassify_text(&conn)?;
+        }
+        // `hash` needs no index of its own: UNIQUE already gives it the one
+        // every lookup, update and delete by hash goes through.
+        conn.execute_batch(
+            "
+            -- Searches match text with LIKE '%q%', which no index can serve,
+            -- so this only held a second copy of every entry.
+            DROP INDEX IF EXISTS idx_mime_text;
+
+            -- Retention looks tags up by name; the primary key leads with hash.
+            CREATE INDEX IF NOT EXISTS idx_tags_tag ON tags (tag);
+            -- Most entries never expire, so only index those that do.
+            CREATE INDEX IF NOT EXISTS idx_expires_at ON items (expires_at)
+                WHERE expires_at IS NOT NULL;
+            -- Likewise only edits are looked up by what they were edited from.
+            CREATE INDEX IF NOT EXISTS idx_edited_from ON items (edited_from)
+                WHERE edited_from IS NOT NULL;
+            -- Only links have a dedupe key.
+            CREATE INDEX IF NOT EXISTS idx_dedupe_key ON items (dedupe_key)
+                WHERE dedupe_key IS NOT NULL;
+            ",
+        )?;
+        if found < SCHEMA_VERSION {
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+        Self::from_connection(conn, path)
+    }
+
+    /// The vault around `conn`, whose schema is already up to date.
+    fn from_connection(conn: Connection, path: Option<PathBuf>) -> Result<Self> {
+        let data_version: i64 = conn.pragma_query_value(None, "data_version", |row| row.get(0))?;
+
+        let audit_enabled = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'audit_enabled';",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .is_some_and(|v| v == "1");
+        let strip_metadata = conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'strip_image_metadata';",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .is_none_or(|v| v == "1");
+
+        Ok(Self {
+            conn,
+            diagnostics: Mutex::new(Vec::new()),
+            audit_enabled: AtomicBool::new(audit_enabled),
+            strip_metadata: AtomicBool::new(strip_metadata),
+            path,
+            spill_attached: AtomicBool::new(false),
+            subscribers: Mutex::new(Vec::new()),
+            health_subscribers: Mutex::new(Vec::new()),
+            write_failing: AtomicBool::new(false),
+            reported_unreadable: Mutex::new(HashSet::new()),
+            device: Mutex::new(crate::device_name()),
+            dedupe: Mutex::new(DedupeRules::default()),
+            data_version: AtomicI64::new(data_version),
+            cipher: CipherOptions::default(),
+            read_only: false,
+            upgraded_copy: None,
+        })
+    }
+
+    /// Whether the vault was opened with
+    /// [`open_read_only`](Self::open_read_only).
+    #[must_use]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Refuse a change to a read-only vault.
+    fn ensure_writable(&self) -> Result<()> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+        Ok(())
+    }
+
+    /// Sidecar file holding full originals of truncated entries. It is
+    /// attached to the main connection, so it shares the vault's key.
+    #[must_use]
+    pub fn spill_path(&self) -> Option<PathBuf> {
+        self.path.as_deref().map(spill_path)
+    }
+
+    /// Secondary vault that old entries are moved to by
+    /// [`archive_older_than`](Self::archive_older_than). It shares the
+    /// vault's key and is only attached while it is being used.
+    #[must_use]
+    pub fn archive_path(&self) -> Option<PathBuf> {
+        self.path.as_deref().map(archive_path)
+    }
+
+    /// Move unprotected, unpinned entries captured more than `age` ago, with
+    /// their tags, formats, representations and copy history, to the archive. They no longer show up in the history or
+    /// ordinary searches, only in [`search_with_archive`](Self::search_with_archive).
+    /// Returns how many were moved; in-memory vaults have no archive.
+    pub fn archive_older_than(&self, age: std::time::Duration) -> Result<usize> {
+        self.ensure_writable()?;
+        let cutoff = Timestamp::now().saturating_sub(age);
+        let hashes: Vec<[u8; 32]> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT hash FROM items WHERE ts < ?1 AND protected = 0 AND pinned = 0;",
+            )?;
+            let mut rows = stmt.query(params![cutoff])?;
+            let mut hashes = Vec::new();
+            while let Some(row) = rows.next()? {
+                hashes.push(row_hash(row)?);
+            }
+            hashes
+        };
+        if hashes.is_empty() {
+            return Ok(0);
+        }
+
+        let moved = self.with_archive(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO archive.items ({ARCHIVE_COLUMNS})
+                     SELECT {ARCHIVE_COLUMNS} FROM main.items
+                     WHERE ts < ?1 AND protected = 0 AND pinned = 0;"
+                ),
+                params![cutoff],
+            )?;
+            for (table, columns) in
+                std::iter::once(("tags", "hash, tag")).chain(ARCHIVE_CHILD_TABLES)
+            {
+                let select = columns
+                    .split(", ")
+                    .map(|c| format!("c.{c}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                tx.execute(
+                    &format!(
+                        "INSERT OR REPLACE INTO archive.{table} ({columns})
+                         SELECT {select} FROM main.{table} c JOIN main.items i ON i.hash = c.hash
+                         WHERE i.ts < ?1 AND i.protected = 0 AND i.pinned = 0;"
+                    ),
+                    params![cutoff],
+                )?;
+            }
+            // Spilled originals go with their entries rather than being left
+            // behind in the vault's spill file.
+            if self.spill_attached.load(Ordering::Relaxed) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO archive.spill (hash, text)
+                     SELECT s.hash, s.text FROM spill.items s JOIN main.items i ON i.hash = s.hash
+                     WHERE i.ts < ?1 AND i.protected = 0 AND i.pinned = 0;",
+                    params![cutoff],
+                )?;
+            }
+            for hash in &hashes {
+                self.delete_spilled(&tx, hash)?;
+                self.audit_on(&tx, AuditAction::Archive, Some(hash))?;
+            }
+            let moved = tx.execute(
+                "DELETE FROM main.items WHERE ts < ?1 AND protected = 0 AND pinned = 0;",
+                params![cutoff],
+            )?;
+            tx.commit()?;
+            Ok(moved)
+        })?;
+        let Some(moved) = moved else {
+            return Ok(0);
+        };
+
+        for hash in hashes {
+            self.notify(&VaultEvent::Deleted { hash });
+        }
+        self.report_pruned(moved, PruneReason::Archived);
+        Ok(moved)
+    }
+
+    /// [`Vault::search`] across the vault and its archive, newest first. An
+    /// entry copied again since it was archived is only listed once.
+    pub fn search_with_archive(
         &self,
+        query: &str,
         limit: Option<usize>,
-        after_timestamp: Option<u64>,
-    ) -> Result<Vec<ClipboardItemWithTimestamp>>;
-    fn search(
+    ) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let mut items = self.search(query, limit, None)?;
+        if !self.archive_path().is_some_and(|p| p.exists()) {
+            return Ok(items);
+        }
+        let (sql, params) = search_sql(
+            "archive",
+            // Archived entries have no id in the vault.
+            "hash, data, ts, context, source_app, source_window, NULL",
+            query,
+            limit,
+            None,
+        );
+        let param_refs: Vec<&dyn rusqlite::ToSql> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+        let archived = self
+            .with_archive(|| self.query_items(&sql, &param_refs))?
+            .unwrap_or_default();
+
+        let hot: HashSet<[u8; 32]> = items.iter().map(|e| e.item.hash()).collect();
+        items.extend(
+            archived
+                .into_iter()
+                .filter(|e| !hot.contains(&e.item.hash())),
+        );
+        items.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        if let Some(limit) = limit {
+            items.truncate(limit);
+        }
+        Ok(items)
+    }
+
+    /// Delete archived entries older than `policy` allows, except those
+    /// with a kept tag. Returns how many were deleted.
+    fn prune_archive(&self, policy: &RetentionPolicy) -> Result<usize> {
+        if policy.is_empty() || !self.archive_path().is_some_and(|p| p.exists()) {
+            return Ok(0);
+        }
+        let now = Timestamp::now();
+        let deleted = self.with_archive(|| {
+            let mut exempt = HashSet::new();
+            {
+                let mut stmt = self
+                    .conn
+                    .prepare("SELECT hash FROM archive.tags WHERE tag = ?1;")?;
+                for tag in policy.keep_tags.keys() {
+                    let mut rows = stmt.query(params![tag])?;
+                    while let Some(row) = rows.next()? {
+                        exempt.insert(row_hash(row)?);
+                    }
+                }
+            }
+            let mut expired = Vec::new();
+            {
+                let mut stmt = self
+                    .conn
+                    .prepare("SELECT hash, mime, text, ts FROM archive.items;")?;
+                let mut rows = stmt.query([])?;
+                while let Some(row) = rows.next()? {
+                    let hash = row_hash(row)?;
+                    if exempt.contains(&hash) {
+                        continue;
+                    }
+                    let mime: String = row.get(1)?;
+                    let text: Option<String> = row.get(2)?;
+                    let ts: Timestamp = row.get(3)?;
+                    let kind = ItemKind::from_row(&mime, text.as_deref());
+                    if policy
+                        .max_age(kind)
+                        .is_some_and(|max_age| now.duration_since(ts) > max_age)
+                    {
+                        expired.push(hash);
+                    }
+                }
+            }
+            let tx = self.conn.unchecked_transaction()?;
+            for hash in &expired {
+                for table in ["items", "tags", "spill"]
+                    .into_iter()
+                    .chain(ARCHIVE_CHILD_TABLES.map(|(table, _)| table))
+                {
+                    tx.execute(
+                        &format!("DELETE FROM archive.{table} WHERE hash = ?1;"),
+                        params![&hash[..]],
+                    )?;
+                }
+            }
+            tx.commit()?;
+            Ok(expired.len())
+        })?;
+        Ok(deleted.unwrap_or(0))
+    }
+
+    /// Attach the archive (creating it if needed) for the length of `f`.
+    /// `None` for in-memory and read-only vaults.
+    fn with_archive<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<Option<T>> {
+        let Some(path) = self.archive_path().filter(|_| !self.read_only) else {
+            return Ok(None);
+        };
+        attach(&self.conn, &path, "archive")?;
+        let result = self
+            .conn
+            .execute_batch(ARCHIVE_SCHEMA)
+            .map_err(Error::from)
+            .and_then(|()| add_archive_columns(&self.conn))
+            .and_then(|()| rehash_files(&self.conn, "archive"))
+            .and_then(|()| f());
+        self.conn.execute_batch("DETACH DATABASE archive;")?;
+        result.map(Some)
+    }
+
+    /// Bytes the vault takes up on disk; 0 for in-memory vaults.
+    #[must_use]
+    pub fn file_size(&self) -> u64 {
+        self.path.as_deref().map_or(0, vault_file_size)
+    }
+
+    /// Disk usage, entry count and recent growth, for spotting a vault
+    /// that is filling up with screenshots.
+    pub fn storage_info(&self) -> Result<StorageInfo> {
+        let window = GROWTH_WINDOW_DAYS * HistogramBucket::Day.as_nanos();
+        let recent: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(LENGTH(data)), 0) FROM items WHERE ts >= ?1;",
+            params![Timestamp::now().as_nanos().saturating_sub(window)],
+            |row| row.get(0),
+        )?;
+        Ok(StorageInfo {
+            file_bytes: self.file_size(),
+            payload_bytes: self.total_size()?,
+            entries: self.len()?,
+            bytes_per_day: u64::try_from(recent).unwrap_or(0) / GROWTH_WINDOW_DAYS,
+        })
+    }
+
+    /// Give space freed by deleted entries back to the file system. `SQLite`
+    /// reuses freed pages but never shrinks the file on its own.
+    pub fn compact(&self) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute_batch("VACUUM;")?;
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE);", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    /// Mark entries written through this connection as coming from `name`
+    /// instead of [`device_name`](crate::device_name).
+    pub fn set_device(&self, name: &str) {
+        name.clone_into(
+            &mut self
+                .device
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        );
+    }
+
+    /// Match single inserts and edits against stored entries by `rules`,
+    /// normally [`Config::dedupe`](crate::Config::dedupe), instead of the
+    /// defaults. Captures and imports carry their own policy.
+    pub fn set_dedupe_rules(&self, rules: DedupeRules) {
+        *self
+            .dedupe
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = rules;
+    }
+
+    fn dedupe_rules(&self) -> DedupeRules {
+        *self
+            .dedupe
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Which device last wrote the entry with `hash`, and what it was
+    /// edited from.
+    pub fn provenance(&self, hash: [u8; 32]) -> Result<Option<Provenance>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT device, generation, edited_from FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| {
+                    Ok(Provenance {
+                        device: row.get(0)?,
+                        generation: row.get(1)?,
+                        edited_from: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    /// Entries edited into different versions on different devices, with
+    /// the most recently edited first.
+    pub fn sync_conflicts(&self) -> Result<Vec<SyncConflict>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT edited_from, hash, data, ts, context, device, generation, source_app, source_window, id
+             FROM items
+             WHERE protected = 0 AND edited_from IN (
+                 SELECT edited_from FROM items
+                 WHERE edited_from IS NOT NULL AND protected = 0
+                 GROUP BY edited_from HAVING COUNT(DISTINCT device) > 1
+             )
+             ORDER BY edited_from, ts DESC;",
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut conflicts: Vec<SyncConflict> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let original = row_hash(row)?;
+            let blob: Vec<u8> = row.get(2)?;
+            // Undecodable versions are left to `diagnostics` and quarantine.
+            let Ok(item) = decode_item(&blob) else {
+                continue;
+            };
+            let version = ConflictVersion {
+                entry: ClipboardItemWithTimestamp {
+                    item,
+                    timestamp: row.get(3)?,
+                    context: row.get(4)?,
+                    source: row_source(row, 7)?,
+                    id: row.get(9)?,
+                },
+                device: row.get(5)?,
+                generation: row.get(6)?,
+            };
+            match conflicts.last_mut() {
+                Some(conflict) if conflict.original == original => conflict.versions.push(version),
+                _ => conflicts.push(SyncConflict {
+                    original,
+                    versions: vec![version],
+                }),
+            }
+        }
+        conflicts.sort_by_key(|c| std::cmp::Reverse(c.versions.first().map(|v| v.entry.timestamp)));
+        Ok(conflicts)
+    }
+
+    /// Settle a conflict by keeping the version with hash `keep` and deleting
+    /// the other versions edited from the same entry. Returns how many were
+    /// deleted; 0, deleting nothing, if `keep` isn't one of the versions of a
+    /// conflict as listed by [`sync_conflicts`](Self::sync_conflicts).
+    pub fn resolve_conflict(&self, keep: [u8; 32]) -> Result<usize> {
+        self.ensure_writable()?;
+        let others: Vec<[u8; 32]> = {
+            let mut stmt = self.conn.prepare(
+                "WITH versions AS (
+                     SELECT hash, device FROM items
+                     WHERE edited_from = (
+                         SELECT edited_from FROM items WHERE hash = ?1 AND protected = 0
+                     ) AND protected = 0
+                 )
+                 SELECT hash FROM versions
+                 WHERE hash != ?1 AND (SELECT COUNT(DISTINCT device) FROM versions) > 1;",
+            )?;
+            let mut rows = stmt.query(params![&keep[..]])?;
+            let mut hashes = Vec::new();
+            while let Some(row) = rows.next()? {
+                hashes.push(row_hash(row)?);
+            }
+            hashes
+        };
+        self.delete_hashes(&others)?;
+        Ok(others.len())
+    }
+
+    /// Record the entry with `hash` as just written by this device.
+    fn stamp(&self, conn: &Connection, hash: &[u8; 32]) -> Result<()> {
+        let generation: i64 = conn.query_row(
+            "INSERT INTO meta (key, value) VALUES ('generation', '1')
+             ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1
+             RETURNING CAST(value AS INTEGER);",
+            [],
+            |row| row.get(0),
+        )?;
+        let device = self
+            .device
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        conn.execute(
+            "UPDATE items SET device = ?2, generation = ?3 WHERE hash = ?1;",
+            params![&hash[..], device, generation],
+        )?;
+        Ok(())
+    }
+
+    /// Keep the full text of an entry that was stored truncated.
+    pub fn spill(&self, hash: [u8; 32], original: &str) -> Result<()> {
+        self.ensure_writable()?;
+        if !self.attach_spill()? {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT OR REPLACE INTO spill.items (hash, text) VALUES (?1, ?2);",
+            params![&hash[..], original],
+        )?;
+        Ok(())
+    }
+
+    /// Full original of a truncated entry, if it was spilled.
+    pub fn spilled(&self, hash: [u8; 32]) -> Result<Option<String>> {
+        if !self.spill_attached.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT text FROM spill.items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Move an entry into (or back out of) the protected area. Protected
+    /// entries are left out of `latest`, `list`, `search` and retention, and
+    /// can only be read through [`Self::protected_items`]. Returns the number
+    /// of rows changed.
+    pub fn set_protected(&self, hash: [u8; 32], protected: bool) -> Result<u64> {
+        self.ensure_writable()?;
+        let changed = self.conn.execute(
+            "UPDATE items SET protected = ?2 WHERE hash = ?1 AND protected != ?2;",
+            params![&hash[..], protected],
+        )?;
+        if changed > 0 {
+            // To everything reading the history the entry came or went.
+            self.notify(&if protected {
+                VaultEvent::Deleted { hash }
+            } else {
+                VaultEvent::Added { hash }
+            });
+        }
+        Ok(changed as u64)
+    }
+
+    /// Protected entries, newest first. `key` must be the vault password
+    /// again, even though the vault is already open.
+    pub fn protected_items(&self, key: &str) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        if !self.verify_key(key)? {
+            return Err(Error::InvalidKey);
+        }
+        self.query_items(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items WHERE protected = 1 ORDER BY ts DESC;",
+            &[],
+        )
+    }
+
+    /// Check `key` against the vault file by opening a second connection
+    /// with it. In-memory vaults have no password, so any key passes.
+    pub fn verify_key(&self, key: &str) -> Result<bool> {
+        let Some(path) = &self.path else {
+            return Ok(true);
+        };
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        CipherOptions::for_vault(path)?.apply(&conn, key)?;
+        Ok(conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master;", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .is_ok())
+    }
+
+    /// The schema version of the vault file at `path` and the migrations
+    /// opening it would apply, read without changing it.
+    pub fn inspect_schema(path: &std::path::Path, key: &str) -> Result<SchemaInfo> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        CipherOptions::for_vault(path)?.apply(&conn, key)?;
+        let found: u32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(|_| Error::InvalidKey)?;
+        Ok(SchemaInfo::new(found))
+    }
+
+    /// Write a consistent snapshot of the vault to `dest`, encrypted with
+    /// the same password and cipher settings, and of its archive, if it has
+    /// one, to [`snapshot_archive_path`]. The cipher settings are saved to
+    /// [`cipher_path`](crate::cipher_path) next to it, defaults included.
+    /// The spill file is not included.
+    pub fn backup_to(&self, dest: &std::path::Path) -> Result<()> {
+        self.export_schema("main", dest)?;
+        self.cipher.save_for_snapshot(dest)?;
+        if self.archive_path().is_some_and(|p| p.exists()) {
+            self.with_archive(|| self.export_schema("archive", &snapshot_archive_path(dest)))?;
+        }
+        self.audit_on(&self.conn, AuditAction::Export, None)
+    }
+
+    /// Copy the attached database `schema` to `dest`, replacing any file
+    /// there. The copy is written to a new file beside `dest` under a
+    /// random name, then renamed over it, so an earlier snapshot is only
+    /// ever replaced by a complete one.
+    fn export_schema(&self, schema: &str, dest: &std::path::Path) -> Result<()> {
+        let name = dest.file_name().ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{} is not a file path", dest.display()),
+            ))
+        })?;
+        let mut temp_name = std::ffi::OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(".{}.tmp", recovery::random_key(8)?));
+        let temp = dest.with_file_name(temp_name);
+        let result = self
+            .export_schema_to(schema, &temp)
+            .and_then(|()| Ok(std::fs::rename(&temp, dest)?));
+        if result.is_err() {
+            std::fs::remove_file(&temp).ok();
+        }
+        result
+    }
+
+    /// Copy the attached database `schema` to a new database at `dest`.
+    fn export_schema_to(&self, schema: &str, dest: &std::path::Path) -> Result<()> {
+        attach(&self.conn, dest, "backup")?;
+        // The snapshot is opened on its own, so it needs the vault's settings.
+        self.cipher.apply_to_schema(&self.conn, Some("backup"))?;
+        let exported = self.conn.query_row(
+            "SELECT sqlcipher_export('backup', ?1);",
+            params![schema],
+            |_| Ok(()),
+        );
+        self.conn.execute_batch("DETACH DATABASE backup;")?;
+        exported?;
+        Ok(())
+    }
+
+    /// Replace the vault file at `dest` with `snapshot`, after checking that
+    /// `key` opens it. Refused while anything has `dest` open. Snapshots
+    /// taken before cipher settings were saved with them are tried with the
+    /// defaults; if those don't open it, the missing settings file is
+    /// reported rather than a wrong password.
+    pub fn restore_from(
+        snapshot: &std::path::Path,
+        dest: &std::path::Path,
+        key: &str,
+    ) -> Result<()> {
+        let saved = CipherOptions::for_snapshot(snapshot)?;
+        let options = saved.unwrap_or_default();
+        {
+            let conn = Connection::open_with_flags(snapshot, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+            options.apply(&conn, key)?;
+            if conn
+                .query_row("SELECT COUNT(*) FROM items;", [], |row| {
+                    row.get::<_, i64>(0)
+                })
+                .is_err()
+            {
+                if saved.is_some() {
+                    return Err(Error::InvalidKey);
+                }
+                return Err(Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!(
+                        "{} can't be opened: the password is wrong, or its cipher settings \
+                         ({}) are missing",
+                        snapshot.display(),
+                        crate::cipher_path(snapshot).display()
+                    ),
+                )));
+            }
+        }
+        if dest.exists() {
+            ensure_vault_closed(dest, key)?;
+        }
+        if let Some(dir) = dest.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::copy(snapshot, dest)?;
+        // Snapshots taken before archives were backed up have none; the
+        // vault's own is left in place then.
+        let archive = snapshot_archive_path(snapshot);
+        if archive.exists() {
+            std::fs::copy(archive, archive_path(dest))?;
+        }
+        options.save_for(dest)?;
+        // Stale WAL frames from the old database would be replayed onto the
+        // restored one.
+        for suffix in ["-wal", "-shm"] {
+            let mut sidecar = dest.as_os_str().to_owned();
+            sidecar.push(suffix);
+            match std::fs::remove_file(sidecar) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate a recovery key for this vault, replacing any earlier one.
+    /// The key is returned once and not stored; `password` must be the
+    /// vault's current password.
+    pub fn create_recovery_key(&self, password: &str) -> Result<String> {
+        self.ensure_writable()?;
+        let Some(path) = &self.path else {
+            return Err(Error::Io(std::io::Error::other(
+                "in-memory vaults have no password to recover",
+            )));
+        };
+        if !self.verify_key(password)? {
+            return Err(Error::InvalidKey);
+        }
+        let key = recovery::generate_recovery_key()?;
+        recovery::write_password(&recovery::recovery_path(path), &key, password)?;
+        Ok(key)
+    }
+
+    /// Set a new password on the vault file at `path` using its recovery
+    /// key. The recovery key keeps working afterwards. Nothing may have the
+    /// vault open at the time.
+    pub fn recover(path: &std::path::Path, recovery_key: &str, new_password: &str) -> Result<()> {
+        let sidecar = recovery::recovery_path(path);
+        if !sidecar.exists() {
+            return Err(Error::InvalidKey);
+        }
+        let old_password = recovery::read_password(&sidecar, recovery_key)?;
+        // Until the vault has the new password, the old sidecar is the one
+        // that opens it.
+        let staged = recovery::stage_password(&sidecar, recovery_key, new_password)?;
+        if let Err(e) = Self::rekey(path, &old_password, new_password) {
+            std::fs::remove_file(&staged).ok();
+            return Err(e);
+        }
+        std::fs::rename(staged, sidecar)?;
+        Ok(())
+    }
+
+    /// Re-encrypt the vault file and its spill and archive files under `new`
+    /// key.
+    fn rekey(path: &std::path::Path, old: &str, new: &str) -> Result<()> {
+        let options = CipherOptions::for_vault(path)?;
+        let conn = Connection::open(path)?;
+        options.apply(&conn, old)?;
+        if conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master;", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .is_err()
+        {
+            return Err(Error::InvalidKey);
+        }
+        // The spill and archive files carry the vault's derived key rather
+        // than the password, so they are attached under the old key and
+        // copied into fresh files attached under the new one.
+        let sidecars: Vec<_> = [(spill_path(path), "spill"), (archive_path(path), "archive")]
+            .into_iter()
+            .filter(|(sidecar, _)| sidecar.exists())
+            .collect();
+        for (sidecar, schema) in &sidecars {
+            attach(&conn, sidecar, schema)?;
+        }
+        // SQLCipher can't rekey a database in WAL mode.
+        conn.pragma_update(None, "journal_mode", "DELETE")?;
+        conn.pragma_update(None, "rekey", new)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        for (sidecar, schema) in &sidecars {
+            let fresh = sidecar.with_extension("rekey.db");
+            match std::fs::remove_file(&fresh) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+            attach(&conn, &fresh, "fresh")?;
+            let exported = conn.query_row(
+                &format!("SELECT sqlcipher_export('fresh', '{schema}');"),
+                [],
+                |_| Ok(()),
+            );
+            conn.execute_batch(&format!("DETACH DATABASE fresh; DETACH DATABASE {schema};"))?;
+            exported?;
+            std::fs::rename(&fresh, sidecar)?;
+        }
+        Ok(())
+    }
+
+    /// Keep the HTML flavour that was on the clipboard alongside a text entry.
+    pub fn set_html(&self, hash: [u8; 32], html: &str) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            "UPDATE items SET html = ?2 WHERE hash = ?1;",
+            params![&hash[..], html],
+        )?;
+        Ok(())
+    }
+
+    /// The HTML flavour captured with an entry, if there was one.
+    pub fn html(&self, hash: [u8; 32]) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT html FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
+    /// Keep the RTF flavour that was on the clipboard alongside a text entry.
+    pub fn set_rtf(&self, hash: [u8; 32], rtf: &str) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            "UPDATE items SET rtf = ?2 WHERE hash = ?1;",
+            params![&hash[..], rtf],
+        )?;
+        Ok(())
+    }
+
+    /// The RTF flavour captured with an entry, if there was one.
+    pub fn rtf(&self, hash: [u8; 32]) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT rtf FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
+    /// Every formatted flavour kept with an entry, to put back when it's
+    /// copied. Empty for entries copied without any, and those that aren't stored.
+    pub fn rich_text(&self, hash: [u8; 32]) -> Result<RichText> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT html, rtf FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| {
+                    Ok(RichText {
+                        html: row.get(0)?,
+                        rtf: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?
+            .unwrap_or_default())
+    }
+
+    /// Keep application-specific clipboard formats with an entry, replacing
+    /// any it had.
+    pub fn set_custom_formats(&self, hash: [u8; 32], formats: &[CustomFormat]) -> Result<()> {
+        self.ensure_writable()?;
+        let tx = self.conn.unchecked_transaction()?;
+        write_custom_formats(&tx, hash, formats, self.strips_image_metadata())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The custom formats captured with an entry, in the order they were on
+    /// the clipboard.
+    pub fn custom_formats(&self, hash: [u8; 32]) -> Result<Vec<CustomFormat>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, data FROM custom_formats WHERE hash = ?1 ORDER BY position;")?;
+        let formats = stmt
+            .query_map(params![&hash[..]], |row| {
+                Ok(CustomFormat {
+                    name: row.get(0)?,
+                    data: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(formats)
+    }
+
+    /// Keep other forms of an entry's copy with it, replacing any it had.
+    pub fn set_representations(
+        &self,
+        hash: [u8; 32],
+        representations: &[Representation],
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        let tx = self.conn.unchecked_transaction()?;
+        write_representations(&tx, hash, representations, self.strips_image_metadata())?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The other forms an entry's copy came in, by MIME type.
+    pub fn representations(&self, hash: [u8; 32]) -> Result<Vec<Representation>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT mime, data FROM representations WHERE hash = ?1 ORDER BY mime;")?;
+        let representations = stmt
+            .query_map(params![&hash[..]], |row| {
+                Ok(Representation {
+                    mime: row.get(0)?,
+                    data: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(representations)
+    }
+
+    /// The hash of the entry with `id`, protected or not.
+    pub fn hash_of(&self, id: i64) -> Result<Option<[u8; 32]>> {
+        Ok(self
+            .conn
+            .query_row("SELECT hash FROM items WHERE id = ?1;", [id], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    /// Note that captures made at `times` looked like a password or API key
+    /// and were skipped, for [`Vault::skipped_secrets_between`]. Only the
+    /// times are kept, and only for the last four weeks.
+    pub fn record_skipped_secrets(&self, times: &[Timestamp]) -> Result<()> {
+        if times.is_empty() {
+            return Ok(());
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        for ts in times {
+            tx.execute("INSERT INTO skipped_secrets (ts) VALUES (?1);", [ts])?;
+        }
+        let kept = std::time::Duration::from_secs(SKIPPED_SECRETS_KEPT_DAYS * 24 * 60 * 60);
+        tx.execute(
+            "DELETE FROM skipped_secrets WHERE ts < ?1;",
+            [Timestamp::now().saturating_sub(kept)],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Run `f` against the vault and return each statement it ran, with
+    /// the plan SQLite chose for it as the `detail` column of `EXPLAIN
+    /// QUERY PLAN`. Lets tests check that the statements the vault really
+    /// runs use its indexes.
+    #[doc(hidden)]
+    pub fn query_plans(
+        &self,
+        f: impl FnOnce(&Self) -> Result<()>,
+    ) -> Result<Vec<(String, Vec<String>)>> {
+        thread_local! {
+            static TRACED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+        // rusqlite's callback signature.
+        #[allow(clippy::needless_pass_by_value)]
+        fn trace(event: TraceEvent<'_>) {
+            if let TraceEvent::Stmt(_, sql) = event {
+                TRACED.with_borrow_mut(|traced| traced.push(sql.to_string()));
+            }
+        }
+
+        TRACED.with_borrow_mut(Vec::clear);
+        self.conn
+            .trace_v2(TraceEventCodes::SQLITE_TRACE_STMT, Some(trace));
+        let ran = f(self);
+        self.conn.trace_v2(TraceEventCodes::empty(), None);
+        ran?;
+        let mut statements = TRACED.with_borrow_mut(std::mem::take);
+        // Statements run by triggers are reported as comments naming them.
+        statements.retain(|sql| !sql.starts_with("--"));
+        statements.dedup();
+        statements
+            .into_iter()
+            .map(|sql| {
+                let mut stmt = self.conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+                // Parameters are left unbound; the plan doesn't depend on them.
+                let mut rows = stmt.raw_query();
+                let mut plan = Vec::new();
+                while let Some(row) = rows.next()? {
+                    plan.push(row.get(3)?);
+                }
+                drop(rows);
+                Ok((sql, plan))
+            })
+            .collect()
+    }
+
+    /// Like [`Vault::search`], but reads only the text, timestamp and context
+    /// columns, never the stored blob, so matching and listing results costs
+    /// the same however large the entries are.
+    pub fn search_previews(
         &self,
         query: &str,
         limit: Option<usize>,
         after_timestamp: Option<u64>,
-    ) -> Result<Vec<ClipboardItemWithTimestamp>>;
-    fn update(&self, old_hash: [u8; 32], new_item: &ClipboardItem) -> Result<()>;
-    fn delete(&self, hash: [u8; 32]) -> Result<()>;
+    ) -> Result<Vec<SearchPreview>> {
+        // File entries keep their paths in `text` for searching, but aren't
+        // text.
+        let columns = format!(
+            "hash, CASE WHEN mime = '{FILES_MIME}' THEN NULL ELSE text END, ts, context, \
+             source_app, source_window, id, size, lines, words"
+        );
+        let (sql, params) = search_sql("main", &columns, query, limit, after_timestamp);
+        let param_refs: Vec<&dyn rusqlite::ToSql> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let previews = stmt
+            .query_map(param_refs.as_slice(), |row| {
+                Ok(SearchPreview {
+                    hash: row.get(0)?,
+                    text: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    context: row.get(3)?,
+                    source: row_source(row, 4)?,
+                    id: row.get(6)?,
+                    stats: row_stats(row, 7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(previews)
+    }
+
+    /// Attach (creating if needed) the spill file. Returns false for
+    /// in-memory vaults, which have nowhere to spill to.
+    fn attach_spill(&self) -> Result<bool> {
+        if self.spill_attached.load(Ordering::Relaxed) {
+            return Ok(true);
+        }
+        let Some(spill_path) = self.spill_path() else {
+            return Ok(false);
+        };
+        attach(&self.conn, &spill_path, "spill")?;
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS spill.items (
+                hash    BLOB PRIMARY KEY,
+                text    TEXT NOT NULL
+            );",
+        )?;
+        self.spill_attached.store(true, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    fn delete_spilled(&self, conn: &Connection, hash: &[u8; 32]) -> Result<()> {
+        if self.spill_attached.load(Ordering::Relaxed) {
+            conn.execute(
+                "DELETE FROM spill.items WHERE hash = ?1;",
+                params![&hash[..]],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Turn the tamper-evident audit log on or off. Existing records are kept
+    /// either way.
+    pub fn set_audit_enabled(&self, enabled: bool) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('audit_enabled', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = ?1;",
+            params![if enabled { "1" } else { "0" }],
+        )?;
+        self.audit_enabled.store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn audit_enabled(&self) -> bool {
+        self.audit_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Choose what re-copying a stored item does. Kept in the vault, so the
+    /// app and CLI agree.
+    pub fn set_duplicate_policy(&self, policy: DuplicatePolicy) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('duplicate_policy', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = ?1;",
+            params![policy.as_str()],
+        )?;
+        Ok(())
+    }
+
+    /// What re-copying a stored item does. Read from the vault each time
+    /// rather than at open, so a change saved by another connection, e.g.
+    /// the CLI while the app runs, applies straight away.
+    pub fn duplicate_policy(&self) -> Result<DuplicatePolicy> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'duplicate_policy';",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .and_then(|v| DuplicatePolicy::parse(&v))
+            .unwrap_or_default())
+    }
+
+    /// Store images without their EXIF, GPS, text and timestamp metadata
+    /// (see [`strip_image_metadata`](crate::strip_image_metadata)), or as
+    /// given. On by default. It applies to every way an image gets in:
+    /// captures, their custom formats and other representations, edits and
+    /// imports.
+    pub fn set_strip_image_metadata(&self, strip: bool) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('strip_image_metadata', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = ?1;",
+            params![if strip { "1" } else { "0" }],
+        )?;
+        self.strip_metadata.store(strip, Ordering::Relaxed);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn strips_image_metadata(&self) -> bool {
+        self.strip_metadata.load(Ordering::Relaxed)
+    }
+
+    /// `item` as it's stored, with its hash: an image loses its metadata
+    /// if the vault strips it.
+    fn stored_item<'a>(
+        &self,
+        hash: [u8; 32],
+        item: &'a ClipboardItem,
+    ) -> (Cow<'a, ClipboardItem>, [u8; 32]) {
+        if let ClipboardItem::Image(data) = item {
+            if self.strips_image_metadata() {
+                let stripped = crate::strip_image_metadata(data);
+                if stripped != *data {
+                    let item = ClipboardItem::Image(stripped);
+                    let hash = item.hash();
+                    return (Cow::Owned(item), hash);
+                }
+            }
+        }
+        (Cow::Borrowed(item), hash)
+    }
+
+    /// When an entry was last re-copied, as recorded under
+    /// [`DuplicatePolicy::RecordLastSeen`]. `None` if it never was.
+    pub fn last_seen(&self, hash: [u8; 32]) -> Result<Option<Timestamp>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT last_seen FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
+    /// Append an audit record if auditing is on. Exporters call this with
+    /// [`AuditAction::Export`]; vault writes are recorded automatically.
+    pub fn record_audit(&self, action: AuditAction, item_hash: Option<&[u8; 32]>) -> Result<()> {
+        self.audit_on(&self.conn, action, item_hash)
+    }
+
+    pub fn audit_log(&self) -> Result<Vec<AuditRecord>> {
+        audit::records(&self.conn)
+    }
+
+    /// Recompute every record hash and back-link in the audit log.
+    pub fn verify_audit_log(&self) -> Result<AuditVerification> {
+        Ok(audit::verify(&self.audit_log()?))
+    }
+
+    fn audit_on(
+        &self,
+        conn: &Connection,
+        action: AuditAction,
+        item_hash: Option<&[u8; 32]>,
+    ) -> Result<()> {
+        if self.audit_enabled() && !self.read_only {
+            audit::append(conn, Timestamp::now(), action, item_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Write one captured item without notifying subscribers, returning the
+    /// hash of the entry it was stored as, or `None` for a re-copy the
+    /// [`DuplicatePolicy`] left as it was. `dedupe` decides what else counts
+    /// as a re-copy.
+    fn insert_row(
+        &self,
+        conn: &Connection,
+        hash: [u8; 32],
+        item: &ClipboardItem,
+        metadata: &CaptureMetadata,
+        timestamp: Timestamp,
+        dedupe: DedupePolicy,
+    ) -> Result<Option<[u8; 32]>> {
+        let context = metadata.context.as_deref();
+        let source = metadata.source.as_ref();
+        let expires_at = metadata.ttl.map(|ttl| timestamp.saturating_add(ttl));
+
+        // A link stored under another spelling is re-copied in place of
+        // storing this one.
+        let dedupe_key = dedupe.key(item);
+        let equivalent = match &dedupe_key {
+            Some(key) => conn
+                .query_row(
+                    "SELECT hash FROM items WHERE dedupe_key = ?1 AND hash != ?2 ORDER BY ts DESC LIMIT 1;",
+                    params![key, &hash[..]],
+                    |row| row.get::<_, Vec<u8>>(0),
+                )
+                .optional()?
+                .and_then(|h| <[u8; 32]>::try_from(h).ok()),
+            None => None,
+        };
+        let hash = equivalent.unwrap_or(hash);
+
+        let (text, mime) = item.clone().into_parts();
+        // Only text entries fill the text column.
+        let text = (mime != "image/png").then_some(text);
+        let mut existed: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM items WHERE hash = ?1);",
+            params![&hash[..]],
+            |row| row.get(0),
+        )?;
+        // Items that never dedupe are always stored as an entry of their
+        // own, so an earlier copy steps aside for this one.
+        if existed && dedupe == DedupePolicy::Never {
+            retire_row(conn, &hash)?;
+            existed = false;
+        }
+        let written = conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO items (hash, mime, text, data, ts, context, expires_at, dedupe_key, source_app, source_window) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) ON CONFLICT(hash) {};",
+                self.duplicate_policy()?.on_conflict()
+            ),
+            params![&hash[..], mime, text, bincode::encode_to_vec(item, bincode::config::standard())?, timestamp, context, expires_at, dedupe_key, source.map(|s| &s.name), source.and_then(|s| s.window_title.as_ref())],
+        )?;
+        let kind = if existed {
+            CopyEventKind::Recopied
+        } else {
+            CopyEventKind::Captured
+        };
+        // A copy out of the vault was recorded when it was made; a monitor
+        // reading it back off the clipboard isn't another one.
+        if kind == CopyEventKind::Captured || !still_copied_out(conn, hash)? {
+            record_copy_event(conn, hash, timestamp, kind)?;
+        }
+        if written == 0 {
+            return Ok(None);
+        }
+        // A re-copy, e.g. of what's on the clipboard when capturing starts,
+        // leaves the entry as its writer left it.
+        if !existed {
+            self.stamp(conn, &hash)?;
+        }
+        if equivalent.is_none() {
+            set_item_info(conn, &hash, item)?;
+        }
+        self.audit_on(conn, AuditAction::Insert, Some(&hash))?;
+        Ok(Some(hash))
+    }
+
+    /// Store captures queued by a [`CaptureQueue`](crate::CaptureQueue) in
+    /// one transaction, oldest first, with their HTML flavour and spilled
+    /// originals. Subscribers hear about them once the batch is committed.
+    /// A capture that can't be stored is left out and returned, and the
+    /// rest are stored; an error means none were.
+    pub fn insert_batch(&self, captures: &[Capture]) -> Result<Vec<FailedCapture>> {
+        self.ensure_writable()?;
+        let result = self.write_batch(captures);
+        self.track_write(result.as_ref().err());
+
+        let (stored, failed) = result?;
+        for hash in stored {
+            self.notify(&VaultEvent::Added { hash });
+        }
+        Ok(failed)
+    }
+
+    /// Returns the hashes the captures were stored as, and those that
+    /// couldn't be. Re-copies left as they were are in neither.
+    fn write_batch(&self, captures: &[Capture]) -> Result<(Vec<[u8; 32]>, Vec<FailedCapture>)> {
+        // ATTACH can't run inside a transaction.
+        let spill = captures.iter().any(|c| c.spill.is_some()) && self.attach_spill()?;
+
+        let mut tx = self.conn.unchecked_transaction()?;
+        let mut stored = Vec::with_capacity(captures.len());
+        let mut failed = Vec::new();
+        for capture in captures {
+            // Each capture gets a savepoint, so one that fails is undone
+            // without the others.
+            let savepoint = tx.savepoint()?;
+            match self.write_capture(&savepoint, capture, spill) {
+                Ok(hash) => {
+                    savepoint.commit()?;
+                    stored.extend(hash);
+                }
+                Err(e) => failed.push(FailedCapture {
+                    hash: capture.hash,
+                    captured_at: capture.captured_at,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        tx.commit()?;
+        Ok((stored, failed))
+    }
+
+    /// Store one capture of a batch, returning the hash it was stored as;
+    /// see [`insert_row`](Self::insert_row).
+    fn write_capture(
+        &self,
+        conn: &Connection,
+        capture: &Capture,
+        spill: bool,
+    ) -> Result<Option<[u8; 32]>> {
+        let strip = self.strips_image_metadata();
+        let (item, own_hash) = self.stored_item(capture.hash, &capture.item);
+        let Some(hash) = self.insert_row(
+            conn,
+            own_hash,
+            &item,
+            &capture.metadata,
+            capture.captured_at,
+            capture.dedupe,
+        )?
+        else {
+            return Ok(None);
+        };
+        // The flavours belong to the copied content, not an equivalent
+        // entry it was folded into.
+        if hash != own_hash {
+            return Ok(Some(hash));
+        }
+        if let Some(html) = &capture.html {
+            conn.execute(
+                "UPDATE items SET html = ?2 WHERE hash = ?1;",
+                params![&hash[..], html],
+            )?;
+        }
+        if let Some(rtf) = &capture.rtf {
+            conn.execute(
+                "UPDATE items SET rtf = ?2 WHERE hash = ?1;",
+                params![&hash[..], rtf],
+            )?;
+        }
+        write_custom_formats(conn, hash, &capture.custom_formats, strip)?;
+        write_representations(conn, hash, &capture.representations, strip)?;
+        if let (true, Some(original)) = (spill, &capture.spill) {
+            conn.execute(
+                "INSERT OR REPLACE INTO spill.items (hash, text) VALUES (?1, ?2);",
+                params![&hash[..], original],
+            )?;
+        }
+        Ok(Some(hash))
+    }
+
+    /// What [`Vault::import`] would store, without storing anything.
+    pub fn preview_import(&self, entries: &[ImportedEntry]) -> Result<ImportSummary> {
+        self.write_imported(entries, false)
+            .map(|(summary, _)| summary)
+    }
+
+    /// Returns what was stored and the hashes of the new entries. Rolls
+    /// back instead of committing unless `commit`.
+    fn write_imported(
+        &self,
+        entries: &[ImportedEntry],
+        commit: bool,
+    ) -> Result<(ImportSummary, Vec<[u8; 32]>)> {
+        // ATTACH can't run inside a transaction.
+        let spill = commit && entries.iter().any(|e| e.spill.is_some()) && self.attach_spill()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut summary = ImportSummary::default();
+        let mut added = Vec::new();
+        for entry in entries {
+            let (item, hash) = self.stored_item(entry.item.hash(), &entry.item);
+            // Already imported if stored as is or, for links, under another
+            // spelling.
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM items WHERE hash = ?1 OR dedupe_key = ?2);",
+                params![&hash[..], entry.dedupe.key(&item)],
+                |row| row.get(0),
+            )?;
+            if exists {
+                summary.duplicates += 1;
+                continue;
+            }
+            let metadata = CaptureMetadata {
+                context: entry.context.clone(),
+                ttl: entry.ttl,
+                source: entry.source.clone(),
+            };
+            let timestamp = entry.timestamp.unwrap_or_else(Timestamp::now);
+            self.insert_row(&tx, hash, &item, &metadata, timestamp, entry.dedupe)?;
+            if let (true, Some(original)) = (spill, &entry.spill) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO spill.items (hash, text) VALUES (?1, ?2);",
+                    params![&hash[..], original],
+                )?;
+            }
+            for tag in &entry.tags {
+                tx.execute(
+                    "INSERT OR IGNORE INTO tags (hash, tag) VALUES (?1, ?2);",
+                    params![&hash[..], tag],
+                )?;
+            }
+            if entry.pinned {
+                tx.execute(
+                    "UPDATE items SET pinned = 1 WHERE hash = ?1;",
+                    params![&hash[..]],
+                )?;
+            }
+            summary.added += 1;
+            added.push(hash);
+        }
+        if commit {
+            tx.commit()?;
+        }
+        Ok((summary, added))
+    }
+
+    /// Unprotected entries newest first, as [`Vault::list`] with `columns`
+    /// selected.
+    fn list_columns(
+        &self,
+        columns: &str,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let mut clauses = vec!["protected = 0"];
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        if let Some(ts) = after_timestamp {
+            clauses.push("ts < ?");
+            params.push(Box::new(ts));
+        }
+        let limit = limit.map_or(String::new(), |n| format!(" LIMIT {n}"));
+        let query = format!(
+            "SELECT {columns} FROM items WHERE {} ORDER BY ts DESC{limit}",
+            clauses.join(" AND ")
+        );
+
+        let param_refs: Vec<&dyn rusqlite::ToSql> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+        self.query_items(&query, &param_refs)
+    }
+
+    /// Run a `SELECT hash, data, ts, context, source_app, source_window, id ...` query. Rows whose blob fails to
+    /// decode are skipped and recorded as diagnostics instead of failing the
+    /// whole query.
+    fn query_items(
+        &self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let mut items = Vec::new();
+        self.for_each_item(sql, params, |item| {
+            items.push(item);
+            Ok(())
+        })?;
+        Ok(items)
+    }
+
+    /// [`query_items`](Self::query_items), handing each entry to `f` as it's
+    /// read instead of collecting them, so only one is in memory at a time.
+    fn for_each_item(
+        &self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+        mut f: impl FnMut(ClipboardItemWithTimestamp) -> Result<()>,
+    ) -> Result<()> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let mut skipped = Vec::new();
+        let mut rows = stmt.query(params)?;
+        while let Some(row) = rows.next()? {
+            let timestamp: Timestamp = row.get(2)?;
+            match decode_item(&row.get::<_, Vec<u8>>(1)?) {
+                Ok(item) => f(ClipboardItemWithTimestamp {
+                    item,
+                    timestamp,
+                    context: row.get(3)?,
+                    source: row_source(row, 4)?,
+                    id: row.get(6)?,
+                })?,
+                Err(e) => skipped.push(RowDiagnostic {
+                    hash: row_hash(row)?,
+                    timestamp,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        self.set_diagnostics(skipped);
+        Ok(())
+    }
+
+    /// Entries matching `filter`, the rest of a `SELECT ... FROM items`
+    /// statement, with the thumbnail of a large image read in place of the
+    /// image. Undecodable rows are recorded as in
+    /// [`query_items`](Self::query_items).
+    fn query_listed(
+        &self,
+        filter: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<ListedEntry>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT hash, CASE WHEN {THUMBNAIL} IS NULL THEN data END, ts, context, source_app,
+                    source_window, id, {THUMBNAIL}, width, height, format, size, lines, words
+             FROM items {filter}"
+        ))?;
+        let mut listed = Vec::new();
+        let mut skipped = Vec::new();
+        let mut rows = stmt.query(params)?;
+        while let Some(row) = rows.next()? {
+            let timestamp: Timestamp = row.get(2)?;
+            let thumbnail: Option<Vec<u8>> = row.get(7)?;
+            let is_thumbnail = thumbnail.is_some();
+            let decoded = match thumbnail {
+                Some(thumbnail) => Ok(ClipboardItem::Image(thumbnail)),
+                None => decode_item(&row.get::<_, Vec<u8>>(1)?),
+            };
+            let item = match decoded {
+                Ok(item) => item,
+                Err(e) => {
+                    skipped.push(RowDiagnostic {
+                        hash: row_hash(row)?,
+                        timestamp,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let stats = row_stats(row, 11)?;
+            let image = match (row.get(8)?, row.get(9)?, row.get(10)?) {
+                (Some(width), Some(height), Some(format)) => Some(ImageInfo {
+                    width,
+                    height,
+                    format,
+                    bytes: stats.bytes,
+                }),
+                _ => None,
+            };
+            listed.push(ListedEntry {
+                hash: row_hash(row)?,
+                entry: ClipboardItemWithTimestamp {
+                    item,
+                    timestamp,
+                    context: row.get(3)?,
+                    source: row_source(row, 4)?,
+                    id: row.get(6)?,
+                },
+                thumbnail: is_thumbnail,
+                image,
+                stats,
+            });
+        }
+
+        self.set_diagnostics(skipped);
+        Ok(listed)
+    }
+
+    /// The entry with `hash` as [`Vault::list_thumbnails`] lists it; `None`
+    /// if it is missing or protected.
+    pub fn get_listed(&self, hash: [u8; 32]) -> Result<Option<ListedEntry>> {
+        Ok(self
+            .query_listed("WHERE hash = ?1 AND protected = 0", &[&&hash[..]])?
+            .pop())
+    }
+
+    /// Like [`get_listed`](Self::get_listed), by the entry's id.
+    pub fn get_listed_by_id(&self, id: i64) -> Result<Option<ListedEntry>> {
+        Ok(self
+            .query_listed("WHERE id = ?1 AND protected = 0", &[&id])?
+            .pop())
+    }
+
+    /// The pinned entries, newest first, as [`Vault::list_thumbnails`] lists
+    /// them.
+    pub fn pinned_thumbnails(&self) -> Result<Vec<ListedEntry>> {
+        self.query_listed("WHERE protected = 0 AND pinned = 1 ORDER BY ts DESC", &[])
+    }
+
+    /// Make thumbnails for up to `batch` large images stored before
+    /// thumbnails were made, returning how many were looked at. Images
+    /// without one are listed whole until then; call this now and then,
+    /// as decoding them all at once would stall opening the vault.
+    pub fn backfill_thumbnails(&self, batch: usize) -> Result<usize> {
+        let rows: Vec<(Vec<u8>, Vec<u8>)> = self
+            .conn
+            .prepare(
+                "SELECT hash, data FROM items
+                 WHERE kind = 'image' AND format = 'PNG' AND thumbnail IS NULL
+                   AND (width > ?1 OR height > ?1)
+                 LIMIT ?2;",
+            )?
+            .query_map(
+                params![THUMBNAIL_SIZE, i64::try_from(batch).unwrap_or(i64::MAX)],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?
+            .collect::<std::result::Result<_, _>>()?;
+        let tx = self.conn.unchecked_transaction()?;
+        for (hash, blob) in &rows {
+            // An empty thumbnail marks an image that can't have one, so it
+            // isn't decoded again.
+            let thumbnail = decode_item(blob)
+                .ok()
+                .as_ref()
+                .and_then(item_thumbnail)
+                .unwrap_or_default();
+            tx.execute(
+                "UPDATE items SET thumbnail = ?2 WHERE hash = ?1;",
+                params![hash, thumbnail],
+            )?;
+        }
+        tx.commit()?;
+        Ok(rows.len())
+    }
+
+    /// Unprotected, unpinned entries older than `policy` allows, then the
+    /// oldest of the rest until those left within reach of the policy fit
+    /// its item cap, then until they fit its size cap, images first,
+    /// leaving out those its kept tags exempt. Pinned and exempt entries
+    /// don't count towards the caps, so keeping many of them never empties
+    /// the rest of the history. What [`Vault::prune`] deletes and
+    /// [`Vault::preview_prune`] reports, along with how many of them, at
+    /// the end, only the size cap removes.
+    fn prune_selection(&self, policy: &RetentionPolicy) -> Result<(Vec<[u8; 32]>, usize)> {
+        if policy.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
+        let now = Timestamp::now();
+
+        let mut exempt = HashSet::new();
+        {
+            // A negative limit is no limit in SQLite.
+            let mut stmt = self.conn.prepare(
+                "SELECT t.hash FROM tags t JOIN items i ON i.hash = t.hash
+                 WHERE t.tag = ?1 ORDER BY i.ts DESC LIMIT ?2;",
+            )?;
+            for (tag, count) in &policy.keep_tags {
+                let limit = count.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+                let mut rows = stmt.query(params![tag, limit])?;
+                while let Some(row) = rows.next()? {
+                    exempt.insert(row_hash(row)?);
+                }
+            }
+        }
+
+        // Oldest first, so the caps take what's left after the age rules
+        // from the old end.
+        let mut expired = Vec::new();
+        let mut remaining = Vec::new();
+        let (mut count, mut size) = (0_u64, 0_u64);
+        {
+            let spilled = if self.spill_attached.load(Ordering::Relaxed) {
+                "(SELECT LENGTH(text) FROM spill.items s WHERE s.hash = i.hash)"
+            } else {
+                "0"
+            };
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT hash, mime, text, ts, LENGTH(data)
+                     + COALESCE((SELECT SUM(LENGTH(data)) FROM representations r
+                                 WHERE r.hash = i.hash), 0)
+                     + COALESCE((SELECT SUM(LENGTH(data)) FROM custom_formats f
+                                 WHERE f.hash = i.hash), 0)
+                     + COALESCE({spilled}, 0)
+                 FROM items i WHERE protected = 0 AND pinned = 0 ORDER BY ts ASC;"
+            ))?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let hash = row_hash(row)?;
+                if exempt.contains(&hash) {
+                    continue;
+                }
+                let mime: String = row.get(1)?;
+                let text: Option<String> = row.get(2)?;
+                let ts: Timestamp = row.get(3)?;
+                let bytes: u64 = row.get(4)?;
+                let kind = ItemKind::from_row(&mime, text.as_deref());
+                if policy
+                    .max_age(kind)
+                    .is_some_and(|max_age| now.duration_since(ts) > max_age)
+                {
+                    expired.push(hash);
+                } else {
+                    count += 1;
+                    size += bytes;
+                    remaining.push((hash, bytes, kind == ItemKind::Image));
+                }
+            }
+        }
+
+        let max_items = policy.max_items.map_or(u64::MAX, |n| n as u64);
+        let over_count = usize::try_from(count.saturating_sub(max_items))
+            .unwrap_or(usize::MAX)
+            .min(remaining.len());
+        let rest = remaining.split_off(over_count);
+        for (hash, bytes, _) in remaining {
+            expired.push(hash);
+            size -= bytes;
+        }
+
+        // Images take the most room, so the size cap takes them first.
+        let max_bytes = policy.max_bytes().unwrap_or(u64::MAX);
+        let mut over_size = 0;
+        for images in [true, false] {
+            for (hash, bytes, image) in &rest {
+                if size <= max_bytes {
+                    break;
+                }
+                if *image == images {
+                    expired.push(*hash);
+                    over_size += 1;
+                    size -= bytes;
+                }
+            }
+        }
+        Ok((expired, over_size))
+    }
+
+    /// Describe the entries with `hashes` as a [`DryRun`].
+    fn dry_run(&self, hashes: &[[u8; 32]]) -> Result<DryRun> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT ts, data FROM items WHERE hash = ?1;")?;
+        let mut entries = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let row = stmt
+                .query_row(params![&hash[..]], |row| {
+                    Ok((row.get::<_, Timestamp>(0)?, row.get::<_, Vec<u8>>(1)?))
+                })
+                .optional()?;
+            if let Some((ts, data)) = row {
+                entries.push(AffectedEntry::new(*hash, ts, &decode_item(&data)?));
+            }
+        }
+        Ok(DryRun::new(entries))
+    }
+
+    /// Delete the entries with `hashes` and their spilled content, auditing
+    /// each deletion, in one transaction, then tell subscribers.
+    fn delete_hashes(&self, hashes: &[[u8; 32]]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for hash in hashes {
+            tx.execute("DELETE FROM items WHERE hash = ?1;", params![&hash[..]])?;
+            self.delete_spilled(&tx, hash)?;
+            self.audit_on(&tx, AuditAction::Delete, Some(hash))?;
+        }
+        tx.commit()?;
+        for &hash in hashes {
+            self.notify(&VaultEvent::Deleted { hash });
+        }
+        Ok(())
+    }
+
+    fn notify(&self, event: &VaultEvent) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
+    fn notify_health(&self, event: &HealthEvent) {
+        self.health_subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .retain(|tx| tx.send(event.clone()).is_ok());
+    }
+
+    /// Report the start and end of a run of failed inserts.
+    fn track_write(&self, error: Option<&Error>) {
+        let failing = error.is_some();
+        if self.write_failing.swap(failing, Ordering::Relaxed) == failing {
+            return;
+        }
+        self.notify_health(&error.map_or(HealthEvent::WriteRecovered, |e| {
+            HealthEvent::WriteFailed {
+                error: e.to_string(),
+                disk_full: e.is_disk_full(),
+            }
+        }));
+    }
+
+    fn report_pruned(&self, removed: usize, reason: PruneReason) {
+        if removed > 0 {
+            self.notify_health(&HealthEvent::Pruned { removed, reason });
+        }
+    }
+
+    fn set_diagnostics(&self, diagnostics: Vec<RowDiagnostic>) {
+        let count = {
+            let mut reported = self
+                .reported_unreadable
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            diagnostics
+                .iter()
+                .filter(|d| reported.insert(d.hash))
+                .count()
+        };
+        if count > 0 {
+            self.notify_health(&HealthEvent::UnreadableEntries { count });
+        }
+        *self
+            .diagnostics
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = diagnostics;
+    }
+}
+
+/// Split oldest-first `entries` by the local hour they were captured in.
+fn group_by_hour(entries: Vec<ClipboardItemWithTimestamp>) -> Vec<TimelineHour> {
+    let mut hours: Vec<TimelineHour> = Vec::new();
+    for entry in entries {
+        let hour = entry.timestamp.to_local().hour();
+        match hours.last_mut() {
+            Some(last) if last.hour == hour => last.entries.push(entry),
+            _ => hours.push(TimelineHour {
+                hour,
+                entries: vec![entry],
+            }),
+        }
+    }
+    hours
+}
+
+/// Bring `items` up to date with the columns added since it was created.
+fn add_item_columns(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "items", "context", "TEXT")?;
+    add_column_if_missing(conn, "items", "use_count", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "items", "last_used", "INTEGER")?;
+    let mut backfill = add_column_if_missing(conn, "items", "size", "INTEGER")?;
+    backfill |= add_column_if_missing(conn, "items", "width", "INTEGER")?;
+    backfill |= add_column_if_missing(conn, "items", "height", "INTEGER")?;
+    backfill |= add_column_if_missing(conn, "items", "format", "TEXT")?;
+    add_column_if_missing(conn, "items", "html", "TEXT")?;
+    add_column_if_missing(conn, "items", "protected", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "items", "expires_at", "INTEGER")?;
+    add_column_if_missing(conn, "items", "last_seen", "INTEGER")?;
+    add_column_if_missing(conn, "items", "device", "TEXT")?;
+    add_column_if_missing(conn, "items", "generation", "INTEGER")?;
+    add_column_if_missing(conn, "items", "edited_from", "BLOB")?;
+    backfill |= add_column_if_missing(conn, "items", "kind", "TEXT NOT NULL DEFAULT 'text'")?;
+    if backfill {
+        backfill_item_info(conn)?;
+    }
+    add_column_if_missing(conn, "items", "source_app", "TEXT")?;
+    add_column_if_missing(conn, "items", "source_window", "TEXT")?;
+    add_column_if_missing(conn, "items", "rtf", "TEXT")?;
+    add_column_if_missing(conn, "items", "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "items", "thumbnail", "BLOB")?;
+    if add_column_if_missing(conn, "items", "dedupe_key", "TEXT")? {
+        backfill_dedupe_keys(conn)?;
+    }
+    add_column_if_missing(conn, "items", "note", "TEXT")?;
+    let mut counts = add_column_if_missing(conn, "items", "lines", "INTEGER NOT NULL DEFAULT 0")?;
+    counts |= add_column_if_missing(conn, "items", "words", "INTEGER NOT NULL DEFAULT 0")?;
+    if counts {
+        backfill_counts(conn)?;
+    }
+    Ok(())
+}
+
+/// Columns added to `items` after archives were introduced, which archives
+/// created before them lack.
+fn add_archive_columns(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "archive.items", "source_app", "TEXT")?;
+    add_column_if_missing(conn, "archive.items", "source_window", "TEXT")?;
+    add_column_if_missing(conn, "archive.items", "rtf", "TEXT")?;
+    add_column_if_missing(conn, "archive.items", "note", "TEXT")?;
+    add_column_if_missing(conn, "archive.items", "thumbnail", "BLOB")?;
+    add_column_if_missing(conn, "archive.items", "lines", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "archive.items", "words", "INTEGER NOT NULL DEFAULT 0")?;
+    Ok(())
+}
+
+/// Fail if another connection has the vault at `path` open. In WAL mode
+/// each one holds a shared lock on the file for as long as it's open, so
+/// taking an exclusive lock fails while any does. Getting that far means
+/// reading the file, which takes its `key`; with a wrong one this can't
+/// tell and passes.
+pub fn ensure_vault_closed(path: &Path, key: &str) -> Result<()> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    CipherOptions::for_vault(path)?.apply(&conn, key)?;
+    conn.busy_timeout(std::time::Duration::ZERO)?;
+    conn.pragma_update(None, "locking_mode", "EXCLUSIVE")?;
+    match conn.execute_batch("BEGIN EXCLUSIVE; COMMIT;") {
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if e.code == rusqlite::ErrorCode::DatabaseBusy =>
+        {
+            Err(Error::Io(std::io::Error::other(format!(
+                "{} is open in another process, such as the app or `clip-vault watch`; \
+                 close it first",
+                path.display()
+            ))))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The source app stored in columns `first` (name) and `first + 1` (window
+/// title).
+fn row_source(row: &rusqlite::Row<'_>, first: usize) -> rusqlite::Result<Option<SourceApp>> {
+    let name: Option<String> = row.get(first)?;
+    let window: Option<String> = row.get(first + 1)?;
+    Ok(name.and_then(|name| SourceApp::new(&name, window.as_deref())))
+}
+
+/// The stats stored in columns `first` (size), `first + 1` (lines) and
+/// `first + 2` (words).
+fn row_stats(row: &rusqlite::Row<'_>, first: usize) -> rusqlite::Result<ItemStats> {
+    Ok(ItemStats {
+        bytes: row.get::<_, Option<u64>>(first)?.unwrap_or(0),
+        lines: row.get(first + 1)?,
+        words: row.get(first + 2)?,
+    })
+}
+
+/// The schema version recorded in the vault, refusing one newer than this
+/// release knows: its tables may have changed in ways the queries here
+/// would misread or damage.
+fn schema_version(conn: &Connection) -> Result<u32> {
+    let found: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if found > SCHEMA_VERSION {
+        return Err(Error::IncompatibleSchema {
+            found,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    Ok(found)
+}
+
+/// Add a column to an existing table. `CREATE TABLE IF NOT EXISTS` leaves
+/// databases created by older versions untouched, so new columns go here.
+/// Returns whether the column was added.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<bool> {
+    let pragma = match table.split_once('.') {
+        Some((schema, table)) => format!("PRAGMA {schema}.table_info({table});"),
+        None => format!("PRAGMA table_info({table});"),
+    };
+    let mut stmt = conn.prepare(&pragma)?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(std::result::Result::ok)
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl};"))?;
+    }
+    Ok(!exists)
+}
+
+/// Record the category, payload size, line and word counts and, for
+/// images, dimensions and format of `item`.
+fn set_item_info(conn: &Connection, hash: &[u8; 32], item: &ClipboardItem) -> Result<()> {
+    let stats = ItemStats::of(item);
+    let kind = ItemKind::of(item);
+    let info = item.image_info();
+    conn.execute(
+        "UPDATE items SET size = ?2, width = ?3, height = ?4, format = ?5, kind = ?6,
+         thumbnail = ?7, lines = ?8, words = ?9 WHERE hash = ?1;",
+        params![
+            &hash[..],
+            stats.bytes,
+            info.as_ref().map(|i| i.width),
+            info.as_ref().map(|i| i.height),
+            info.as_ref().map(|i| i.format.as_str()),
+            kind.as_str(),
+            item_thumbnail(item),
+            stats.lines,
+            stats.words,
+        ],
+    )?;
+    Ok(())
+}
+
+fn item_thumbnail(item: &ClipboardItem) -> Option<Vec<u8>> {
+    match item {
+        ClipboardItem::Image(data) => crate::thumbnail(data),
+        ClipboardItem::Text(_) | ClipboardItem::Files(_) => None,
+    }
+}
+
+/// Sort text stored before links, emails, colors, JSON, paths and code had
+/// kinds of their own, or before `rgb()` and `hsl()` counted as colors,
+/// into them.
+fn reclassify_text(conn: &Connection) -> Result<()> {
+    let rows: Vec<(Vec<u8>, String)> = conn
+        .prepare("SELECT hash, text FROM items WHERE kind = 'text' AND text IS NOT NULL;")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    let tx = conn.unchecked_transaction()?;
+    for (hash, text) in rows {
+        let kind = ItemKind::from_row("text/plain", Some(&text));
+        if kind != ItemKind::Text {
+            tx.execute(
+                "UPDATE items SET kind = ?2 WHERE hash = ?1;",
+                params![hash, kind.as_str()],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Give file entries stored under [`OLD_FILES_MIME`] their current hash and
+/// MIME type, moving their tags, formats and copy events along. The old
+/// hash was the paths prefixed with the MIME type, which text could match.
+fn rehash_files(conn: &Connection, schema: &str) -> Result<()> {
+    let rows: Vec<(Vec<u8>, Vec<u8>)> = conn
+        .prepare(&format!(
+            "SELECT hash, data FROM {schema}.items WHERE mime = '{OLD_FILES_MIME}';"
+        ))?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    let tx = conn.unchecked_transaction()?;
+    for (old, blob) in rows {
+        let Ok(item) = decode_item(&blob) else {
+            continue;
+        };
+        let new = item.hash();
+        tx.execute(
+            &format!("UPDATE {schema}.items SET hash = ?2, mime = ?3 WHERE hash = ?1;"),
+            params![old, &new[..], FILES_MIME],
+        )?;
+        // The vault's own triggers have moved these already; the archive
+        // has none.
+        for table in ["tags", "custom_formats", "representations", "copy_events"] {
+            tx.execute(
+                &format!("UPDATE {schema}.{table} SET hash = ?2 WHERE hash = ?1;"),
+                params![old, &new[..]],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Fill in size and image info for rows written before those columns existed.
+fn backfill_item_info(conn: &Connection) -> Result<()> {
+    let rows: Vec<(Vec<u8>, Vec<u8>)> = conn
+        .prepare("SELECT hash, data FROM items;")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    let tx = conn.unchecked_transaction()?;
+    for (hash, blob) in rows {
+        // Undecodable rows are surfaced through diagnostics on read.
+        let (Ok(hash), Ok(item)) = (<[u8; 32]>::try_from(hash), decode_item(&blob)) else {
+            continue;
+        };
+        set_item_info(&tx, &hash, &item)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Count the lines and words of text and file entries stored before the
+/// counts were recorded, from their text so no blob is decoded.
+fn backfill_counts(conn: &Connection) -> Result<()> {
+    let rows: Vec<(Vec<u8>, String, String)> = conn
+        .prepare("SELECT hash, mime, text FROM items WHERE text IS NOT NULL AND kind != 'image';")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    let tx = conn.unchecked_transaction()?;
+    for (hash, mime, text) in rows {
+        let stats = if mime == FILES_MIME {
+            ItemStats::of(&ClipboardItem::Files(crate::files_from_text(&text)))
+        } else {
+            ItemStats::of_text(&text)
+        };
+        tx.execute(
+            "UPDATE items SET lines = ?2, words = ?3 WHERE hash = ?1;",
+            params![hash, stats.lines, stats.words],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Give links stored before dedupe keys were recorded theirs.
+fn backfill_dedupe_keys(conn: &Connection) -> Result<()> {
+    let rows: Vec<(Vec<u8>, String)> = conn
+        .prepare("SELECT hash, text FROM items WHERE text LIKE 'http%';")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    let tx = conn.unchecked_transaction()?;
+    for (hash, text) in rows {
+        if let Some(key) = crate::normalize_url(&text) {
+            tx.execute(
+                "UPDATE items SET dedupe_key = ?2 WHERE hash = ?1;",
+                params![hash, key],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
 
-    fn len(&self) -> Result<usize>;
+/// The query behind [`Vault::search`] and [`SqliteVault::search_previews`],
+/// selecting `columns` from the matching rows.
+/// A search of `schema` ("main", or "archive" while it is attached).
+fn search_sql(
+    schema: &str,
+    columns: &str,
+    query: &str,
+    limit: Option<usize>,
+    after_timestamp: Option<u64>,
+) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let query = SearchQuery::parse(query);
+    let mut clauses = vec!["protected = 0".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
-    fn is_empty(&self) -> Result<bool> {
-        Ok(self.len()? == 0)
+    // A bare filter like `dim:>1000x1000` should find images, so the
+    // text match only applies when there is text to match. Notes match
+    // too, which finds images by what was noted about them.
+    if query.text.is_empty() && query.filters.is_empty() {
+        clauses.push("mime != 'image/png'".to_string());
+    } else if !query.text.is_empty() {
+        clauses.push(
+            "((text LIKE ? ESCAPE '\\' AND mime != 'image/png') OR note LIKE ? ESCAPE '\\')"
+                .to_string(),
+        );
+        let pattern = format!("%{}%", escape_like(&query.text));
+        params.push(Box::new(pattern.clone()));
+        params.push(Box::new(pattern));
     }
+    for filter in query.filters {
+        match filter {
+            Filter::Size(cmp, bytes) => {
+                clauses.push(format!("size {} ?", cmp.as_sql()));
+                params.push(Box::new(bytes));
+            }
+            Filter::Dim(cmp, width, height) => {
+                clauses.push(format!("width {op} ? AND height {op} ?", op = cmp.as_sql()));
+                params.push(Box::new(width));
+                params.push(Box::new(height));
+            }
+            Filter::Tag(tag) => {
+                clauses.push(format!(
+                    "hash IN (SELECT hash FROM {schema}.tags WHERE tag = ?)"
+                ));
+                params.push(Box::new(tag));
+            }
+            // LIKE ignores ASCII case.
+            Filter::App(app) => {
+                clauses.push("source_app LIKE '%' || ? || '%' ESCAPE '\\'".to_string());
+                params.push(Box::new(escape_like(&app)));
+            }
+            Filter::Kind(kind) => {
+                clauses.push("kind = ?".to_string());
+                params.push(Box::new(kind.as_str()));
+            }
+            Filter::Since(ts) => {
+                clauses.push("ts >= ?".to_string());
+                params.push(Box::new(ts));
+            }
+            Filter::Before(ts) => {
+                clauses.push("ts < ?".to_string());
+                params.push(Box::new(ts));
+            }
+        }
+    }
+    if let Some(ts) = after_timestamp {
+        clauses.push("ts < ?".to_string());
+        params.push(Box::new(ts));
+    }
+
+    let limit = limit.map_or(String::new(), |n| format!(" LIMIT {n}"));
+    let sql = format!(
+        "SELECT {columns} FROM {schema}.items WHERE {} ORDER BY ts DESC{limit}",
+        clauses.join(" AND ")
+    );
+    (sql, params)
 }
 
-use rusqlite::{params, Connection};
+fn decode_item(blob: &[u8]) -> Result<ClipboardItem> {
+    let (item, _): (ClipboardItem, usize) =
+        bincode::decode_from_slice(blob, bincode::config::standard())?;
+    Ok(item)
+}
 
-pub struct SqliteVault {
-    conn: Connection,
+/// `text` escaped for a `LIKE ... ESCAPE '\'` pattern, so its `%` and `_`
+/// match themselves rather than any characters.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
-impl SqliteVault {
-    pub fn open<P: AsRef<std::path::Path>>(path: P, key: &str) -> Result<Self> {
-        let conn = Connection::open(path)?;
-        conn.pragma_update(None, "key", key)?;
-        conn.pragma_update(None, "journal_mode", "WAL")?;
+/// The hash in a row's first column. A blob of another length is an error
+/// rather than a made-up hash.
+fn row_hash(row: &rusqlite::Row<'_>) -> Result<[u8; 32]> {
+    Ok(row.get(0)?)
+}
 
-        conn.execute_batch(
-            "
-            CREATE TABLE IF NOT EXISTS items (
-                id      INTEGER PRIMARY KEY,
-                hash    BLOB    UNIQUE NOT NULL,
-                mime    TEXT    NOT NULL,
-                text    TEXT,
-                data    BLOB    NOT NULL,
-                ts      INTEGER NOT NULL
-            );
-            
-            CREATE INDEX IF NOT EXISTS idx_mime_text ON items (mime, text);
-            CREATE INDEX IF NOT EXISTS idx_ts ON items (ts);
-            ",
+/// Note a copy of the entry with `hash`, dropping its oldest events past
+/// [`MAX_COPY_EVENTS`].
+/// Move the entry with `hash`, and what's stored against it, to a hash of
+/// its own derived from its id, freeing `hash` for a new copy of the same
+/// content. The moved entry keeps its place and is found by id.
+fn retire_row(conn: &Connection, hash: &[u8; 32]) -> Result<()> {
+    let id: i64 = conn.query_row(
+        "SELECT id FROM items WHERE hash = ?1;",
+        params![&hash[..]],
+        |row| row.get(0),
+    )?;
+    let mut hasher = Sha256::new();
+    hasher.update(hash);
+    hasher.update(id.to_le_bytes());
+    let retired: [u8; 32] = hasher.finalize().into();
+    for table in [
+        "items",
+        "tags",
+        "custom_formats",
+        "representations",
+        "copy_events",
+    ] {
+        conn.execute(
+            &format!("UPDATE {table} SET hash = ?2 WHERE hash = ?1;"),
+            params![&hash[..], &retired[..]],
+        )?;
+    }
+    Ok(())
+}
+
+fn record_copy_event(
+    conn: &Connection,
+    hash: [u8; 32],
+    timestamp: Timestamp,
+    kind: CopyEventKind,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO copy_events (hash, ts, kind) VALUES (?1, ?2, ?3);",
+        params![&hash[..], timestamp, kind.as_str()],
+    )?;
+    conn.execute(
+        "DELETE FROM copy_events WHERE hash = ?1 AND id NOT IN
+            (SELECT id FROM copy_events WHERE hash = ?1 ORDER BY ts DESC, id DESC LIMIT ?2);",
+        params![&hash[..], MAX_COPY_EVENTS],
+    )?;
+    Ok(())
+}
+
+/// Whether the latest copy of the entry with `hash` was out of the vault
+/// and nothing else has been captured since, so the clipboard still holds
+/// that copy.
+fn still_copied_out(conn: &Connection, hash: [u8; 32]) -> Result<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT kind = 'copied_out'
+                AND NOT EXISTS (SELECT 1 FROM items WHERE ts > copy_events.ts AND hash != ?1)
+             FROM copy_events WHERE hash = ?1 ORDER BY ts DESC, id DESC LIMIT 1;",
+            params![&hash[..]],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(false))
+}
+
+/// Replace the custom formats of the entry with `hash`. An entry keeps the
+/// formats of its latest copy, so copying the same text from a plain editor
+/// drops those a spreadsheet left. With `strip`, PNGs and JPEGs among them
+/// lose their metadata.
+fn write_custom_formats(
+    conn: &Connection,
+    hash: [u8; 32],
+    formats: &[CustomFormat],
+    strip: bool,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM custom_formats WHERE hash = ?1;",
+        params![&hash[..]],
+    )?;
+    for (position, format) in (0_i64..).zip(formats) {
+        conn.execute(
+            "INSERT INTO custom_formats (hash, position, name, data) VALUES (?1, ?2, ?3, ?4);",
+            params![
+                &hash[..],
+                position,
+                format.name,
+                stored_bytes(&format.data, strip)
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_representations(
+    conn: &Connection,
+    hash: [u8; 32],
+    representations: &[Representation],
+    strip: bool,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM representations WHERE hash = ?1;",
+        params![&hash[..]],
+    )?;
+    for representation in representations {
+        conn.execute(
+            "INSERT OR REPLACE INTO representations (hash, mime, data) VALUES (?1, ?2, ?3);",
+            params![
+                &hash[..],
+                representation.mime,
+                stored_bytes(&representation.data, strip)
+            ],
         )?;
+    }
+    Ok(())
+}
+
+/// `data` as stored: without image metadata if `strip`. Anything that isn't
+/// a PNG or JPEG is kept as is.
+fn stored_bytes(data: &[u8], strip: bool) -> Cow<'_, [u8]> {
+    if strip {
+        Cow::Owned(crate::strip_image_metadata(data))
+    } else {
+        Cow::Borrowed(data)
+    }
+}
+
+/// Attach the database at `path` as `schema`. Without a KEY clause
+/// `SQLCipher` attaches with the main database's derived key and its own
+/// default settings, whatever cipher settings the vault has.
+fn attach(conn: &Connection, path: &Path, schema: &str) -> Result<()> {
+    conn.execute(
+        &format!("ATTACH DATABASE ?1 AS {schema};"),
+        params![path.to_string_lossy()],
+    )?;
+    Ok(())
+}
+
+/// Next to the vault, unless [`blob_dir`](crate::blob_dir) says otherwise.
+/// There it's named after a hash of the vault's full path as well as its
+/// file name, as profiles' vaults are often all called `clip_vault.db`.
+fn spill_path(vault: &Path) -> PathBuf {
+    let beside = vault.with_extension("spill.db");
+    let (Some(dir), Some(stem)) = (crate::blob_dir(), vault.file_stem()) else {
+        return beside;
+    };
+    let full = std::path::absolute(vault).unwrap_or_else(|_| vault.to_path_buf());
+    let digest: [u8; 32] = Sha256::digest(full.as_os_str().as_encoded_bytes()).into();
+    dir.join(format!(
+        "{}-{}.spill.db",
+        stem.to_string_lossy(),
+        &crate::hash_to_hex(&digest)[..16]
+    ))
+}
+
+/// Move a spill file left next to the vault into the
+/// [`blob_dir`](crate::blob_dir) set since, along with its journals. Copies
+/// and deletes where the two are on different filesystems.
+fn move_spill_file(vault: &Path) -> Result<()> {
+    let beside = vault.with_extension("spill.db");
+    let dest = spill_path(vault);
+    if dest == beside || !beside.exists() || dest.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    for suffix in ["-wal", "-shm", ""] {
+        let with_suffix = |path: &Path| {
+            let mut name = path.as_os_str().to_owned();
+            name.push(suffix);
+            PathBuf::from(name)
+        };
+        let (from, to) = (with_suffix(&beside), with_suffix(&dest));
+        if !from.exists() {
+            continue;
+        }
+        if std::fs::rename(&from, &to).is_err() {
+            std::fs::copy(&from, &to)?;
+            std::fs::remove_file(&from)?;
+        }
+    }
+    Ok(())
+}
+
+/// Next to the vault, like the vault itself under [`data_dir`](crate::data_dir).
+fn archive_path(vault: &Path) -> PathBuf {
+    vault.with_extension("archive.db")
+}
+
+/// Where [`SqliteVault::backup_to`] writes the archive of a snapshot at
+/// `snapshot`: named like a vault's own archive.
+#[must_use]
+pub fn snapshot_archive_path(snapshot: &Path) -> PathBuf {
+    archive_path(snapshot)
+}
 
-        Ok(Self { conn })
+/// Bytes the vault at `path` takes up on disk, counting its write-ahead log
+/// and spill file, without opening it.
+#[must_use]
+pub fn vault_file_size(path: &Path) -> u64 {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    [path.to_path_buf(), PathBuf::from(wal), spill_path(path)]
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Every file that makes up the vault at `path`: the database with its
+/// journals, the archive and spill sidecars, and the cipher settings,
+/// password hint and recovery copy kept next to it. Not all of them exist.
+#[must_use]
+pub fn vault_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for db in [
+        path.to_path_buf(),
+        archive_path(path),
+        spill_path(path),
+        crate::recovery::recovery_path(path),
+    ] {
+        for suffix in ["-wal", "-shm", "-journal"] {
+            let mut journal = db.as_os_str().to_owned();
+            journal.push(suffix);
+            files.push(PathBuf::from(journal));
+        }
+        files.push(db);
     }
+    files.push(crate::cipher_path(path));
+    files.push(crate::recovery::hint_path(path));
+    files
 }
 
 unsafe impl Send for SqliteVault {}
 unsafe impl Sync for SqliteVault {}
 
 impl Vault for SqliteVault {
-    fn insert(&self, hash: [u8; 32], item: &ClipboardItem) -> Result<()> {
-        let timestamp = u64::try_from(
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_nanos(),
-        )
-        .unwrap();
-
-        let (text, mime) = item.clone().into_parts();
-        if mime == "image/png" {
-            self.conn.execute(
-                "INSERT OR IGNORE INTO items (hash, mime, data, ts) VALUES (?1, ?2, ?3, ?4) ON CONFLICT(hash) DO UPDATE SET ts = ?4;",
-                params![&hash[..], mime, bincode::encode_to_vec(item, bincode::config::standard())?, timestamp],
-            )?;
-        } else {
-            self.conn.execute(
-                "INSERT OR IGNORE INTO items (hash, mime, text, data, ts) VALUES (?1, ?2, ?3, ?4, ?5) ON CONFLICT(hash) DO UPDATE SET ts = ?5;",
-                params![&hash[..], mime, text, bincode::encode_to_vec(item, bincode::config::standard())?, timestamp],
-            )?;
+    fn insert_with_metadata(
+        &self,
+        hash: [u8; 32],
+        item: &ClipboardItem,
+        metadata: &CaptureMetadata,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        let (item, hash) = self.stored_item(hash, item);
+        let dedupe = self.dedupe_rules().resolve(&item);
+        // The row, its stats, copy event and audit record land together.
+        let result = self
+            .conn
+            .unchecked_transaction()
+            .map_err(Error::from)
+            .and_then(|tx| {
+                let hash = self.insert_row(&tx, hash, &item, metadata, Timestamp::now(), dedupe)?;
+                tx.commit()?;
+                Ok(hash)
+            });
+        self.track_write(result.as_ref().err());
+        if let Some(hash) = result? {
+            self.notify(&VaultEvent::Added { hash });
         }
-
         Ok(())
     }
 
+    fn get(&self, hash: [u8; 32]) -> Result<Option<ClipboardItemWithTimestamp>> {
+        Ok(self
+            .query_items(
+                "SELECT hash, data, ts, context, source_app, source_window, id FROM items WHERE hash = ?1 AND protected = 0;",
+                &[&&hash[..]],
+            )?
+            .pop())
+    }
+
+    fn get_by_id(&self, id: i64) -> Result<Option<ClipboardItemWithTimestamp>> {
+        Ok(self
+            .query_items(
+                "SELECT hash, data, ts, context, source_app, source_window, id FROM items WHERE id = ?1 AND protected = 0;",
+                &[&id],
+            )?
+            .pop())
+    }
+
     fn latest(&self) -> Result<Option<ClipboardItem>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT data FROM items ORDER BY ts DESC LIMIT 1;")?;
+        // Walk newest-first so one undecodable row doesn't hide the latest
+        // readable entry.
+        let mut stmt = self.conn.prepare(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items WHERE protected = 0 ORDER BY ts DESC;",
+        )?;
         let mut rows = stmt.query([])?;
-        if let Some(row) = rows.next()? {
-            let blob: Vec<u8> = row.get(0)?;
-            let (item, _): (ClipboardItem, usize) =
-                bincode::decode_from_slice(&blob, bincode::config::standard())?;
-            Ok(Some(item))
-        } else {
-            Ok(None)
+        let mut skipped = Vec::new();
+        let mut latest = None;
+        while let Some(row) = rows.next()? {
+            let blob: Vec<u8> = row.get(1)?;
+            match decode_item(&blob) {
+                Ok(item) => {
+                    latest = Some(item);
+                    break;
+                }
+                Err(e) => skipped.push(RowDiagnostic {
+                    hash: row_hash(row)?,
+                    timestamp: row.get(2)?,
+                    error: e.to_string(),
+                }),
+            }
         }
+
+        self.set_diagnostics(skipped);
+        Ok(latest)
     }
 
     fn list(
@@ -105,48 +2855,36 @@ impl Vault for SqliteVault {
         limit: Option<usize>,
         after_timestamp: Option<u64>,
     ) -> Result<Vec<ClipboardItemWithTimestamp>> {
-        let (query, params): (String, Vec<Box<dyn rusqlite::ToSql>>) =
-            match (limit, after_timestamp) {
-                (Some(n), Some(ts)) => (
-                    format!("SELECT data, ts FROM items WHERE ts < ? ORDER BY ts DESC LIMIT {n}"),
-                    vec![Box::new(ts)],
-                ),
-                (Some(n), None) => (
-                    format!("SELECT data, ts FROM items ORDER BY ts DESC LIMIT {n}"),
-                    vec![],
-                ),
-                (None, Some(ts)) => (
-                    "SELECT data, ts FROM items WHERE ts < ? ORDER BY ts DESC".to_string(),
-                    vec![Box::new(ts)],
-                ),
-                (None, None) => (
-                    "SELECT data, ts FROM items ORDER BY ts DESC".to_string(),
-                    vec![],
-                ),
-            };
+        self.list_columns(
+            "hash, data, ts, context, source_app, source_window, id",
+            limit,
+            after_timestamp,
+        )
+    }
 
-        let mut stmt = self.conn.prepare(&query)?;
-        let param_refs: Vec<&dyn rusqlite::ToSql> =
-            params.iter().map(std::convert::AsRef::as_ref).collect();
-        let rows = stmt.query_map(&param_refs[..], |row| {
-            let blob: Vec<u8> = row.get(0)?;
-            let timestamp: u64 = row.get(1)?;
-            let (item, _): (ClipboardItem, usize) =
-                bincode::decode_from_slice(&blob, bincode::config::standard()).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Blob,
-                        Box::new(e),
-                    )
-                })?;
-            Ok(ClipboardItemWithTimestamp { item, timestamp })
-        })?;
+    fn list_thumbnails(
+        &self,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ListedEntry>> {
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        let before = after_timestamp.map_or(i64::MAX, |ts| i64::try_from(ts).unwrap_or(i64::MAX));
+        self.query_listed(
+            "WHERE protected = 0 AND ts < ?1 ORDER BY ts DESC LIMIT ?2",
+            &[&before, &limit],
+        )
+    }
 
-        let mut items = Vec::new();
-        for row in rows {
-            items.push(row?);
-        }
-        Ok(items)
+    fn thumbnail(&self, hash: [u8; 32]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!("SELECT {THUMBNAIL} FROM items WHERE hash = ?1 AND protected = 0;"),
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
     }
 
     fn search(
@@ -155,65 +2893,124 @@ impl Vault for SqliteVault {
         limit: Option<usize>,
         after_timestamp: Option<u64>,
     ) -> Result<Vec<ClipboardItemWithTimestamp>> {
-        // Add wildcards for LIKE pattern matching
-        let like_pattern = format!("%{query}%");
+        let (sql, params) = search_sql(
+            "main",
+            "hash, data, ts, context, source_app, source_window, id",
+            query,
+            limit,
+            after_timestamp,
+        );
+        let param_refs: Vec<&dyn rusqlite::ToSql> =
+            params.iter().map(std::convert::AsRef::as_ref).collect();
+        self.query_items(&sql, &param_refs)
+    }
+
+    fn list_in_context(
+        &self,
+        context: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let context = context.trim_end_matches('/');
+        let pattern = escape_like(context);
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        self.query_items(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items
+             WHERE protected = 0 AND (context = ?1 OR context LIKE '%/' || ?2 ESCAPE '\\')
+             ORDER BY ts DESC LIMIT ?3",
+            &[&context, &pattern, &limit],
+        )
+    }
 
-        let (sql, params): (String, Vec<Box<dyn rusqlite::ToSql>>) = match (limit, after_timestamp)
+    fn frecent(&self, limit: Option<usize>) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let now = Timestamp::now().as_nanos();
+        let mut scores = std::collections::HashMap::new();
         {
-            (Some(n), Some(ts)) => (
-                format!(
-                    "SELECT data, ts FROM items 
-                    WHERE text LIKE ? AND ts < ? AND mime != 'image/png'
-                    ORDER BY ts DESC LIMIT {n}"
-                ),
-                vec![Box::new(like_pattern), Box::new(ts)],
-            ),
-            (Some(n), None) => (
-                format!(
-                    "SELECT data, ts FROM items 
-                    WHERE text LIKE ? AND mime != 'image/png'
-                    ORDER BY ts DESC LIMIT {n}"
-                ),
-                vec![Box::new(like_pattern)],
-            ),
-            (None, Some(ts)) => (
-                "SELECT data, ts FROM items 
-                WHERE text LIKE ? AND ts < ? AND mime != 'image/png'
-                ORDER BY ts DESC"
-                    .to_string(),
-                vec![Box::new(like_pattern), Box::new(ts)],
-            ),
-            (None, None) => (
-                "SELECT data, ts FROM items 
-                WHERE text LIKE ? AND mime != 'image/png'
-                ORDER BY ts DESC"
-                    .to_string(),
-                vec![Box::new(like_pattern)],
-            ),
-        };
+            let mut stmt = self
+                .conn
+                .prepare("SELECT hash, use_count, MAX(ts, COALESCE(last_used, 0)) FROM items;")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let score = frecency_score(row.get(1)?, row.get(2)?, now);
+                scores.insert(row_hash(row)?, score);
+            }
+        }
 
-        let mut stmt = self.conn.prepare(&sql)?;
-        let param_refs: Vec<&dyn rusqlite::ToSql> =
-            params.iter().map(std::convert::AsRef::as_ref).collect();
-        let rows = stmt.query_map(&param_refs[..], |row| {
-            let blob: Vec<u8> = row.get(0)?;
-            let timestamp: u64 = row.get(1)?;
-            let (item, _): (ClipboardItem, usize) =
-                bincode::decode_from_slice(&blob, bincode::config::standard()).map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        0,
-                        rusqlite::types::Type::Blob,
-                        Box::new(e),
-                    )
-                })?;
-            Ok(ClipboardItemWithTimestamp { item, timestamp })
-        })?;
+        // `list` is newest-first and the sort is stable, so ties stay by recency.
+        let mut ranked: Vec<_> = self
+            .list(None, None)?
+            .into_iter()
+            .map(|e| (scores.get(&e.item.hash()).copied().unwrap_or(0.0), e))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(ranked
+            .into_iter()
+            .map(|(_, e)| e)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect())
+    }
 
-        let mut items = Vec::new();
+    fn record_use(&self, hash: [u8; 32]) -> Result<()> {
+        // A snapshot being browsed keeps the usage it was taken with.
+        if self.read_only {
+            return Ok(());
+        }
+        // Nothing is recorded against entries that aren't there or that
+        // are protected.
+        let used = self.conn.execute(
+            "UPDATE items SET use_count = use_count + 1, last_used = ?2 WHERE hash = ?1 AND protected = 0;",
+            params![&hash[..], Timestamp::now()],
+        )?;
+        if used == 0 {
+            return Ok(());
+        }
+        record_copy_event(&self.conn, hash, Timestamp::now(), CopyEventKind::CopiedOut)
+    }
+
+    fn copy_events(&self, hash: [u8; 32]) -> Result<Vec<CopyEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ts, kind FROM copy_events WHERE hash = ?1 ORDER BY ts DESC, id DESC;",
+        )?;
+        let rows = stmt.query_map(params![&hash[..]], |row| {
+            Ok((row.get::<_, Timestamp>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut events = Vec::new();
         for row in rows {
-            items.push(row?);
+            let (timestamp, kind) = row?;
+            if let Some(kind) = CopyEventKind::parse(&kind) {
+                events.push(CopyEvent { timestamp, kind });
+            }
         }
-        Ok(items)
+        Ok(events)
+    }
+
+    fn subscribe(&self) -> Receiver<VaultEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(tx);
+        rx
+    }
+
+    fn subscribe_health(&self) -> Receiver<HealthEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.health_subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(tx);
+        rx
+    }
+
+    fn poll_changes(&self) -> Result<bool> {
+        // data_version only moves when *another* connection commits.
+        let version: i64 = self
+            .conn
+            .pragma_query_value(None, "data_version", |row| row.get(0))?;
+        let changed = self.data_version.swap(version, Ordering::Relaxed) != version;
+        if changed {
+            self.notify(&VaultEvent::Changed);
+        }
+        Ok(changed)
     }
 
     fn len(&self) -> Result<usize> {
@@ -223,34 +3020,436 @@ impl Vault for SqliteVault {
         Ok(usize::try_from(count).unwrap())
     }
 
-    fn update(&self, old_hash: [u8; 32], new_item: &ClipboardItem) -> Result<()> {
-        let new_hash = new_item.hash();
+    fn update(&self, old_hash: [u8; 32], new_item: &ClipboardItem) -> Result<u64> {
+        self.ensure_writable()?;
+        let (new_item, new_hash) = self.stored_item(new_item.hash(), new_item);
+        let new_item = new_item.as_ref();
         let (text, mime) = new_item.clone().into_parts();
-        let timestamp = u64::try_from(
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_nanos(),
-        )
-        .unwrap();
+        let timestamp = Timestamp::now();
+        let dedupe_key = self.dedupe_rules().resolve(new_item).key(new_item);
 
-        self.conn.execute(
-            "UPDATE items SET hash = ?1, mime = ?2, text = ?3, data = ?4, ts = ?5 WHERE hash = ?6;",
+        // The new payload and its size/dimension columns land together.
+        let tx = self.conn.unchecked_transaction()?;
+        let changed = tx.execute(
+            "UPDATE items SET hash = ?1, mime = ?2, text = ?3, data = ?4, ts = ?5, html = NULL, rtf = NULL, dedupe_key = ?7 WHERE hash = ?6;",
             params![
                 &new_hash[..],
                 mime,
                 text,
                 bincode::encode_to_vec(new_item, bincode::config::standard())?,
                 timestamp,
-                &old_hash[..]
+                &old_hash[..],
+                dedupe_key
             ],
         )?;
+        if changed > 0 {
+            // Edits of edits still point at the entry first edited.
+            tx.execute(
+                "UPDATE items SET edited_from = COALESCE(edited_from, ?2) WHERE hash = ?1;",
+                params![&new_hash[..], &old_hash[..]],
+            )?;
+            self.stamp(&tx, &new_hash)?;
+            set_item_info(&tx, &new_hash, new_item)?;
+            self.audit_on(&tx, AuditAction::Update, Some(&new_hash))?;
+        }
+        tx.commit()?;
+        if changed > 0 {
+            self.notify(&VaultEvent::Updated {
+                old_hash,
+                hash: new_hash,
+            });
+        }
+        Ok(changed as u64)
+    }
+
+    fn delete(&self, hash: [u8; 32]) -> Result<u64> {
+        self.ensure_writable()?;
+        let tx = self.conn.unchecked_transaction()?;
+        let removed = tx.execute("DELETE FROM items WHERE hash = ?1;", params![&hash[..]])?;
+        if removed > 0 {
+            self.delete_spilled(&tx, &hash)?;
+            self.audit_on(&tx, AuditAction::Delete, Some(&hash))?;
+        }
+        tx.commit()?;
+        if removed > 0 {
+            self.notify(&VaultEvent::Deleted { hash });
+        }
+        Ok(removed as u64)
+    }
+
+    fn update_by_id(&self, id: i64, new_item: &ClipboardItem) -> Result<u64> {
+        match self.hash_of(id)? {
+            Some(hash) => self.update(hash, new_item),
+            None => Ok(0),
+        }
+    }
+
+    fn delete_by_id(&self, id: i64) -> Result<u64> {
+        match self.hash_of(id)? {
+            Some(hash) => self.delete(hash),
+            None => Ok(0),
+        }
+    }
+
+    fn pin(&self, hash: [u8; 32], pinned: bool) -> Result<u64> {
+        self.ensure_writable()?;
+        let changed = self.conn.execute(
+            "UPDATE items SET pinned = ?2 WHERE hash = ?1 AND pinned != ?2;",
+            params![&hash[..], pinned],
+        )?;
+        if changed > 0 {
+            // Lists ordered by pin need to move it.
+            self.notify(&VaultEvent::Updated {
+                old_hash: hash,
+                hash,
+            });
+        }
+        Ok(changed as u64)
+    }
+
+    fn pinned(&self) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        self.query_items(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items
+             WHERE protected = 0 AND pinned = 1 ORDER BY ts DESC;",
+            &[],
+        )
+    }
+
+    fn list_pinned_first(&self, limit: Option<usize>) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        self.query_items(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items
+             WHERE protected = 0 ORDER BY pinned DESC, ts DESC LIMIT ?1",
+            &[&limit],
+        )
+    }
+
+    fn total_size(&self) -> Result<u64> {
+        let size: i64 = self.conn.query_row(
+            "SELECT COALESCE(SUM(LENGTH(data)), 0) FROM items;",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(u64::try_from(size).unwrap_or(0))
+    }
+
+    fn prune(&self, policy: &RetentionPolicy) -> Result<usize> {
+        self.ensure_writable()?;
+        let (expired, over_size) = self.prune_selection(policy)?;
+        self.delete_hashes(&expired)?;
+        let archived = self.prune_archive(policy)?;
+        self.report_pruned(expired.len() - over_size + archived, PruneReason::Retention);
+        self.report_pruned(over_size, PruneReason::SizeCap);
+        Ok(expired.len() + archived)
+    }
+
+    fn preview_prune(&self, policy: &RetentionPolicy) -> Result<DryRun> {
+        self.dry_run(&self.prune_selection(policy)?.0)
+    }
+
+    fn add_tag(&self, hash: [u8; 32], tag: &str) -> Result<()> {
+        self.ensure_writable()?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tags (hash, tag) VALUES (?1, ?2);",
+            params![&hash[..], tag],
+        )?;
         Ok(())
     }
 
-    fn delete(&self, hash: [u8; 32]) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM items WHERE hash = ?1;", params![&hash[..]])?;
+    fn remove_tag(&self, hash: [u8; 32], tag: &str) -> Result<u64> {
+        self.ensure_writable()?;
+        let removed = self.conn.execute(
+            "DELETE FROM tags WHERE hash = ?1 AND tag = ?2;",
+            params![&hash[..], tag],
+        )?;
+        Ok(removed as u64)
+    }
+
+    fn tags(&self, hash: [u8; 32]) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT tag FROM tags WHERE hash = ?1 ORDER BY tag;")?;
+        let tags = stmt
+            .query_map(params![&hash[..]], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(tags)
+    }
+
+    fn set_note(&self, hash: [u8; 32], note: Option<&str>) -> Result<u64> {
+        self.ensure_writable()?;
+        let note = note.map(str::trim).filter(|note| !note.is_empty());
+        let changed = self.conn.execute(
+            "UPDATE items SET note = ?2 WHERE hash = ?1;",
+            params![&hash[..], note],
+        )?;
+        Ok(changed as u64)
+    }
+
+    fn note(&self, hash: [u8; 32]) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT note FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
+    fn item_stats(&self, hash: [u8; 32]) -> Result<Option<ItemStats>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT size, lines, words FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row_stats(row, 0),
+            )
+            .optional()?)
+    }
+
+    fn list_by_tag(
+        &self,
+        tag: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        self.query_items(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items
+             WHERE protected = 0 AND hash IN (SELECT hash FROM tags WHERE tag = ?1)
+             ORDER BY ts DESC LIMIT ?2",
+            &[&tag, &limit],
+        )
+    }
+
+    fn all_tags(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT tag FROM tags
+             WHERE hash IN (SELECT hash FROM items WHERE protected = 0) ORDER BY tag;",
+        )?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(tags)
+    }
+
+    fn export(
+        &self,
+        writer: &mut dyn std::io::Write,
+        format: ExportFormat,
+        filter: &ExportFilter,
+    ) -> Result<usize> {
+        let mut clauses = vec!["protected = 0"];
+        let mut params: Vec<&dyn rusqlite::ToSql> = Vec::new();
+        if filter.skips_otp() {
+            clauses.push("kind != 'otp'");
+        }
+        if let Some(tag) = &filter.only_tag {
+            clauses.push("hash IN (SELECT hash FROM tags WHERE tag = ?)");
+            params.push(tag);
+        }
+        export::write_header(writer, format)?;
+        let mut written = 0;
+        // Written as read, so an export of a large vault isn't held in
+        // memory.
+        self.for_each_item(
+            &format!(
+                "SELECT hash, data, ts, context, source_app, source_window, id FROM items WHERE {} ORDER BY ts ASC",
+                clauses.join(" AND ")
+            ),
+            &params,
+            |entry| {
+                let tags = self.tags(entry.item.hash())?;
+                if export::write_entry(writer, format, &entry, tags, filter)? {
+                    written += 1;
+                }
+                Ok(())
+            },
+        )?;
+        self.audit_on(&self.conn, AuditAction::Export, None)?;
+        Ok(written)
+    }
+
+    fn import(&self, entries: &[ImportedEntry]) -> Result<ImportSummary> {
+        self.ensure_writable()?;
+        let result = self.write_imported(entries, true);
+        self.track_write(result.as_ref().err());
+        let (summary, added) = result?;
+        for hash in added {
+            self.notify(&VaultEvent::Added { hash });
+        }
+        Ok(summary)
+    }
+
+    fn purge_expired(&self) -> Result<usize> {
+        self.ensure_writable()?;
+        let expired: Vec<[u8; 32]> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT hash FROM items WHERE expires_at <= ?1 AND pinned = 0;")?;
+            let mut rows = stmt.query(params![Timestamp::now()])?;
+            let mut hashes = Vec::new();
+            while let Some(row) = rows.next()? {
+                hashes.push(row_hash(row)?);
+            }
+            hashes
+        };
+        self.delete_hashes(&expired)?;
+        self.report_pruned(expired.len(), PruneReason::Expired);
+        Ok(expired.len())
+    }
+
+    fn expiry(&self, hash: [u8; 32]) -> Result<Option<Timestamp>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT expires_at FROM items WHERE hash = ?1;",
+                params![&hash[..]],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten())
+    }
+
+    fn set_expiry(&self, hash: [u8; 32], expires_at: Option<Timestamp>) -> Result<u64> {
+        self.ensure_writable()?;
+        let changed = self.conn.execute(
+            "UPDATE items SET expires_at = ?2 WHERE hash = ?1;",
+            params![&hash[..], expires_at],
+        )?;
+        Ok(changed as u64)
+    }
+
+    fn activity_histogram(
+        &self,
+        bucket: HistogramBucket,
+        range: Range<Timestamp>,
+    ) -> Result<Vec<ActivityBucket>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT (ts / ?1) * ?1 AS bucket, COUNT(*) FROM items
+             WHERE ts >= ?2 AND ts < ?3
+             GROUP BY bucket ORDER BY bucket;",
+        )?;
+        let rows = stmt.query_map(params![bucket.as_nanos(), range.start, range.end], |row| {
+            Ok(ActivityBucket {
+                start: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?;
+
+        let mut buckets = Vec::new();
+        for row in rows {
+            buckets.push(row?);
+        }
+        Ok(buckets)
+    }
+
+    fn timeline(&self, day: NaiveDate) -> Result<Vec<TimelineHour>> {
+        Ok(group_by_hour(self.captured_between(local_day_range(day))?))
+    }
+
+    fn captured_between(&self, range: Range<Timestamp>) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let mut entries = Vec::new();
+        self.for_each_captured_between(range, &mut |entry| {
+            entries.push(entry);
+            Ok(())
+        })?;
+        Ok(entries)
+    }
+
+    fn for_each_captured_between(
+        &self,
+        range: Range<Timestamp>,
+        f: &mut dyn FnMut(ClipboardItemWithTimestamp) -> Result<()>,
+    ) -> Result<()> {
+        self.for_each_item(
+            "SELECT hash, data, ts, context, source_app, source_window, id FROM items
+             WHERE protected = 0 AND ts >= ?1 AND ts < ?2 ORDER BY ts ASC;",
+            &[&range.start, &range.end],
+            f,
+        )
+    }
+
+    fn copied_between(&self, range: Range<Timestamp>) -> Result<Vec<ClipboardItemWithTimestamp>> {
+        let mut entries = Vec::new();
+        self.for_each_item(
+            "SELECT items.hash, data, copy_events.ts, context, source_app, source_window, items.id
+             FROM copy_events JOIN items ON items.hash = copy_events.hash
+             WHERE protected = 0 AND copy_events.kind <> 'copied_out'
+               AND copy_events.ts >= ?1 AND copy_events.ts < ?2
+             UNION ALL
+             SELECT hash, data, ts, context, source_app, source_window, id FROM items
+             WHERE protected = 0 AND ts >= ?1 AND ts < ?2
+               AND NOT EXISTS (SELECT 1 FROM copy_events WHERE copy_events.hash = items.hash)
+             ORDER BY 3 ASC;",
+            &[&range.start, &range.end],
+            |entry| {
+                entries.push(entry);
+                Ok(())
+            },
+        )?;
+        Ok(entries)
+    }
+
+    fn skipped_secrets_between(&self, range: Range<Timestamp>) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM skipped_secrets WHERE ts >= ?1 AND ts < ?2;",
+            params![range.start, range.end],
+            |row| row.get(0),
+        )?;
+        Ok(usize::try_from(count).unwrap_or(0))
+    }
+
+    fn related(&self, hash: [u8; 32]) -> Result<Vec<RelatedItem>> {
+        let Some(target) = self.get(hash)? else {
+            return Ok(Vec::new());
+        };
+        // Related copies cluster in time, so only the entries nearest to the
+        // target are compared rather than the whole history.
+        let candidates = self.query_items(
+            &format!(
+                "SELECT hash, data, ts, context, source_app, source_window, id FROM items
+                 WHERE protected = 0 AND hash != ?1
+                 ORDER BY ABS(ts - ?2) LIMIT {RELATED_CANDIDATES};"
+            ),
+            &[&&hash[..], &target.timestamp],
+        )?;
+        let mut related: Vec<RelatedItem> = candidates
+            .into_iter()
+            .filter_map(|entry| {
+                let relations = crate::relations(&target, &entry);
+                (!relations.is_empty()).then_some(RelatedItem { entry, relations })
+            })
+            .collect();
+        related.sort_by_key(|r| {
+            (
+                std::cmp::Reverse(r.relations.len()),
+                r.entry.timestamp.abs_diff(target.timestamp),
+            )
+        });
+        related.truncate(RELATED_LIMIT);
+        Ok(related)
+    }
+
+    fn diagnostics(&self) -> Vec<RowDiagnostic> {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn quarantine(&self, hash: [u8; 32]) -> Result<()> {
+        self.ensure_writable()?;
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO quarantine (hash, mime, data, ts, quarantined_at)
+             SELECT hash, mime, data, ts, ?2 FROM items WHERE hash = ?1;",
+            params![&hash[..], Timestamp::now()],
+        )?;
+        tx.execute("DELETE FROM items WHERE hash = ?1;", params![&hash[..]])?;
+        self.audit_on(&tx, AuditAction::Delete, Some(&hash))?;
+        tx.commit()?;
+        self.notify(&VaultEvent::Deleted { hash });
         Ok(())
     }
 }
@@ -0,0 +1,85 @@
+//! Size cap for captured text, so an accidentally copied multi-hundred-MB log
+//! doesn't stall capture or bloat the vault.
+
+use serde::{Deserialize, Serialize};
+
+/// What to do with text over the limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OversizeAction {
+    /// Don't capture it at all.
+    Skip,
+    /// Keep the first `max_bytes` followed by a marker.
+    #[default]
+    Truncate,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextLimit {
+    /// `None` disables the limit.
+    pub max_bytes: Option<usize>,
+    pub action: OversizeAction,
+    /// Keep the full original in the encrypted spill file
+    /// (see [`SqliteVault::spill`](crate::SqliteVault::spill)).
+    pub spill: bool,
+}
+
+/// Result of applying a [`TextLimit`] to captured text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Limited {
+    Unchanged(String),
+    /// `original` is only kept when spilling is enabled.
+    Truncated {
+        text: String,
+        original: Option<String>,
+    },
+    Skipped,
+}
+
+impl TextLimit {
+    #[must_use]
+    pub fn apply(&self, text: String) -> Limited {
+        let Some(max) = self.max_bytes.filter(|&max| text.len() > max) else {
+            return Limited::Unchanged(text);
+        };
+        match self.action {
+            OversizeAction::Skip => Limited::Skipped,
+            OversizeAction::Truncate => {
+                let mut cut = max;
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                let truncated = format!(
+                    "{}\n[truncated, original {}]",
+                    &text[..cut],
+                    format_size(text.len())
+                );
+                Limited::Truncated {
+                    text: truncated,
+                    original: self.spill.then_some(text),
+                }
+            }
+        }
+    }
+}
+
+/// Human-readable byte count, e.g. `200 MB` or `2.3 MB`.
+#[must_use]
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    // Display only; precision loss above 2^52 bytes doesn't matter.
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    let rounded = (size * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{rounded:.0} {}", UNITS[unit])
+    } else {
+        format!("{rounded:.1} {}", UNITS[unit])
+    }
+}
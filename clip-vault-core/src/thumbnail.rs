@@ -0,0 +1,27 @@
+//! Small previews of image entries, made once when they're stored so a
+//! list of results never has to decode the full images.
+
+use image::{ImageFormat, ImageReader};
+use std::io::Cursor;
+
+/// Thumbnails fit in a square this many pixels wide.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// A PNG of `data` scaled to fit [`THUMBNAIL_SIZE`], keeping its aspect
+/// ratio. `None` for images already that small, which are their own
+/// thumbnail, and for anything that isn't a readable PNG.
+#[must_use]
+pub fn thumbnail(data: &[u8]) -> Option<Vec<u8>> {
+    let image = ImageReader::with_format(Cursor::new(data), ImageFormat::Png)
+        .decode()
+        .ok()?;
+    if image.width() <= THUMBNAIL_SIZE && image.height() <= THUMBNAIL_SIZE {
+        return None;
+    }
+    let mut png = Vec::new();
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
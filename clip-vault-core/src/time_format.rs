@@ -0,0 +1,59 @@
+//! Human-readable capture times in the local timezone, so the CLI, TUI and
+//! app show the same thing. Absolute styles follow the locale's date and
+//! time conventions; relative labels are short enough for a list column.
+
+use chrono::{DateTime, Local, Locale};
+use serde::{Deserialize, Serialize};
+
+/// Relative labels give way to a date after this long.
+const RELATIVE_LIMIT_SECS: i64 = 7 * 86_400;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampStyle {
+    /// "just now", "5m ago", "3h ago", "2d ago", then the date.
+    #[default]
+    Relative,
+    /// Date and time.
+    Absolute,
+    /// Date only.
+    Date,
+}
+
+/// Format a nanosecond timestamp. `locale` is a name like `de_DE` or
+/// `de-DE`; the system locale is used when it's `None` or unknown.
+#[must_use]
+pub fn format_timestamp(timestamp: u64, style: TimestampStyle, locale: Option<&str>) -> String {
+    let nanos = i64::try_from(timestamp).unwrap_or(i64::MAX);
+    let when = DateTime::from_timestamp_nanos(nanos).with_timezone(&Local);
+    let locale = locale
+        .and_then(parse_locale)
+        .unwrap_or_else(|| parse_locale(&system_locale()).unwrap_or(Locale::POSIX));
+
+    match style {
+        TimestampStyle::Relative => {
+            let secs = (Local::now() - when).num_seconds();
+            match secs {
+                ..60 => "just now".to_string(),
+                60..3_600 => format!("{}m ago", secs / 60),
+                3_600..86_400 => format!("{}h ago", secs / 3_600),
+                86_400..RELATIVE_LIMIT_SECS => format!("{}d ago", secs / 86_400),
+                _ => when.format_localized("%x", locale).to_string(),
+            }
+        }
+        TimestampStyle::Absolute => when.format_localized("%x %X", locale).to_string(),
+        TimestampStyle::Date => when.format_localized("%x", locale).to_string(),
+    }
+}
+
+/// The user's locale as reported by the OS, e.g. `en-US`.
+#[must_use]
+pub fn system_locale() -> String {
+    sys_locale::get_locale().unwrap_or_else(|| "POSIX".to_string())
+}
+
+/// Accept BCP 47 (`en-US`) and POSIX (`en_US.UTF-8`) spellings.
+fn parse_locale(name: &str) -> Option<Locale> {
+    let name = name.split(['.', '@']).next().unwrap_or_default();
+    Locale::try_from(name.replace('-', "_").as_str()).ok()
+}
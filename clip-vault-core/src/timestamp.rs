@@ -0,0 +1,189 @@
+//! When something happened, as nanoseconds since the Unix epoch. That's how
+//! the vault stores capture times; [`Timestamp`] keeps the unit in the type
+//! so callers convert and format through it instead of by hand.
+
+use crate::time_format::{format_timestamp, TimestampStyle};
+use chrono::{DateTime, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+/// The database stores integers as `i64`, so that's as late as a timestamp goes.
+const MAX_NANOS: u64 = i64::MAX.unsigned_abs();
+
+/// Serialized as the bare nanosecond count, so stored and exported entries
+/// read the same as before it had a type.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Timestamp(u64);
+
+impl Timestamp {
+    pub const EPOCH: Self = Self(0);
+    /// Around the year 2262; later times clamp to it.
+    pub const MAX: Self = Self(MAX_NANOS);
+
+    /// The current time; the epoch if the clock is set before it.
+    #[must_use]
+    pub fn now() -> Self {
+        Self::from_system_time(SystemTime::now())
+    }
+
+    #[must_use]
+    pub const fn from_nanos(nanos: u64) -> Self {
+        if nanos > MAX_NANOS {
+            Self::MAX
+        } else {
+            Self(nanos)
+        }
+    }
+
+    #[must_use]
+    pub const fn from_secs(secs: u64) -> Self {
+        Self::from_nanos(secs.saturating_mul(NANOS_PER_SEC))
+    }
+
+    /// Times before the epoch clamp to it.
+    #[must_use]
+    pub fn from_system_time(time: SystemTime) -> Self {
+        time.duration_since(UNIX_EPOCH)
+            .map_or(Self::EPOCH, |since| Self::EPOCH.saturating_add(since))
+    }
+
+    /// Times before the epoch clamp to it, and times past
+    /// [`Timestamp::MAX`] to that.
+    #[must_use]
+    pub fn from_datetime<Tz: TimeZone>(time: &DateTime<Tz>) -> Self {
+        time.timestamp_nanos_opt().map_or_else(
+            || {
+                if time.timestamp() < 0 {
+                    Self::EPOCH
+                } else {
+                    Self::MAX
+                }
+            },
+            |nanos| Self(u64::try_from(nanos).unwrap_or(0)),
+        )
+    }
+
+    #[must_use]
+    pub const fn as_nanos(self) -> u64 {
+        self.0
+    }
+
+    /// Whole seconds, rounded down.
+    #[must_use]
+    pub const fn as_secs(self) -> u64 {
+        self.0 / NANOS_PER_SEC
+    }
+
+    #[must_use]
+    pub fn to_system_time(self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_nanos(self.0)
+    }
+
+    #[must_use]
+    pub fn to_utc(self) -> DateTime<Utc> {
+        DateTime::from_timestamp_nanos(i64::try_from(self.0).unwrap_or(i64::MAX))
+    }
+
+    /// In the local timezone.
+    #[must_use]
+    pub fn to_local(self) -> DateTime<Local> {
+        self.to_utc().with_timezone(&Local)
+    }
+
+    #[must_use]
+    pub fn saturating_add(self, duration: Duration) -> Self {
+        Self::from_nanos(
+            self.0
+                .saturating_add(u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)),
+        )
+    }
+
+    #[must_use]
+    pub fn saturating_sub(self, duration: Duration) -> Self {
+        Self(
+            self.0
+                .saturating_sub(u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)),
+        )
+    }
+
+    /// How long after `earlier` this is; zero if it isn't after it.
+    #[must_use]
+    pub fn duration_since(self, earlier: Self) -> Duration {
+        Duration::from_nanos(self.0.saturating_sub(earlier.0))
+    }
+
+    /// How long ago this was; zero for times in the future.
+    #[must_use]
+    pub fn elapsed(self) -> Duration {
+        Self::now().duration_since(self)
+    }
+
+    /// The distance between two times, whichever is first.
+    #[must_use]
+    pub fn abs_diff(self, other: Self) -> Duration {
+        Duration::from_nanos(self.0.abs_diff(other.0))
+    }
+
+    /// See [`format_timestamp`].
+    #[must_use]
+    pub fn format(self, style: TimestampStyle, locale: Option<&str>) -> String {
+        format_timestamp(self.0, style, locale)
+    }
+
+    /// "5m ago" and the like, in the system locale.
+    #[must_use]
+    pub fn relative(self) -> String {
+        self.format(TimestampStyle::Relative, None)
+    }
+
+    /// Date and time in the system locale.
+    #[must_use]
+    pub fn absolute(self) -> String {
+        self.format(TimestampStyle::Absolute, None)
+    }
+}
+
+impl From<u64> for Timestamp {
+    fn from(nanos: u64) -> Self {
+        Self::from_nanos(nanos)
+    }
+}
+
+impl From<Timestamp> for u64 {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        Self::from_system_time(time)
+    }
+}
+
+/// RFC 3339 in UTC, for logs and machine-readable output.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_utc().to_rfc3339())
+    }
+}
+
+#[cfg(feature = "sqlcipher")]
+impl rusqlite::ToSql for Timestamp {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        // Deserialized values skip the clamp in the constructors.
+        Ok(i64::try_from(self.0).unwrap_or(i64::MAX).into())
+    }
+}
+
+#[cfg(feature = "sqlcipher")]
+impl rusqlite::types::FromSql for Timestamp {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        u64::column_result(value).map(Self::from_nanos)
+    }
+}
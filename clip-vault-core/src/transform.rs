@@ -0,0 +1,259 @@
+//! Rewrites of copied text. Capture transforms clean text up on its way
+//! into the vault; copy formats reshape it on its way back out, for pasting
+//! into places that want it differently from how it was copied: a config
+//! file, a shell prompt, a Markdown document.
+
+use crate::{Error, ItemKind, Result, Rgba};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// A cleanup applied to copied text before it is hashed and stored, so the
+/// same output copied with and without colour is one entry. Capture runs
+/// none unless they're turned on, so text is kept as copied by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureTransform {
+    /// Remove ANSI escape sequences: colours, cursor movement, hyperlinks.
+    StripAnsi,
+    /// Remove spaces and tabs at the end of every line, as terminals pad
+    /// selections to the window width.
+    TrimTrailingWhitespace,
+}
+
+impl CaptureTransform {
+    /// Every transform, in the order a pipeline runs them.
+    pub const ALL: [CaptureTransform; 2] = [
+        CaptureTransform::StripAnsi,
+        CaptureTransform::TrimTrailingWhitespace,
+    ];
+
+    #[must_use]
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            CaptureTransform::StripAnsi => strip_ansi(text),
+            CaptureTransform::TrimTrailingWhitespace => text
+                .split_inclusive('\n')
+                .map(|line| {
+                    let body = line.trim_end_matches(['\r', '\n']);
+                    let ending = &line[body.len()..];
+                    body.trim_end_matches([' ', '\t']).to_string() + ending
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Run `text` through each transform in turn.
+#[must_use]
+pub fn apply_capture_transforms(text: String, transforms: &[CaptureTransform]) -> String {
+    transforms
+        .iter()
+        .fold(text, |text, transform| transform.apply(&text))
+}
+
+/// Drop CSI sequences (`ESC [ ... final`), OSC sequences (`ESC ] ...`
+/// ended by BEL or `ESC \`) and two-byte escapes.
+fn strip_ansi(text: &str) -> String {
+    if !text.contains(['\x1b', '\u{9b}']) {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => skip_csi(&mut chars),
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Charset selection like `ESC ( B` takes one more character.
+                Some('(' | ')') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\u{9b}' => skip_csi(&mut chars),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Skip a CSI sequence's parameter and intermediate bytes and its final byte.
+fn skip_csi(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.next_if(|c| matches!(c, '\x20'..='\x3f')).is_some() {}
+    chars.next_if(|c| matches!(c, '\x40'..='\x7e'));
+}
+
+/// A way to reshape text when copying it out of the vault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyFormat {
+    /// JSON on a single line, without insignificant whitespace.
+    JsonMinified,
+    /// JSON indented two spaces per level.
+    JsonPretty,
+    /// A single shell word that expands back to the text.
+    ShellEscaped,
+    /// A fenced Markdown code block, tagged with the detected language.
+    MarkdownCodeBlock,
+    /// A color as `#1e90ff` (see [`Rgba`]).
+    ColorHex,
+    /// A color as `rgb(30, 144, 255)`.
+    ColorRgb,
+    /// A color as `hsl(210, 100%, 56%)`.
+    ColorHsl,
+}
+
+impl CopyFormat {
+    /// Every format, in the order menus list them.
+    pub const ALL: [CopyFormat; 7] = [
+        CopyFormat::JsonMinified,
+        CopyFormat::JsonPretty,
+        CopyFormat::ShellEscaped,
+        CopyFormat::MarkdownCodeBlock,
+        CopyFormat::ColorHex,
+        CopyFormat::ColorRgb,
+        CopyFormat::ColorHsl,
+    ];
+
+    #[must_use]
+    pub fn title(self) -> &'static str {
+        match self {
+            CopyFormat::JsonMinified => "JSON (minified)",
+            CopyFormat::JsonPretty => "JSON (pretty)",
+            CopyFormat::ShellEscaped => "Shell-escaped",
+            CopyFormat::MarkdownCodeBlock => "Markdown code block",
+            CopyFormat::ColorHex => "Hex color",
+            CopyFormat::ColorRgb => "RGB color",
+            CopyFormat::ColorHsl => "HSL color",
+        }
+    }
+
+    /// Whether menus offer this format for entries of `kind`: the color
+    /// formats for colors only, the others for any text.
+    #[must_use]
+    pub fn applies_to(self, kind: ItemKind) -> bool {
+        match self {
+            CopyFormat::ColorHex | CopyFormat::ColorRgb | CopyFormat::ColorHsl => {
+                kind == ItemKind::Color
+            }
+            _ => !matches!(kind, ItemKind::Image | ItemKind::Files),
+        }
+    }
+}
+
+/// Reshape `text` into `format`. Fails for the JSON formats when `text`
+/// isn't valid JSON, and for the color formats when it isn't a color.
+pub fn copy_as(text: &str, format: CopyFormat) -> Result<String> {
+    match format {
+        CopyFormat::JsonMinified | CopyFormat::JsonPretty => {
+            let value: serde_json::Value = serde_json::from_str(text)
+                .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+            let out = if format == CopyFormat::JsonPretty {
+                serde_json::to_string_pretty(&value)
+            } else {
+                serde_json::to_string(&value)
+            };
+            out.map_err(|e| Error::Io(io::Error::other(e)))
+        }
+        CopyFormat::ShellEscaped => Ok(shell_escape(text)),
+        CopyFormat::MarkdownCodeBlock => Ok(markdown_code_block(text)),
+        CopyFormat::ColorHex | CopyFormat::ColorRgb | CopyFormat::ColorHsl => {
+            let color = Rgba::parse(text).ok_or_else(|| {
+                Error::Io(io::Error::new(io::ErrorKind::InvalidData, "not a color"))
+            })?;
+            Ok(match format {
+                CopyFormat::ColorHex => color.to_hex(),
+                CopyFormat::ColorRgb => color.to_rgb(),
+                _ => color.to_hsl(),
+            })
+        }
+    }
+}
+
+/// Quote `text` for POSIX shells. Text made only of characters no shell
+/// treats specially is left bare; anything else is single-quoted.
+fn shell_escape(text: &str) -> String {
+    let bare = !text.is_empty()
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-+=./:,@%".contains(c));
+    if bare {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+fn markdown_code_block(text: &str) -> String {
+    // The fence has to be longer than any run of backticks inside.
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let language = detect_language(text).unwrap_or_default();
+    format!(
+        "{fence}{language}\n{}\n{fence}",
+        text.trim_end_matches('\n')
+    )
+}
+
+/// Guess the language of a code snippet for a Markdown fence, from a
+/// shebang or from keywords characteristic enough not to misfire on prose.
+#[must_use]
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let trimmed = text.trim();
+    if let Some(shebang) = trimmed.strip_prefix("#!") {
+        let interpreter = shebang.lines().next().unwrap_or_default();
+        return [
+            ("python", "python"),
+            ("node", "javascript"),
+            ("ruby", "ruby"),
+            ("perl", "perl"),
+            ("bash", "bash"),
+            ("zsh", "bash"),
+            ("sh", "sh"),
+        ]
+        .into_iter()
+        .find(|(name, _)| interpreter.contains(name))
+        .map(|(_, language)| language);
+    }
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some("json");
+    }
+    if trimmed.starts_with("<?php") {
+        return Some("php");
+    }
+    if trimmed.starts_with('<') && trimmed.ends_with('>') {
+        return Some("html");
+    }
+
+    let has_line = |prefixes: &[&str]| {
+        trimmed
+            .lines()
+            .map(str::trim_start)
+            .any(|line| prefixes.iter().any(|p| line.starts_with(p)))
+    };
+    if has_line(&["fn ", "pub fn ", "use std::", "impl ", "let mut "]) {
+        Some("rust")
+    } else if has_line(&["package main", "func "]) {
+        Some("go")
+    } else if has_line(&["#include"]) {
+        Some("c")
+    } else if has_line(&["def ", "import ", "elif "]) && !trimmed.contains(';') {
+        Some("python")
+    } else if has_line(&["function ", "const ", "export ", "console.log("]) {
+        Some("javascript")
+    } else if has_line(&["SELECT ", "INSERT INTO", "UPDATE ", "CREATE TABLE"]) {
+        Some("sql")
+    } else if has_line(&["$ ", "sudo ", "cd "]) {
+        Some("sh")
+    } else {
+        None
+    }
+}
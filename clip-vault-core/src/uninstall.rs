@@ -0,0 +1,180 @@
+//! Removing clip-vault from a machine. Deleting the binaries leaves behind
+//! the app's launch at login entry, the cached password and the one in the
+//! keyring; [`uninstall`] removes those, and the vault too when asked.
+
+use crate::{cache_dir, context_path, vault_files, Result};
+use serde::Serialize;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+#[cfg(any(target_os = "macos", windows))]
+use std::process::Command;
+
+/// The app's name, which its launch at login entry is named after.
+const APP_NAME: &str = "Clip Vault";
+/// The CLI's password cache, in the cache directory.
+const SESSION_FILE: &str = "session.json";
+
+/// What [`uninstall`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UninstallReport {
+    /// Launch at login entries that were stopped and removed.
+    pub startup_entries: Vec<String>,
+    /// The cached password and the shell hook's context, if they existed.
+    pub removed_files: Vec<PathBuf>,
+    /// Whether a password saved in the keyring was removed.
+    pub keyring_cleared: bool,
+    /// Vault files overwritten and deleted.
+    pub shredded: Vec<PathBuf>,
+    /// Steps that failed. The others still ran.
+    pub errors: Vec<String>,
+}
+
+/// Stop and remove the app's launch at login entry, delete the password
+/// cache and the keyring entries of the vaults at `vaults`, and
+/// with `shred` overwrite and delete those vaults and their sidecar files.
+/// The shared config and logs stay.
+#[must_use]
+pub fn uninstall(vaults: &[PathBuf], shred: bool) -> UninstallReport {
+    let mut report = UninstallReport::default();
+    remove_startup_entries(&mut report);
+
+    for path in [cache_dir().join(SESSION_FILE), context_path()] {
+        match std::fs::remove_file(&path) {
+            Ok(()) => report.removed_files.push(path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => report.errors.push(format!("{}: {e}", path.display())),
+        }
+    }
+
+    for vault in vaults {
+        #[cfg(feature = "keyring")]
+        match crate::forget_keyring_password(vault) {
+            Ok(cleared) => report.keyring_cleared |= cleared,
+            Err(e) => report.errors.push(e.to_string()),
+        }
+
+        if shred {
+            match shred_vault(vault) {
+                Ok(shredded) => report.shredded.extend(shredded),
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+    }
+    report
+}
+
+/// Overwrite the vault at `vault` and its sidecar files with zeros and
+/// delete them, returning the files that existed. The vault is encrypted
+/// either way; overwriting keeps the ciphertext from being recovered off
+/// the disk and brute-forced later, though SSDs and copy-on-write
+/// filesystems may still hold old copies.
+pub fn shred_vault(vault: &Path) -> Result<Vec<PathBuf>> {
+    let mut shredded = Vec::new();
+    for path in vault_files(vault) {
+        if !path.is_file() {
+            continue;
+        }
+        shred_file(&path)?;
+        shredded.push(path);
+    }
+    Ok(shredded)
+}
+
+fn shred_file(path: &Path) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let mut left = file.metadata()?.len();
+    let zeros = [0u8; 16 * 1024];
+    file.seek(SeekFrom::Start(0))?;
+    while left > 0 {
+        let n = usize::try_from(left).map_or(zeros.len(), |left| left.min(zeros.len()));
+        file.write_all(&zeros[..n])?;
+        left -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Run a service manager command, noting a failure in `report`.
+#[cfg(any(target_os = "macos", windows))]
+fn run(report: &mut UninstallReport, program: &str, args: &[&str]) -> bool {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => true,
+        Ok(output) => {
+            report.errors.push(format!(
+                "{program} {}: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+            false
+        }
+        Err(e) => {
+            report.errors.push(format!("{program}: {e}"));
+            false
+        }
+    }
+}
+
+/// Remove `path`, recording it as a startup entry.
+#[cfg(unix)]
+fn remove_entry(report: &mut UninstallReport, path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => report.startup_entries.push(path.display().to_string()),
+        Err(e) => report.errors.push(format!("{}: {e}", path.display())),
+    }
+}
+
+/// The launch agent the app's launch at login writes, unloaded first.
+#[cfg(target_os = "macos")]
+fn remove_startup_entries(report: &mut UninstallReport) {
+    let Some(home) = dirs::home_dir() else {
+        return;
+    };
+    let plist = home
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{APP_NAME}.plist"));
+    if plist.is_file() {
+        run(
+            report,
+            "launchctl",
+            &["unload", "-w", &plist.to_string_lossy()],
+        );
+        remove_entry(report, &plist);
+    }
+}
+
+/// The autostart entry the app's launch at login writes.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn remove_startup_entries(report: &mut UninstallReport) {
+    let Some(config) = dirs::config_dir() else {
+        return;
+    };
+    let desktop = config.join("autostart").join(format!("{APP_NAME}.desktop"));
+    if desktop.is_file() {
+        remove_entry(report, &desktop);
+    }
+}
+
+/// The value the app's launch at login writes under the Run key.
+#[cfg(windows)]
+fn remove_startup_entries(report: &mut UninstallReport) {
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    let exists = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+    if exists("reg", &["query", RUN_KEY, "/v", APP_NAME])
+        && run(report, "reg", &["delete", RUN_KEY, "/v", APP_NAME, "/f"])
+    {
+        report
+            .startup_entries
+            .push(format!(r"{RUN_KEY}\{APP_NAME}"));
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn remove_startup_entries(_report: &mut UninstallReport) {}
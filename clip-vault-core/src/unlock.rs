@@ -0,0 +1,187 @@
+//! How the vault gets unlocked: with the password at every launch, or with a
+//! copy of it kept in the OS keyring. Kept in the shared config, so the CLI
+//! and the desktop app follow the same policy. Also how the vault gets
+//! locked again on request.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "sqlcipher")]
+use crate::{Error, Result, SqliteVault};
+
+/// Where the password comes from when the vault is opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnlockPolicy {
+    /// Ask for the password at every launch.
+    #[default]
+    Password,
+    /// Unlock with the password saved in the OS keyring.
+    Keyring,
+    /// Unlock from the keyring, but ask for the password again before
+    /// anything in [`SensitiveAction`].
+    KeyringConfirmSensitive,
+}
+
+/// Operations that can take history off the machine or destroy it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensitiveAction {
+    /// Writing entries out of the vault: exports, backups and share files.
+    Export,
+    /// Deleting or overwriting entries: one at a time, by pruning or an
+    /// expiry, by editing them, or by restoring a backup over the vault.
+    Delete,
+}
+
+impl fmt::Display for SensitiveAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Export => f.write_str("export"),
+            Self::Delete => f.write_str("delete"),
+        }
+    }
+}
+
+impl UnlockPolicy {
+    /// Whether the password is read from and saved to the keyring.
+    #[must_use]
+    pub fn uses_keyring(self) -> bool {
+        matches!(self, Self::Keyring | Self::KeyringConfirmSensitive)
+    }
+
+    /// Whether `action` needs the password entered again.
+    #[must_use]
+    pub fn requires_password(self, action: SensitiveAction) -> bool {
+        match action {
+            SensitiveAction::Export | SensitiveAction::Delete => {
+                self == Self::KeyringConfirmSensitive
+            }
+        }
+    }
+
+    /// Let `action` on `vault` go ahead. If the policy wants the password
+    /// again, `prompt` is asked for it; a missing or wrong answer is
+    /// [`Error::InvalidKey`].
+    #[cfg(feature = "sqlcipher")]
+    pub fn authorize(
+        self,
+        action: SensitiveAction,
+        vault: &SqliteVault,
+        prompt: impl FnOnce(SensitiveAction) -> Option<String>,
+    ) -> Result<()> {
+        if !self.requires_password(action) {
+            return Ok(());
+        }
+        match prompt(action) {
+            Some(password) if vault.verify_key(&password)? => Ok(()),
+            _ => Err(Error::InvalidKey),
+        }
+    }
+}
+
+#[cfg(feature = "keyring")]
+mod keyring_store {
+    use crate::{Error, Result};
+    use std::path::Path;
+
+    const SERVICE: &str = "clip-vault";
+
+    /// One keyring entry per vault file, so vaults with different
+    /// passwords don't overwrite each other's.
+    fn entry(vault_path: &Path) -> Result<keyring::Entry> {
+        keyring::Entry::new(SERVICE, &vault_path.to_string_lossy()).map_err(|e| keyring_error(&e))
+    }
+
+    fn keyring_error(e: &keyring::Error) -> Error {
+        Error::Io(std::io::Error::other(format!("keyring: {e}")))
+    }
+
+    /// The password saved for the vault at `vault_path`, if any.
+    pub fn keyring_password(vault_path: &Path) -> Result<Option<String>> {
+        match entry(vault_path)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keyring_error(&e)),
+        }
+    }
+
+    /// Save `password` for the vault at `vault_path`, replacing any saved
+    /// before.
+    pub fn save_keyring_password(vault_path: &Path, password: &str) -> Result<()> {
+        entry(vault_path)?
+            .set_password(password)
+            .map_err(|e| keyring_error(&e))
+    }
+
+    /// Remove the saved password. Returns whether there was one.
+    pub fn forget_keyring_password(vault_path: &Path) -> Result<bool> {
+        match entry(vault_path)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(keyring_error(&e)),
+        }
+    }
+}
+
+#[cfg(feature = "keyring")]
+pub use keyring_store::{forget_keyring_password, keyring_password, save_keyring_password};
+
+const LOCK_REQUEST_FILE: &str = "lock-request";
+
+/// Ask every running clip-vault process to lock: the app drops its key as
+/// soon as it notices, and the CLI stops using passwords it cached before
+/// now. Recorded as a file in the cache directory, which they all share.
+pub fn request_lock() -> std::io::Result<()> {
+    let path = crate::cache_dir().join(LOCK_REQUEST_FILE);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(std::io::Error::other)?;
+    std::fs::write(path, now.as_nanos().to_string())
+}
+
+/// When [`request_lock`] was last called, by any process.
+#[must_use]
+pub fn last_lock_request() -> Option<SystemTime> {
+    let text = std::fs::read_to_string(crate::cache_dir().join(LOCK_REQUEST_FILE)).ok()?;
+    let nanos: u64 = text.trim().parse().ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_nanos(nanos))
+}
+
+/// Notices [`request_lock`] calls made after it was created, for processes
+/// that keep the vault open and should let go of it when asked.
+#[derive(Debug)]
+pub struct LockRequests {
+    seen: Option<SystemTime>,
+}
+
+impl LockRequests {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            seen: last_lock_request(),
+        }
+    }
+
+    /// Whether a lock was requested since the last call, or since this was
+    /// created. Reads a small file, so call it at most every few hundred
+    /// milliseconds.
+    pub fn requested(&mut self) -> bool {
+        let latest = last_lock_request();
+        if latest == self.seen {
+            return false;
+        }
+        self.seen = latest;
+        true
+    }
+}
+
+impl Default for LockRequests {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,296 @@
+//! The storage interface, kept apart from its `SQLCipher` implementation so
+//! it is available when the `sqlcipher` feature is off.
+
+use crate::{
+    ActivityBucket, CaptureMetadata, ClipboardItem, ClipboardItemWithTimestamp, CopyEvent, DryRun,
+    ExportFilter, ExportFormat, HealthEvent, HistogramBucket, ImportSummary, ImportedEntry,
+    ItemStats, ListedEntry, RelatedItem, Result, RetentionPolicy, RowDiagnostic, TimelineHour,
+    Timestamp, VaultEvent,
+};
+use chrono::NaiveDate;
+use std::ops::Range;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+pub trait Vault {
+    fn insert(&self, hash: [u8; 32], item: &ClipboardItem) -> Result<()> {
+        self.insert_with_metadata(hash, item, &CaptureMetadata::default())
+    }
+    /// Insert an item that [`purge_expired`](Self::purge_expired) deletes
+    /// once `ttl` has passed.
+    fn insert_with_ttl(&self, hash: [u8; 32], item: &ClipboardItem, ttl: Duration) -> Result<()> {
+        let metadata = CaptureMetadata {
+            ttl: Some(ttl),
+            ..CaptureMetadata::default()
+        };
+        self.insert_with_metadata(hash, item, &metadata)
+    }
+    /// Insert an item along with where it was captured. Re-copying an
+    /// existing item bumps its timestamp and context, unless the vault is
+    /// set to a different [`DuplicatePolicy`](crate::DuplicatePolicy).
+    fn insert_with_metadata(
+        &self,
+        hash: [u8; 32],
+        item: &ClipboardItem,
+        metadata: &CaptureMetadata,
+    ) -> Result<()>;
+    fn latest(&self) -> Result<Option<ClipboardItem>>;
+    /// The entry with `hash` and what was recorded with it, without
+    /// reading any other; `None` if it is missing or protected. By default
+    /// it is looked for in the [`list`](Self::list).
+    fn get(&self, hash: [u8; 32]) -> Result<Option<ClipboardItemWithTimestamp>> {
+        Ok(self
+            .list(None, None)?
+            .into_iter()
+            .find(|entry| entry.item.hash() == hash))
+    }
+    /// Like [`get`](Self::get), by the entry's
+    /// [`id`](ClipboardItemWithTimestamp::id).
+    fn get_by_id(&self, id: i64) -> Result<Option<ClipboardItemWithTimestamp>> {
+        Ok(self
+            .list(None, None)?
+            .into_iter()
+            .find(|entry| entry.id == Some(id)))
+    }
+    fn list(
+        &self,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>>;
+    /// Like [`list`](Self::list), but image entries hold their
+    /// [`thumbnail`](Self::thumbnail) instead of the full image where they
+    /// have one, for showing a long list cheaply. Load the entry by
+    /// [`ListedEntry::hash`] before copying it. By default every entry
+    /// holds its full item, with the [`item_stats`](Self::item_stats)
+    /// recorded for it.
+    fn list_thumbnails(
+        &self,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ListedEntry>> {
+        self.list(limit, after_timestamp)?
+            .into_iter()
+            .map(|entry| {
+                let hash = entry.item.hash();
+                Ok(ListedEntry {
+                    hash,
+                    thumbnail: false,
+                    image: entry.item.image_info(),
+                    stats: self.item_stats(hash)?.unwrap_or_default(),
+                    entry,
+                })
+            })
+            .collect()
+    }
+    /// The PNG thumbnail of an image entry, made when it was stored. `None`
+    /// for small images, which are their own thumbnail, and other entries.
+    fn thumbnail(&self, hash: [u8; 32]) -> Result<Option<Vec<u8>>>;
+    /// Substring search over text entries. `size:` and `dim:` filters (see
+    /// [`SearchQuery`]) narrow by payload size and image dimensions; a query
+    /// made only of filters can match images too.
+    fn search(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        after_timestamp: Option<u64>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>>;
+    /// Newest-first entries captured in `context`, matched either by full
+    /// path or by its last path component (e.g. a repo name).
+    fn list_in_context(
+        &self,
+        context: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>>;
+    /// All entries, best frecency first.
+    fn frecent(&self, limit: Option<usize>) -> Result<Vec<ClipboardItemWithTimestamp>>;
+    /// Note that an entry was picked or copied back out, boosting its
+    /// frecency.
+    fn record_use(&self, hash: [u8; 32]) -> Result<()>;
+    /// Each time the entry was captured, copied again or copied back out,
+    /// newest first. At most [`MAX_COPY_EVENTS`](crate::MAX_COPY_EVENTS).
+    fn copy_events(&self, hash: [u8; 32]) -> Result<Vec<CopyEvent>>;
+    /// Replace the entry `old_hash` with `new_item`. Returns the number of
+    /// rows changed, so 0 means there was no such entry.
+    fn update(&self, old_hash: [u8; 32], new_item: &ClipboardItem) -> Result<u64>;
+    /// Delete an entry. Returns the number of rows removed.
+    fn delete(&self, hash: [u8; 32]) -> Result<u64>;
+    /// [`update`](Self::update) the entry with this
+    /// [`id`](ClipboardItemWithTimestamp::id).
+    fn update_by_id(&self, id: i64, new_item: &ClipboardItem) -> Result<u64>;
+    /// [`delete`](Self::delete) the entry with this
+    /// [`id`](ClipboardItemWithTimestamp::id).
+    fn delete_by_id(&self, id: i64) -> Result<u64>;
+
+    /// Pin an entry, or unpin it with `pinned` false. Pinned entries are
+    /// listed first by [`list_pinned_first`](Self::list_pinned_first) and
+    /// never pruned, evicted, expired or archived. Returns the number of
+    /// rows changed.
+    fn pin(&self, hash: [u8; 32], pinned: bool) -> Result<u64>;
+    /// Pinned entries, newest first.
+    fn pinned(&self) -> Result<Vec<ClipboardItemWithTimestamp>>;
+    /// [`list`](Self::list) with the pinned entries first, each group
+    /// newest first.
+    fn list_pinned_first(&self, limit: Option<usize>) -> Result<Vec<ClipboardItemWithTimestamp>>;
+
+    /// Rows skipped by the most recent `latest`/`list`/`search` call because
+    /// their stored blob could not be decoded.
+    fn diagnostics(&self) -> Vec<RowDiagnostic>;
+
+    /// Move an entry out of the history into the quarantine table, so a
+    /// corrupted row stops being reported on every read.
+    fn quarantine(&self, hash: [u8; 32]) -> Result<()>;
+
+    /// Total bytes of stored item payloads.
+    fn total_size(&self) -> Result<u64>;
+
+    /// Delete entries older than the policy allows for their category, then
+    /// the oldest until the vault is within the policy's item cap, then the
+    /// oldest images and after them the oldest of the rest until it's
+    /// within its size cap.
+    /// Pinned entries are never deleted. Archived entries older than the
+    /// policy allows are deleted too; the caps only apply to the vault.
+    /// Returns the number of deleted entries.
+    fn prune(&self, policy: &RetentionPolicy) -> Result<usize>;
+
+    /// The entries [`prune`](Self::prune) would delete, without deleting
+    /// them.
+    fn preview_prune(&self, policy: &RetentionPolicy) -> Result<DryRun>;
+
+    /// Attach a free-form tag to an entry.
+    fn add_tag(&self, hash: [u8; 32], tag: &str) -> Result<()>;
+    /// Returns the number of tags removed (0 or 1).
+    fn remove_tag(&self, hash: [u8; 32], tag: &str) -> Result<u64>;
+    /// Tags of an entry, alphabetically.
+    fn tags(&self, hash: [u8; 32]) -> Result<Vec<String>>;
+    /// Newest-first entries tagged `tag`.
+    fn list_by_tag(
+        &self,
+        tag: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<ClipboardItemWithTimestamp>>;
+    /// Every tag in use on an entry of the history, alphabetically.
+    fn all_tags(&self) -> Result<Vec<String>>;
+
+    /// Keep a note with an entry, e.g. why it was saved; [`search`](Self::search)
+    /// matches it too. `None` or a blank note removes it. Returns the number
+    /// of rows changed, so 0 means there was no such entry.
+    fn set_note(&self, hash: [u8; 32], note: Option<&str>) -> Result<u64>;
+    /// The entry's note, if it has one.
+    fn note(&self, hash: [u8; 32]) -> Result<Option<String>>;
+
+    /// Size, line and word counts of an entry, recorded when it was stored,
+    /// so showing them doesn't load the entry. `None` if there's no such
+    /// entry.
+    fn item_stats(&self, hash: [u8; 32]) -> Result<Option<ItemStats>>;
+
+    /// Write entries matching `filter` to `writer` in `format`, oldest
+    /// first. Returns the number of entries written.
+    fn export(
+        &self,
+        writer: &mut dyn std::io::Write,
+        format: ExportFormat,
+        filter: &ExportFilter,
+    ) -> Result<usize>;
+
+    /// Store entries read from another clipboard manager (see
+    /// [`read_history`](crate::read_history)) with their recorded times,
+    /// tags, pins, expiry and spilled originals, skipping any already in the
+    /// vault. Runs in one transaction. Entries are stored as given: run them
+    /// through `clip_vault_monitor::prepare_import` first for the capture
+    /// settings to apply. By default the entries are inserted one by one and
+    /// stamped with the time of the import.
+    fn import(&self, entries: &[ImportedEntry]) -> Result<ImportSummary> {
+        let mut summary = ImportSummary::default();
+        for entry in entries {
+            let hash = entry.item.hash();
+            if self.get(hash)?.is_some() {
+                summary.duplicates += 1;
+                continue;
+            }
+            let metadata = CaptureMetadata {
+                context: entry.context.clone(),
+                ttl: entry.ttl,
+                source: entry.source.clone(),
+            };
+            self.insert_with_metadata(hash, &entry.item, &metadata)?;
+            for tag in &entry.tags {
+                self.add_tag(hash, tag)?;
+            }
+            if entry.pinned {
+                self.pin(hash, true)?;
+            }
+            summary.added += 1;
+        }
+        Ok(summary)
+    }
+
+    /// Delete entries whose capture TTL has run out. Pinned entries are
+    /// kept whatever their expiry. Returns the number of deleted entries.
+    fn purge_expired(&self) -> Result<usize>;
+    /// When an entry expires; `None` if it never does. A pinned entry
+    /// doesn't expire while it stays pinned.
+    fn expiry(&self, hash: [u8; 32]) -> Result<Option<Timestamp>>;
+    /// Make an entry expire at `expires_at`, or never with `None`. Returns
+    /// the number of rows changed.
+    fn set_expiry(&self, hash: [u8; 32], expires_at: Option<Timestamp>) -> Result<u64>;
+
+    /// Capture counts per bucket for timestamps in `range`. Empty buckets are
+    /// omitted.
+    fn activity_histogram(
+        &self,
+        bucket: HistogramBucket,
+        range: Range<Timestamp>,
+    ) -> Result<Vec<ActivityBucket>>;
+
+    /// Everything copied on `day` (local time), oldest first, grouped by
+    /// hour. Hours without entries are left out.
+    fn timeline(&self, day: NaiveDate) -> Result<Vec<TimelineHour>>;
+
+    /// Unprotected entries captured at a time in `range`, oldest first. A
+    /// copy made again later counts at its latest capture.
+    fn captured_between(&self, range: Range<Timestamp>) -> Result<Vec<ClipboardItemWithTimestamp>>;
+
+    /// [`captured_between`](Self::captured_between), handing each entry to
+    /// `f` as it's read instead of collecting them.
+    fn for_each_captured_between(
+        &self,
+        range: Range<Timestamp>,
+        f: &mut dyn FnMut(ClipboardItemWithTimestamp) -> Result<()>,
+    ) -> Result<()>;
+
+    /// Unprotected entries copied at a time in `range`, oldest first, once for each time they were copied and at the time of
+    /// that copy. Copies out of the vault don't count. An entry without
+    /// recorded copies counts at its latest capture.
+    fn copied_between(&self, range: Range<Timestamp>) -> Result<Vec<ClipboardItemWithTimestamp>>;
+
+    /// How many captures at a time in `range` looked like a password or
+    /// API key and were skipped instead of stored, as when
+    /// copied with `clip-vault copy --no-store`.
+    fn skipped_secrets_between(&self, range: Range<Timestamp>) -> Result<usize>;
+
+    /// Entries related to the one with `hash` (see [`Relation`]), those
+    /// with the most reasons first, then the nearest in time. Empty if the
+    /// entry doesn't exist.
+    ///
+    /// [`Relation`]: crate::Relation
+    fn related(&self, hash: [u8; 32]) -> Result<Vec<RelatedItem>>;
+
+    /// Receive an event for every change made through this vault. Changes
+    /// made through other connections arrive as [`VaultEvent::Changed`] once
+    /// [`poll_changes`](Vault::poll_changes) notices them.
+    fn subscribe(&self) -> Receiver<VaultEvent>;
+
+    /// Receive a [`HealthEvent`] whenever storing entries starts or stops
+    /// failing, unreadable entries turn up, or housekeeping deletes entries.
+    fn subscribe_health(&self) -> Receiver<HealthEvent>;
+
+    /// Check whether another connection wrote to the vault since the last
+    /// check and notify subscribers if so. Cheap enough to call every tick.
+    fn poll_changes(&self) -> Result<bool>;
+
+    fn len(&self) -> Result<usize>;
+
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
@@ -0,0 +1,508 @@
+//! The capture loop shared by the desktop app's monitor and
+//! `clip-vault --ephemeral`: reading the clipboard, applying the capture
+//! settings, skipping repeats and storing changes in batches. Features added
+//! here reach every capturing binary at once.
+
+mod provider;
+mod source;
+mod suppress;
+mod trace;
+
+pub use provider::{ArboardClipboard, ClipboardError, ClipboardProvider, ImageData, MockClipboard};
+pub use suppress::{suppress_capture, take_suppressed};
+pub use trace::{CaptureDecision, CaptureTrace, PollOutcome, TRACE_LEN};
+
+use clip_vault_core::{
+    apply_capture_transforms, contains_secret, strip_image_metadata, AlertConfig, Capture,
+    CaptureAlert, CaptureMetadata, CaptureQueue, CaptureTransform, ClipboardItem, DedupeRules,
+    FailedCapture, ImportedEntry, ItemKind, Limited, OverflowPolicy, Representation,
+    RetentionPolicy, RichText, SqliteVault, TextLimit, TextNormalization, Timestamp, Vault,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Captures held back while the clipboard changes rapidly.
+const QUEUE_CAPACITY: usize = 32;
+/// Queued captures are stored at most this often, so a burst becomes a few
+/// transactions rather than one per change. A change after a quiet spell
+/// is stored right away.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+/// How often [`run`] applies the retention policy.
+const PRUNE_INTERVAL: Duration = Duration::from_mins(1);
+
+/// How clipboard contents become captures.
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct CaptureOptions {
+    pub capture_images: bool,
+    /// Record files copied in a file manager as file entries rather than
+    /// the text or icon that comes with them.
+    pub capture_files: bool,
+    /// Drop EXIF/GPS and other metadata from images before hashing them.
+    /// The vault strips what it stores on its own (see
+    /// [`SqliteVault::set_strip_image_metadata`]); this keeps the hashes of
+    /// captures in step with it.
+    pub strip_image_metadata: bool,
+    /// Cap on captured text; oversized copies are skipped or truncated.
+    pub text_limit: TextLimit,
+    /// Cleanups run on captured text, in order, before it's hashed.
+    pub transforms: Vec<CaptureTransform>,
+    /// Line endings and trailing whitespace evened out after the
+    /// transforms, so copies from different editors dedupe.
+    pub normalize_text: TextNormalization,
+    /// Keep the HTML and RTF flavours copied alongside text.
+    pub capture_rich_text: bool,
+    /// Application-specific formats kept with each capture, at most this
+    /// many; 0 keeps none.
+    pub custom_formats: usize,
+    /// Tag captures with the current context (see
+    /// [`clip_vault_core::current_context`]).
+    pub record_context: bool,
+    /// Record the frontmost app and window title with each capture (see
+    /// [`clip_vault_core::SourceApp::current`]). It is looked up in the
+    /// background every half second, so a copy made within that of switching
+    /// apps is credited to the app before. [`app_ttls`](Self::app_ttls) look it up
+    /// even when this is off.
+    pub record_source: bool,
+    /// Delete captures from matching apps this long after they were copied
+    /// (see [`CaptureMetadata::expire_from_app`]).
+    pub app_ttls: BTreeMap<String, Duration>,
+    /// Which captures count as copies of stored entries, by category.
+    pub dedupe: DedupeRules,
+    pub overflow: OverflowPolicy,
+    /// Captures that raise alerts, and the webhook they're sent to.
+    pub alerts: AlertConfig,
+    /// Applied to the vault every minute by [`run`]; the desktop app prunes
+    /// on its own schedule. Captured one-time codes expire after its
+    /// one-time code age either way.
+    pub retention: RetentionPolicy,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            capture_images: true,
+            capture_files: true,
+            strip_image_metadata: true,
+            text_limit: TextLimit::default(),
+            transforms: Vec::new(),
+            normalize_text: TextNormalization::default(),
+            capture_rich_text: true,
+            custom_formats: 0,
+            record_context: true,
+            record_source: true,
+            app_ttls: BTreeMap::new(),
+            dedupe: DedupeRules::default(),
+            overflow: OverflowPolicy::default(),
+            alerts: AlertConfig::default(),
+            retention: clip_vault_core::Config::default().retention,
+        }
+    }
+}
+
+/// Read the clipboard as a capture. `None` when it's empty, unreadable or
+/// skipped by the options; an error when an image couldn't be encoded.
+pub fn read(
+    clipboard: &mut dyn ClipboardProvider,
+    options: &CaptureOptions,
+) -> Result<Option<Capture>, String> {
+    match read_outcome(clipboard, options) {
+        Ok(capture) => Ok(Some(capture)),
+        Err(PollOutcome::Failed { error }) => Err(error),
+        Err(_) => Ok(None),
+    }
+}
+
+/// [`read`], saying why nothing was captured instead of returning `None`.
+pub fn read_outcome(
+    clipboard: &mut dyn ClipboardProvider,
+    options: &CaptureOptions,
+) -> Result<Capture, PollOutcome> {
+    let mut rich = RichText::default();
+    let mut representations = Vec::new();
+    let mut spill = None;
+    let files = if options.capture_files {
+        clipboard.get_files().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    // A copied file often comes with its icon and name too; the file wins.
+    let image = if !files.is_empty() {
+        Ok(None)
+    } else if options.capture_images {
+        clipboard.get_image().map(Some)
+    } else {
+        Err(ClipboardError::Empty)
+    };
+    let item = match image {
+        Ok(None) => ClipboardItem::Files(files),
+        Ok(Some(image_data)) => {
+            let data = image_data
+                .encode_png()
+                .map_err(|error| PollOutcome::Failed { error })?;
+            (representations, rich) = read_alongside_image(clipboard, options);
+            ClipboardItem::Image(prepare_image(data, options))
+        }
+        Err(image_error) => {
+            let text = match clipboard.get_text() {
+                Ok(text) => text,
+                // No text either: report whichever read actually failed.
+                Err(ClipboardError::Empty) if image_error == ClipboardError::Empty => {
+                    return Err(PollOutcome::Empty);
+                }
+                Err(ClipboardError::Empty) => {
+                    return Err(PollOutcome::BackendError {
+                        error: image_error.to_string(),
+                    });
+                }
+                Err(e) => {
+                    return Err(PollOutcome::BackendError {
+                        error: e.to_string(),
+                    });
+                }
+            };
+            let (text, original) = prepare_text(text, options)?;
+            if options.capture_rich_text {
+                rich.html = clipboard.get_html().ok();
+                rich.rtf = clipboard.get_rtf().ok();
+            }
+            spill = original;
+            ClipboardItem::Text(text)
+        }
+    };
+
+    let custom_formats = if options.custom_formats > 0 {
+        clipboard
+            .get_custom_formats(options.custom_formats)
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let metadata = if options.record_context {
+        CaptureMetadata::current()
+    } else {
+        CaptureMetadata::default()
+    };
+    let mut metadata = metadata.expire_otp(&item, options.retention.max_age(ItemKind::Otp));
+    // The source app is looked up for its TTL even when it isn't recorded.
+    if options.record_source || !options.app_ttls.is_empty() {
+        metadata.source = source::source_app();
+        metadata = metadata.expire_from_app(&options.app_ttls);
+        if !options.record_source {
+            metadata.source = None;
+        }
+    }
+    let mut capture = Capture::new(item, metadata);
+    capture.dedupe = options.dedupe.resolve(&capture.item);
+    capture.html = rich.html;
+    capture.rtf = rich.rtf;
+    capture.spill = spill;
+    capture.custom_formats = custom_formats;
+    capture.representations = representations;
+    Ok(capture)
+}
+
+/// Apply the capture settings to an entry read from another clipboard
+/// manager, as if it had been copied from its source app at its recorded
+/// time: the text transforms, normalization and limit, image metadata
+/// stripping, the one-time code and app TTLs, and the dedupe rules. `None`
+/// when they would have skipped it.
+#[must_use]
+pub fn prepare_import(mut entry: ImportedEntry, options: &CaptureOptions) -> Option<ImportedEntry> {
+    entry.item = match entry.item {
+        ClipboardItem::Text(text) => {
+            let (text, spill) = prepare_text(text, options).ok()?;
+            entry.spill = spill;
+            ClipboardItem::Text(text)
+        }
+        ClipboardItem::Image(data) if options.capture_images => {
+            ClipboardItem::Image(prepare_image(data, options))
+        }
+        ClipboardItem::Files(paths) if options.capture_files => ClipboardItem::Files(paths),
+        _ => return None,
+    };
+    let metadata = CaptureMetadata {
+        context: entry.context.clone(),
+        ttl: entry.ttl,
+        source: entry.source.clone(),
+    }
+    .expire_otp(&entry.item, options.retention.max_age(ItemKind::Otp))
+    .expire_from_app(&options.app_ttls);
+    entry.ttl = metadata.ttl;
+    entry.dedupe = options.dedupe.resolve(&entry.item);
+    Some(entry)
+}
+
+/// Copied text after the transforms, normalization and text limit, with
+/// the original when the limit cut it short.
+fn prepare_text(
+    text: String,
+    options: &CaptureOptions,
+) -> Result<(String, Option<String>), PollOutcome> {
+    let text = options
+        .normalize_text
+        .apply(apply_capture_transforms(text, &options.transforms));
+    if text.is_empty() {
+        return Err(PollOutcome::Skipped {
+            reason: "only whitespace or escape codes".to_string(),
+        });
+    }
+    match options.text_limit.apply(text) {
+        Limited::Unchanged(text) => Ok((text, None)),
+        Limited::Truncated { text, original } => Ok((text, original)),
+        Limited::Skipped => Err(PollOutcome::Skipped {
+            reason: "over the text size limit".to_string(),
+        }),
+    }
+}
+
+/// A PNG as stored, without its metadata if the options say so.
+fn prepare_image(png: Vec<u8>, options: &CaptureOptions) -> Vec<u8> {
+    if options.strip_image_metadata {
+        strip_image_metadata(&png)
+    } else {
+        png
+    }
+}
+
+/// What a spreadsheet range or a browser image comes with besides the
+/// image: its text, and the HTML and RTF flavours. The image is the entry;
+/// these are kept with it.
+fn read_alongside_image(
+    clipboard: &mut dyn ClipboardProvider,
+    options: &CaptureOptions,
+) -> (Vec<Representation>, RichText) {
+    let mut representations = Vec::new();
+    // Prepared like copied text, so the text limit caps it too.
+    if let Ok((text, _)) = clipboard
+        .get_text()
+        .map_err(|_| PollOutcome::Empty)
+        .and_then(|text| prepare_text(text, options))
+    {
+        representations.push(Representation::text(&text));
+    }
+    let mut rich = RichText::default();
+    if options.capture_rich_text {
+        rich.html = clipboard.get_html().ok();
+        rich.rtf = clipboard.get_rtf().ok();
+    }
+    (representations, rich)
+}
+
+/// Send `alerts` about the entry with id `entry_id` to the configured
+/// webhook, from a background thread so a slow endpoint doesn't hold up
+/// capture. Failures are returned through `on_error` on that thread.
+pub fn send_alerts(
+    config: &AlertConfig,
+    entry_id: i64,
+    alerts: Vec<CaptureAlert>,
+    on_error: impl Fn(clip_vault_core::Error) + Send + 'static,
+) {
+    let Some(url) = config.webhook.clone().filter(|_| !alerts.is_empty()) else {
+        return;
+    };
+    std::thread::spawn(move || {
+        for alert in &alerts {
+            if let Err(e) = clip_vault_core::send_webhook(&url, entry_id, alert) {
+                on_error(e);
+            }
+        }
+    });
+}
+
+/// Dedupe and batching state for one capture loop.
+#[derive(Debug)]
+pub struct Monitor {
+    last_hash: Option<[u8; 32]>,
+    queue: CaptureQueue,
+    last_flush: Option<Instant>,
+    /// Alerts about queued captures, sent once they're stored and have an
+    /// id.
+    held_alerts: Vec<([u8; 32], Vec<CaptureAlert>)>,
+    /// When skipped captures that looked like secrets were made, recorded
+    /// by the next flush.
+    skipped_secrets: Vec<Timestamp>,
+}
+
+impl Monitor {
+    /// `last_hash` is the last capture of a previous run, so the clipboard
+    /// isn't captured again after a restart.
+    #[must_use]
+    pub fn new(last_hash: Option<[u8; 32]>, overflow: OverflowPolicy) -> Self {
+        Self {
+            last_hash,
+            queue: CaptureQueue::new(QUEUE_CAPACITY, overflow),
+            last_flush: None,
+            held_alerts: Vec::new(),
+            skipped_secrets: Vec::new(),
+        }
+    }
+
+    pub fn set_overflow(&mut self, policy: OverflowPolicy) {
+        self.queue.set_policy(policy);
+    }
+
+    /// Queue `capture` unless it repeats the previous one. Returns whether
+    /// it was new.
+    pub fn offer(&mut self, capture: Capture) -> bool {
+        if self.last_hash == Some(capture.hash) {
+            return false;
+        }
+        self.last_hash = Some(capture.hash);
+        self.queue.push(capture);
+        true
+    }
+
+    /// Keep `alerts` about the queued capture with `hash` until it's stored;
+    /// [`stored_alerts`](Self::stored_alerts) hands them out then.
+    pub fn hold_alerts(&mut self, hash: [u8; 32], alerts: Vec<CaptureAlert>) {
+        if !alerts.is_empty() {
+            self.held_alerts.push((hash, alerts));
+        }
+    }
+
+    /// The held alerts about captures stored by the last flush, with their
+    /// entries' ids. Alerts about captures still queued are kept; those
+    /// about captures dropped from the queue or since removed are not.
+    pub fn stored_alerts(&mut self, vault: &SqliteVault) -> Vec<(i64, Vec<CaptureAlert>)> {
+        let queued: HashSet<[u8; 32]> = self.queue.pending().iter().map(|c| c.hash).collect();
+        let (waiting, stored) = std::mem::take(&mut self.held_alerts)
+            .into_iter()
+            .partition(|(hash, _)| queued.contains(hash));
+        self.held_alerts = waiting;
+        stored
+            .into_iter()
+            .filter_map(|(hash, alerts)| {
+                let id = vault.get(hash).ok().flatten()?.id?;
+                Some((id, alerts))
+            })
+            .collect()
+    }
+
+    /// Treat `hash` as the previous capture without storing it, so the
+    /// content it belongs to isn't captured while it stays on the clipboard.
+    pub fn skip(&mut self, hash: [u8; 32]) {
+        self.last_hash = Some(hash);
+    }
+
+    /// [`skip`](Self::skip) a capture marked not to be recorded, counting
+    /// it for the weekly digest if it looked like a password or API key.
+    pub fn skip_marked(&mut self, capture: &Capture) {
+        self.skip(capture.hash);
+        let text = match &capture.item {
+            ClipboardItem::Text(text) => Some(text.as_str()),
+            _ => None,
+        };
+        let mut texts = text.into_iter().chain(
+            capture
+                .representations
+                .iter()
+                .filter_map(Representation::as_text),
+        );
+        if texts.any(contains_secret) {
+            self.skipped_secrets.push(capture.captured_at);
+        }
+    }
+
+    /// Captures lost to the overflow policy since the last call.
+    pub fn take_dropped(&mut self) -> usize {
+        self.queue.take_dropped()
+    }
+
+    /// Whether there are captures waiting and it's time to store them.
+    #[must_use]
+    pub fn flush_due(&self) -> bool {
+        !self.skipped_secrets.is_empty()
+            || !self.queue.is_empty()
+                && (self.queue.is_full()
+                    || self
+                        .last_flush
+                        .is_none_or(|t| t.elapsed() >= FLUSH_INTERVAL))
+    }
+
+    /// Store the waiting captures in one transaction. Captures the vault
+    /// can't store are dropped and reported, so they don't hold up the
+    /// rest. If the transaction fails as a whole they all stay queued and
+    /// are retried by the next flush.
+    pub fn flush(&mut self, vault: &SqliteVault) -> clip_vault_core::Result<Flushed> {
+        self.last_flush = Some(Instant::now());
+        vault.record_skipped_secrets(&self.skipped_secrets)?;
+        self.skipped_secrets.clear();
+        let failed = vault.insert_batch(self.queue.pending())?;
+        let flushed = Flushed {
+            stored: self.queue.len() - failed.len(),
+            newest: self.queue.pending().last().map(|c| c.hash),
+            failed,
+        };
+        self.queue.clear();
+        Ok(flushed)
+    }
+}
+
+/// What [`Monitor::flush`] stored.
+#[derive(Debug, Default)]
+pub struct Flushed {
+    pub stored: usize,
+    /// The hash of the newest capture in the batch.
+    pub newest: Option<[u8; 32]>,
+    /// Captures the vault couldn't store; the others were.
+    pub failed: Vec<FailedCapture>,
+}
+
+/// Capture into `vault` every `interval` until `keep_going` returns false,
+/// also removing expired entries and applying the retention policy. For callers that own a vault connection,
+/// like a capture thread. Failed reads and writes are retried on the next
+/// tick; only a missing clipboard is an error.
+pub fn run(
+    vault: &SqliteVault,
+    options: &CaptureOptions,
+    interval: Duration,
+    keep_going: impl Fn() -> bool,
+) -> Result<(), String> {
+    let mut clipboard = ArboardClipboard::new().map_err(|e| e.to_string())?;
+    run_with(&mut clipboard, vault, options, interval, keep_going);
+    Ok(())
+}
+
+/// [`run`] on `clipboard` instead of the system clipboard, e.g. a
+/// [`MockClipboard`] in tests or on headless machines.
+pub fn run_with(
+    clipboard: &mut dyn ClipboardProvider,
+    vault: &SqliteVault,
+    options: &CaptureOptions,
+    interval: Duration,
+    keep_going: impl Fn() -> bool,
+) {
+    let mut monitor = Monitor::new(None, options.overflow);
+    let mut last_sequence = None;
+    let mut last_prune: Option<Instant> = None;
+    while keep_going() {
+        let sequence = clipboard.sequence_number();
+        if sequence.is_none() || sequence != last_sequence {
+            if let Ok(capture) = read(clipboard, options) {
+                last_sequence = sequence;
+                if let Some(capture) = capture {
+                    let alerts = options.alerts.check_capture(&capture);
+                    let hash = capture.hash;
+                    if take_suppressed(&capture) {
+                        monitor.skip_marked(&capture);
+                    } else if monitor.offer(capture) {
+                        monitor.hold_alerts(hash, alerts);
+                    }
+                }
+            }
+        }
+        if monitor.flush_due() && monitor.flush(vault).is_ok() {
+            for (id, alerts) in monitor.stored_alerts(vault) {
+                send_alerts(&options.alerts, id, alerts, drop);
+            }
+        }
+        vault.purge_expired().ok();
+        if last_prune.is_none_or(|t| t.elapsed() >= PRUNE_INTERVAL) {
+            vault.prune(&options.retention).ok();
+            last_prune = Some(Instant::now());
+        }
+        std::thread::sleep(interval);
+    }
+}
@@ -0,0 +1,679 @@
+//! Access to a clipboard behind a trait, so capture loops and copy commands
+//! can run against the system clipboard, a virtual one in tests, or a
+//! platform arboard doesn't cover.
+
+use clip_vault_core::{files_text, ClipboardItem, CustomFormat, Representation, RichText};
+use image::{ImageBuffer, RgbaImage};
+use std::borrow::Cow;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Why a clipboard operation failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// The clipboard holds nothing in the requested format.
+    Empty,
+    /// There is no usable clipboard, e.g. no display server.
+    Unsupported(String),
+    Failed(String),
+}
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("the clipboard is empty"),
+            Self::Unsupported(e) => write!(f, "clipboard unavailable: {e}"),
+            Self::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+impl std::error::Error for ClipboardError {}
+
+impl From<arboard::Error> for ClipboardError {
+    fn from(e: arboard::Error) -> Self {
+        match e {
+            arboard::Error::ContentNotAvailable => Self::Empty,
+            arboard::Error::ClipboardNotSupported | arboard::Error::Unknown { .. } => {
+                Self::Unsupported(e.to_string())
+            }
+            e => Self::Failed(e.to_string()),
+        }
+    }
+}
+
+/// Raw RGBA pixels, as clipboards hand out images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageData {
+    pub width: usize,
+    pub height: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl ImageData {
+    /// Decode a stored image into pixels. Only the pixels survive, so
+    /// metadata left in entries stored before stripping was enabled isn't
+    /// copied again.
+    pub fn decode(data: &[u8]) -> Result<Self, ClipboardError> {
+        let image =
+            image::load_from_memory(data).map_err(|e| ClipboardError::Failed(e.to_string()))?;
+        Ok(Self {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: image.to_rgba8().into_raw(),
+        })
+    }
+
+    /// Encode the pixels as PNG.
+    pub fn encode_png(self) -> Result<Vec<u8>, String> {
+        let width = u32::try_from(self.width).map_err(|e| e.to_string())?;
+        let height = u32::try_from(self.height).map_err(|e| e.to_string())?;
+        let image: RgbaImage = ImageBuffer::from_raw(width, height, self.bytes)
+            .ok_or("image data doesn't match its dimensions")?;
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut buffer, image::ImageFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Ok(buffer.into_inner())
+    }
+}
+
+/// A clipboard that can be read and written.
+pub trait ClipboardProvider: Send {
+    fn get_text(&mut self) -> Result<String, ClipboardError>;
+
+    /// The HTML flavour copied alongside text, if the clipboard keeps one.
+    fn get_html(&mut self) -> Result<String, ClipboardError> {
+        Err(ClipboardError::Empty)
+    }
+
+    /// The RTF flavour copied alongside text, if the clipboard keeps one.
+    fn get_rtf(&mut self) -> Result<String, ClipboardError> {
+        Err(ClipboardError::Empty)
+    }
+
+    fn get_image(&mut self) -> Result<ImageData, ClipboardError>;
+
+    /// Files copied in a file manager.
+    fn get_files(&mut self) -> Result<Vec<PathBuf>, ClipboardError> {
+        Err(ClipboardError::Empty)
+    }
+
+    /// Up to `limit` application-specific formats on the clipboard, in the
+    /// order the application put them there.
+    fn get_custom_formats(&mut self, _limit: usize) -> Result<Vec<CustomFormat>, ClipboardError> {
+        Err(ClipboardError::Empty)
+    }
+
+    /// Put `item` on the clipboard. Images are PNG bytes, as stored; files
+    /// go on as their paths where the clipboard can't hold file references.
+    fn set(&mut self, item: &ClipboardItem) -> Result<(), ClipboardError>;
+
+    /// [`set`](Self::set), with `formats` put back alongside `item`.
+    /// Clipboards that can't hold custom formats get `item` alone.
+    fn set_with_formats(
+        &mut self,
+        item: &ClipboardItem,
+        _formats: &[CustomFormat],
+    ) -> Result<(), ClipboardError> {
+        self.set(item)
+    }
+
+    /// [`set_with_formats`](Self::set_with_formats), with the formatted
+    /// flavours in `rich` put back alongside text. Clipboards that can't
+    /// hold a flavour leave it out.
+    fn set_formatted(
+        &mut self,
+        item: &ClipboardItem,
+        _rich: &RichText,
+        formats: &[CustomFormat],
+    ) -> Result<(), ClipboardError> {
+        self.set_with_formats(item, formats)
+    }
+
+    /// [`set_formatted`](Self::set_formatted), with the other forms the
+    /// copy came in put back too, like the text copied with an image.
+    /// Clipboards that hold one form at a time get `item` and its flavours
+    /// alone.
+    fn set_bundle(
+        &mut self,
+        item: &ClipboardItem,
+        rich: &RichText,
+        formats: &[CustomFormat],
+        _representations: &[Representation],
+    ) -> Result<(), ClipboardError> {
+        self.set_formatted(item, rich, formats)
+    }
+
+    /// A counter that changes whenever the clipboard does, so pollers can
+    /// skip reading unchanged contents. `None` if the platform has none.
+    fn sequence_number(&mut self) -> Option<u64> {
+        None
+    }
+}
+
+/// The system clipboard, through arboard. RTF and custom formats are read
+/// and written on Windows only, as are file references; several forms of
+/// one copy are put back on Windows and macOS. arboard itself knows text,
+/// HTML and images and reads copied files, but holds one form at a time
+/// on Linux, so an image goes back there without its text. Only Windows has a sequence number to skip unchanged
+/// polls with; elsewhere every poll reads the clipboard.
+pub struct ArboardClipboard(arboard::Clipboard);
+
+impl ArboardClipboard {
+    pub fn new() -> Result<Self, ClipboardError> {
+        Ok(Self(arboard::Clipboard::new()?))
+    }
+}
+
+impl ClipboardProvider for ArboardClipboard {
+    fn get_text(&mut self) -> Result<String, ClipboardError> {
+        Ok(self.0.get_text()?)
+    }
+
+    fn get_html(&mut self) -> Result<String, ClipboardError> {
+        Ok(self.0.get().html()?)
+    }
+
+    #[cfg(windows)]
+    fn get_rtf(&mut self) -> Result<String, ClipboardError> {
+        windows::get_rtf()
+    }
+
+    fn get_image(&mut self) -> Result<ImageData, ClipboardError> {
+        let image = self.0.get_image()?;
+        Ok(ImageData {
+            width: image.width,
+            height: image.height,
+            bytes: image.bytes.into_owned(),
+        })
+    }
+
+    fn get_files(&mut self) -> Result<Vec<PathBuf>, ClipboardError> {
+        Ok(self.0.get().file_list()?)
+    }
+
+    #[cfg(windows)]
+    fn get_custom_formats(&mut self, limit: usize) -> Result<Vec<CustomFormat>, ClipboardError> {
+        windows::get_custom_formats(limit)
+    }
+
+    fn set(&mut self, item: &ClipboardItem) -> Result<(), ClipboardError> {
+        match item {
+            ClipboardItem::Text(text) => self.0.set_text(text.as_str())?,
+            ClipboardItem::Image(data) => {
+                let image = ImageData::decode(data)?;
+                self.0.set_image(arboard::ImageData {
+                    width: image.width,
+                    height: image.height,
+                    bytes: Cow::Owned(image.bytes),
+                })?;
+            }
+            #[cfg(windows)]
+            ClipboardItem::Files(paths) => windows::set_files(paths)?,
+            #[cfg(not(windows))]
+            ClipboardItem::Files(paths) => self.0.set_text(files_text(paths))?,
+            _ => {
+                return Err(ClipboardError::Unsupported(
+                    "unknown kind of entry".to_string(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn set_with_formats(
+        &mut self,
+        item: &ClipboardItem,
+        formats: &[CustomFormat],
+    ) -> Result<(), ClipboardError> {
+        self.set(item)?;
+        windows::add_custom_formats(formats)
+    }
+
+    fn set_formatted(
+        &mut self,
+        item: &ClipboardItem,
+        rich: &RichText,
+        formats: &[CustomFormat],
+    ) -> Result<(), ClipboardError> {
+        let ClipboardItem::Text(text) = item else {
+            return self.set_with_formats(item, formats);
+        };
+        match &rich.html {
+            Some(html) => self.0.set().html(html.as_str(), Some(text.as_str()))?,
+            None => self.0.set_text(text.as_str())?,
+        }
+        #[cfg(windows)]
+        {
+            if let Some(rtf) = &rich.rtf {
+                windows::add_rtf(rtf)?;
+            }
+            windows::add_custom_formats(formats)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn set_bundle(
+        &mut self,
+        item: &ClipboardItem,
+        rich: &RichText,
+        formats: &[CustomFormat],
+        representations: &[Representation],
+    ) -> Result<(), ClipboardError> {
+        self.set_formatted(item, rich, formats)?;
+        // The flavours went on with text; an image needs them added.
+        if let ClipboardItem::Image(_) = item {
+            if let Some(html) = &rich.html {
+                windows::add_html(html)?;
+            }
+            if let Some(rtf) = &rich.rtf {
+                windows::add_rtf(rtf)?;
+            }
+        }
+        windows::add_representations(representations)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn set_bundle(
+        &mut self,
+        item: &ClipboardItem,
+        rich: &RichText,
+        formats: &[CustomFormat],
+        representations: &[Representation],
+    ) -> Result<(), ClipboardError> {
+        // arboard covers text with its HTML; anything more goes on directly.
+        let extra = match item {
+            ClipboardItem::Text(_) => !representations.is_empty(),
+            ClipboardItem::Image(_) => !representations.is_empty() || !rich.is_empty(),
+            _ => false,
+        };
+        if extra {
+            macos::set_bundle(item, rich, representations)
+        } else {
+            self.set_formatted(item, rich, formats)
+        }
+    }
+
+    #[cfg(windows)]
+    fn sequence_number(&mut self) -> Option<u64> {
+        windows::sequence_number()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::ClipboardError;
+    use clip_vault_core::{ClipboardItem, Representation, RichText};
+    use objc2::rc::Retained;
+    use objc2::{msg_send, ClassType};
+    use objc2_app_kit::{
+        NSPasteboard, NSPasteboardType, NSPasteboardTypeHTML, NSPasteboardTypePNG,
+        NSPasteboardTypeRTF, NSPasteboardTypeString,
+    };
+    use objc2_foundation::{NSData, NSString};
+
+    /// Replace the pasteboard with `item`, its flavours and the other
+    /// forms it was copied in, as one copy. arboard writes one form at a
+    /// time, each clearing the last.
+    pub(super) fn set_bundle(
+        item: &ClipboardItem,
+        rich: &RichText,
+        representations: &[Representation],
+    ) -> Result<(), ClipboardError> {
+        // `generalPasteboard` can be NULL when run as a daemon, despite its
+        // documentation; arboard looks it up the same way.
+        let pasteboard: Option<Retained<NSPasteboard>> =
+            unsafe { msg_send![NSPasteboard::class(), generalPasteboard] };
+        let pasteboard =
+            pasteboard.ok_or_else(|| ClipboardError::Failed("no pasteboard".to_string()))?;
+        let text = match item {
+            ClipboardItem::Text(text) => Some(text.as_str()),
+            _ => representations.iter().find_map(Representation::as_text),
+        };
+        let png = match item {
+            ClipboardItem::Image(data) => Some(data.as_slice()),
+            _ => representations.iter().find_map(Representation::as_png),
+        };
+        // SAFETY: the type names are immutable strings AppKit exports.
+        let (string_type, png_type, html_type, rtf_type) = unsafe {
+            (
+                NSPasteboardTypeString,
+                NSPasteboardTypePNG,
+                NSPasteboardTypeHTML,
+                NSPasteboardTypeRTF,
+            )
+        };
+        pasteboard.clearContents();
+        let strings = [
+            (text, string_type),
+            (rich.html.as_deref(), html_type),
+            (rich.rtf.as_deref(), rtf_type),
+        ];
+        for (value, kind) in strings {
+            if let Some(value) = value {
+                if !pasteboard.setString_forType(&NSString::from_str(value), kind) {
+                    return Err(refused(kind));
+                }
+            }
+        }
+        if let Some(png) = png {
+            if !pasteboard.setData_forType(Some(&NSData::with_bytes(png)), png_type) {
+                return Err(refused(png_type));
+            }
+        }
+        Ok(())
+    }
+
+    fn refused(kind: &NSPasteboardType) -> ClipboardError {
+        ClipboardError::Failed(format!("the pasteboard refused {kind}"))
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::ClipboardError;
+    use clip_vault_core::{CustomFormat, Representation, MAX_CUSTOM_FORMAT_BYTES};
+    use clipboard_win::{options, raw, Clipboard};
+    use std::path::PathBuf;
+
+    /// Registered formats start here; below it are the system's own.
+    const FIRST_REGISTERED_FORMAT: u32 = 0xC000;
+    /// Registered formats clip-vault keeps another way: the HTML and RTF
+    /// flavours, and the PNG arboard writes with every image.
+    const KEPT_ELSEWHERE: [&str; 3] = ["HTML Format", RTF_FORMAT, "PNG"];
+    const RTF_FORMAT: &str = "Rich Text Format";
+    const OPEN_ATTEMPTS: usize = 10;
+
+    fn failed(e: impl std::fmt::Display) -> ClipboardError {
+        ClipboardError::Failed(e.to_string())
+    }
+
+    pub(super) fn get_custom_formats(limit: usize) -> Result<Vec<CustomFormat>, ClipboardError> {
+        let _open = Clipboard::new_attempts(OPEN_ATTEMPTS).map_err(failed)?;
+        let mut formats = Vec::new();
+        for id in raw::EnumFormats::new().filter(|id| *id >= FIRST_REGISTERED_FORMAT) {
+            if formats.len() == limit {
+                break;
+            }
+            let Some(name) = raw::format_name_big(id) else {
+                continue;
+            };
+            if KEPT_ELSEWHERE.contains(&name.as_str())
+                || raw::size(id).is_some_and(|size| size.get() > MAX_CUSTOM_FORMAT_BYTES)
+            {
+                continue;
+            }
+            let mut data = Vec::new();
+            if raw::get_vec(id, &mut data).is_ok() {
+                formats.push(CustomFormat { name, data });
+            }
+        }
+        Ok(formats)
+    }
+
+    /// Windows' clipboard sequence number, which needs no open clipboard.
+    pub(super) fn sequence_number() -> Option<u64> {
+        raw::seq_num().map(|n| u64::from(n.get()))
+    }
+
+    pub(super) fn get_rtf() -> Result<String, ClipboardError> {
+        let _open = Clipboard::new_attempts(OPEN_ATTEMPTS).map_err(failed)?;
+        let id = raw::register_format(RTF_FORMAT).ok_or(ClipboardError::Empty)?;
+        let mut data = Vec::new();
+        raw::get_vec(id.get(), &mut data).map_err(|_| ClipboardError::Empty)?;
+        // Applications write it NUL-terminated.
+        let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+        Ok(String::from_utf8_lossy(&data[..end]).into_owned())
+    }
+
+    /// Replace the clipboard with references to `paths`, as Explorer
+    /// copies files.
+    pub(super) fn set_files(paths: &[PathBuf]) -> Result<(), ClipboardError> {
+        let paths: Vec<_> = paths.iter().map(|path| path.to_string_lossy()).collect();
+        let _open = Clipboard::new_attempts(OPEN_ATTEMPTS).map_err(failed)?;
+        raw::empty().map_err(failed)?;
+        raw::set_file_list(&paths).map_err(failed)
+    }
+
+    /// Add HTML to what is already on the clipboard.
+    pub(super) fn add_html(html: &str) -> Result<(), ClipboardError> {
+        let _open = Clipboard::new_attempts(OPEN_ATTEMPTS).map_err(failed)?;
+        let id = raw::register_format("HTML Format")
+            .ok_or_else(|| failed("can't register format HTML Format"))?;
+        raw::set_html(id.get(), html).map_err(failed)
+    }
+
+    /// Add the other forms of a copy to what is already on the clipboard:
+    /// text as Unicode text and images as the PNG format arboard writes.
+    pub(super) fn add_representations(
+        representations: &[Representation],
+    ) -> Result<(), ClipboardError> {
+        for representation in representations {
+            if let Some(text) = representation.as_text() {
+                let _open = Clipboard::new_attempts(OPEN_ATTEMPTS).map_err(failed)?;
+                raw::set_string_with(text, options::NoClear).map_err(failed)?;
+            } else if let Some(png) = representation.as_png() {
+                add_custom_formats(&[CustomFormat {
+                    name: "PNG".to_string(),
+                    data: png.to_vec(),
+                }])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add RTF to the text already on the clipboard.
+    pub(super) fn add_rtf(rtf: &str) -> Result<(), ClipboardError> {
+        add_custom_formats(&[CustomFormat {
+            name: RTF_FORMAT.to_string(),
+            data: rtf.as_bytes().to_vec(),
+        }])
+    }
+
+    /// Add `formats` to what is already on the clipboard. Their ids are
+    /// registered again, as they differ between sessions.
+    pub(super) fn add_custom_formats(formats: &[CustomFormat]) -> Result<(), ClipboardError> {
+        if formats.is_empty() {
+            return Ok(());
+        }
+        let _open = Clipboard::new_attempts(OPEN_ATTEMPTS).map_err(failed)?;
+        for format in formats {
+            let id = raw::register_format(&format.name)
+                .ok_or_else(|| failed(format!("can't register format {}", format.name)))?;
+            raw::set_without_clear(id.get(), &format.data).map_err(failed)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockContents {
+    text: Option<String>,
+    html: Option<String>,
+    rtf: Option<String>,
+    image: Option<ImageData>,
+    files: Option<Vec<PathBuf>>,
+    custom_formats: Vec<CustomFormat>,
+    sequence: u64,
+}
+
+/// An in-memory clipboard for tests and headless use. Clones share the
+/// same contents, so a test can keep one to copy into while a capture
+/// loop reads another.
+#[derive(Debug, Clone, Default)]
+pub struct MockClipboard(Arc<Mutex<MockContents>>);
+
+impl MockClipboard {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_text(&self, text: &str) {
+        self.update(|contents| contents.text = Some(text.to_string()));
+    }
+
+    /// Copy `text` with an HTML flavour, as a browser would.
+    pub fn set_html(&self, text: &str, html: &str) {
+        self.update(|contents| {
+            contents.text = Some(text.to_string());
+            contents.html = Some(html.to_string());
+        });
+    }
+
+    /// Copy `text` with an RTF flavour, as a word processor would.
+    pub fn set_rtf(&self, text: &str, rtf: &str) {
+        self.update(|contents| {
+            contents.text = Some(text.to_string());
+            contents.rtf = Some(rtf.to_string());
+        });
+    }
+
+    /// Copy `text` with application-specific formats, as a spreadsheet
+    /// would.
+    pub fn set_custom_formats(&self, text: &str, formats: Vec<CustomFormat>) {
+        self.update(|contents| {
+            contents.text = Some(text.to_string());
+            contents.custom_formats = formats;
+        });
+    }
+
+    /// Copy files, as a file manager would.
+    pub fn set_files(&self, paths: Vec<PathBuf>) {
+        self.update(|contents| contents.files = Some(paths));
+    }
+
+    /// Copy raw pixels.
+    pub fn set_image(&self, image: ImageData) {
+        self.update(|contents| contents.image = Some(image));
+    }
+
+    /// Copy raw pixels with text, as a spreadsheet copies a range.
+    pub fn set_image_with_text(&self, image: ImageData, text: &str) {
+        self.update(|contents| {
+            contents.image = Some(image);
+            contents.text = Some(text.to_string());
+        });
+    }
+
+    pub fn clear(&self) {
+        self.update(|_| {});
+    }
+
+    /// Replace the contents and bump the sequence number.
+    fn update(&self, set: impl FnOnce(&mut MockContents)) {
+        let mut contents = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let sequence = contents.sequence + 1;
+        *contents = MockContents {
+            sequence,
+            ..MockContents::default()
+        };
+        set(&mut contents);
+    }
+
+    fn contents<T>(
+        &self,
+        get: impl FnOnce(&MockContents) -> Option<T>,
+    ) -> Result<T, ClipboardError> {
+        get(&self.0.lock().unwrap_or_else(PoisonError::into_inner)).ok_or(ClipboardError::Empty)
+    }
+}
+
+impl ClipboardProvider for MockClipboard {
+    fn get_text(&mut self) -> Result<String, ClipboardError> {
+        self.contents(|c| c.text.clone())
+    }
+
+    fn get_html(&mut self) -> Result<String, ClipboardError> {
+        self.contents(|c| c.html.clone())
+    }
+
+    fn get_rtf(&mut self) -> Result<String, ClipboardError> {
+        self.contents(|c| c.rtf.clone())
+    }
+
+    fn get_image(&mut self) -> Result<ImageData, ClipboardError> {
+        self.contents(|c| c.image.clone())
+    }
+
+    fn get_files(&mut self) -> Result<Vec<PathBuf>, ClipboardError> {
+        self.contents(|c| c.files.clone())
+    }
+
+    fn get_custom_formats(&mut self, limit: usize) -> Result<Vec<CustomFormat>, ClipboardError> {
+        self.contents(|c| {
+            let formats: Vec<_> = c.custom_formats.iter().take(limit).cloned().collect();
+            (!formats.is_empty()).then_some(formats)
+        })
+    }
+
+    fn set(&mut self, item: &ClipboardItem) -> Result<(), ClipboardError> {
+        self.set_with_formats(item, &[])
+    }
+
+    fn set_with_formats(
+        &mut self,
+        item: &ClipboardItem,
+        formats: &[CustomFormat],
+    ) -> Result<(), ClipboardError> {
+        self.set_formatted(item, &RichText::default(), formats)
+    }
+
+    fn set_formatted(
+        &mut self,
+        item: &ClipboardItem,
+        rich: &RichText,
+        formats: &[CustomFormat],
+    ) -> Result<(), ClipboardError> {
+        self.set_bundle(item, rich, formats, &[])
+    }
+
+    fn set_bundle(
+        &mut self,
+        item: &ClipboardItem,
+        rich: &RichText,
+        formats: &[CustomFormat],
+        representations: &[Representation],
+    ) -> Result<(), ClipboardError> {
+        let mut image = match item {
+            ClipboardItem::Image(data) => Some(ImageData::decode(data)?),
+            _ => None,
+        };
+        let mut text = None;
+        for representation in representations {
+            if let Some(alongside) = representation.as_text() {
+                text = Some(alongside.to_string());
+            } else if let Some(png) = representation.as_png().filter(|_| image.is_none()) {
+                image = Some(ImageData::decode(png)?);
+            }
+        }
+        self.update(|c| {
+            match item {
+                ClipboardItem::Text(item_text) => text = Some(item_text.clone()),
+                ClipboardItem::Files(paths) => c.files = Some(paths.clone()),
+                _ => {}
+            }
+            c.text = text;
+            c.image = image;
+            if !matches!(item, ClipboardItem::Files(_)) {
+                c.html.clone_from(&rich.html);
+                c.rtf.clone_from(&rich.rtf);
+            }
+            c.custom_formats = formats.to_vec();
+        });
+        Ok(())
+    }
+
+    fn sequence_number(&mut self) -> Option<u64> {
+        Some(
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .sequence,
+        )
+    }
+}
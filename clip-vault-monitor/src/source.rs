@@ -0,0 +1,63 @@
+//! The frontmost app, looked up on a background thread rather than at each
+//! capture. A lookup spawns a process (`osascript` or `xdotool`; Windows
+//! asks the window manager directly) that can take longer than a poll; done
+//! in the capture loop it
+//! held up every capture, and by the time it answered the user could have
+//! switched apps. The thread keeps the latest answer, taken before the
+//! change was noticed, for captures to read.
+
+use clip_vault_core::SourceApp;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How often the frontmost app is looked up while captures ask for it.
+const LOOKUP_INTERVAL: Duration = Duration::from_millis(500);
+/// The lookups stop after this long without a capture asking, and start
+/// again with the next one.
+const IDLE_AFTER: Duration = Duration::from_mins(5);
+
+struct Tracker {
+    latest: Option<SourceApp>,
+    asked: Option<Instant>,
+    running: bool,
+}
+
+static TRACKER: Mutex<Tracker> = Mutex::new(Tracker {
+    latest: None,
+    asked: None,
+    running: false,
+});
+
+/// The app that was frontmost at the last background lookup. The first
+/// call, and the first after the lookups went idle, looks it up on the
+/// spot and starts them. `CLIP_VAULT_SOURCE_APP` is read directly (see
+/// [`SourceApp::current`]).
+pub(crate) fn source_app() -> Option<SourceApp> {
+    if std::env::var_os("CLIP_VAULT_SOURCE_APP").is_some() {
+        return SourceApp::current();
+    }
+    let mut tracker = TRACKER.lock().unwrap_or_else(PoisonError::into_inner);
+    tracker.asked = Some(Instant::now());
+    if !tracker.running {
+        tracker.latest = SourceApp::current();
+        tracker.running = true;
+        std::thread::spawn(look_up);
+    }
+    tracker.latest.clone()
+}
+
+fn look_up() {
+    loop {
+        std::thread::sleep(LOOKUP_INTERVAL);
+        let current = SourceApp::current();
+        let mut tracker = TRACKER.lock().unwrap_or_else(PoisonError::into_inner);
+        if tracker
+            .asked
+            .is_none_or(|asked| asked.elapsed() >= IDLE_AFTER)
+        {
+            tracker.running = false;
+            return;
+        }
+        tracker.latest = current;
+    }
+}
@@ -0,0 +1,157 @@
+//! One-shot "do not record" markers for content placed on the clipboard on
+//! purpose, like a generated password. A marker is a line in a file under
+//! the cache directory, so it reaches a monitor running in another process.
+//! The first capture loop to read the marked content takes the marker and
+//! from then on treats the content as already seen.
+
+use crate::ImageData;
+use clip_vault_core::{
+    apply_capture_transforms, files_text, hash_from_hex, hash_to_hex, strip_image_metadata,
+    Capture, CaptureTransform, ClipboardItem, TextNormalization,
+};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MARKER_FILE: &str = "do-not-record";
+/// Markers still untaken after this long are dropped: the content was
+/// replaced before any monitor read it, or none is running.
+const MARKER_TTL_SECS: u64 = 60;
+
+fn marker_path() -> PathBuf {
+    clip_vault_core::cache_dir().join(MARKER_FILE)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The hashes `item` may have once a monitor reads it back: as given, after
+/// whichever text cleanups are turned on, with its whitespace evened out or
+/// not, as a re-encoded (and stripped) image, and files as their paths.
+fn marked_hashes(item: &ClipboardItem) -> Vec<[u8; 32]> {
+    let mut hashes = vec![item.hash()];
+    match item {
+        ClipboardItem::Text(text) => {
+            let [first, second] = CaptureTransform::ALL;
+            for transforms in [&[][..], &[first], &[second], &[first, second]] {
+                let cleaned = apply_capture_transforms(text.clone(), transforms);
+                let normalized = TextNormalization::Whitespace.apply(cleaned.clone());
+                hashes.push(ClipboardItem::Text(cleaned).hash());
+                hashes.push(ClipboardItem::Text(normalized).hash());
+            }
+        }
+        ClipboardItem::Image(data) => {
+            let png = ImageData::decode(data)
+                .ok()
+                .and_then(|image| image.encode_png().ok());
+            if let Some(png) = png {
+                hashes.push(ClipboardItem::Image(strip_image_metadata(&png)).hash());
+                hashes.push(ClipboardItem::Image(png).hash());
+            }
+        }
+        // Where the clipboard only offers the paths as text.
+        ClipboardItem::Files(paths) => {
+            hashes.push(ClipboardItem::Text(files_text(paths)).hash());
+        }
+        _ => {}
+    }
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes
+}
+
+/// One marked copy: when it was marked, and every hash it may be read as.
+struct Marker {
+    set: u64,
+    hashes: Vec<[u8; 32]>,
+}
+
+/// Markers that haven't expired. Each is a line of the time it was set
+/// followed by its hashes in hex.
+fn load_markers() -> Vec<Marker> {
+    let Ok(contents) = std::fs::read_to_string(marker_path()) else {
+        return Vec::new();
+    };
+    let now = now_secs();
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let set = fields.next()?.parse().ok()?;
+            let hashes = fields.map(hash_from_hex).collect::<Option<_>>()?;
+            Some(Marker { set, hashes })
+        })
+        .filter(|marker| now.saturating_sub(marker.set) < MARKER_TTL_SECS)
+        .collect()
+}
+
+fn save_markers(markers: &[Marker]) -> io::Result<()> {
+    let path = marker_path();
+    if markers.is_empty() {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let contents: String = markers
+        .iter()
+        .map(|marker| {
+            let mut line = marker.set.to_string();
+            for hash in &marker.hashes {
+                line.push(' ');
+                line.push_str(&hash_to_hex(hash));
+            }
+            line.push('\n');
+            line
+        })
+        .collect();
+    // The hashes tell whoever can read them what was copied, if they can
+    // guess it, so the file is only readable by the current user.
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    let mut file = {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let file = options.open(&path)?;
+        // An existing file keeps its mode otherwise.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file
+    };
+    #[cfg(not(unix))]
+    let mut file = options.open(&path)?;
+    file.write_all(contents.as_bytes())
+}
+
+/// Mark `item` so the next capture of it is skipped. Call it just before
+/// putting `item` on the clipboard.
+pub fn suppress_capture(item: &ClipboardItem) -> io::Result<()> {
+    let mut markers = load_markers();
+    markers.push(Marker {
+        set: now_secs(),
+        hashes: marked_hashes(item),
+    });
+    save_markers(&markers)
+}
+
+/// Whether `capture` was marked with [`suppress_capture`]. Takes the
+/// marker, so this is true once per marked copy.
+#[must_use]
+pub fn take_suppressed(capture: &Capture) -> bool {
+    let mut markers = load_markers();
+    let Some(index) = markers
+        .iter()
+        .position(|marker| marker.hashes.contains(&capture.hash))
+    else {
+        return false;
+    };
+    markers.remove(index);
+    save_markers(&markers).ok();
+    true
+}
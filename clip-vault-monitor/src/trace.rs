@@ -0,0 +1,169 @@
+//! A record of what the capture loop decided at each poll, for working out
+//! why copies from some app never show up: whether the clipboard changed,
+//! whether it could be read, and whether the capture was new.
+
+use clip_vault_core::{ClipboardItem, Timestamp};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Decisions [`CaptureTrace::default`] keeps.
+pub const TRACE_LEN: usize = 50;
+/// A decision repeating poll after poll is logged again at most this often.
+const REPEAT_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What one poll of the clipboard came to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum PollOutcome {
+    /// The clipboard's change counter hadn't moved, so it wasn't read.
+    Unchanged,
+    /// Nothing on the clipboard could be read as files, text or an image.
+    Empty,
+    /// Reading the clipboard failed.
+    BackendError {
+        error: String,
+    },
+    /// Read, but left out by the capture settings.
+    Skipped {
+        reason: String,
+    },
+    /// The same as the previous capture.
+    Duplicate,
+    Captured {
+        image: bool,
+        bytes: usize,
+    },
+    /// An image that couldn't be encoded for storage.
+    Failed {
+        error: String,
+    },
+}
+
+impl PollOutcome {
+    /// `item` was stored.
+    #[must_use]
+    pub fn captured(item: &ClipboardItem) -> Self {
+        match item {
+            ClipboardItem::Text(text) => Self::Captured {
+                image: false,
+                bytes: text.len(),
+            },
+            ClipboardItem::Image(data) => Self::Captured {
+                image: true,
+                bytes: data.len(),
+            },
+            _ => Self::Captured {
+                image: false,
+                bytes: item.size(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for PollOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unchanged => f.write_str("unchanged"),
+            Self::Empty => f.write_str("nothing to capture"),
+            Self::BackendError { error } => write!(f, "clipboard error: {error}"),
+            Self::Skipped { reason } => write!(f, "skipped: {reason}"),
+            Self::Duplicate => f.write_str("duplicate of the last capture"),
+            Self::Captured { image, bytes } => write!(
+                f,
+                "captured {}, {}",
+                if *image { "image" } else { "text" },
+                clip_vault_core::format_size(*bytes)
+            ),
+            Self::Failed { error } => write!(f, "failed: {error}"),
+        }
+    }
+}
+
+/// One run of polls that came to the same outcome.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CaptureDecision {
+    /// When the latest of the polls ran.
+    pub timestamp: Timestamp,
+    #[serde(flatten)]
+    pub outcome: PollOutcome,
+    /// Polls in the run.
+    pub polls: u32,
+    /// Time the slowest of them spent reading the clipboard.
+    pub max_poll_micros: u64,
+}
+
+impl fmt::Display for CaptureDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (poll took {} µs", self.outcome, self.max_poll_micros)?;
+        if self.polls > 1 {
+            write!(f, " at most, {} polls in a row", self.polls)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// The latest capture decisions, with consecutive polls that came to the
+/// same outcome folded into one, so a quiet clipboard doesn't push out the
+/// polls that mattered.
+#[derive(Debug)]
+pub struct CaptureTrace {
+    decisions: VecDeque<CaptureDecision>,
+    capacity: usize,
+    last_logged: Option<Instant>,
+}
+
+impl Default for CaptureTrace {
+    fn default() -> Self {
+        Self::new(TRACE_LEN)
+    }
+}
+
+impl CaptureTrace {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            decisions: VecDeque::with_capacity(capacity),
+            capacity,
+            last_logged: None,
+        }
+    }
+
+    /// Add a poll that took `elapsed` and came to `outcome`. Returns the
+    /// line to log for it, if any: every new outcome is logged, a repeated
+    /// one at most every ten seconds.
+    pub fn record(&mut self, outcome: PollOutcome, elapsed: Duration) -> Option<String> {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let timestamp = Timestamp::now();
+        if let Some(last) = self.decisions.back_mut().filter(|d| d.outcome == outcome) {
+            last.timestamp = timestamp;
+            last.polls = last.polls.saturating_add(1);
+            last.max_poll_micros = last.max_poll_micros.max(micros);
+            if self
+                .last_logged
+                .is_some_and(|t| t.elapsed() < REPEAT_LOG_INTERVAL)
+            {
+                return None;
+            }
+        } else {
+            if self.decisions.len() == self.capacity {
+                self.decisions.pop_front();
+            }
+            self.decisions.push_back(CaptureDecision {
+                timestamp,
+                outcome,
+                polls: 1,
+                max_poll_micros: micros,
+            });
+        }
+        self.last_logged = Some(Instant::now());
+        self.decisions.back().map(ToString::to_string)
+    }
+
+    /// The kept decisions, oldest first.
+    #[must_use]
+    pub fn decisions(&self) -> Vec<CaptureDecision> {
+        self.decisions.iter().cloned().collect()
+    }
+}